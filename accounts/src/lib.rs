@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// Username and password submitted to register or log in.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub username: String,
+    #[serde(default)]
+    pub dictionary: Dictionary,
+}
+
+/// Words rated more obscure than this don't count in the strict dictionary.
+pub const STRICT_MAX_OBSCURITY: u8 = 3;
+
+/// Which words a player wants puzzles to accept, saved with their account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dictionary {
+    /// Every word in the list.
+    #[default]
+    Permissive,
+    /// Only common words. Words that haven't been rated count as common.
+    Strict,
+}
+
+impl Dictionary {
+    /// Whether a word rated `obscurity` is in the dictionary.
+    pub fn allows(self, obscurity: Option<u8>) -> bool {
+        match self {
+            Self::Permissive => true,
+            Self::Strict => obscurity.is_none_or(|o| o <= STRICT_MAX_OBSCURITY),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Permissive => "permissive",
+            Self::Strict => "strict",
+        }
+    }
+}
+
+impl std::str::FromStr for Dictionary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "permissive" => Ok(Self::Permissive),
+            "strict" => Ok(Self::Strict),
+            _ => Err(format!("Unknown dictionary {:?}", s)),
+        }
+    }
+}
+
+/// Settings saved with a player's account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preferences {
+    pub dictionary: Dictionary,
+}
@@ -0,0 +1,447 @@
+//! Typed client for the server's JSON API, shared by the frontend in the
+//! browser and by native code such as integration tests.
+//!
+//! Requests go through `gloo-net` when compiled to wasm and through `hyper`
+//! otherwise. Native clients only speak plain HTTP.
+
+use accounts::{Credentials, Dictionary, Preferences, User};
+use puzzle_config::{
+    CustomPuzzle, Difficulty, NewCustomPuzzle, Note, Progress, PuzzleConfig, Schedule,
+    ScheduledPuzzle,
+};
+use search::SearchMode;
+use serde::{Serialize, de::DeserializeOwned};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+#[cfg(not(target_arch = "wasm32"))]
+use native as transport;
+#[cfg(target_arch = "wasm32")]
+use wasm as transport;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// The server responded with an error status, along with the message of
+    /// the response if it had one.
+    Status {
+        status: u16,
+        message: Option<String>,
+    },
+    Network(String),
+    Decode(String),
+    /// The server is failing and there is no earlier response to fall back
+    /// on, for clients that stop sending requests while it is.
+    Unavailable,
+}
+
+impl ApiError {
+    /// Status of the response, if the server responded.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Status {
+                status,
+                message: Some(message),
+            } => write!(f, "request failed ({}): {}", status, message),
+            Self::Status {
+                status,
+                message: None,
+            } => write!(f, "request failed ({})", status),
+            Self::Network(cause) => write!(f, "request failed: {}", cause),
+            Self::Decode(cause) => write!(f, "invalid response: {}", cause),
+            Self::Unavailable => write!(f, "the server is unavailable, try again later"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// HTTP methods the API uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Method {
+    Get,
+    Post,
+    Put,
+}
+
+/// A request as handed to the transport.
+pub(crate) struct Request<'a> {
+    pub(crate) method: Method,
+    pub(crate) url: String,
+    pub(crate) query: Vec<(&'a str, &'a str)>,
+    pub(crate) body: Option<String>,
+    pub(crate) admin_token: Option<&'a str>,
+}
+
+/// Client of one server. Word list methods act on the dictionary named with
+/// [`Client::with_word_list`], the standard one unless named.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Client {
+    base_url: String,
+    admin_token: Option<String>,
+    word_list: Option<String>,
+}
+
+impl Client {
+    /// A client of the server at `base_url`, like `http://localhost:3000`.
+    /// In the browser, the default empty base URL is the server the app was
+    /// served from.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Authenticate with the server's admin token, for clients without an
+    /// admin session.
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    /// Manage the words of the dictionary called `word_list`, or of the
+    /// standard one if `None`.
+    pub fn with_word_list(mut self, word_list: Option<impl Into<String>>) -> Self {
+        self.word_list = word_list.map(Into::into);
+        self
+    }
+
+    /// The body of a successful GET of `path` with `query`.
+    pub async fn get_text(&self, path: &str, query: &[(&str, &str)]) -> Result<String, ApiError> {
+        self.send(Method::Get, path, query, None).await
+    }
+
+    /// GET `path` with `query` and parse the JSON response.
+    pub async fn get_json<T>(&self, path: &str, query: &[(&str, &str)]) -> Result<T, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        let body = self.get_text(path, query).await?;
+        serde_json::from_str(&body).map_err(|e| ApiError::Decode(e.to_string()))
+    }
+
+    /// POST `body` as JSON to `path`, ignoring the response body.
+    pub async fn post_json<B>(&self, path: &str, body: &B) -> Result<(), ApiError>
+    where
+        B: Serialize,
+    {
+        self.send(Method::Post, path, &[], Some(to_json(body)?))
+            .await?;
+        Ok(())
+    }
+
+    /// Start a session for the account `credentials` name.
+    pub async fn log_in(&self, credentials: &Credentials) -> Result<User, ApiError> {
+        self.send_json(Method::Post, "/api/auth/login", &[], credentials)
+            .await
+    }
+
+    /// Create an account and start a session for it.
+    pub async fn register(&self, credentials: &Credentials) -> Result<User, ApiError> {
+        self.send_json(Method::Post, "/api/auth/register", &[], credentials)
+            .await
+    }
+
+    /// The user of the current session, or `None` without one.
+    pub async fn current_user(&self) -> Result<Option<User>, ApiError> {
+        match self.get_json("/api/auth/me", &[]).await {
+            Ok(user) => Ok(Some(user)),
+            Err(e) if e.status() == Some(401) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn save_preferences(&self, preferences: &Preferences) -> Result<(), ApiError> {
+        self.send(
+            Method::Put,
+            "/api/auth/me/preferences",
+            &[],
+            Some(to_json(preferences)?),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn log_out(&self) -> Result<(), ApiError> {
+        self.send(Method::Post, "/api/auth/logout", &[], None)
+            .await?;
+        Ok(())
+    }
+
+    /// The player's progress on today's puzzle in the timezone `tz` merged
+    /// with `progress`.
+    pub async fn save_progress(
+        &self,
+        tz: &str,
+        difficulty: Difficulty,
+        progress: &Progress,
+    ) -> Result<Progress, ApiError> {
+        self.send_json(
+            Method::Put,
+            "/api/puzzle/daily/progress",
+            &[("tz", tz), ("difficulty", difficulty.as_str())],
+            progress,
+        )
+        .await
+    }
+
+    /// Save the player's note on the puzzle of `date`.
+    pub async fn save_note(&self, date: &str, note: &Note) -> Result<(), ApiError> {
+        self.send(
+            Method::Put,
+            &format!("/api/notes/{}", date),
+            &[],
+            Some(to_json(note)?),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn create_custom_puzzle(
+        &self,
+        puzzle: &NewCustomPuzzle,
+    ) -> Result<CustomPuzzle, ApiError> {
+        self.send_json(Method::Post, "/api/puzzle/custom", &[], puzzle)
+            .await
+    }
+
+    /// Report a word missing from the list or wrongly in it.
+    pub async fn report_word(&self, report: &words_list::WordReport) -> Result<(), ApiError> {
+        self.post_json("/api/feedback/words", report).await
+    }
+
+    /// Puzzles scheduled between the dates `from` and `to`, inclusive.
+    pub async fn schedule(&self, from: &str, to: &str) -> Result<Schedule, ApiError> {
+        self.get_json("/api/admin/puzzle/schedule", &[("from", from), ("to", to)])
+            .await
+    }
+
+    pub async fn schedule_puzzle(&self, puzzle: &ScheduledPuzzle) -> Result<(), ApiError> {
+        self.post_json("/api/admin/puzzle/schedule", puzzle).await
+    }
+
+    /// Generate the stored puzzles of `date` again.
+    pub async fn regenerate_puzzle(&self, date: &str) -> Result<(), ApiError> {
+        self.send(
+            Method::Post,
+            &format!("/api/admin/puzzle/{}/regenerate", date),
+            &[],
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Errors reported by players' browsers.
+    pub async fn client_errors(&self) -> Result<client_errors::ReportedErrors, ApiError> {
+        self.get_json("/api/client-errors", &[]).await
+    }
+
+    /// Today's puzzle in the timezone `tz`, an offset like `+09:00` or a zone
+    /// like `Asia/Tokyo`.
+    pub async fn get_daily_config(
+        &self,
+        tz: &str,
+        dictionary: Dictionary,
+        difficulty: Difficulty,
+    ) -> Result<PuzzleConfig, ApiError> {
+        self.get_json(
+            "/api/puzzle/daily/config",
+            &[
+                ("tz", tz),
+                ("dictionary", dictionary.as_str()),
+                ("difficulty", difficulty.as_str()),
+            ],
+        )
+        .await
+    }
+
+    /// The page of words starting at `cursor`, or the first one.
+    pub async fn list_words(&self, cursor: Option<&str>) -> Result<words_list::Words, ApiError> {
+        let query: Vec<_> = cursor
+            .map(|cursor| ("cursor", cursor))
+            .into_iter()
+            .collect();
+        self.get_json("/api/words", &query).await
+    }
+
+    pub async fn search_words(
+        &self,
+        q: &str,
+        mode: SearchMode,
+    ) -> Result<search::SearchedWords, ApiError> {
+        self.get_json("/api/words/search", &[("q", q), ("mode", mode.as_str())])
+            .await
+    }
+
+    /// Words flagged for review.
+    pub async fn flagged_words(&self) -> Result<words_list::FlaggedWords, ApiError> {
+        self.get_json("/api/words/flagged", &[]).await
+    }
+
+    pub async fn add_words(&self, words: &[String]) -> Result<(), ApiError> {
+        self.post_words("/api/words", words).await
+    }
+
+    pub async fn remove_words(&self, words: &[String]) -> Result<(), ApiError> {
+        self.post_words("/api/words/remove", words).await
+    }
+
+    pub async fn restore_words(&self, words: &[String]) -> Result<(), ApiError> {
+        self.post_words("/api/words/restore", words).await
+    }
+
+    /// Everything known about `word`.
+    pub async fn word_detail(&self, word: &str) -> Result<words_list::WordDetail, ApiError> {
+        self.get_json(&format!("/api/words/{}", word), &[]).await
+    }
+
+    /// Flag `word` for review, keeping it out of puzzles, or clear its flag.
+    pub async fn set_flagged(&self, word: &str, flagged: bool) -> Result<(), ApiError> {
+        let action = if flagged { "flag" } else { "unflag" };
+        self.send(
+            Method::Post,
+            &format!("/api/words/{}/{}", word, action),
+            &[],
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Words the morphology policy keeps out of puzzles.
+    pub async fn excluded_words(&self) -> Result<words_list::ExcludedWords, ApiError> {
+        self.get_json("/api/words/excluded", &[]).await
+    }
+
+    /// The latest changes to the word list.
+    pub async fn word_audit(&self) -> Result<words_list::WordAudit, ApiError> {
+        self.get_json("/api/words/audit", &[]).await
+    }
+
+    /// Words players reported as missing from the list or wrongly in it.
+    pub async fn word_reports(&self) -> Result<words_list::ReportedWords, ApiError> {
+        self.get_json("/api/admin/feedback/words", &[]).await
+    }
+
+    pub async fn list_dictionaries(&self) -> Result<words_list::Dictionaries, ApiError> {
+        self.get_json("/api/admin/dictionaries", &[]).await
+    }
+
+    pub async fn create_dictionary(&self, name: &str) -> Result<(), ApiError> {
+        self.post_json(
+            "/api/admin/dictionaries",
+            &words_list::NewDictionary {
+                name: name.to_owned(),
+            },
+        )
+        .await
+    }
+
+    async fn post_words(&self, path: &str, words: &[String]) -> Result<(), ApiError> {
+        self.post_json(path, &serde_json::json!({ "words": words }))
+            .await
+    }
+
+    /// Send `body` as JSON and parse the JSON response.
+    async fn send_json<B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: &B,
+    ) -> Result<T, ApiError>
+    where
+        B: Serialize,
+        T: DeserializeOwned,
+    {
+        let body = self.send(method, path, query, Some(to_json(body)?)).await?;
+        serde_json::from_str(&body).map_err(|e| ApiError::Decode(e.to_string()))
+    }
+
+    /// Send a request, returning the body of a successful response.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<String>,
+    ) -> Result<String, ApiError> {
+        transport::send(Request {
+            method,
+            url: format!("{}{}", self.base_url, path),
+            query: self.query_for(path, query),
+            body,
+            admin_token: self.admin_token.as_deref(),
+        })
+        .await
+    }
+
+    /// `query` for a request to `path`. Requests under `/api/words` name the
+    /// dictionary they act on.
+    fn query_for<'a>(
+        &'a self,
+        path: &str,
+        query: &[(&'a str, &'a str)],
+    ) -> Vec<(&'a str, &'a str)> {
+        let word_list = self
+            .word_list
+            .as_deref()
+            .filter(|_| path.starts_with("/api/words"))
+            .map(|word_list| ("word_list", word_list));
+        query.iter().copied().chain(word_list).collect()
+    }
+}
+
+fn to_json<B: Serialize>(body: &B) -> Result<String, ApiError> {
+    serde_json::to_string(body).map_err(|e| ApiError::Decode(e.to_string()))
+}
+
+/// The message of an error response, which the server sends as JSON.
+pub(crate) fn error_message(body: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|body| body["message"].as_str().map(str::to_owned))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_the_word_list_of_word_requests() {
+        let client = Client::new("http://localhost:3000/").with_word_list(Some("expanded"));
+        assert_eq!(
+            vec![("q", "bee"), ("word_list", "expanded")],
+            client.query_for("/api/words/search", &[("q", "bee")])
+        );
+        assert_eq!(
+            vec![("tz", "+00:00")],
+            client.query_for("/api/puzzle/daily/config", &[("tz", "+00:00")])
+        );
+        assert!(Client::default().query_for("/api/words", &[]).is_empty());
+    }
+
+    #[test]
+    fn reads_error_messages() {
+        let error = ApiError::Status {
+            status: 422,
+            message: error_message(r#"{"message":"Too short"}"#),
+        };
+        assert_eq!("request failed (422): Too short", error.to_string());
+        assert_eq!(Some(422), error.status());
+        assert_eq!(None, error_message("<html>"));
+    }
+}
@@ -0,0 +1,70 @@
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+
+use crate::{ApiError, Method, Request};
+
+pub(crate) async fn send(request: Request<'_>) -> Result<String, ApiError> {
+    let query: Vec<String> = request
+        .query
+        .iter()
+        .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+        .collect();
+    let url = match query.as_slice() {
+        [] => request.url,
+        _ => format!("{}?{}", request.url, query.join("&")),
+    };
+    let method = match request.method {
+        Method::Get => hyper::Method::GET,
+        Method::Post => hyper::Method::POST,
+        Method::Put => hyper::Method::PUT,
+    };
+    let mut builder = hyper::Request::builder()
+        .method(method)
+        .uri(&url)
+        .header(hyper::header::ACCEPT, "application/json");
+    if request.body.is_some() {
+        builder = builder.header(hyper::header::CONTENT_TYPE, "application/json");
+    }
+    if let Some(token) = request.admin_token {
+        builder = builder.header(hyper::header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let http_request = builder
+        .body(Full::new(Bytes::from(request.body.unwrap_or_default())))
+        .map_err(|e| ApiError::Network(format!("invalid request to {}: {}", url, e)))?;
+
+    let client: Client<_, Full<Bytes>> = Client::builder(TokioExecutor::new()).build_http();
+    let response = client
+        .request(http_request)
+        .await
+        .map_err(|e| ApiError::Network(e.to_string()))?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| ApiError::Network(e.to_string()))?
+        .to_bytes();
+    let body = String::from_utf8(body.to_vec()).map_err(|e| ApiError::Decode(e.to_string()))?;
+    if !status.is_success() {
+        return Err(ApiError::Status {
+            status: status.as_u16(),
+            message: crate::error_message(&body),
+        });
+    }
+    Ok(body)
+}
+
+/// Percent-encode `value` for a query string.
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,38 @@
+use crate::{ApiError, Method, Request};
+
+pub(crate) async fn send(request: Request<'_>) -> Result<String, ApiError> {
+    let builder = match request.method {
+        Method::Get => gloo_net::http::Request::get(&request.url),
+        Method::Post => gloo_net::http::Request::post(&request.url),
+        Method::Put => gloo_net::http::Request::put(&request.url),
+    };
+    let mut builder = builder
+        .query(request.query.iter().copied())
+        .header("accept", "application/json");
+    if let Some(token) = request.admin_token {
+        builder = builder.header("authorization", &format!("Bearer {}", token));
+    }
+    let sent = match request.body {
+        Some(body) => {
+            builder
+                .header("content-type", "application/json")
+                .body(body)
+                .map_err(|e| ApiError::Network(e.to_string()))?
+                .send()
+                .await
+        }
+        None => builder.send().await,
+    };
+    let resp = sent.map_err(|e| ApiError::Network(e.to_string()))?;
+    let body = resp
+        .text()
+        .await
+        .map_err(|e| ApiError::Network(e.to_string()))?;
+    if !resp.ok() {
+        return Err(ApiError::Status {
+            status: resp.status(),
+            message: crate::error_message(&body),
+        });
+    }
+    Ok(body)
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// An error captured in the browser, stripped of anything identifying the player.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClientErrorReport {
+    pub message: String,
+    pub location: Option<String>,
+    pub route: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReportedError {
+    pub id: i64,
+    pub message: String,
+    pub location: Option<String>,
+    pub route: Option<String>,
+    pub reported_at: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReportedErrors {
+    pub errors: Vec<ReportedError>,
+}
@@ -0,0 +1,80 @@
+// Trunk post-build hook making the app installable and playable offline.
+//
+// Writes a web manifest and a service worker precaching every file of the
+// build into the staging directory, and links the manifest from index.html.
+// The worker's cache is named after a hash of the file names, which Trunk
+// hashes by content, so each build replaces the previous build's cache.
+//
+// Lists the files Trunk named after a hash of their contents in
+// asset-manifest.json, which the server lets browsers cache for good.
+//
+// Also writes gzip and brotli copies of the WASM and JavaScript next to them,
+// which the server sends as they are to browsers accepting them rather than
+// compressing the largest files of the build on every request.
+
+import { createHash } from "node:crypto"
+import { readdirSync, readFileSync, writeFileSync } from "node:fs"
+import { join, relative } from "node:path"
+import { brotliCompressSync, constants, gzipSync } from "node:zlib"
+
+const staging = process.env.TRUNK_STAGING_DIR
+const publicUrl = process.env.TRUNK_PUBLIC_URL ?? "/"
+
+function files(dir) {
+  return readdirSync(dir, { withFileTypes: true }).flatMap(entry => {
+    const path = join(dir, entry.name)
+    return entry.isDirectory() ? files(path) : [relative(staging, path)]
+  })
+}
+
+const built = files(staging).filter(
+  file => file !== "index.html" && file !== "sw.js" && !/\.(gz|br)$/.test(file),
+)
+const urls = ["/", ...built.map(file => publicUrl + file)]
+const version = createHash("sha256").update(urls.join("\n")).digest("hex").slice(0, 16)
+const icon = built.find(file => /^bee.*\.svg$/.test(file))
+
+const manifest = {
+  name: "bee",
+  short_name: "bee",
+  description: "A daily word puzzle",
+  start_url: "/",
+  scope: "/",
+  display: "standalone",
+  background_color: "#ffffff",
+  theme_color: "#fbbd23",
+  icons: icon ? [{ src: publicUrl + icon, sizes: "any", type: "image/svg+xml" }] : [],
+}
+writeFileSync(join(staging, "manifest.webmanifest"), JSON.stringify(manifest, null, 2))
+
+const fingerprinted = built.filter(file => /-[0-9a-f]{8,16}(_bg)?\.[a-z0-9]+$/.test(file))
+writeFileSync(join(staging, "asset-manifest.json"), JSON.stringify({ fingerprinted }, null, 2))
+
+const template = readFileSync(join(process.env.TRUNK_SOURCE_DIR, "build", "sw.js"), "utf8")
+writeFileSync(
+  join(staging, "sw.js"),
+  template
+    .replace("__CACHE_NAME__", JSON.stringify(`bee-${version}`))
+    .replace("__PRECACHE__", JSON.stringify([...urls, publicUrl + "manifest.webmanifest"])),
+)
+
+const indexPath = join(staging, "index.html")
+const index = readFileSync(indexPath, "utf8")
+writeFileSync(
+  indexPath,
+  index.replace(
+    "</head>",
+    `<link rel="manifest" href="${publicUrl}manifest.webmanifest" />\n` +
+      `<meta name="theme-color" content="${manifest.theme_color}" />\n</head>`,
+  ),
+)
+
+for (const file of built.filter(file => /\.(wasm|js)$/.test(file))) {
+  const path = join(staging, file)
+  const data = readFileSync(path)
+  writeFileSync(`${path}.gz`, gzipSync(data, { level: 9 }))
+  writeFileSync(
+    `${path}.br`,
+    brotliCompressSync(data, { params: { [constants.BROTLI_PARAM_QUALITY]: 11 } }),
+  )
+}
@@ -0,0 +1,50 @@
+// Service worker template filled in by build/pwa.mjs.
+//
+// The app shell and bundle are served from the cache so the game starts
+// without a connection. API requests always go to the network; the app keeps
+// the day's puzzle in localStorage and falls back on it by itself.
+
+const CACHE_NAME = __CACHE_NAME__
+const PRECACHE = __PRECACHE__
+
+self.addEventListener("install", event => {
+  event.waitUntil(
+    caches
+      .open(CACHE_NAME)
+      .then(cache => cache.addAll(PRECACHE))
+      .then(() => self.skipWaiting()),
+  )
+})
+
+self.addEventListener("activate", event => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then(names =>
+        Promise.all(names.filter(name => name !== CACHE_NAME).map(name => caches.delete(name))),
+      )
+      .then(() => self.clients.claim()),
+  )
+})
+
+self.addEventListener("fetch", event => {
+  const request = event.request
+  const url = new URL(request.url)
+  if (request.method !== "GET" || url.origin !== self.location.origin) {
+    return
+  }
+  if (url.pathname.startsWith("/api/") || url.pathname === "/metrics") {
+    return
+  }
+
+  if (request.mode === "navigate") {
+    // Every page is the same app shell, so fresh HTML is preferred but any
+    // cached copy will do offline.
+    event.respondWith(fetch(request).catch(() => caches.match("/")))
+    return
+  }
+
+  event.respondWith(
+    caches.match(request).then(cached => cached ?? fetch(request)),
+  )
+})
@@ -0,0 +1,239 @@
+use accounts::{Credentials, Preferences, User};
+use leptos::prelude::*;
+use leptos_router::{components::A, hooks::use_navigate};
+
+/// Share the logged in user, if any, with the rest of the app. The session
+/// itself lives in an HTTP-only cookie, so the user is looked up from the
+/// server on startup.
+pub(crate) fn provide_account() {
+    let (user, set_user) = signal(None::<User>);
+    provide_context((user, set_user));
+    leptos::task::spawn_local(async move {
+        if let Ok(current) = fetch_current_user().await {
+            set_user.set(current);
+        }
+    });
+}
+
+/// Keep the dictionary setting in step with the logged in user's account:
+/// adopt the account's choice on login and save changes made while logged in.
+pub(crate) fn sync_dictionary() {
+    let (user, set_user) = use_account();
+    let (settings, set_settings) = crate::settings::use_settings();
+    Effect::watch(
+        move || user.get().map(|user| user.dictionary),
+        move |dictionary, _, _| {
+            if let Some(dictionary) = *dictionary
+                && settings.get_untracked().dictionary != dictionary
+            {
+                set_settings.write().dictionary = dictionary;
+            }
+        },
+        false,
+    );
+    Effect::watch(
+        move || settings.read().dictionary,
+        move |dictionary, _, _| {
+            let dictionary = *dictionary;
+            if user
+                .get_untracked()
+                .is_none_or(|user| user.dictionary == dictionary)
+            {
+                return;
+            }
+            leptos::task::spawn_local(async move {
+                match save_preferences(&Preferences { dictionary }).await {
+                    Ok(()) => set_user.update(|user| {
+                        if let Some(user) = user {
+                            user.dictionary = dictionary;
+                        }
+                    }),
+                    Err(e) => leptos::logging::error!("{}", e),
+                }
+            });
+        },
+        false,
+    );
+}
+
+pub(crate) fn use_account() -> (ReadSignal<Option<User>>, WriteSignal<Option<User>>) {
+    use_context::<(ReadSignal<Option<User>>, WriteSignal<Option<User>>)>()
+        .expect("No account provided")
+}
+
+#[component]
+pub(crate) fn AccountButton() -> impl IntoView {
+    let (user, set_user) = use_account();
+    let logout = move |_| {
+        leptos::task::spawn_local(async move {
+            match log_out().await {
+                Ok(()) => set_user.set(None),
+                Err(e) => leptos::logging::error!("{}", e),
+            }
+        });
+    };
+
+    view! {
+        {move || match user.get() {
+            Some(user) => leptos::either::Either::Left(view! {
+                <span class="text-sm">{user.username}</span>
+                <button type="button" class="btn btn-ghost btn-sm" on:click=logout>
+                    log out
+                </button>
+            }),
+            None => leptos::either::Either::Right(view! {
+                <A href="/login" attr:class="btn btn-ghost btn-sm">
+                    log in
+                </A>
+            }),
+        }}
+    }
+}
+
+#[component]
+pub(crate) fn Login() -> impl IntoView {
+    view! {
+        <CredentialsForm
+            title="Log in"
+            action=CredentialsAction::LogIn
+            password_autocomplete="current-password"
+        />
+        <p class="text-center">
+            "No account? " <A href="/register" attr:class="link">register</A>
+        </p>
+    }
+}
+
+#[component]
+pub(crate) fn Register() -> impl IntoView {
+    view! {
+        <CredentialsForm
+            title="Register"
+            action=CredentialsAction::Register
+            password_autocomplete="new-password"
+        />
+        <p class="text-center">
+            "Already registered? " <A href="/login" attr:class="link">log in</A>
+        </p>
+    }
+}
+
+/// What a [`CredentialsForm`] does with the credentials, both of which start a
+/// session.
+#[derive(Debug, Clone, Copy)]
+enum CredentialsAction {
+    LogIn,
+    Register,
+}
+
+/// Username and password form starting a session with `action`.
+#[component]
+fn CredentialsForm(
+    title: &'static str,
+    action: CredentialsAction,
+    password_autocomplete: &'static str,
+) -> impl IntoView {
+    let (_, set_user) = use_account();
+    let (username, set_username) = signal(String::new());
+    let (password, set_password) = signal(String::new());
+    let (status, set_status) = signal(None::<String>);
+    let navigate = use_navigate();
+
+    let submit = move |e: web_sys::SubmitEvent| {
+        e.prevent_default();
+        let credentials = Credentials {
+            username: username.get(),
+            password: password.get(),
+        };
+        let navigate = navigate.clone();
+        leptos::task::spawn_local(async move {
+            match submit_credentials(action, &credentials).await {
+                Ok(user) => {
+                    set_user.set(Some(user));
+                    navigate("/", Default::default());
+                }
+                Err(e) => set_status.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <main class="container p-4 flex flex-col items-center gap-4">
+            <h1 class="text-3xl">{title}</h1>
+            <p class="text-error" aria-live="polite">{status}</p>
+            <form on:submit=submit class="flex flex-col gap-2 w-full max-w-xs">
+                <label class="flex flex-col gap-1">
+                    username
+                    <input
+                        type="text"
+                        class="input w-full"
+                        autocomplete="username"
+                        required
+                        bind:value=(username, set_username)
+                    />
+                </label>
+                <label class="flex flex-col gap-1">
+                    password
+                    <input
+                        type="password"
+                        class="input w-full"
+                        autocomplete=password_autocomplete
+                        required
+                        bind:value=(password, set_password)
+                    />
+                </label>
+                <button type="submit" class="btn btn-primary">
+                    {title}
+                </button>
+            </form>
+        </main>
+    }
+}
+
+async fn submit_credentials(
+    action: CredentialsAction,
+    credentials: &Credentials,
+) -> Result<User, String> {
+    let client = api_client::Client::default();
+    let user = match action {
+        CredentialsAction::LogIn => client.log_in(credentials).await,
+        CredentialsAction::Register => client.register(credentials).await,
+    };
+    user.map_err(error_message)
+}
+
+async fn fetch_current_user() -> Result<Option<User>, String> {
+    api_client::Client::default()
+        .current_user()
+        .await
+        .map_err(error_message)
+}
+
+async fn save_preferences(preferences: &Preferences) -> Result<(), String> {
+    api_client::Client::default()
+        .save_preferences(preferences)
+        .await
+        .map_err(error_message)
+}
+
+async fn log_out() -> Result<(), String> {
+    api_client::Client::default()
+        .log_out()
+        .await
+        .map_err(error_message)
+}
+
+/// The message of an error response, falling back to its status.
+pub(crate) fn error_message(error: api_client::ApiError) -> String {
+    match error {
+        api_client::ApiError::Status {
+            message: Some(message),
+            ..
+        } => message,
+        api_client::ApiError::Status {
+            status,
+            message: None,
+        } => format!("Request failed ({})", status),
+        error => error.to_string(),
+    }
+}
@@ -0,0 +1,286 @@
+//! Resilient GETs of the server's JSON API, sent with [`api_client`].
+//!
+//! GETs are idempotent, so failed ones are retried with exponential backoff and
+//! jitter. Requests that still fail count towards a circuit breaker. Once it
+//! opens, requests aren't sent until it cools down and the last response seen
+//! for the same url is served instead. The app is reported as degraded while the
+//! breaker is open.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use leptos::prelude::*;
+use serde::de::DeserializeOwned;
+
+pub(crate) use api_client::ApiError;
+
+/// Tries per GET, including the first.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled for each retry after it.
+const BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// Failed requests in a row that open the breaker.
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// How long the breaker stays open before letting a request through to see if
+/// the server has recovered.
+const COOLDOWN_MS: f64 = 30_000.0;
+
+thread_local! {
+    static BREAKER: RefCell<CircuitBreaker> = RefCell::new(CircuitBreaker::default());
+    /// Last successful response body by request url.
+    static RESPONSES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    static DEGRADED: ArcRwSignal<bool> = ArcRwSignal::new(false);
+}
+
+/// Whether the server is failing and responses may be stale.
+pub(crate) fn degraded() -> ArcRwSignal<bool> {
+    DEGRADED.with(Clone::clone)
+}
+
+/// WebSocket URL of `path` on the host the app was served from.
+pub(crate) fn websocket_url(path: &str) -> String {
+    let location = web_sys::window().map(|window| window.location());
+    let host = location
+        .as_ref()
+        .and_then(|location| location.host().ok())
+        .unwrap_or_default();
+    let scheme = match location.and_then(|location| location.protocol().ok()) {
+        Some(protocol) if protocol == "https:" => "wss",
+        _ => "ws",
+    };
+    format!("{}://{}{}", scheme, host, path)
+}
+
+/// GET `url` with `query` and parse the JSON response.
+pub(crate) async fn get_json<T>(url: &str, query: &[(&str, &str)]) -> Result<T, ApiError>
+where
+    T: DeserializeOwned,
+{
+    let key = cache_key(url, query);
+    let now = js_sys::Date::now();
+    if !BREAKER.with_borrow(|breaker| breaker.allows(now)) {
+        return parse(&cached(&key).ok_or(ApiError::Unavailable)?);
+    }
+
+    let result = get_with_retries(url, query).await;
+    record(|breaker| breaker.record_result(&result, js_sys::Date::now()));
+    let body = match result {
+        Ok(body) => body,
+        Err(e) if is_retryable(&e) => return parse(&cached(&key).ok_or(e)?),
+        Err(e) => return Err(e),
+    };
+
+    let parsed = parse(&body)?;
+    RESPONSES.with_borrow_mut(|responses| responses.insert(key, body));
+    Ok(parsed)
+}
+
+async fn get_with_retries(url: &str, query: &[(&str, &str)]) -> Result<String, ApiError> {
+    let mut attempt = 0;
+    loop {
+        match get_once(url, query).await {
+            Err(e) if is_retryable(&e) && attempt + 1 < MAX_ATTEMPTS => {
+                sleep(backoff(attempt, js_sys::Math::random())).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn get_once(url: &str, query: &[(&str, &str)]) -> Result<String, ApiError> {
+    api_client::Client::default().get_text(url, query).await
+}
+
+/// Network errors, server errors and rate limiting may clear up on their own.
+/// Until they do, the server is taken to be failing.
+fn is_retryable(error: &ApiError) -> bool {
+    match error {
+        ApiError::Network(_) => true,
+        ApiError::Status { status, .. } => *status >= 500 || *status == 429,
+        ApiError::Decode(_) | ApiError::Unavailable => false,
+    }
+}
+
+/// Delay before retry number `attempt + 1`: half of the exponential delay plus
+/// a random share of the other half, so clients that failed together don't
+/// retry together. `random` is in `[0, 1)`.
+fn backoff(attempt: u32, random: f64) -> Duration {
+    let delay = BASE_DELAY * 2u32.pow(attempt);
+    delay / 2 + delay.mul_f64(random.clamp(0.0, 1.0) / 2.0)
+}
+
+fn record(update: impl FnOnce(&mut CircuitBreaker)) {
+    let open = BREAKER.with_borrow_mut(|breaker| {
+        update(breaker);
+        breaker.is_open()
+    });
+    let degraded = degraded();
+    if degraded.get_untracked() != open {
+        degraded.set(open);
+    }
+}
+
+/// Parse a JSON response body, fresh or cached.
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|e| ApiError::Decode(e.to_string()))
+}
+
+fn cached(key: &str) -> Option<String> {
+    RESPONSES.with_borrow(|responses| responses.get(key).cloned())
+}
+
+fn cache_key(url: &str, query: &[(&str, &str)]) -> String {
+    query.iter().fold(url.to_owned(), |key, (name, value)| {
+        format!("{}&{}={}", key, name, value)
+    })
+}
+
+async fn sleep(delay: Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let scheduled = web_sys::window().and_then(|window| {
+            window
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    &resolve,
+                    delay.as_millis() as i32,
+                )
+                .ok()
+        });
+        if scheduled.is_none() {
+            let _ = resolve.call0(&web_sys::wasm_bindgen::JsValue::NULL);
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// Tracks failed requests in a row. Open once there have been enough of them,
+/// and half open after the cooldown, letting a request through whose outcome
+/// closes or reopens it.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CircuitBreaker {
+    failures: u32,
+    open_until_ms: Option<f64>,
+}
+
+impl CircuitBreaker {
+    fn allows(&self, now_ms: f64) -> bool {
+        self.open_until_ms.is_none_or(|until| now_ms >= until)
+    }
+
+    fn is_open(&self) -> bool {
+        self.open_until_ms.is_some()
+    }
+
+    fn record_success(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Count the outcome of a request. The server is up if it answered, even
+    /// if it refused the request, unless it answered that it's failing or
+    /// overloaded.
+    fn record_result<T>(&mut self, result: &Result<T, ApiError>, now_ms: f64) {
+        match result {
+            Err(e) if is_retryable(e) => self.record_failure(now_ms),
+            _ => self.record_success(),
+        }
+    }
+
+    fn record_failure(&mut self, now_ms: f64) {
+        self.failures += 1;
+        if self.failures >= FAILURE_THRESHOLD {
+            self.open_until_ms = Some(now_ms + COOLDOWN_MS);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn status(status: u16) -> ApiError {
+        ApiError::Status {
+            status,
+            message: None,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn backs_off_exponentially_with_jitter() {
+        assert_eq!(Duration::from_millis(125), backoff(0, 0.0));
+        assert_eq!(Duration::from_millis(250), backoff(0, 1.0));
+        assert_eq!(Duration::from_millis(250), backoff(1, 0.0));
+        assert_eq!(Duration::from_millis(750), backoff(2, 0.5));
+    }
+
+    #[wasm_bindgen_test]
+    fn opens_after_repeated_failures() {
+        let mut breaker = CircuitBreaker::default();
+        breaker.record_failure(0.0);
+        breaker.record_failure(1.0);
+        assert!(!breaker.is_open());
+        assert!(breaker.allows(2.0));
+
+        breaker.record_failure(2.0);
+        assert!(breaker.is_open());
+        assert!(!breaker.allows(3.0));
+        assert!(breaker.allows(2.0 + COOLDOWN_MS));
+
+        // A failed trial after the cooldown reopens it straight away.
+        breaker.record_failure(2.0 + COOLDOWN_MS);
+        assert!(!breaker.allows(3.0 + COOLDOWN_MS));
+
+        breaker.record_success();
+        assert!(!breaker.is_open());
+        assert!(breaker.allows(3.0 + COOLDOWN_MS));
+    }
+
+    #[wasm_bindgen_test]
+    fn opens_after_repeated_server_errors() {
+        let mut breaker = CircuitBreaker::default();
+        for now in [0.0, 1.0, 2.0] {
+            breaker.record_result::<()>(&Err(status(503)), now);
+        }
+        assert!(breaker.is_open());
+
+        let mut breaker = CircuitBreaker::default();
+        breaker.record_result::<()>(&Err(status(429)), 0.0);
+        breaker.record_result::<()>(&Err(ApiError::Network("offline".into())), 1.0);
+        breaker.record_result::<()>(&Err(status(500)), 2.0);
+        assert!(breaker.is_open());
+
+        // Refused requests show the server is up.
+        let mut breaker = CircuitBreaker::default();
+        breaker.record_result::<()>(&Err(status(503)), 0.0);
+        breaker.record_result::<()>(&Err(status(503)), 1.0);
+        breaker.record_result::<()>(&Err(status(404)), 2.0);
+        breaker.record_result::<()>(&Err(status(503)), 3.0);
+        assert!(!breaker.is_open());
+        breaker.record_result(&Ok(()), 4.0);
+        assert_eq!(CircuitBreaker::default(), breaker);
+    }
+
+    #[wasm_bindgen_test]
+    fn retries_transient_errors_only() {
+        assert!(is_retryable(&ApiError::Network("offline".into())));
+        assert!(is_retryable(&status(503)));
+        assert!(is_retryable(&status(429)));
+        assert!(!is_retryable(&status(404)));
+        assert!(!is_retryable(&ApiError::Decode("eof".into())));
+    }
+
+    #[wasm_bindgen_test]
+    fn parses_cached_responses() {
+        let key = cache_key("/api/test", &[("q", "cached")]);
+        RESPONSES.with_borrow_mut(|responses| responses.insert(key.clone(), "[1, 2]".to_owned()));
+        assert_eq!(
+            vec![1, 2],
+            parse::<Vec<u32>>(&cached(&key).unwrap()).unwrap()
+        );
+        assert!(matches!(parse::<Vec<u32>>("{}"), Err(ApiError::Decode(_))));
+    }
+}
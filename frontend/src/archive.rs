@@ -0,0 +1,37 @@
+use leptos::prelude::*;
+use leptos_router::{hooks::use_params, params::Params};
+
+use crate::game::{Puzzle, fetch_archived_config};
+use crate::settings::use_settings;
+
+#[derive(Debug, PartialEq, Params, Clone)]
+struct ArchiveParams {
+    date: Option<String>,
+}
+
+/// Play the puzzle from a previous day. Progress is stored separately from the daily puzzle.
+#[component]
+pub(crate) fn Archive() -> impl IntoView {
+    let date = use_params::<ArchiveParams>()
+        .get_untracked()
+        .ok()
+        .and_then(|p| p.date)
+        .unwrap_or_default();
+
+    let (settings, _) = use_settings();
+    let config = LocalResource::new({
+        let date = date.clone();
+        move || {
+            let date = date.clone();
+            let dictionary = settings.read().dictionary;
+            async move { fetch_archived_config(&date, dictionary).await }
+        }
+    });
+
+    view! {
+        <div class="container px-4 pt-4">
+            <h1 class="text-xl font-bold">{format!("Puzzle for {}", date)}</h1>
+        </div>
+        <Puzzle storage_key=format!("archive/{}", date) config notes_date=date.clone() />
+    }
+}
@@ -0,0 +1,449 @@
+use leptos::prelude::*;
+use leptos_router::{
+    components::A,
+    hooks::use_query,
+    params::{Params, ParamsError},
+};
+use puzzle_config::{Letter, PuzzleConfig, ScheduledPuzzle};
+
+#[component]
+pub fn PuzzleCalendar() -> impl IntoView {
+    let query = use_query::<CalendarQuery>();
+    let month = Memo::new(move |_| month_from_query(query.get()));
+    let schedule = LocalResource::new(move || fetch_schedule(month.get()));
+    let (selected, set_selected) = signal(None::<String>);
+    let on_change = Callback::new(move |_| schedule.refetch());
+
+    view! {
+        <main class="container p-4 flex flex-col gap-4">
+            <div class="flex flex-row justify-between items-center">
+                <A href=move || format!("/manage/puzzles?month={}", month.get().prev())>
+                    prev
+                </A>
+                <h1 class="text-2xl font-bold">{move || month.get().to_string()}</h1>
+                <A href=move || format!("/manage/puzzles?month={}", month.get().next())>
+                    next
+                </A>
+            </div>
+            <Suspense fallback=|| "Loading...">
+                {move || Suspend::new(async move {
+                    let schedule = schedule.await.unwrap_or_default();
+                    view! { <MonthGrid month=month.get() schedule selected set_selected /> }
+                })}
+            </Suspense>
+            {move || {
+                selected
+                    .get()
+                    .map(|date| {
+                        let scheduled = schedule
+                            .get()
+                            .flatten()
+                            .and_then(|s| s.into_iter().find(|p| p.date == date));
+                        view! { <DayActions date scheduled on_change /> }
+                    })
+            }}
+        </main>
+    }
+}
+
+#[derive(Debug, PartialEq, Params, Clone)]
+struct CalendarQuery {
+    month: Option<String>,
+}
+
+#[component]
+fn MonthGrid(
+    month: Month,
+    schedule: Vec<ScheduledPuzzle>,
+    selected: ReadSignal<Option<String>>,
+    set_selected: WriteSignal<Option<String>>,
+) -> impl IntoView {
+    let leading = (0..month.first_weekday())
+        .map(|_| view! { <div></div> })
+        .collect_view();
+    let days = (1..=month.days())
+        .map(|day| {
+            let date = month.date(day);
+            let scheduled = schedule.iter().find(|p| p.date == date).cloned();
+            let is_selected = {
+                let date = date.clone();
+                move || selected.read().as_ref() == Some(&date)
+            };
+            let letters = scheduled.as_ref().map(scheduled_letters);
+            let event = scheduled.and_then(|p| p.event);
+            let label = format!("puzzle for {}", date);
+
+            view! {
+                <button
+                    type="button"
+                    class="btn btn-soft h-24 flex flex-col items-start justify-start"
+                    class:btn-primary=is_selected
+                    aria-label=label
+                    on:click=move |_| set_selected.set(Some(date.clone()))
+                >
+                    <span class="font-bold">{day}</span>
+                    <span class="uppercase text-xs">{letters}</span>
+                    {event.map(|event| view! { <span class="badge badge-accent">{event}</span> })}
+                </button>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class="grid grid-cols-7 gap-1" role="grid" aria-label="puzzle calendar">
+            {WEEKDAYS
+                .iter()
+                .map(|day| view! { <div class="font-bold text-center">{*day}</div> })
+                .collect_view()}
+            {leading}
+            {days}
+        </div>
+    }
+}
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+#[component]
+fn DayActions(
+    date: String,
+    scheduled: Option<ScheduledPuzzle>,
+    on_change: Callback<()>,
+) -> impl IntoView {
+    let (required, set_required) = signal(
+        scheduled
+            .as_ref()
+            .and_then(|p| p.required_letter.as_ref())
+            .map(|l| l.0.to_string())
+            .unwrap_or_default(),
+    );
+    let (others, set_others) = signal(
+        scheduled
+            .as_ref()
+            .map(|p| p.other_letters.iter().map(|l| l.0).collect::<String>())
+            .unwrap_or_default(),
+    );
+    let (event, set_event) = signal(
+        scheduled
+            .as_ref()
+            .and_then(|p| p.event.clone())
+            .unwrap_or_default(),
+    );
+    let (status, set_status) = signal(None::<String>);
+    let (previewing, set_previewing) = signal(false);
+
+    let preview = LocalResource::new({
+        let date = date.clone();
+        move || {
+            let date = date.clone();
+            let previewing = previewing.get();
+            async move {
+                if previewing {
+                    Some(
+                        crate::game::fetch_archived_config(&date, accounts::Dictionary::Permissive)
+                            .await
+                            .map_err(|e| e.to_string()),
+                    )
+                } else {
+                    None
+                }
+            }
+        }
+    });
+
+    let save = {
+        let date = date.clone();
+        move |e: web_sys::SubmitEvent| {
+            e.prevent_default();
+            let required_letter = required
+                .get()
+                .to_lowercase()
+                .chars()
+                .next()
+                .map(Letter::new);
+            let other_letters = others
+                .get()
+                .to_lowercase()
+                .chars()
+                .map(Letter::new)
+                .collect::<Vec<_>>();
+            let overrides_letters = required_letter.is_some() || !other_letters.is_empty();
+            if overrides_letters && (required_letter.is_none() || other_letters.len() != 6) {
+                set_status.set(Some(
+                    "Override letters need a required letter and six other letters".into(),
+                ));
+                return;
+            }
+            let event = Some(event.get()).filter(|e| !e.trim().is_empty());
+            let puzzle = ScheduledPuzzle {
+                date: date.clone(),
+                required_letter,
+                other_letters,
+                event,
+            };
+            leptos::task::spawn_local(async move {
+                set_status.set(Some(match save_scheduled(&puzzle).await {
+                    Ok(()) => "Saved".into(),
+                    Err(e) => e,
+                }));
+                on_change.run(());
+            });
+        }
+    };
+
+    let regenerate = {
+        let date = date.clone();
+        move |_| {
+            let date = date.clone();
+            leptos::task::spawn_local(async move {
+                set_status.set(Some(match regenerate_puzzle(&date).await {
+                    Ok(()) => "Regenerated".into(),
+                    Err(e) => e,
+                }));
+                preview.refetch();
+                on_change.run(());
+            });
+        }
+    };
+
+    view! {
+        <section class="card card-border" aria-label="puzzle actions">
+            <div class="card-body">
+                <h2 class="card-title">{date}</h2>
+                <p aria-live="polite">{status}</p>
+                <form on:submit=save class="flex flex-row flex-wrap gap-2 items-end">
+                    <label class="flex flex-col">
+                        required
+                        <input
+                            type="text"
+                            class="input w-16 uppercase"
+                            maxlength=1
+                            bind:value=(required, set_required)
+                        />
+                    </label>
+                    <label class="flex flex-col">
+                        other letters
+                        <input
+                            type="text"
+                            class="input w-32 uppercase"
+                            maxlength=6
+                            bind:value=(others, set_others)
+                        />
+                    </label>
+                    <label class="flex flex-col">
+                        event
+                        <input
+                            type="text"
+                            class="input"
+                            placeholder="e.g. Spelling Bee Day"
+                            bind:value=(event, set_event)
+                        />
+                    </label>
+                    <button type="submit" class="btn btn-primary">
+                        save
+                    </button>
+                </form>
+                <div class="card-actions">
+                    <button
+                        type="button"
+                        class="btn btn-outline"
+                        on:click=move |_| set_previewing.update(|p| *p = !*p)
+                    >
+                        {move || if previewing.get() { "hide preview" } else { "preview" }}
+                    </button>
+                    <button type="button" class="btn btn-warning btn-outline" on:click=regenerate>
+                        regenerate
+                    </button>
+                </div>
+                <Suspense fallback=|| "Loading...">
+                    {move || Suspend::new(async move {
+                        preview
+                            .await
+                            .map(|config| match config {
+                                Ok(config) => {
+                                    leptos::either::Either::Left(view! { <PuzzleStats config /> })
+                                }
+                                Err(e) => leptos::either::Either::Right(view! { <p>{e}</p> }),
+                            })
+                    })}
+                </Suspense>
+            </div>
+        </section>
+    }
+}
+
+#[component]
+fn PuzzleStats(config: PuzzleConfig) -> impl IntoView {
+    let pangrams = config.pangram_count();
+    let perfect_pangrams = config.perfect_pangram_count();
+    let max_score = config.scoring.max_score(&config.valid_words);
+    let mut words = config
+        .valid_words
+        .iter()
+        .map(|w| w.word.clone())
+        .collect::<Vec<_>>();
+    words.sort();
+    let letters = std::iter::once(&config.required_letter)
+        .chain(config.other_letters.iter())
+        .map(|l| l.0)
+        .collect::<String>();
+
+    view! {
+        <div class="flex flex-col gap-2">
+            <p class="uppercase font-bold">{letters}</p>
+            <dl class="grid grid-cols-2">
+                <dt>words</dt>
+                <dd>{words.len()}</dd>
+                <dt>pangrams</dt>
+                <dd>{pangrams}</dd>
+                <dt>perfect pangrams</dt>
+                <dd>{perfect_pangrams}</dd>
+                <dt>max score</dt>
+                <dd>{max_score}</dd>
+                <dt>genius</dt>
+                <dd>{config.score_buckets[8].1}</dd>
+            </dl>
+            <details>
+                <summary>word list</summary>
+                <ul class="columns-3">
+                    {words.into_iter().map(|w| view! { <li>{w}</li> }).collect_view()}
+                </ul>
+            </details>
+        </div>
+    }
+}
+
+fn scheduled_letters(puzzle: &ScheduledPuzzle) -> String {
+    puzzle
+        .required_letter
+        .iter()
+        .chain(puzzle.other_letters.iter())
+        .map(|l| l.0)
+        .collect()
+}
+
+/// A calendar month, with `month` counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Month {
+    year: i32,
+    month: u32,
+}
+
+impl Month {
+    fn current() -> Self {
+        let now = js_sys::Date::new_0();
+        Self {
+            year: now.get_full_year() as i32,
+            month: now.get_month() + 1,
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        let (year, month) = s.split_once('-')?;
+        let year = year.parse().ok()?;
+        let month = month.parse().ok()?;
+        (1..=12).contains(&month).then_some(Self { year, month })
+    }
+
+    fn prev(&self) -> Self {
+        if self.month == 1 {
+            Self {
+                year: self.year - 1,
+                month: 12,
+            }
+        } else {
+            Self {
+                year: self.year,
+                month: self.month - 1,
+            }
+        }
+    }
+
+    fn next(&self) -> Self {
+        if self.month == 12 {
+            Self {
+                year: self.year + 1,
+                month: 1,
+            }
+        } else {
+            Self {
+                year: self.year,
+                month: self.month + 1,
+            }
+        }
+    }
+
+    fn days(&self) -> u32 {
+        match self.month {
+            2 if self.is_leap_year() => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    fn is_leap_year(&self) -> bool {
+        (self.year % 4 == 0 && self.year % 100 != 0) || self.year % 400 == 0
+    }
+
+    /// Day of the week the month starts on, with Sunday as 0.
+    fn first_weekday(&self) -> u32 {
+        // Sakamoto's method
+        const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let year = if self.month < 3 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let day = year + year / 4 - year / 100 + year / 400 + OFFSETS[self.month as usize - 1] + 1;
+        day.rem_euclid(7) as u32
+    }
+
+    fn date(&self, day: u32) -> String {
+        format!("{}-{:02}", self, day)
+    }
+}
+
+impl std::fmt::Display for Month {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+fn month_from_query(query: Result<CalendarQuery, ParamsError>) -> Month {
+    query
+        .ok()
+        .and_then(|q| q.month)
+        .and_then(|m| Month::parse(&m))
+        .unwrap_or_else(Month::current)
+}
+
+async fn fetch_schedule(month: Month) -> Option<Vec<ScheduledPuzzle>> {
+    let schedule = api_client::Client::default()
+        .schedule(&month.date(1), &month.date(month.days()))
+        .await
+        .ok()?;
+
+    Some(schedule.puzzles)
+}
+
+async fn save_scheduled(puzzle: &ScheduledPuzzle) -> Result<(), String> {
+    api_client::Client::default()
+        .schedule_puzzle(puzzle)
+        .await
+        .map_err(|e| failure("save", e))
+}
+
+async fn regenerate_puzzle(date: &str) -> Result<(), String> {
+    api_client::Client::default()
+        .regenerate_puzzle(date)
+        .await
+        .map_err(|e| failure("regenerate", e))
+}
+
+/// Message for a failure to `action` a puzzle.
+fn failure(action: &str, error: api_client::ApiError) -> String {
+    match error.status() {
+        Some(status) => format!("Failed to {} ({})", action, status),
+        None => format!("Failed to {}: {}", action, error),
+    }
+}
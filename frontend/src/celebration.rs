@@ -0,0 +1,168 @@
+//! Celebrations for reaching Genius and for finding every word of a puzzle,
+//! which makes the player Queen Bee.
+
+use leptos::prelude::*;
+
+pub(crate) const GENIUS_ID: &str = "genius";
+pub(crate) const QUEEN_BEE_ID: &str = "queen-bee";
+
+/// Number of confetti pieces falling over the Queen Bee screen.
+const CONFETTI: usize = 40;
+
+const CONFETTI_COLORS: [&str; 4] = ["warning", "info", "accent", "secondary"];
+
+/// A milestone a submission took the player past.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Milestone {
+    Genius,
+    QueenBee,
+}
+
+/// The milestone reached by a submission taking the score from `before` to
+/// `after` points, after which `found` of the puzzle's `total` words are found.
+pub(crate) fn milestone(
+    genius: u32,
+    before: u32,
+    after: u32,
+    found: usize,
+    total: usize,
+) -> Option<Milestone> {
+    if total > 0 && found >= total {
+        Some(Milestone::QueenBee)
+    } else if before < genius && after >= genius {
+        Some(Milestone::Genius)
+    } else {
+        None
+    }
+}
+
+/// How a finished puzzle went, shown on the Queen Bee screen.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Completion {
+    /// Milliseconds from opening the puzzle to finding the last word, if it's
+    /// known when the puzzle was opened.
+    pub(crate) elapsed_ms: Option<f64>,
+    pub(crate) words: usize,
+    pub(crate) score: u32,
+    pub(crate) pangrams_found: usize,
+    pub(crate) pangrams: usize,
+}
+
+/// `ms` as hours and minutes, like `1h 5m`, or seconds under a minute.
+fn format_elapsed(ms: f64) -> String {
+    let seconds = (ms.max(0.0) / 1000.0) as u64;
+    let (hours, minutes) = (seconds / 3600, seconds / 60 % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{}s", seconds),
+        (0, _) => format!("{}m", minutes),
+        _ => format!("{}h {}m", hours, minutes),
+    }
+}
+
+#[component]
+pub(crate) fn GeniusModal() -> impl IntoView {
+    view! {
+        <dialog id=GENIUS_ID class="modal">
+            <section class="modal-box flex flex-col gap-4 text-center">
+                <h1 class="text-4xl font-bold">Genius!</h1>
+                <p>"You've reached the top rank. Find every word to become Queen Bee."</p>
+                <div class="modal-action justify-center">
+                    <form method="dialog">
+                        <button type="submit" class="btn btn-primary">
+                            keep going
+                        </button>
+                    </form>
+                </div>
+            </section>
+        </dialog>
+    }
+}
+
+#[component]
+pub(crate) fn QueenBeeModal(completion: ReadSignal<Option<Completion>>) -> impl IntoView {
+    let confetti = (0..CONFETTI)
+        .map(|i| {
+            // Spread the pieces across the screen and over time without
+            // bunching up neighbours.
+            let style = format!(
+                "left: {}%; animation-delay: {}ms; background-color: var(--color-{})",
+                i * 37 % 100,
+                i * 173 % 1500,
+                CONFETTI_COLORS[i % CONFETTI_COLORS.len()],
+            );
+            view! { <span class="confetti-piece" style=style></span> }
+        })
+        .collect_view();
+
+    view! {
+        <dialog id=QUEEN_BEE_ID class="modal">
+            <div class="confetti" aria-hidden="true">
+                {confetti}
+            </div>
+            <section class="modal-box flex flex-col gap-4 text-center">
+                <h1 class="text-4xl font-bold">Queen Bee!</h1>
+                <p>"You found every word."</p>
+                {move || {
+                    completion
+                        .get()
+                        .map(|completion| {
+                            view! {
+                                <dl class="grid grid-cols-2 gap-2 text-left">
+                                    {completion
+                                        .elapsed_ms
+                                        .map(|ms| {
+                                            view! {
+                                                <dt>time taken</dt>
+                                                <dd>{format_elapsed(ms)}</dd>
+                                            }
+                                        })}
+                                    <dt>words</dt>
+                                    <dd>{completion.words}</dd>
+                                    <dt>score</dt>
+                                    <dd>{completion.score}</dd>
+                                    <dt>pangrams found</dt>
+                                    <dd>
+                                        {format!(
+                                            "{} of {}",
+                                            completion.pangrams_found,
+                                            completion.pangrams,
+                                        )}
+                                    </dd>
+                                </dl>
+                            }
+                        })
+                }}
+                <div class="modal-action justify-center">
+                    <form method="dialog">
+                        <button type="submit" class="btn btn-primary">
+                            close
+                        </button>
+                    </form>
+                </div>
+            </section>
+        </dialog>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn celebrates_crossing_milestones() {
+        assert_eq!(Some(Milestone::Genius), milestone(70, 65, 72, 30, 40));
+        assert_eq!(None, milestone(70, 70, 75, 31, 40));
+        assert_eq!(None, milestone(70, 10, 15, 3, 40));
+        assert_eq!(Some(Milestone::QueenBee), milestone(70, 95, 100, 40, 40));
+        assert_eq!(None, milestone(0, 0, 0, 0, 0));
+    }
+
+    #[wasm_bindgen_test]
+    fn formats_time_taken() {
+        assert_eq!("42s", format_elapsed(42_500.0));
+        assert_eq!("12m", format_elapsed(12.0 * 60_000.0 + 3_000.0));
+        assert_eq!("1h 5m", format_elapsed(65.0 * 60_000.0));
+    }
+}
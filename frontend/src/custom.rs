@@ -0,0 +1,159 @@
+use leptos::prelude::*;
+use leptos_router::{hooks::use_params, params::Params};
+use puzzle_config::{CustomPuzzle, NewCustomPuzzle};
+
+use crate::game::{Puzzle, fetch_custom_config};
+use crate::settings::use_settings;
+use crate::toast::{ToastKind, use_toasts};
+
+#[derive(Debug, PartialEq, Params, Clone)]
+struct CustomParams {
+    id: Option<String>,
+}
+
+/// Pick the seven letters of a puzzle, one of them required, and get a link
+/// to share it with.
+#[component]
+pub(crate) fn CustomBuilder() -> impl IntoView {
+    let (letters, set_letters) = signal(String::new());
+    let (required, set_required) = signal(String::new());
+    let (status, set_status) = signal(None::<String>);
+    let (link, set_link) = signal(None::<String>);
+
+    let submit = move |e: web_sys::SubmitEvent| {
+        e.prevent_default();
+        let puzzle = NewCustomPuzzle {
+            letters: letters.get(),
+            required: required.get(),
+        };
+        leptos::task::spawn_local(async move {
+            match create_custom_puzzle(&puzzle).await {
+                Ok(created) => {
+                    set_status.set(None);
+                    set_link.set(Some(share_link(&created.id)));
+                }
+                Err(e) => {
+                    set_link.set(None);
+                    set_status.set(Some(e));
+                }
+            }
+        });
+    };
+
+    view! {
+        <main class="container p-4 flex flex-col items-center gap-4">
+            <h1 class="text-3xl">"Make a puzzle"</h1>
+            <p class="text-error" aria-live="polite">{status}</p>
+            <form on:submit=submit class="flex flex-col gap-2 w-full max-w-xs">
+                <label class="flex flex-col gap-1">
+                    "letters"
+                    <input
+                        type="text"
+                        class="input w-full uppercase"
+                        minlength="7"
+                        maxlength="7"
+                        pattern="[A-Za-z]{7}"
+                        required
+                        bind:value=(letters, set_letters)
+                    />
+                </label>
+                <label class="flex flex-col gap-1">
+                    "required letter"
+                    <input
+                        type="text"
+                        class="input w-full uppercase"
+                        maxlength="1"
+                        pattern="[A-Za-z]"
+                        required
+                        bind:value=(required, set_required)
+                    />
+                </label>
+                <button type="submit" class="btn btn-primary">
+                    "create"
+                </button>
+            </form>
+            {move || link.get().map(|link| view! { <ShareLink link /> })}
+        </main>
+    }
+}
+
+/// The link to a custom puzzle, with a button copying it.
+#[component]
+fn ShareLink(link: String) -> impl IntoView {
+    let toasts = use_toasts();
+    let copy = {
+        let link = link.clone();
+        move |_| {
+            let link = link.clone();
+            leptos::task::spawn_local(async move {
+                match crate::share::copy_to_clipboard(&link).await {
+                    Ok(()) => toasts.show(ToastKind::Success, "Link copied to the clipboard."),
+                    Err(e) => {
+                        leptos::logging::warn!("failed to copy link: {}", e);
+                        toasts.show(
+                            ToastKind::Warning,
+                            "Couldn't copy the link to the clipboard.",
+                        );
+                    }
+                }
+            });
+        }
+    };
+
+    view! {
+        <div class="flex flex-row items-center gap-2">
+            <a class="link" href=link.clone()>
+                {link.clone()}
+            </a>
+            <button type="button" class="btn btn-ghost btn-sm" on:click=copy>
+                "copy"
+            </button>
+        </div>
+    }
+}
+
+/// Play a puzzle a player made, from the ID in its link. Progress is stored
+/// separately from the daily puzzle.
+#[component]
+pub(crate) fn Custom() -> impl IntoView {
+    let id = use_params::<CustomParams>()
+        .get_untracked()
+        .ok()
+        .and_then(|p| p.id)
+        .unwrap_or_default();
+
+    let (settings, _) = use_settings();
+    let config = LocalResource::new({
+        let id = id.clone();
+        move || {
+            let id = id.clone();
+            let dictionary = settings.read().dictionary;
+            async move { fetch_custom_config(&id, dictionary).await }
+        }
+    });
+
+    view! {
+        <div class="container px-4 pt-4 flex flex-row justify-between items-center">
+            <h1 class="text-xl font-bold">"Custom puzzle"</h1>
+            <a class="link" href="/custom">
+                "make your own"
+            </a>
+        </div>
+        <Puzzle storage_key=format!("custom/{}", id) config />
+    }
+}
+
+/// Absolute URL of the custom puzzle `id`, to paste wherever it's shared.
+fn share_link(id: &str) -> String {
+    let origin = web_sys::window()
+        .and_then(|window| window.location().origin().ok())
+        .unwrap_or_default();
+    format!("{}/custom/{}", origin, id)
+}
+
+async fn create_custom_puzzle(puzzle: &NewCustomPuzzle) -> Result<CustomPuzzle, String> {
+    api_client::Client::default()
+        .create_custom_puzzle(puzzle)
+        .await
+        .map_err(crate::account::error_message)
+}
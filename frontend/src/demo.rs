@@ -0,0 +1,17 @@
+use leptos::prelude::*;
+
+use crate::game::{Puzzle, fetch_demo_config};
+
+/// Play the fixed demo puzzle, which the server can serve without a word list.
+#[component]
+pub(crate) fn Demo() -> impl IntoView {
+    let config = LocalResource::new(fetch_demo_config);
+
+    view! {
+        <div class="container px-4 pt-4">
+            <h1 class="text-xl font-bold">"Demo puzzle"</h1>
+            <p class="text-sm opacity-70">"Find words using the letters below. Every word needs the center letter."</p>
+        </div>
+        <Puzzle storage_key="demo".to_owned() config />
+    }
+}
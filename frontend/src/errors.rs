@@ -0,0 +1,127 @@
+use client_errors::ClientErrorReport;
+use leptos::prelude::*;
+use web_sys::wasm_bindgen::JsValue;
+
+const REPORT_URL: &str = "/api/client-errors";
+
+/// Install a panic hook that keeps the console output from `console_error_panic_hook`
+/// and, if the player has opted in, reports the panic to the server.
+pub(crate) fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic".to_owned());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        report(message, location);
+    }));
+}
+
+/// Report an error if the player has consented to error reporting.
+///
+/// Only the message, source location and current path are sent; query strings
+/// are dropped since they can contain guesses from deep links.
+pub(crate) fn report(message: String, location: Option<String>) {
+    if !crate::settings::load_settings().error_reporting {
+        return;
+    }
+
+    let report = ClientErrorReport {
+        message,
+        location,
+        route: current_path(),
+    };
+    if let Err(e) = send(&report) {
+        leptos::logging::warn!("Failed to report error: {:?}", e);
+    }
+}
+
+// A beacon is queued by the browser synchronously, so reports still go out when
+// they are sent from the panic hook right before the wasm instance aborts.
+fn send(report: &ClientErrorReport) -> Result<(), JsValue> {
+    let body = serde_json::to_string(report).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let parts = js_sys::Array::of1(&JsValue::from_str(&body));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("application/json");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("Window unavailable"))?;
+    window
+        .navigator()
+        .send_beacon_with_opt_blob(REPORT_URL, Some(&blob))
+        .map(|_| ())
+}
+
+fn current_path() -> Option<String> {
+    web_sys::window()?.location().pathname().ok()
+}
+
+/// Fallback for the app-wide error boundary: reports each error and shows a message.
+#[component]
+pub(crate) fn ErrorFallback(errors: ArcRwSignal<Errors>) -> impl IntoView {
+    let messages = errors.with_untracked(|errors| {
+        errors
+            .iter()
+            .map(|(_, e)| e.to_string())
+            .collect::<Vec<_>>()
+    });
+    for message in &messages {
+        report(message.clone(), None);
+    }
+
+    view! {
+        <div class="container p-4">
+            <h1>Oopsie!</h1>
+            <ul>{messages.into_iter().map(|m| view! { <li>{m}</li> }).collect_view()}</ul>
+        </div>
+    }
+}
+
+#[component]
+pub(crate) fn ClientErrors() -> impl IntoView {
+    let errors = LocalResource::new(fetch_errors);
+
+    view! {
+        <main class="container p-4">
+            <h1 class="text-2xl font-bold">Client errors</h1>
+            <Suspense fallback=|| "Loading...">
+                {move || Suspend::new(async move {
+                    let errors = errors.await.unwrap_or_default();
+                    view! {
+                        <table class="table">
+                            <thead>
+                                <tr>
+                                    <th scope="col">reported</th>
+                                    <th scope="col">route</th>
+                                    <th scope="col">location</th>
+                                    <th scope="col">message</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                <For each=move || errors.clone() key=|e| e.id let(error)>
+                                    <tr>
+                                        <td>{error.reported_at}</td>
+                                        <td>{error.route}</td>
+                                        <td>{error.location}</td>
+                                        <td class="whitespace-pre-wrap">{error.message}</td>
+                                    </tr>
+                                </For>
+                            </tbody>
+                        </table>
+                    }
+                })}
+            </Suspense>
+        </main>
+    }
+}
+
+async fn fetch_errors() -> Option<Vec<client_errors::ReportedError>> {
+    let reported = api_client::Client::default().client_errors().await.ok()?;
+
+    Some(reported.errors)
+}
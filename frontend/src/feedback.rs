@@ -0,0 +1,54 @@
+//! Players' reports of words the list is missing or shouldn't have, reviewed
+//! by curators in the management page.
+
+use leptos::prelude::*;
+use words_list::{WordReport, WordReportKind};
+
+use crate::toast::{ToastKind, use_toasts};
+
+/// Sends a report of `word` once, thanking the player for it.
+#[component]
+pub(crate) fn ReportWordButton(word: String, kind: WordReportKind) -> impl IntoView {
+    let toasts = use_toasts();
+    let (sent, set_sent) = signal(false);
+    let label = match kind {
+        WordReportKind::Missing => "report missing word",
+        WordReportKind::Invalid => "report invalid word",
+    };
+    let report = move |_| {
+        let report = WordReport {
+            word: word.clone(),
+            kind,
+        };
+        leptos::task::spawn_local(async move {
+            match send_report(&report).await {
+                Ok(()) => {
+                    set_sent.set(true);
+                    toasts.show(ToastKind::Success, "Thanks! A curator will take a look.");
+                }
+                Err(e) => toasts.show(ToastKind::Warning, e),
+            }
+        });
+    };
+
+    view! {
+        <button
+            type="button"
+            class="btn btn-ghost btn-xs"
+            on:click=report
+            disabled=move || sent.get()
+        >
+            {label}
+        </button>
+    }
+}
+
+async fn send_report(report: &WordReport) -> Result<(), String> {
+    api_client::Client::default()
+        .report_word(report)
+        .await
+        .map_err(|e| match e.status() {
+            Some(status) => format!("Failed to send the report ({})", status),
+            None => format!("Failed to send the report: {}", e),
+        })
+}
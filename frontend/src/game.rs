@@ -1,27 +1,94 @@
+use accounts::Dictionary;
 use leptos::prelude::*;
+use leptos_router::{hooks::use_query, params::Params};
 use rand::SeedableRng;
 
-use puzzle_config::{Letter, PuzzleConfig, ScoreBuckets, Word};
+use puzzle_config::{
+    Alphabet, Difficulty, Letter, OfflinePuzzleConfig, PuzzleConfig, ScoreBuckets, ScoringRules,
+    Word,
+};
 
-use std::collections::{BTreeSet, HashSet};
+use crate::account::AccountButton;
+use crate::celebration::{
+    Completion, GENIUS_ID, GeniusModal, Milestone, QUEEN_BEE_ID, QueenBeeModal, milestone,
+};
+use crate::feedback::ReportWordButton;
+use crate::hints::HintsButton;
+use crate::keymap::{self, Command};
+use crate::notes::PuzzleNotes;
+use crate::rules::{
+    Debounce, Paginator, ValidationError, check_word, current_rank, daydex, max_word_length,
+    prefill_word, push_letter,
+};
+use crate::settings::{SettingsButton, use_settings};
+use crate::share::ShareButton;
+use crate::stats::StatsButton;
+use crate::theme::ThemeToggle;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::time::Duration;
 
 #[component]
 pub(crate) fn Game() -> impl IntoView {
-    let storage_key = day_64().to_string();
+    let (settings, _) = use_settings();
+    let difficulty = Memo::new(move |_| settings.read().difficulty);
+    let config = LocalResource::new(move || load(settings.read().dictionary, difficulty.get()));
+    // Each difficulty is a puzzle of its own, with its own progress.
+    move || {
+        view! {
+            <Puzzle
+                storage_key=daily_key(day_64(), difficulty.get())
+                config
+                daily=true
+                notes_date=local_date()
+            />
+        }
+    }
+}
+
+/// Key the progress on the daily puzzle of `difficulty` for the day `day` is
+/// stored under. Medium puzzles keep the key daily puzzles always had.
+pub(crate) fn daily_key(day: u64, difficulty: Difficulty) -> String {
+    match difficulty {
+        Difficulty::Medium => day.to_string(),
+        difficulty => format!("{}/{}", day, difficulty.as_str()),
+    }
+}
 
+/// A playable puzzle with progress stored under `storage_key`.
+#[component]
+pub(crate) fn Puzzle(
+    storage_key: String,
+    config: LocalResource<Result<PuzzleConfig, AppError>>,
+    /// Whether this is today's puzzle, which offers hints and syncs progress
+    /// for logged in users.
+    #[prop(optional)]
+    daily: bool,
+    /// Date of the puzzle to keep notes on, if players can keep notes on it.
+    #[prop(optional, into)]
+    notes_date: Option<String>,
+) -> impl IntoView {
     let (score, set_score, _) = leptos_use::storage::use_local_storage::<
         u32,
         codee::string::JsonSerdeCodec,
     >(format!("{}/score", storage_key));
     provide_context((score, set_score));
     let (submitted, set_submitted, _) = leptos_use::storage::use_local_storage::<
-        Vec<_>,
+        Vec<String>,
         codee::string::JsonSerdeCodec,
     >(format!("{}/submitted", storage_key));
     provide_context((submitted, set_submitted));
+    let (started, set_started, _) = leptos_use::storage::use_local_storage::<
+        Option<f64>,
+        codee::string::JsonSerdeCodec,
+    >(format!("{}/started", storage_key));
+    if started.get_untracked().is_none() {
+        set_started.set(Some(js_sys::Date::now()));
+    }
 
-    let config = LocalResource::new(move || load());
+    let deep_link = use_query::<DeepLink>();
+    let (settings, _) = use_settings();
+    let notes_date = StoredValue::new(notes_date);
     view! {
         <Suspense
             fallback=move || view! { <p>"Loading ..."</p> }
@@ -30,33 +97,110 @@ pub(crate) fn Game() -> impl IntoView {
             match config.await {
                 Ok(PuzzleConfig {
                 score_buckets,
+                scoring,
+                alphabet,
                 required_letter,
                 other_letters,
                 valid_words,
-            }) =>
+            }) => {
+            // Played from the saved copy, only the words found so far are known.
+            let offline = daily
+                .then(|| {
+                    settings.with_untracked(|settings| {
+                        crate::storage::playing_offline(settings.dictionary, settings.difficulty)
+                    })
+                })
+                .flatten();
+            let mut valid_words = valid_words;
+            if let Some(offline) = &offline {
+                valid_words.extend(
+                    submitted.get_untracked().iter().filter_map(|w| offline.word(w)),
+                );
+            }
+            let initial_word = deep_link
+                .get_untracked()
+                .ok()
+                .and_then(|link| link.w)
+                .and_then(|w| prefill_word(&w, &alphabet, &required_letter, &other_letters));
+            if daily {
+                crate::progress::sync_progress(
+                    valid_words.clone(),
+                    scoring.clone(),
+                    score,
+                    set_score,
+                    submitted,
+                    set_submitted,
+                );
+            }
             leptos::either::Either::Left(view! {
             <div class="container p-4 h-full">
                 <div class="container flex flex-col w-full justify-between gap-1">
+                    <div class="self-end">
+                        <AccountButton />
+                        {daily.then(|| view! { <HintsButton /> })}
+                        {daily.then(|| {
+                            let words = valid_words
+                                .iter()
+                                .map(|w| (w.word.clone(), w.is_pangram))
+                                .collect::<Vec<_>>();
+                            view! { <ShareButton buckets=score_buckets.clone() words /> }
+                        })}
+                        <StatsButton valid_words=valid_words.clone() buckets=score_buckets.clone() />
+                        <ThemeToggle />
+                        <SettingsButton />
+                    </div>
                     <div class="self-start w-full">
-                        <Score score=score buckets=score_buckets />
+                        <Score score=score buckets=score_buckets.clone() />
                     </div>
 
-                    <GuessedWords submitted />
+                    <GuessedWords submitted definitions=definitions(&valid_words) />
                 </div>
 
                 <div class="divider divider-secondary"></div>
 
-                <Board
-                    required_letter=required_letter
-                    other_letters=other_letters
-                    valid_words=valid_words
-                />
+                {move || {
+                    if settings.read().screen_reader_mode {
+                        leptos::either::Either::Left(view! {
+                            <LinearBoard
+                                required_letter=required_letter.clone()
+                                other_letters=other_letters.clone()
+                                valid_words=valid_words.clone()
+                                offline=offline.clone()
+                                buckets=score_buckets.clone()
+                                scoring=scoring.clone()
+                                alphabet
+                                initial_word=initial_word.clone()
+                            />
+                        })
+                    } else {
+                        leptos::either::Either::Right(view! {
+                            <Board
+                                required_letter=required_letter.clone()
+                                other_letters=other_letters.clone()
+                                valid_words=valid_words.clone()
+                                offline=offline.clone()
+                                buckets=score_buckets.clone()
+                                scoring=scoring.clone()
+                                alphabet
+                                started
+                                initial_word=initial_word.clone()
+                            />
+                        })
+                    }
+                }}
+
+                {notes_date.get_value().map(|date| view! {
+                    <div class="divider divider-secondary"></div>
+                    <PuzzleNotes date />
+                })}
             </div>
-            }),
+            })
+            }
             Err(AppError::ConfigLoadError(e)) => leptos::either::Either::Right( view! {
                 <div>
                     <h1>Oopsie!</h1>
                     <p>{e}</p>
+                    <a class="link" href="/demo">"Try the demo puzzle while you wait"</a>
                     </div>
             })
         }
@@ -66,18 +210,45 @@ pub(crate) fn Game() -> impl IntoView {
     }
 }
 
+/// Query parameters that deep-link into the board, e.g. `/?w=honey`.
+#[derive(Debug, PartialEq, Params, Clone)]
+pub(crate) struct DeepLink {
+    w: Option<String>,
+}
+
 #[component]
 pub(crate) fn Board(
     required_letter: Letter,
     other_letters: Vec<Letter>,
     valid_words: HashSet<Word>,
+    /// The saved copy being played offline, which has the words not yet found.
+    #[prop(optional_no_strip)]
+    offline: Option<OfflinePuzzleConfig>,
+    buckets: ScoreBuckets,
+    scoring: ScoringRules,
+    alphabet: Alphabet,
+    /// When the player opened the puzzle, in milliseconds since the epoch.
+    started: Signal<Option<f64>>,
+    #[prop(optional_no_strip)] initial_word: Option<String>,
 ) -> impl IntoView {
+    let limit = WordLimit {
+        max_len: max_word_length(&valid_words)
+            .max(offline.as_ref().map_or(0, |o| o.max_word_length)),
+        hit: RwSignal::new(false),
+    };
+    provide_context(limit);
+    let total_words = offline
+        .as_ref()
+        .map_or(valid_words.len(), |o| o.word_count as usize);
+    let filter = StoredValue::new(offline.map(|o| o.words));
+    let genius = buckets[8].1;
+    let scoring = StoredValue::new(scoring);
     let (valid_words, _) = signal(valid_words);
     let (required_letter, _) = signal(required_letter);
     let (other_letters, set_other_letters) = signal(other_letters);
     let (_, rng) = signal(rand::rngs::SmallRng::seed_from_u64(day_64()));
 
-    let (word, set_word) = signal(String::new());
+    let (word, set_word) = signal(initial_word.unwrap_or_default());
     provide_context(set_word);
     Effect::watch(
         move || word.get(),
@@ -87,135 +258,445 @@ pub(crate) fn Board(
         false,
     );
 
-    let (_score, set_score) =
+    let (score, set_score) =
         use_context::<(Signal<u32>, WriteSignal<u32>)>().expect("No writable score provided");
     let (submitted, set_submitted) =
         use_context::<(Signal<Vec<String>>, WriteSignal<Vec<String>>)>()
             .expect("No writable submittion list provided");
-    let (set_error, error) = use_validation_errors();
-    let submit = move |e: web_sys::SubmitEvent| {
-        e.prevent_default();
-
-        let word = std::mem::take(&mut *set_word.write());
-        if word.len() < 4 {
-            set_error.set(Some(ValidationError::TooShort));
-            return;
-        }
-
-        if submitted.read().contains(&word) {
-            set_error.set(Some(ValidationError::AlreadyGuessed));
-            return;
+    let submissions = Submissions::new();
+    // Submissions may include words the player's dictionary has since dropped,
+    // so only the puzzle's own words count towards finding them all.
+    let found_words = Memo::new(move |_| {
+        let submitted = submitted.read();
+        valid_words
+            .read()
+            .iter()
+            .filter(|w| submitted.contains(&w.word))
+            .map(|w| w.is_pangram)
+            .collect::<Vec<_>>()
+    });
+    let (completion, set_completion) = signal(None);
+    // The last word rejected for not being in the list, which the player may
+    // think should be.
+    let (missing, set_missing) = signal(None::<String>);
+    let celebrate = move |before: u32, after: u32| {
+        let found = found_words.get_untracked();
+        match milestone(genius, before, after, found.len(), total_words) {
+            Some(Milestone::Genius) => keymap::show_modal(GENIUS_ID),
+            Some(Milestone::QueenBee) => {
+                set_completion.set(Some(Completion {
+                    elapsed_ms: started
+                        .get_untracked()
+                        .map(|started| js_sys::Date::now() - started),
+                    words: total_words,
+                    score: after,
+                    pangrams_found: found.iter().filter(|is_pangram| **is_pangram).count(),
+                    pangrams: valid_words
+                        .read_untracked()
+                        .iter()
+                        .filter(|w| w.is_pangram)
+                        .count(),
+                }));
+                keymap::show_modal(QUEEN_BEE_ID);
+            }
+            None => {}
         }
-
+    };
+    let submit_word = move || {
+        let word = std::mem::take(&mut *set_word.write());
         leptos::logging::log!("Checking {}", word);
-        if !word.contains(required_letter.read().0) {
-            set_error.set(Some(ValidationError::MissingRequiredLetter));
-            return;
-        }
-
-        if word.chars().any(|c| {
-            !(required_letter.read().0 == c || other_letters.read().contains(&Letter::new(c)))
-        }) {
-            set_error.set(Some(ValidationError::BadLetters));
-            return;
-        }
-
-        let mut candidate = Word::new(&word, false);
-        if !valid_words.read().contains(&candidate) {
-            set_error.set(Some(ValidationError::NotInList));
-            return;
+        let result = filter.with_value(|filter| {
+            check_word(
+                &word,
+                &alphabet,
+                &required_letter.read(),
+                &other_letters.read(),
+                &valid_words.read(),
+                filter.as_ref(),
+                &submitted.read(),
+            )
+        });
+        match result {
+            Err(e) => {
+                set_missing.set((e == ValidationError::NotInList).then_some(word));
+                submissions.emit(SubmissionResult::Rejected(e));
+            }
+            Ok(candidate) => {
+                set_missing.set(None);
+                let points = scoring.with_value(|scoring| candidate.score(scoring));
+                submissions.emit(SubmissionResult::Accepted {
+                    points,
+                    pangram: candidate.is_pangram,
+                });
+                let before = score.get_untracked();
+                *set_score.write() += points;
+                set_submitted.write().push(candidate.word);
+                celebrate(before, before + points);
+            }
         }
-
-        candidate.is_pangram = candidate.contains(&*required_letter.read())
-            && other_letters.read().iter().all(|l| candidate.contains(l));
-
-        *set_score.write() += candidate.score();
-        set_submitted.write().push(word);
+    };
+    let submit = move |e: web_sys::SubmitEvent| {
+        e.prevent_default();
+        submit_word();
     };
 
-    let shuffle_letters = move |_| {
+    let shuffle_letters = Callback::new(move |_| {
         use rand::seq::SliceRandom;
         let rng = &mut *rng.write();
         set_other_letters.write().shuffle(rng);
-    };
+    });
+    keymap::bind(Command::Submit, submit_word);
+    keymap::bind(Command::Delete, move || {
+        set_word.write().pop();
+    });
+    keymap::bind(Command::Shuffle, move || shuffle_letters.run(()));
+    keymap::bind_letters(move |letter| {
+        let in_puzzle = required_letter.read().0 == letter
+            || other_letters.read().iter().any(|l| l.0 == letter);
+        if !in_puzzle {
+            submissions.emit(SubmissionResult::Rejected(ValidationError::BadLetters));
+        } else if !push_letter(&mut set_word.write(), letter, limit.max_len) {
+            limit.flash();
+        }
+    });
 
-    view! {
-        <div id="board">
-            {error}
+    let (settings, _) = use_settings();
+    let layout = Memo::new(move |_| settings.read().layout);
+    let input = move || {
+        view! {
             <form id="word-form" on:submit=submit class="w-full h-auto">
                 <input
+                    id=keymap::WORD_INPUT_ID
                     type="text"
                     class="input input-ghost input-xl w-full text-center"
+                    class:input-error=move || limit.hit.get()
                     bind:value=(word, set_word)
                     aria-label="word"
                     minlength=4
+                    maxlength=limit.max_len
                 />
             </form>
-            <LetterGrid required_letter=required_letter other_letters=other_letters />
+        }
+    };
+    let grid = move || {
+        view! { <LetterGrid required_letter=required_letter other_letters=other_letters /> }
+    };
+    let buttons = move || {
+        view! { <ActionButtons mirrored=layout.get().left_handed on_shuffle=shuffle_letters /> }
+    };
+
+    view! {
+        <div id="board" class:thumb-reach=move || layout.get().thumb_reach>
+            <SubmissionFeedback submissions />
+            <MissingWordReport missing />
+            {move || {
+                // Thumb-reach keeps the word input next to the buttons at the bottom of
+                // the board so everything that is tapped sits in the lower half of the screen.
+                if layout.get().thumb_reach {
+                    leptos::either::Either::Left(view! { {grid()} {input()} {buttons()} })
+                } else {
+                    leptos::either::Either::Right(view! { {input()} {grid()} {buttons()} })
+                }
+            }}
+        </div>
+        <GeniusModal />
+        <QueenBeeModal completion />
+    }
+}
+
+/// Delete, shuffle and submit controls. `mirrored` swaps delete and submit for left-handed play.
+#[component]
+pub(crate) fn ActionButtons(mirrored: bool, on_shuffle: Callback<()>) -> impl IntoView {
+    let set_word = use_context::<WriteSignal<String>>().expect("No word context provided");
+    let (left, right) = if mirrored {
+        ("col-start-8", "col-start-2")
+    } else {
+        ("col-start-2", "col-start-8")
+    };
+
+    let delete = view! {
+        <button
+            type="button"
+            class=format!("btn btn-warning btn-outline join-item {} col-span-4", left)
+            on:click=move |_| {
+                set_word.write().pop();
+            }
+        >
+            delete
+        </button>
+    };
+    let shuffle = view! {
+        <button
+            type="button"
+            aria-label="shuffle letters"
+            class="btn btn-accent btn-outline col-span-2 join-item grid justify-items-center"
+            on:click=move |_| on_shuffle.run(())
+        >
+            <ShuffleIcon />
+        </button>
+    };
+    let submit = view! {
+        <button
+            type="submit"
+            form="word-form"
+            class=format!("btn btn-primary btn-outline join-item {} col-span-4", right)
+        >
+            submit
+        </button>
+    };
+
+    if mirrored {
+        leptos::either::Either::Left(view! {
             <div class="grid grid-cols-12 button-container join join-horizontal">
-                <button
-                    type="button"
-                    class="btn btn-warning btn-outline join-item col-start-2 col-span-4"
-                    on:click=move |_| {
-                        set_word.write().pop();
-                    }
-                >
-                    delete
-                </button>
-                <button
-                    type="button"
-                    aria-label="shuffle letters"
-                    class="btn btn-accent btn-outline col-span-2 join-item grid justify-items-center"
-                    on:click=shuffle_letters
-                >
-                    <ShuffleIcon />
-                </button>
-                <button
-                    type="submit"
-                    form="word-form"
-                    class="btn btn-primary btn-outline join-item col-start-8 col-span-4"
-                >
+                {submit} {shuffle} {delete}
+            </div>
+        })
+    } else {
+        leptos::either::Either::Right(view! {
+            <div class="grid grid-cols-12 button-container join join-horizontal">
+                {delete} {shuffle} {submit}
+            </div>
+        })
+    }
+}
+
+/// Linear, screen-reader-first alternative to [`Board`].
+///
+/// Letters are presented as a list, the text input is the only control, and
+/// every outcome is announced through a live region with a results summary
+/// landmark that stays in sync with the shared score and submission stores.
+#[component]
+pub(crate) fn LinearBoard(
+    required_letter: Letter,
+    other_letters: Vec<Letter>,
+    valid_words: HashSet<Word>,
+    /// The saved copy being played offline, which has the words not yet found.
+    #[prop(optional_no_strip)]
+    offline: Option<OfflinePuzzleConfig>,
+    buckets: ScoreBuckets,
+    scoring: ScoringRules,
+    alphabet: Alphabet,
+    #[prop(optional_no_strip)] initial_word: Option<String>,
+) -> impl IntoView {
+    let total_words = offline
+        .as_ref()
+        .map_or(valid_words.len(), |o| o.word_count as usize);
+    let max_len =
+        max_word_length(&valid_words).max(offline.as_ref().map_or(0, |o| o.max_word_length));
+    let filter = StoredValue::new(offline.map(|o| o.words));
+    let (valid_words, _) = signal(valid_words);
+    let (buckets, _) = signal(buckets);
+    let scoring = StoredValue::new(scoring);
+    let (word, set_word) = signal(initial_word.unwrap_or_default());
+    let (announcement, set_announcement) = signal(String::new());
+    let (missing, set_missing) = signal(None::<String>);
+
+    let (score, set_score) =
+        use_context::<(Signal<u32>, WriteSignal<u32>)>().expect("No writable score provided");
+    let (submitted, set_submitted) =
+        use_context::<(Signal<Vec<String>>, WriteSignal<Vec<String>>)>()
+            .expect("No writable submittion list provided");
+    let rank = Signal::derive(move || current_rank(&buckets.read(), score.get()));
+    let remaining = move || total_words.saturating_sub(submitted.read().len());
+
+    let required = format!("{} (required)", required_letter.0.to_uppercase());
+    let letters = std::iter::once(required)
+        .chain(other_letters.iter().map(|l| l.0.to_uppercase().to_string()))
+        .map(|letter| view! { <li>{letter}</li> })
+        .collect_view();
+
+    let submit = move |e: web_sys::SubmitEvent| {
+        e.prevent_default();
+
+        let word = std::mem::take(&mut *set_word.write());
+        let result = filter.with_value(|filter| {
+            check_word(
+                &word,
+                &alphabet,
+                &required_letter,
+                &other_letters,
+                &valid_words.read(),
+                filter.as_ref(),
+                &submitted.read(),
+            )
+        });
+        match result {
+            Err(e) => {
+                set_announcement.set(format!("{} not accepted: {}.", word, e.message()));
+                set_missing.set((e == ValidationError::NotInList).then_some(word));
+            }
+            Ok(candidate) => {
+                set_missing.set(None);
+                let previous_rank = rank.get_untracked();
+                let points = scoring.with_value(|scoring| candidate.score(scoring));
+                let new_score = score.get_untracked() + points;
+                let new_rank = current_rank(&buckets.read_untracked(), new_score);
+                *set_score.write() += points;
+                set_submitted.write().push(candidate.word.clone());
+
+                let mut message = format!(
+                    "{} accepted{}, {} points. Score {}. {} words remaining.",
+                    candidate.word,
+                    if candidate.is_perfect_pangram() {
+                        ", perfect pangram"
+                    } else if candidate.is_pangram {
+                        ", pangram"
+                    } else {
+                        ""
+                    },
+                    points,
+                    new_score,
+                    total_words.saturating_sub(submitted.read_untracked().len()),
+                );
+                if new_rank != previous_rank {
+                    message.push_str(&format!(" New rank: {}.", new_rank));
+                }
+                set_announcement.set(message);
+            }
+        }
+    };
+
+    view! {
+        <div id="linear-board" class="flex flex-col gap-4">
+            <section aria-labelledby="letters-heading">
+                <h2 id="letters-heading" class="font-bold">
+                    Letters
+                </h2>
+                <ul class="flex flex-row gap-4">{letters}</ul>
+            </section>
+            <form on:submit=submit class="flex flex-row gap-2">
+                <label for="linear-word" class="sr-only">
+                    word
+                </label>
+                <input
+                    id="linear-word"
+                    type="text"
+                    class="input input-xl w-full"
+                    autocomplete="off"
+                    autofocus
+                    maxlength=max_len
+                    bind:value=(word, set_word)
+                />
+                <button type="submit" class="btn btn-primary btn-xl">
                     submit
                 </button>
+            </form>
+            <div role="status" aria-live="polite" aria-atomic="true">
+                {announcement}
             </div>
+            <MissingWordReport missing />
+            <section role="region" aria-labelledby="results-heading">
+                <h2 id="results-heading" class="font-bold">
+                    Results
+                </h2>
+                <dl class="grid grid-cols-2">
+                    <dt>score</dt>
+                    <dd>{score}</dd>
+                    <dt>rank</dt>
+                    <dd>{rank}</dd>
+                    <dt>words found</dt>
+                    <dd>{move || format!("{} of {}", submitted.read().len(), total_words)}</dd>
+                    <dt>words remaining</dt>
+                    <dd>{remaining}</dd>
+                </dl>
+            </section>
         </div>
     }
 }
 
-pub(crate) fn use_validation_errors() -> (WriteSignal<Option<ValidationError>>, impl IntoView) {
-    let (error, set_error) = signal(None);
-    let message = move || {
-        error.read().as_ref().map(|error| match error {
-            ValidationError::BadLetters => "Bad letters",
-            ValidationError::TooShort => "Too short",
-            ValidationError::MissingRequiredLetter => "Missing center letter",
-            ValidationError::AlreadyGuessed => "Already found",
-            ValidationError::NotInList => "Not in word list",
-        })
-    };
+/// Offers to report the last word rejected for not being in the list.
+#[component]
+fn MissingWordReport(missing: ReadSignal<Option<String>>) -> impl IntoView {
+    view! {
+        <div class="flex justify-center">
+            {move || {
+                missing
+                    .get()
+                    .map(|word| {
+                        view! { <ReportWordButton word kind=words_list::WordReportKind::Missing /> }
+                    })
+            }}
+        </div>
+    }
+}
+
+/// Outcome of submitting a word, shown by [`SubmissionFeedback`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SubmissionResult {
+    Rejected(ValidationError),
+    Accepted { points: u32, pangram: bool },
+}
+
+/// The latest [`SubmissionResult`], numbered so that repeating a result
+/// replays its animation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Submissions(RwSignal<Option<(u64, SubmissionResult)>>);
+
+impl Submissions {
+    pub(crate) fn new() -> Self {
+        Self(RwSignal::new(None))
+    }
+
+    pub(crate) fn emit(&self, result: SubmissionResult) {
+        self.0
+            .update(|last| *last = Some((last.map_or(0, |(id, _)| id + 1), result)));
+    }
+}
+
+/// How long feedback on a submission stays on screen.
+const FEEDBACK_DURATION: Duration = Duration::from_millis(1000);
+
+/// Shakes the reason a word was rejected, or shows the points an accepted word
+/// scored rising off the board, with a flourish for pangrams.
+#[component]
+pub(crate) fn SubmissionFeedback(submissions: Submissions) -> impl IntoView {
+    let last = submissions.0;
     Effect::watch(
-        move || error.get(),
-        move |error, prev_error, _| {
-            if error.is_some() && prev_error.flatten().is_none() {
-                set_timeout(move || set_error.set(None), Duration::from_millis(1000))
+        move || last.get().map(|(id, _)| id),
+        move |id, _, _| {
+            if let Some(id) = *id {
+                set_timeout(
+                    move || {
+                        last.update(|last| {
+                            // A newer result keeps its own full duration.
+                            if last.is_some_and(|(last_id, _)| last_id == id) {
+                                *last = None;
+                            }
+                        })
+                    },
+                    FEEDBACK_DURATION,
+                );
             }
         },
         false,
     );
 
-    (
-        set_error,
-        view! {
-            <div
-                aria-live="polite"
-                class="alert alert-info text-2xl transition-opacity  duration-300"
-                class=("opacity-100", move || error.read().is_some())
-                class=("opacity-0", move || error.read().is_none())
-            >
-                {message}
-            </div>
-        },
-    )
+    view! {
+        <div aria-live="polite" class="relative flex justify-center">
+            {move || {
+                last.get()
+                    .map(|(_, result)| match result {
+                        SubmissionResult::Rejected(e) => leptos::either::EitherOf3::A(view! {
+                            <div class="alert alert-info text-2xl feedback-shake">{e.message()}</div>
+                        }),
+                        SubmissionResult::Accepted { points, pangram: false } => {
+                            leptos::either::EitherOf3::B(view! {
+                                <div class="text-2xl font-bold text-success feedback-rise">
+                                    {format!("+{}", points)}
+                                </div>
+                            })
+                        }
+                        SubmissionResult::Accepted { points, pangram: true } => {
+                            leptos::either::EitherOf3::C(view! {
+                                <div class="text-3xl font-extrabold text-warning feedback-pangram">
+                                    {format!("Pangram! +{}", points)}
+                                </div>
+                            })
+                        }
+                    })
+            }}
+        </div>
+    }
 }
 
 #[component]
@@ -265,25 +746,46 @@ pub(crate) const PAGE_SIZE: usize = 10;
 #[cfg(debug_assertions)]
 pub(crate) const PAGE_SIZE: usize = 1;
 
+/// Definitions of the puzzle's words that have one, by word.
+fn definitions(valid_words: &HashSet<Word>) -> HashMap<String, String> {
+    valid_words
+        .iter()
+        .filter_map(|w| Some((w.word.clone(), w.definition.clone()?)))
+        .collect()
+}
+
 #[component]
-pub(crate) fn GuessedWords(#[prop(into)] submitted: Signal<Vec<String>>) -> impl IntoView {
-    let (current_page, set_current_page) = signal(0);
-    let submitted_alphabetically =
-        Signal::derive(move || submitted.get().into_iter().collect::<BTreeSet<_>>());
-    let pages = move || {
-        submitted_alphabetically
+pub(crate) fn GuessedWords(
+    #[prop(into)] submitted: Signal<Vec<String>>,
+    /// Definitions shown as a tooltip on found words.
+    #[prop(optional)]
+    definitions: HashMap<String, String>,
+) -> impl IntoView {
+    let paginator = RwSignal::new(Paginator::new(PAGE_SIZE));
+    let alphabetical = Memo::new(move |_| {
+        submitted
+            .get()
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>()
+    });
+    // Words of a team's list are credited to whoever found them.
+    let contributors = use_context::<crate::team::Contributors>();
+    let page = Memo::new(move |_| {
+        paginator
             .read()
+            .page_of(&alphabetical.read())
             .iter()
-            .fold(vec![vec![]], |mut pages, word| {
-                let page = pages.last_mut().unwrap();
-                if page.len() >= PAGE_SIZE {
-                    pages.push(vec![word.clone()])
-                } else {
-                    page.push(word.clone());
-                }
-                pages
+            .map(|word| {
+                let contributor =
+                    contributors.and_then(|contributors| contributors.0.read().get(word).cloned());
+                (word.clone(), definitions.get(word).cloned(), contributor)
             })
-    };
+            .collect::<Vec<_>>()
+    });
+    let len = move || alphabetical.read().len();
+    keymap::bind(Command::Guessed, || keymap::show_modal("guessed"));
 
     let latest_words = move || {
         submitted
@@ -312,31 +814,68 @@ pub(crate) fn GuessedWords(#[prop(into)] submitted: Signal<Vec<String>>) -> impl
             <dialog id="guessed" class="modal">
                 <section class="modal-box">
                     <h1>Guessed words</h1>
-                    <ul>
-                        <For
-                            each=move || pages()[*current_page.read()].clone()
-                            key=|w| w.clone()
-                            let(word)
-                        >
-                            <li>{word}</li>
-                        </For>
-                    </ul>
+                    <Show
+                        when=move || { len() > 0 }
+                        fallback=|| view! { <p>"No words found yet."</p> }
+                    >
+                        <ul>
+                            <For
+                                each=move || page.get()
+                                key=|(word, _, contributor)| (word.clone(), contributor.clone())
+                                children=|(word, definition, contributor)| {
+                                    let report = view! {
+                                        {contributor.map(|name| view! {
+                                            <span class="badge badge-ghost badge-sm">{name}</span>
+                                        })}
+                                        <ReportWordButton
+                                            word=word.clone()
+                                            kind=words_list::WordReportKind::Invalid
+                                        />
+                                    };
+                                    match definition {
+                                        Some(definition) => leptos::either::Either::Left(view! {
+                                            <li>
+                                                <span
+                                                    class="tooltip tooltip-right underline decoration-dotted"
+                                                    tabindex="0"
+                                                    data-tip=definition
+                                                >
+                                                    {word}
+                                                </span>
+                                                {report}
+                                            </li>
+                                        }),
+                                        None => leptos::either::Either::Right(view! {
+                                            <li>{word} {report}</li>
+                                        }),
+                                    }
+                                }
+                            />
+                        </ul>
+                        <p class="text-sm">
+                            {move || {
+                                format!(
+                                    "Page {} of {}",
+                                    paginator.read().current(len()) + 1,
+                                    paginator.read().page_count(len()),
+                                )
+                            }}
+                        </p>
+                    </Show>
                     <div class="modal-action">
                         <button
                             type="button"
                             class="btn"
-                            on:click=move |_| *set_current_page.write() -= 1
-                            disabled=move || !(1..pages().len()).contains(&*current_page.read())
+                            on:click=move |_| paginator.update(|p| p.prev(len()))
+                            disabled=move || !paginator.read().has_prev(len())
                         >
                             prev
                         </button>
                         <button
                             type="button"
                             class="btn"
-                            on:click=move |_| *set_current_page.write() += 1
-                            disabled=move || {
-                                !(0..(pages().len() - 1)).contains(&*current_page.read())
-                            }
+                            on:click=move |_| paginator.update(|p| p.next(len()))
+                            disabled=move || !paginator.read().has_next(len())
                         >
                             next
                         </button>
@@ -356,15 +895,7 @@ pub(crate) fn GuessedWords(#[prop(into)] submitted: Signal<Vec<String>>) -> impl
 pub(crate) fn Score(score: Signal<u32>, buckets: ScoreBuckets) -> impl IntoView {
     let max = buckets[8].1;
     let (buckets, _) = signal(buckets);
-    let current_threshold = Signal::derive(move || {
-        buckets
-            .get()
-            .iter()
-            .rfind(|(_label, thresh)| score.get() >= *thresh)
-            .cloned()
-            .map(|(label, _score)| label)
-            .unwrap_or_else(|| buckets.get()[8].0.clone())
-    });
+    let current_threshold = Signal::derive(move || current_rank(&buckets.read(), score.get()));
 
     view! {
         <div>
@@ -479,9 +1010,44 @@ pub(crate) fn OtherLetter(letter: ReadSignal<Letter>) -> impl IntoView {
     })
 }
 
+/// Presses of the same hex within this many milliseconds count once.
+const HEX_DEBOUNCE_MS: f64 = 150.0;
+
+/// Longest word that can be entered on the [`Board`], which is the longest
+/// word in the puzzle.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WordLimit {
+    pub(crate) max_len: usize,
+    /// Set for a moment when a letter is dropped for going over the limit.
+    pub(crate) hit: RwSignal<bool>,
+}
+
+impl WordLimit {
+    fn flash(&self) {
+        let hit = self.hit;
+        hit.set(true);
+        set_timeout(move || hit.set(false), Duration::from_millis(400));
+    }
+}
+
 #[component]
 pub(crate) fn LetterHex(class: String, letter: ReadSignal<Letter>) -> impl IntoView {
     let add_letter = use_context::<WriteSignal<String>>().expect("No word context provided");
+    let limit = use_context::<WordLimit>().expect("No word limit provided");
+    let debounce = StoredValue::new(Debounce::new(HEX_DEBOUNCE_MS));
+    let press = move || {
+        let ready = debounce
+            .try_update_value(|d| d.ready(js_sys::Date::now()))
+            .unwrap_or_default();
+        if !ready {
+            return;
+        }
+        let letter = letter.read().0;
+        leptos::logging::log!("CLICKED LETTER {}", letter);
+        if !push_letter(&mut add_letter.write(), letter, limit.max_len) {
+            limit.flash();
+        }
+    };
 
     view! {
         <button
@@ -491,15 +1057,7 @@ pub(crate) fn LetterHex(class: String, letter: ReadSignal<Letter>) -> impl IntoV
             aria-label=move || format!("letter {}", letter.read().0)
             on:click:target=move |e| {
                 e.prevent_default();
-                leptos::logging::log!("CLICKED LETTER {}", letter.read().0);
-                add_letter.write().push(letter.read().0)
-            }
-            on:keyup:target=move |e| {
-                e.prevent_default();
-                if e.key() == "Enter" {
-                    leptos::logging::log!("CLICKED LETTER {}", letter.read().0);
-                    add_letter.write().push(letter.read().0)
-                }
+                press();
             }
         >
             <span>{move || letter.read().0}</span>
@@ -539,36 +1097,26 @@ pub(crate) fn LetterGrid(
 }
 
 pub(crate) fn day_64() -> u64 {
-    let datetime = js_sys::Date::new_0();
-    datetime.set_hours(0);
-    datetime.set_minutes(0);
-    datetime.set_seconds(0);
-    datetime.set_milliseconds(0);
-    leptos::logging::log!("datetime {:?}", datetime);
-    let daydex = datetime.get_time() as u64;
+    let daydex = daydex(&js_sys::Date::new_0());
     leptos::logging::log!("daydex {}", daydex);
     daydex
 }
 
-#[derive(Clone)]
-pub(crate) enum ValidationError {
-    MissingRequiredLetter,
-    TooShort,
-    BadLetters,
-    NotInList,
-    AlreadyGuessed,
-}
-
-pub(crate) async fn load() -> Result<PuzzleConfig, AppError> {
-    if let Some(config) = load_config_from_storage() {
-        return Ok(config);
-    }
-
-    let fetched = fetch_config().await?;
-    if let Err(e) = store_config(&fetched) {
-        leptos::logging::error!("{}", e);
+pub(crate) async fn load(
+    dictionary: Dictionary,
+    difficulty: Difficulty,
+) -> Result<PuzzleConfig, AppError> {
+    match fetch_config(dictionary, difficulty).await {
+        Ok(fetched) => {
+            if let Err(e) = crate::storage::store_config(&fetched, dictionary, difficulty) {
+                leptos::logging::error!("{}", e);
+            }
+            Ok(fetched)
+        }
+        // The saved copy doesn't have the words, so it's only played when the
+        // server can't be reached.
+        Err(e) => crate::storage::play_offline(dictionary, difficulty).ok_or(e),
     }
-    Ok(fetched)
 }
 
 #[derive(Debug, Clone)]
@@ -595,47 +1143,101 @@ impl From<web_sys::wasm_bindgen::JsValue> for AppError {
     }
 }
 
-pub(crate) fn store_config(config: &PuzzleConfig) -> Result<(), AppError> {
-    let storage = get_storage()?;
-    let data =
-        serde_json::to_string(config).map_err(|e| AppError::ConfigLoadError(e.to_string()))?;
-    storage.set(&config_key(), &data).map_err(AppError::from)
+pub(crate) async fn fetch_config(
+    dictionary: Dictionary,
+    difficulty: Difficulty,
+) -> Result<PuzzleConfig, AppError> {
+    let tz = get_current_tz()?;
+    crate::api::get_json(
+        "/api/puzzle/daily/config",
+        &[
+            ("tz", tz.as_str()),
+            ("dictionary", dictionary.as_str()),
+            ("difficulty", difficulty.as_str()),
+        ],
+    )
+    .await
+    .map_err(|e| AppError::ConfigLoadError(e.to_string()))
 }
 
-pub(crate) fn load_config_from_storage() -> Option<PuzzleConfig> {
-    let storage = get_storage().ok()?;
-    let data = storage.get(&config_key()).ok().flatten()?;
-
-    serde_json::from_str(&data).ok()
+pub(crate) async fn fetch_archived_config(
+    date: &str,
+    dictionary: Dictionary,
+) -> Result<PuzzleConfig, AppError> {
+    let tz = get_current_tz()?;
+    crate::api::get_json(
+        &format!("/api/puzzle/archive/{}", date),
+        &[("tz", tz.as_str()), ("dictionary", dictionary.as_str())],
+    )
+    .await
+    .map_err(|e| match e {
+        crate::api::ApiError::Status { status, .. } => {
+            AppError::ConfigLoadError(format!("no puzzle available for {} ({})", date, status))
+        }
+        e => AppError::ConfigLoadError(e.to_string()),
+    })
 }
 
-pub(crate) fn get_storage() -> Result<web_sys::Storage, AppError> {
-    let window =
-        web_sys::window().ok_or_else(|| AppError::ConfigLoadError("Window unavailable".into()))?;
-    window
-        .local_storage()
-        .map_err(AppError::from)?
-        .ok_or_else(|| AppError::ConfigLoadError("Local storage unavailable".into()))
+pub(crate) async fn fetch_random_config(
+    seed: u64,
+    dictionary: Dictionary,
+    difficulty: Difficulty,
+) -> Result<PuzzleConfig, AppError> {
+    let seed = seed.to_string();
+    crate::api::get_json(
+        "/api/puzzle/random/config",
+        &[
+            ("seed", seed.as_str()),
+            ("dictionary", dictionary.as_str()),
+            ("difficulty", difficulty.as_str()),
+        ],
+    )
+    .await
+    .map_err(|e| AppError::ConfigLoadError(e.to_string()))
 }
 
-pub(crate) fn config_key() -> String {
-    format!("puzzle-storage/{}", day_64())
+pub(crate) async fn fetch_custom_config(
+    id: &str,
+    dictionary: Dictionary,
+) -> Result<PuzzleConfig, AppError> {
+    crate::api::get_json(
+        &format!("/api/puzzle/custom/{}/config", id),
+        &[("dictionary", dictionary.as_str())],
+    )
+    .await
+    .map_err(|e| AppError::ConfigLoadError(e.to_string()))
 }
 
-pub(crate) async fn fetch_config() -> Result<PuzzleConfig, AppError> {
-    let tz = get_current_tz()?;
-    let resp = gloo_net::http::Request::get("/api/puzzle/daily/config")
-        .query([("tz", tz)])
-        .header("accept", "application/json")
-        .send()
+pub(crate) async fn fetch_demo_config() -> Result<PuzzleConfig, AppError> {
+    crate::api::get_json("/api/puzzle/demo/config", &[])
         .await
-        .map_err(|e| AppError::ConfigLoadError(e.to_string()))?;
+        .map_err(|e| AppError::ConfigLoadError(e.to_string()))
+}
 
-    let json = resp.json().await;
-    json.map_err(|e| AppError::ConfigLoadError(e.to_string()))
+/// Today's date in the player's timezone, formatted like `2024-01-31`.
+pub(crate) fn local_date() -> String {
+    let date = js_sys::Date::new_0();
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date()
+    )
 }
 
+/// The player's IANA timezone, such as `America/New_York`, so that the server
+/// can follow its daylight saving changes. Falls back to the current UTC offset
+/// when the browser doesn't report one.
 pub(crate) fn get_current_tz() -> Result<String, AppError> {
+    let options = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new())
+        .resolved_options();
+    if let Some(zone) = js_sys::Reflect::get(&options, &"timeZone".into())
+        .ok()
+        .and_then(|zone| zone.as_string())
+    {
+        return Ok(zone);
+    }
+
     let date = js_sys::Date::new_0();
     let minutes_to_utc = date.get_timezone_offset();
     let is_behind = minutes_to_utc.is_sign_positive();
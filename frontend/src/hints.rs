@@ -0,0 +1,140 @@
+use accounts::Dictionary;
+use leptos::prelude::*;
+
+use puzzle_config::{Difficulty, Hints};
+
+use crate::game::{AppError, get_current_tz};
+use crate::settings::use_settings;
+
+#[component]
+pub(crate) fn HintsButton() -> impl IntoView {
+    view! {
+        <button type="button" class="btn btn-ghost btn-sm" onclick="hints.showModal()">
+            hints
+        </button>
+        <HintsModal />
+    }
+}
+
+/// Hint grid for the daily puzzle: word counts by starting letter and length,
+/// two-letter prefix counts and the number of pangrams, perfect or not.
+#[component]
+pub(crate) fn HintsModal() -> impl IntoView {
+    let (settings, _) = use_settings();
+    let hints = LocalResource::new(move || {
+        fetch_daily_hints(settings.read().dictionary, settings.read().difficulty)
+    });
+    crate::keymap::bind(crate::keymap::Command::Hints, || {
+        crate::keymap::show_modal("hints")
+    });
+
+    view! {
+        <dialog id="hints" class="modal">
+            <section class="modal-box flex flex-col gap-4">
+                <h1 class="text-3xl">Hints</h1>
+                <Suspense fallback=move || view! { <p>"Loading ..."</p> }>
+                    {move || Suspend::new(async move {
+                        match hints.await {
+                            Ok(hints) => leptos::either::Either::Left(view! { <HintGrid hints /> }),
+                            Err(e) => leptos::either::Either::Right(view! {
+                                <p class="text-error">{e.to_string()}</p>
+                            }),
+                        }
+                    })}
+                </Suspense>
+                <div class="modal-action">
+                    <form method="dialog">
+                        <button type="submit" class="btn btn-primary">
+                            close
+                        </button>
+                    </form>
+                </div>
+            </section>
+        </dialog>
+    }
+}
+
+#[component]
+fn HintGrid(hints: Hints) -> impl IntoView {
+    let column_totals: Vec<u32> = (0..hints.lengths.len())
+        .map(|i| hints.grid.iter().map(|row| row.counts[i]).sum())
+        .collect();
+
+    view! {
+        <p>
+            {format!(
+                "Words: {}, Pangrams: {} ({} perfect)",
+                hints.word_count,
+                hints.pangram_count,
+                hints.perfect_pangram_count,
+            )}
+        </p>
+        <div class="overflow-x-auto">
+            <table class="table table-xs text-center">
+                <thead>
+                    <tr>
+                        <th></th>
+                        {hints.lengths.iter().map(|len| view! { <th>{*len}</th> }).collect_view()}
+                        <th>"Σ"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {hints
+                        .grid
+                        .into_iter()
+                        .map(|row| {
+                            let total: u32 = row.counts.iter().sum();
+                            view! {
+                                <tr>
+                                    <th class="uppercase">{row.letter.0.to_string()}</th>
+                                    {row
+                                        .counts
+                                        .into_iter()
+                                        .map(|count| {
+                                            view! {
+                                                <td>{if count == 0 { "-".to_owned() } else { count.to_string() }}</td>
+                                            }
+                                        })
+                                        .collect_view()}
+                                    <td class="font-bold">{total}</td>
+                                </tr>
+                            }
+                        })
+                        .collect_view()}
+                </tbody>
+                <tfoot>
+                    <tr>
+                        <th>"Σ"</th>
+                        {column_totals.into_iter().map(|total| view! { <th>{total}</th> }).collect_view()}
+                        <th>{hints.word_count}</th>
+                    </tr>
+                </tfoot>
+            </table>
+        </div>
+        <h2 class="text-xl">Two letter list</h2>
+        <ul class="flex flex-wrap gap-x-4 gap-y-1 uppercase">
+            {hints
+                .two_letter_prefixes
+                .into_iter()
+                .map(|(prefix, count)| view! { <li>{format!("{}-{}", prefix, count)}</li> })
+                .collect_view()}
+        </ul>
+    }
+}
+
+async fn fetch_daily_hints(
+    dictionary: Dictionary,
+    difficulty: Difficulty,
+) -> Result<Hints, AppError> {
+    let tz = get_current_tz()?;
+    crate::api::get_json(
+        "/api/puzzle/daily/hints",
+        &[
+            ("tz", tz.as_str()),
+            ("dictionary", dictionary.as_str()),
+            ("difficulty", difficulty.as_str()),
+        ],
+    )
+    .await
+    .map_err(|e| AppError::ConfigLoadError(format!("failed to load hints: {}", e)))
+}
@@ -0,0 +1,351 @@
+//! Keyboard shortcuts.
+//!
+//! A single window listener maps key presses to commands, and components bind
+//! what each command does while they're mounted. Keys typed into text fields
+//! are left alone, except in the word input where only keys that can't be part
+//! of a word are taken. Elsewhere, typed letters go to the board's word.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
+use puzzle_config::Alphabet;
+use web_sys::wasm_bindgen::JsCast as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Command {
+    Submit,
+    Delete,
+    Shuffle,
+    Hints,
+    Guessed,
+    Help,
+    Palette,
+}
+
+/// Shortcuts listed in the help overlay.
+const SHORTCUTS: [(&str, &str); 8] = [
+    ("A-Z", "Type a letter"),
+    ("Enter", "Submit the word"),
+    ("Backspace", "Delete the last letter"),
+    ("Space", "Shuffle the letters"),
+    ("Shift+H", "Show hints"),
+    ("Shift+G", "Show guessed words"),
+    ("?", "Show keyboard shortcuts"),
+    ("Ctrl+K", "Go to another page"),
+];
+
+/// Pages offered by the command palette.
+const ROUTES: [(&str, &str); 12] = [
+    ("Today's puzzle", "/"),
+    ("Yesterday's answers", "/yesterday"),
+    ("Demo puzzle", "/demo"),
+    ("Practice puzzle", "/practice"),
+    ("Custom puzzle", "/custom"),
+    ("Race a friend", "/race"),
+    ("Play as a team", "/team"),
+    ("Log in", "/login"),
+    ("Register", "/register"),
+    ("Manage words", "/manage/words"),
+    ("Puzzle calendar", "/manage/puzzles"),
+    ("Error reports", "/manage/errors"),
+];
+
+/// Id of the board's word input, whose own keys are left to the input.
+pub(crate) const WORD_INPUT_ID: &str = "word-input";
+
+/// Handler of letters typed away from the word input.
+type LetterHandler = Rc<dyn Fn(char)>;
+
+thread_local! {
+    static BINDINGS: RefCell<HashMap<Command, Rc<dyn Fn()>>> = RefCell::new(HashMap::new());
+    static LETTERS: RefCell<Option<LetterHandler>> = const { RefCell::new(None) };
+}
+
+/// Where a key was pressed, which decides what it may do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    WordInput,
+    /// Other text fields and selects.
+    Field,
+    /// Buttons and links, which handle Enter and Space themselves.
+    Control,
+    /// Anywhere in an open dialog.
+    Dialog,
+    Page,
+}
+
+fn command_for(key: &str, ctrl: bool, focus: Focus) -> Option<Command> {
+    if ctrl {
+        return matches!(key, "k" | "K").then_some(Command::Palette);
+    }
+    match (focus, key) {
+        (Focus::Field | Focus::Dialog, _) => None,
+        (Focus::WordInput | Focus::Page, " ") => Some(Command::Shuffle),
+        (Focus::Page, "Enter") => Some(Command::Submit),
+        (Focus::Page | Focus::Control, "Backspace") => Some(Command::Delete),
+        // Shifted, since the letters themselves are typed into the word.
+        (Focus::Page | Focus::Control, "H") => Some(Command::Hints),
+        (Focus::Page | Focus::Control, "G") => Some(Command::Guessed),
+        (_, "?") => Some(Command::Help),
+        _ => None,
+    }
+}
+
+/// The letter typed outside of text fields, if `key` is one of any alphabet
+/// puzzles are made in.
+fn letter_for(key: &str, ctrl: bool, focus: Focus) -> Option<char> {
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None)
+            if !ctrl
+                && matches!(focus, Focus::Page | Focus::Control)
+                && Alphabet::SUPPORTED.iter().any(|a| a.contains(letter)) =>
+        {
+            Some(letter)
+        }
+        _ => None,
+    }
+}
+
+fn focus_of(target: Option<web_sys::EventTarget>) -> Focus {
+    let Some(element) = target.and_then(|t| t.dyn_into::<web_sys::Element>().ok()) else {
+        return Focus::Page;
+    };
+    if element.closest("dialog").ok().flatten().is_some() {
+        return Focus::Dialog;
+    }
+    match element.tag_name().as_str() {
+        "INPUT" if element.id() == WORD_INPUT_ID => Focus::WordInput,
+        "INPUT" | "TEXTAREA" | "SELECT" => Focus::Field,
+        "BUTTON" | "A" | "SUMMARY" => Focus::Control,
+        _ => Focus::Page,
+    }
+}
+
+/// Run `action` for `command` until the calling component is unmounted.
+pub(crate) fn bind(command: Command, action: impl Fn() + 'static) {
+    BINDINGS.with_borrow_mut(|bindings| bindings.insert(command, Rc::new(action)));
+    on_cleanup(move || {
+        BINDINGS.with_borrow_mut(|bindings| bindings.remove(&command));
+    });
+}
+
+/// Run `action` with each letter typed outside of text fields until the
+/// calling component is unmounted.
+pub(crate) fn bind_letters(action: impl Fn(char) + 'static) {
+    LETTERS.set(Some(Rc::new(action)));
+    on_cleanup(|| LETTERS.set(None));
+}
+
+/// Listen for shortcuts for as long as the app runs.
+pub(crate) fn install() {
+    let _ = window_event_listener(leptos::ev::keydown, |e| {
+        let ctrl = e.ctrl_key() || e.meta_key();
+        if e.alt_key() {
+            return;
+        }
+        let (key, focus) = (e.key(), focus_of(e.target()));
+        if let Some(command) = command_for(&key, ctrl, focus) {
+            let action = BINDINGS.with_borrow(|bindings| bindings.get(&command).cloned());
+            if let Some(action) = action {
+                e.prevent_default();
+                action();
+            }
+        } else if let Some(letter) = letter_for(&key, ctrl, focus) {
+            let action = LETTERS.with_borrow(Clone::clone);
+            if let Some(action) = action {
+                e.prevent_default();
+                action(letter);
+            }
+        }
+    });
+}
+
+/// Call a method of the dialog with `id`, if it's on the page.
+fn dialog(id: &str, method: &str) {
+    let Some(dialog) = document().get_element_by_id(id) else {
+        return;
+    };
+    let method = js_sys::Reflect::get(&dialog, &method.into())
+        .ok()
+        .and_then(|method| method.dyn_into::<js_sys::Function>().ok());
+    if let Some(method) = method {
+        // Showing a dialog that is already open throws, which is harmless.
+        let _ = method.call0(&dialog);
+    }
+}
+
+pub(crate) fn show_modal(id: &str) {
+    dialog(id, "showModal");
+}
+
+fn close_modal(id: &str) {
+    dialog(id, "close");
+}
+
+/// Overlay listing the shortcuts, opened with `?`.
+#[component]
+pub(crate) fn ShortcutsHelp() -> impl IntoView {
+    bind(Command::Help, || show_modal("shortcuts"));
+
+    view! {
+        <dialog id="shortcuts" class="modal">
+            <section class="modal-box flex flex-col gap-4">
+                <h1 class="text-3xl">Keyboard shortcuts</h1>
+                <table class="table">
+                    <tbody>
+                        {SHORTCUTS
+                            .into_iter()
+                            .map(|(key, action)| {
+                                view! {
+                                    <tr>
+                                        <td>
+                                            <kbd class="kbd">{key}</kbd>
+                                        </td>
+                                        <td>{action}</td>
+                                    </tr>
+                                }
+                            })
+                            .collect_view()}
+                    </tbody>
+                </table>
+                <div class="modal-action">
+                    <form method="dialog">
+                        <button type="submit" class="btn btn-primary">
+                            close
+                        </button>
+                    </form>
+                </div>
+            </section>
+        </dialog>
+    }
+}
+
+/// Pages whose name contains `query`, ignoring case.
+fn matching_routes(query: &str) -> Vec<(&'static str, &'static str)> {
+    let query = query.trim().to_lowercase();
+    ROUTES
+        .into_iter()
+        .filter(|(name, _)| name.to_lowercase().contains(&query))
+        .collect()
+}
+
+/// Palette for jumping between pages, opened with Ctrl+K. Enter goes to the
+/// first page matching what's typed.
+#[component]
+pub(crate) fn CommandPalette() -> impl IntoView {
+    bind(Command::Palette, || show_modal("palette"));
+    let (query, set_query) = signal(String::new());
+    let navigate = use_navigate();
+    let go = move |path: &str| {
+        close_modal("palette");
+        set_query.set(String::new());
+        navigate(path, Default::default());
+    };
+
+    let submit = {
+        let go = go.clone();
+        move |e: web_sys::SubmitEvent| {
+            e.prevent_default();
+            let first = matching_routes(&query.read_untracked())
+                .first()
+                .map(|(_, path)| *path);
+            if let Some(path) = first {
+                go(path);
+            }
+        }
+    };
+
+    view! {
+        <dialog id="palette" class="modal">
+            <section class="modal-box flex flex-col gap-4">
+                <form on:submit=submit>
+                    <input
+                        type="text"
+                        class="input w-full"
+                        placeholder="Go to..."
+                        aria-label="page"
+                        autofocus
+                        bind:value=(query, set_query)
+                    />
+                </form>
+                <ul class="menu w-full">
+                    {move || {
+                        matching_routes(&query.read())
+                            .into_iter()
+                            .map(|(name, path)| {
+                                let go = go.clone();
+                                view! {
+                                    <li>
+                                        <button type="button" on:click=move |_| go(path)>
+                                            {name}
+                                        </button>
+                                    </li>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </ul>
+            </section>
+            <form method="dialog" class="modal-backdrop">
+                <button type="submit">close</button>
+            </form>
+        </dialog>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn leaves_typing_to_text_fields() {
+        assert_eq!(
+            Some(Command::Submit),
+            command_for("Enter", false, Focus::Page)
+        );
+        assert_eq!(None, command_for("Enter", false, Focus::WordInput));
+        assert_eq!(None, command_for("Enter", false, Focus::Control));
+        assert_eq!(
+            Some(Command::Shuffle),
+            command_for(" ", false, Focus::WordInput)
+        );
+        assert_eq!(Some(Command::Hints), command_for("H", false, Focus::Page));
+        assert_eq!(None, command_for("h", false, Focus::Page));
+        assert_eq!(None, command_for("H", false, Focus::WordInput));
+        assert_eq!(None, command_for("g", false, Focus::Field));
+        assert_eq!(None, command_for("?", false, Focus::Dialog));
+        assert_eq!(Some(Command::Palette), command_for("k", true, Focus::Field));
+        assert_eq!(None, command_for("Enter", true, Focus::Page));
+    }
+
+    #[wasm_bindgen_test]
+    fn types_letters_outside_text_fields() {
+        assert_eq!(Some('h'), letter_for("h", false, Focus::Page));
+        assert_eq!(Some('q'), letter_for("q", false, Focus::Control));
+        assert_eq!(Some('ñ'), letter_for("ñ", false, Focus::Page));
+        assert_eq!(None, letter_for("h", false, Focus::WordInput));
+        assert_eq!(None, letter_for("h", true, Focus::Page));
+        assert_eq!(None, letter_for("H", false, Focus::Page));
+        assert_eq!(None, letter_for("Enter", false, Focus::Page));
+        assert_eq!(None, letter_for("é", false, Focus::Page));
+    }
+
+    #[wasm_bindgen_test]
+    fn filters_routes_by_name() {
+        assert_eq!(ROUTES.len(), matching_routes("").len());
+        assert_eq!(
+            vec![("Today's puzzle", "/"), ("Demo puzzle", "/demo")],
+            matching_routes(" PUZZLE")
+                .into_iter()
+                .take(2)
+                .collect::<Vec<_>>()
+        );
+        assert!(matching_routes("nothing").is_empty());
+    }
+}
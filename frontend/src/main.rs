@@ -4,22 +4,79 @@ use leptos_router::{
     path,
 };
 
+mod account;
+mod api;
+mod archive;
+mod calendar;
+mod celebration;
+mod custom;
+mod demo;
+mod errors;
+mod feedback;
 mod game;
+mod hints;
+mod keymap;
 mod management;
+mod notes;
+mod offline;
+mod practice;
+mod progress;
+mod race;
+mod rules;
+mod settings;
+mod share;
+mod stats;
+mod storage;
+mod team;
+mod theme;
+mod toast;
+mod yesterday;
 
 fn main() {
-    console_error_panic_hook::set_once();
+    errors::install_panic_hook();
     leptos::mount::mount_to_body(App);
 }
 
 #[component]
 fn App() -> impl IntoView {
+    settings::provide_settings();
+    account::provide_account();
+    account::sync_dictionary();
+    toast::provide_toasts();
+    toast::announce_degraded_mode();
+    keymap::install();
+    offline::provide_online();
+    offline::register_service_worker();
+    let (settings, _) = settings::use_settings();
+    Effect::new(move |_| theme::apply_theme(settings.read().theme));
+    Effect::new(move |_| theme::apply_palette(settings.read().palette));
+
     view! {
+        <offline::OfflineBanner />
         <Router>
-            <Routes fallback=|| "Not found">
-                <Route path=path!("/") view=game::Game />
-                <Route path=path!("/manage/words") view=management::Management />
-            </Routes>
+            <ErrorBoundary fallback=|errors| view! { <errors::ErrorFallback errors /> }>
+                <Routes fallback=|| "Not found">
+                    <Route path=path!("/") view=game::Game />
+                    <Route path=path!("/archive/:date") view=archive::Archive />
+                    <Route path=path!("/custom") view=custom::CustomBuilder />
+                    <Route path=path!("/custom/:id") view=custom::Custom />
+                    <Route path=path!("/demo") view=demo::Demo />
+                    <Route path=path!("/practice") view=practice::Practice />
+                    <Route path=path!("/race") view=race::RaceLobby />
+                    <Route path=path!("/race/:room") view=race::Race />
+                    <Route path=path!("/team") view=team::TeamLobby />
+                    <Route path=path!("/team/:team") view=team::Team />
+                    <Route path=path!("/yesterday") view=yesterday::Yesterday />
+                    <Route path=path!("/login") view=account::Login />
+                    <Route path=path!("/register") view=account::Register />
+                    <Route path=path!("/manage/words") view=management::Management />
+                    <Route path=path!("/manage/puzzles") view=calendar::PuzzleCalendar />
+                    <Route path=path!("/manage/errors") view=errors::ClientErrors />
+                </Routes>
+            </ErrorBoundary>
+            <keymap::ShortcutsHelp />
+            <keymap::CommandPalette />
         </Router>
+        <toast::ToastContainer />
     }
 }
@@ -1,31 +1,56 @@
 use leptos::prelude::*;
 use leptos_router::{
-    components::Form,
-    hooks::use_query,
+    components::{A, Form},
+    hooks::{use_navigate, use_query},
     params::{Params, ParamsError},
 };
-use serde::Deserialize;
+use search::SearchMode;
+
+use crate::toast::{ToastKind, use_toasts};
+
+/// Shortest word the server accepts.
+const MIN_WORD_LEN: usize = 4;
 
 #[component]
 pub fn Management() -> impl IntoView {
     let search_term = use_query::<WordSearch>();
+    let word_list = Memo::new(move |_| {
+        search_term
+            .read()
+            .as_ref()
+            .ok()
+            .and_then(|s| s.word_list.clone())
+    });
+    provide_context(SelectedDictionary(word_list));
     let words = LocalResource::new(move || {
         let search_term = search_term.get();
         leptos::logging::debug_warn!("search term: {:?}", search_term);
         search_words(search_term)
     });
+    let (selected, select) = signal(None::<String>);
 
     view! {
         <main class="container">
+            <DictionarySelector />
             <Search />
+            <FilterTabs />
             <Suspense fallback=|| "Loading...">
                 {move || Suspend::new(async move {
-                    let words = words.await.unwrap_or_default();
+                    let page = words.await.unwrap_or_default();
+                    let list = RwSignal::new(page.words);
+                    let next_page = RwSignal::new(page.next_page);
                     view! {
-                        <WordList words />
+                        <AddWordsForm list />
+                        <Pager prev_page=page.prev_page />
+                        <WordList list select />
+                        <LoadMore list next_page />
                     }
                 })}
             </Suspense>
+            <ExcludedWords />
+            <RecentChanges />
+            <PlayerReports />
+            <WordDetailDrawer selected />
         </main>
     }
 }
@@ -33,13 +58,164 @@ pub fn Management() -> impl IntoView {
 #[derive(Debug, PartialEq, Params, Clone)]
 struct WordSearch {
     q: Option<String>,
+    mode: Option<SearchMode>,
+    cursor: Option<String>,
+    /// Only list the words flagged for review.
+    flagged: Option<bool>,
+    /// Dictionary managed, the standard one unless named.
+    word_list: Option<String>,
+}
+
+/// Name of the dictionary managed, `None` for the standard one.
+#[derive(Clone, Copy)]
+struct SelectedDictionary(Memo<Option<String>>);
+
+fn use_word_list() -> Memo<Option<String>> {
+    use_context::<SelectedDictionary>().expect("No word list provided").0
+}
+
+/// Client of the server managing the words of `word_list`.
+fn api(word_list: Option<&str>) -> api_client::Client {
+    api_client::Client::default().with_word_list(word_list)
+}
+
+/// Page of the words of `word_list`, with `query` added to it.
+fn words_path(word_list: Option<&str>, query: &str) -> String {
+    let params: Vec<String> = word_list
+        .map(|name| format!("word_list={}", name))
+        .into_iter()
+        .chain((!query.is_empty()).then(|| query.to_owned()))
+        .collect();
+    match params.as_slice() {
+        [] => "/manage/words".to_owned(),
+        _ => format!("/manage/words?{}", params.join("&")),
+    }
+}
+
+/// Picks the dictionary whose words are managed.
+#[component]
+fn DictionarySelector() -> impl IntoView {
+    let word_list = use_word_list();
+    let dictionaries = LocalResource::new(fetch_dictionaries);
+    let navigate = use_navigate();
+    let select = move |e: web_sys::Event| {
+        let name = event_target_value(&e);
+        let word_list = (name != STANDARD_WORD_LIST).then_some(name);
+        navigate(&words_path(word_list.as_deref(), ""), Default::default());
+    };
+    let current = move || {
+        word_list
+            .get()
+            .unwrap_or_else(|| STANDARD_WORD_LIST.to_owned())
+    };
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || {
+                let select = select.clone();
+                Suspend::new(async move {
+                    let dictionaries = dictionaries
+                        .await
+                        .map(|d| d.dictionaries)
+                        .unwrap_or_default();
+                    view! {
+                        <select
+                            aria-label="Dictionary"
+                            class="select select-sm"
+                            on:change=select
+                        >
+                            {dictionaries
+                                .into_iter()
+                                .map(|dictionary| {
+                                    let name = dictionary.name.clone();
+                                    view! {
+                                        <option
+                                            value=dictionary.name.clone()
+                                            selected=move || current() == name
+                                        >
+                                            {format!(
+                                                "{} ({} words)",
+                                                dictionary.name,
+                                                dictionary.words,
+                                            )}
+                                        </option>
+                                    }
+                                })
+                                .collect_view()}
+                        </select>
+                    }
+                })
+            }}
+        </Suspense>
+    }
+}
+
+/// Dictionary managed when none is named.
+const STANDARD_WORD_LIST: &str = "standard";
+
+async fn fetch_dictionaries() -> Option<words_list::Dictionaries> {
+    api(None).list_dictionaries().await.ok()
+}
+
+/// Switches between every word and the words flagged for review.
+#[component]
+fn FilterTabs() -> impl IntoView {
+    let search = use_query::<WordSearch>();
+    let word_list = use_word_list();
+    let flagged = move || {
+        search
+            .read()
+            .as_ref()
+            .ok()
+            .and_then(|s| s.flagged)
+            .unwrap_or_default()
+    };
+
+    view! {
+        <div role="tablist" class="tabs tabs-border">
+            <A
+                href=move || words_path(word_list.read().as_deref(), "")
+                attr:role="tab"
+                attr:class=move || tab_class(!flagged())
+            >
+                "all"
+            </A>
+            <A
+                href=move || words_path(word_list.read().as_deref(), "flagged=true")
+                attr:role="tab"
+                attr:class=move || tab_class(flagged())
+            >
+                "flagged"
+            </A>
+        </div>
+    }
+}
+
+fn tab_class(active: bool) -> &'static str {
+    if active { "tab tab-active" } else { "tab" }
 }
 
 #[component]
 fn Search() -> impl IntoView {
+    let search = use_query::<WordSearch>();
+    let word_list = use_word_list();
+    let current_mode = move || {
+        search
+            .read()
+            .as_ref()
+            .ok()
+            .and_then(|s| s.mode)
+            .unwrap_or_default()
+    };
+
     view! {
         <div id="word-search">
             <Form method="GET" action="/manage/words">
+                {move || {
+                    word_list
+                        .get()
+                        .map(|name| view! { <input type="hidden" name="word_list" value=name /> })
+                }}
                 <input
                     type="search"
                     name="q"
@@ -47,62 +223,708 @@ fn Search() -> impl IntoView {
                     placeholder="Search words..."
                     oninput="this.form.requestSubmit()"
                 />
+                <select
+                    name="mode"
+                    aria-label="Search mode"
+                    class="select select-sm"
+                    onchange="this.form.requestSubmit()"
+                >
+                    {SearchMode::ALL
+                        .into_iter()
+                        .map(|mode| {
+                            view! {
+                                <option value=mode.as_str() selected=move || current_mode() == mode>
+                                    {mode.as_str()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
             </Form>
         </div>
     }
 }
 
+/// Adds the words typed in, checked the way the server checks them. Words
+/// show up in `list` before the server confirms they were added.
+#[component]
+fn AddWordsForm(list: RwSignal<Vec<String>>) -> impl IntoView {
+    let toasts = use_toasts();
+    let word_list = use_word_list();
+    let (input, set_input) = signal(String::new());
+    let invalid = Memo::new(move |_| {
+        let input = input.read();
+        (!input.trim().is_empty())
+            .then(|| parse_new_words(&input).err())
+            .flatten()
+    });
+    let submit = move |e: web_sys::SubmitEvent| {
+        e.prevent_default();
+        let words = match parse_new_words(&input.get_untracked()) {
+            Ok(words) => words,
+            Err(e) => return toasts.show(ToastKind::Warning, e),
+        };
+        let new_words: Vec<String> = words
+            .iter()
+            .filter(|w| !list.read_untracked().contains(w))
+            .cloned()
+            .collect();
+        list.update(|list| {
+            list.extend(new_words.iter().cloned());
+            list.sort();
+        });
+        set_input.set(String::new());
+        let word_list = word_list.get_untracked();
+        leptos::task::spawn_local(async move {
+            match add_words(word_list.as_deref(), &words).await {
+                Ok(()) => toasts.show(ToastKind::Success, format!("Added {}", words.join(", "))),
+                Err(e) => {
+                    list.update(|list| list.retain(|w| !new_words.contains(w)));
+                    toasts.show(ToastKind::Warning, e);
+                }
+            }
+        });
+    };
+
+    view! {
+        <form on:submit=submit class="flex flex-row gap-2 items-start my-2">
+            <div class="flex flex-col">
+                <input
+                    type="text"
+                    class="input input-sm"
+                    class:input-error=move || invalid.read().is_some()
+                    aria-label="Words to add"
+                    placeholder="Add words..."
+                    bind:value=(input, set_input)
+                />
+                <p class="text-error text-sm" role="alert">
+                    {move || invalid.get()}
+                </p>
+            </div>
+            <button type="submit" class="btn btn-primary btn-sm" disabled=move || invalid.read().is_some()>
+                "add"
+            </button>
+        </form>
+    }
+}
+
+/// The words typed into [`AddWordsForm`], separated by spaces or commas, or
+/// why the server would reject them.
+fn parse_new_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words: Vec<String> = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        return Err("Type a word to add".to_owned());
+    }
+    if let Some(word) = words
+        .iter()
+        .find(|w| !w.chars().all(|c| c.is_ascii_lowercase()))
+    {
+        return Err(format!("{} may only have letters from a to z", word));
+    }
+    if let Some(word) = words.iter().find(|w| w.len() < MIN_WORD_LEN) {
+        return Err(format!("{} is shorter than {} letters", word, MIN_WORD_LEN));
+    }
+    words.sort();
+    words.dedup();
+    Ok(words)
+}
+
+/// Words of the current page, which can be removed one at a time or all the
+/// checked ones at once. Removed words leave `list` before the server confirms
+/// it.
 #[component]
-fn WordList(words: Vec<String>) -> impl IntoView {
+fn WordList(list: RwSignal<Vec<String>>, select: WriteSignal<Option<String>>) -> impl IntoView {
+    let toasts = use_toasts();
+    let word_list = use_word_list();
+    let checked = RwSignal::new(std::collections::HashSet::<String>::new());
+    let remove = move |words: Vec<String>| {
+        let question = match words.as_slice() {
+            [word] => format!("Remove {}?", word),
+            _ => format!("Remove {} words?", words.len()),
+        };
+        if words.is_empty() || !window().confirm_with_message(&question).unwrap_or(false) {
+            return;
+        }
+        list.update(|list| list.retain(|w| !words.contains(w)));
+        checked.update(|checked| checked.retain(|w| !words.contains(w)));
+        let word_list = word_list.get_untracked();
+        leptos::task::spawn_local(async move {
+            if let Err(e) = remove_words(word_list.as_deref(), &words).await {
+                list.update(|list| {
+                    list.extend(words);
+                    list.sort();
+                });
+                toasts.show(ToastKind::Warning, e);
+            }
+        });
+    };
+    let all_checked = move || {
+        let checked = checked.read();
+        let list = list.read();
+        !list.is_empty() && list.iter().all(|w| checked.contains(w))
+    };
+
     view! {
         <table>
             <thead>
                 <tr>
+                    <td>
+                        <input
+                            type="checkbox"
+                            class="checkbox checkbox-sm"
+                            aria-label="Select every word"
+                            prop:checked=all_checked
+                            on:change=move |_| {
+                                if all_checked() {
+                                    checked.set(Default::default());
+                                } else {
+                                    checked.set(list.get_untracked().into_iter().collect());
+                                }
+                            }
+                        />
+                    </td>
                     <th scope="col">word</th>
+                    <td></td>
                 </tr>
             </thead>
 
             <For
-                each=move || words.clone()
+                each=move || list.get()
                 key=|w| w.clone()
                 let(word)
             >
-                <tr><th scope="row">{word}</th></tr>
+                <tr>
+                    <td>
+                        <input
+                            type="checkbox"
+                            class="checkbox checkbox-sm"
+                            aria-label=format!("Select {}", word)
+                            prop:checked={
+                                let word = word.clone();
+                                move || checked.read().contains(&word)
+                            }
+                            on:change={
+                                let word = word.clone();
+                                move |_| {
+                                    checked
+                                        .update(|checked| {
+                                            if !checked.remove(&word) {
+                                                checked.insert(word.clone());
+                                            }
+                                        })
+                                }
+                            }
+                        />
+                    </td>
+                    <th scope="row">
+                        <button
+                            type="button"
+                            class="link link-hover"
+                            on:click={
+                                let word = word.clone();
+                                move |_| {
+                                    select.set(Some(word.clone()));
+                                    crate::keymap::show_modal(WORD_DETAIL_ID);
+                                }
+                            }
+                        >
+                            {word.clone()}
+                        </button>
+                    </th>
+                    <td>
+                        <button
+                            type="button"
+                            class="btn btn-ghost btn-xs text-error"
+                            aria-label=format!("Remove {}", word)
+                            on:click=move |_| remove(vec![word.clone()])
+                        >
+                            "remove"
+                        </button>
+                    </td>
+                </tr>
             </For>
         </table>
+        <button
+            type="button"
+            class="btn btn-error btn-outline btn-sm"
+            disabled=move || checked.read().is_empty()
+            on:click=move |_| remove(checked.get_untracked().into_iter().collect())
+        >
+            {move || format!("remove {} selected", checked.read().len())}
+        </button>
     }
 }
 
-async fn search_words(term: Result<WordSearch, ParamsError>) -> Option<Vec<String>> {
-    if let Some(term) = term.ok()?.q
-        && term != ""
-    {
-        let resp = gloo_net::http::Request::get("/api/words/search")
-            .query([("q", term)])
-            .header("accept", "application/json")
-            .send()
-            .await
-            .ok()?;
-        let json = resp.json::<search::SearchedWords>().await.ok()?;
-
-        Some(json.words)
+async fn add_words(word_list: Option<&str>, words: &[String]) -> Result<(), String> {
+    api(word_list)
+        .add_words(words)
+        .await
+        .map_err(|e| format!("Failed to add {} ({})", words.join(", "), e))
+}
+
+async fn remove_words(word_list: Option<&str>, words: &[String]) -> Result<(), String> {
+    api(word_list)
+        .remove_words(words)
+        .await
+        .map_err(|e| format!("Failed to remove {} ({})", words.join(", "), e))
+}
+
+const WORD_DETAIL_ID: &str = "word-detail";
+
+/// Side panel with everything known about the word picked from the list.
+#[component]
+fn WordDetailDrawer(selected: ReadSignal<Option<String>>) -> impl IntoView {
+    let word_list = use_word_list();
+    let detail = LocalResource::new(move || fetch_word_detail(word_list.get(), selected.get()));
+    let on_change = Callback::new(move |_| detail.refetch());
+
+    view! {
+        <dialog id=WORD_DETAIL_ID class="modal modal-end">
+            <section class="modal-box flex flex-col gap-4">
+                <Suspense fallback=|| "Loading...">
+                    {move || Suspend::new(async move {
+                        detail.await.map(|detail| view! { <WordDetailView detail on_change /> })
+                    })}
+                </Suspense>
+                <div class="modal-action">
+                    <form method="dialog">
+                        <button type="submit" class="btn">
+                            close
+                        </button>
+                    </form>
+                </div>
+            </section>
+        </dialog>
+    }
+}
+
+#[component]
+fn WordDetailView(detail: words_list::WordDetail, on_change: Callback<()>) -> impl IntoView {
+    let (status, set_status) = signal(None::<String>);
+    let word_list = use_word_list();
+    let flagged = detail.flagged_at.is_some();
+    let toggle_flag = {
+        let word = detail.word.clone();
+        move |_| {
+            let word = word.clone();
+            let word_list = word_list.get_untracked();
+            leptos::task::spawn_local(async move {
+                match set_flagged(word_list.as_deref(), &word, !flagged).await {
+                    Ok(()) => on_change.run(()),
+                    Err(e) => set_status.set(Some(e)),
+                }
+            });
+        }
+    };
+
+    let puzzles = if detail.puzzles.is_empty() {
+        leptos::either::Either::Left(view! { <p>"Not in any stored puzzle"</p> })
     } else {
-        let resp = gloo_net::http::Request::get("/api/words")
-            .header("accept", "application/json")
-            .send()
-            .await
-            .ok()?;
-        let json = resp.json::<SearchResponse>().await.ok()?;
+        leptos::either::Either::Right(view! {
+            <ul>
+                {detail
+                    .puzzles
+                    .into_iter()
+                    .map(|puzzle| {
+                        view! {
+                            <li class:font-bold=puzzle.pangram>
+                                {format!("{} {}", puzzle.date, utc_offset(puzzle.utc_offset))}
+                                {puzzle.pangram.then_some(" (pangram)")}
+                            </li>
+                        }
+                    })
+                    .collect_view()}
+            </ul>
+        })
+    };
+
+    view! {
+        <h2 class="text-2xl">{detail.word}</h2>
+        <dl class="grid grid-cols-2 gap-1">
+            <dt>"letters"</dt>
+            <dd>{detail.letters}</dd>
+            <dt>"mask"</dt>
+            <dd>{format!("{:#b}", detail.letter_mask)}</dd>
+            <dt>"length"</dt>
+            <dd>{detail.length}</dd>
+            <dt>"could be a pangram"</dt>
+            <dd>{if detail.pangram_potential { "yes" } else { "no" }}</dd>
+            <dt>"definition"</dt>
+            <dd>{detail.definition.unwrap_or_else(|| "none".to_owned())}</dd>
+            <dt>"obscurity"</dt>
+            <dd>{detail.obscurity.map_or_else(|| "unrated".to_owned(), |o| o.to_string())}</dd>
+            <dt>"added"</dt>
+            <dd>{detail.added_at.unwrap_or_else(|| "unknown".to_owned())}</dd>
+            <dt>"flagged"</dt>
+            <dd>{detail.flagged_at.unwrap_or_else(|| "no".to_owned())}</dd>
+        </dl>
+        <div>
+            <button type="button" class="btn btn-warning btn-outline btn-sm" on:click=toggle_flag>
+                {if flagged { "unflag" } else { "flag" }}
+            </button>
+            <p role="status">{move || status.get()}</p>
+        </div>
+        <h3 class="text-xl">"Puzzles"</h3>
+        {puzzles}
+    }
+}
+
+/// `seconds` east of UTC as `UTC+hh:mm`.
+fn utc_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+    format!("UTC{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
 
-        Some(json.words)
+/// Flag `word` for review, keeping it out of puzzles, or clear its flag.
+async fn set_flagged(word_list: Option<&str>, word: &str, flagged: bool) -> Result<(), String> {
+    let action = if flagged { "flag" } else { "unflag" };
+    api(word_list)
+        .set_flagged(word, flagged)
+        .await
+        .map_err(|e| format!("Failed to {} {} ({})", action, word, e))
+}
+
+async fn fetch_word_detail(
+    word_list: Option<String>,
+    word: Option<String>,
+) -> Option<words_list::WordDetail> {
+    api(word_list.as_deref()).word_detail(&word?).await.ok()
+}
+
+/// Words in the list that the morphology policy keeps out of puzzles.
+#[component]
+fn ExcludedWords() -> impl IntoView {
+    let word_list = use_word_list();
+    let excluded = LocalResource::new(move || fetch_excluded_words(word_list.get()));
+
+    view! {
+        <details class="mt-4">
+            <summary>"Words excluded by the morphology policy"</summary>
+            <Suspense fallback=|| "Loading...">
+                {move || Suspend::new(async move {
+                    let words = excluded.await.map(|e| e.words).unwrap_or_default();
+                    if words.is_empty() {
+                        return leptos::either::Either::Left(view! { <p>"None"</p> });
+                    }
+                    leptos::either::Either::Right(view! {
+                        <table>
+                            <thead>
+                                <tr>
+                                    <th scope="col">word</th>
+                                    <th scope="col">inflection of</th>
+                                    <th scope="col">rule</th>
+                                </tr>
+                            </thead>
+                            <For each=move || words.clone() key=|w| w.word.clone() let(word)>
+                                <tr>
+                                    <th scope="row">{word.word}</th>
+                                    <td>{word.stem}</td>
+                                    <td>{word.inflection.replace('_', " ")}</td>
+                                </tr>
+                            </For>
+                        </table>
+                    })
+                })}
+            </Suspense>
+        </details>
+    }
+}
+
+async fn fetch_excluded_words(word_list: Option<String>) -> Option<words_list::ExcludedWords> {
+    api(word_list.as_deref()).excluded_words().await.ok()
+}
+
+/// Words players reported as missing from the list or wrongly in it.
+#[component]
+fn PlayerReports() -> impl IntoView {
+    let reports = LocalResource::new(fetch_word_reports);
+
+    view! {
+        <details class="mt-4">
+            <summary>"Player reports"</summary>
+            <Suspense fallback=|| "Loading...">
+                {move || Suspend::new(async move {
+                    let words = reports.await.map(|r| r.words).unwrap_or_default();
+                    if words.is_empty() {
+                        return leptos::either::Either::Left(view! { <p>"None"</p> });
+                    }
+                    leptos::either::Either::Right(view! {
+                        <table>
+                            <thead>
+                                <tr>
+                                    <th scope="col">word</th>
+                                    <th scope="col">reported as</th>
+                                    <th scope="col">reports</th>
+                                    <th scope="col">last reported</th>
+                                    <th scope="col">in the list</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {words
+                                    .into_iter()
+                                    .map(|report| {
+                                        view! {
+                                            <tr>
+                                                <th scope="row">{report.word}</th>
+                                                <td>{report.kind.as_str()}</td>
+                                                <td>{report.reports}</td>
+                                                <td>{report.last_reported_at}</td>
+                                                <td>{if report.in_list { "yes" } else { "no" }}</td>
+                                            </tr>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </tbody>
+                        </table>
+                    })
+                })}
+            </Suspense>
+        </details>
+    }
+}
+
+async fn fetch_word_reports() -> Option<words_list::ReportedWords> {
+    api(None).word_reports().await.ok()
+}
+
+/// The latest additions and removals of words, where removals can be undone.
+#[component]
+fn RecentChanges() -> impl IntoView {
+    let word_list = use_word_list();
+    let audit = LocalResource::new(move || fetch_word_audit(word_list.get()));
+    let (status, set_status) = signal(None::<String>);
+    let on_undo = Callback::new(move |word: String| {
+        let word_list = word_list.get_untracked();
+        leptos::task::spawn_local(async move {
+            set_status.set(Some(
+                match restore_word(word_list.as_deref(), &word).await {
+                    Ok(()) => format!("Restored {}", word),
+                    Err(e) => e,
+                },
+            ));
+            audit.refetch();
+        });
+    });
+
+    view! {
+        <details class="mt-4">
+            <summary>"Recent changes"</summary>
+            <p role="status">{move || status.get()}</p>
+            <Suspense fallback=|| "Loading...">
+                {move || Suspend::new(async move {
+                    let entries = audit.await.map(|a| a.entries).unwrap_or_default();
+                    if entries.is_empty() {
+                        return leptos::either::Either::Left(view! { <p>"None"</p> });
+                    }
+                    // Only the latest change to a word can be undone.
+                    let mut seen = std::collections::HashSet::new();
+                    let rows = entries
+                        .into_iter()
+                        .map(|entry| {
+                            let undoable = seen.insert(entry.word.clone())
+                                && entry.action == "removed";
+                            view! { <AuditRow entry undoable on_undo /> }
+                        })
+                        .collect_view();
+                    leptos::either::Either::Right(view! {
+                        <table>
+                            <thead>
+                                <tr>
+                                    <th scope="col">word</th>
+                                    <th scope="col">change</th>
+                                    <th scope="col">by</th>
+                                    <th scope="col">when</th>
+                                    <td></td>
+                                </tr>
+                            </thead>
+                            <tbody>{rows}</tbody>
+                        </table>
+                    })
+                })}
+            </Suspense>
+        </details>
+    }
+}
+
+#[component]
+fn AuditRow(
+    entry: words_list::WordAuditEntry,
+    undoable: bool,
+    on_undo: Callback<String>,
+) -> impl IntoView {
+    let word = entry.word.clone();
+    view! {
+        <tr>
+            <th scope="row">{entry.word}</th>
+            <td>{entry.action}</td>
+            <td>{entry.username.unwrap_or_else(|| "server".to_owned())}</td>
+            <td>{entry.recorded_at}</td>
+            <td>
+                {undoable
+                    .then(|| {
+                        view! {
+                            <button
+                                type="button"
+                                class="btn btn-ghost btn-xs"
+                                on:click=move |_| on_undo.run(word.clone())
+                            >
+                                "undo removal"
+                            </button>
+                        }
+                    })}
+            </td>
+        </tr>
     }
 }
 
-#[derive(Deserialize, Clone)]
-struct SearchResponse {
+async fn fetch_word_audit(word_list: Option<String>) -> Option<words_list::WordAudit> {
+    api(word_list.as_deref()).word_audit().await.ok()
+}
+
+async fn restore_word(word_list: Option<&str>, word: &str) -> Result<(), String> {
+    api(word_list)
+        .restore_words(&[word.to_owned()])
+        .await
+        .map_err(|e| format!("Failed to restore {} ({})", word, e))
+}
+
+/// Link to the page before the first loaded one, if there is one. Later
+/// pages are appended by [`LoadMore`].
+#[component]
+fn Pager(prev_page: Option<String>) -> impl IntoView {
+    let word_list = use_word_list();
+    prev_page.map(|cursor| {
+        let href = words_path(
+            word_list.get_untracked().as_deref(),
+            &format!("cursor={}", cursor),
+        );
+        view! {
+            <nav aria-label="Word list pages">
+                <A href=href attr:class="btn btn-ghost btn-sm">
+                    "previous"
+                </A>
+            </nav>
+        }
+    })
+}
+
+/// Appends the page after the last loaded one to `list`, following the
+/// `next_page` cursor of each page in turn.
+#[component]
+fn LoadMore(list: RwSignal<Vec<String>>, next_page: RwSignal<Option<String>>) -> impl IntoView {
+    let toasts = use_toasts();
+    let word_list = use_word_list();
+    let (loading, set_loading) = signal(false);
+    let load = move |_| {
+        let Some(cursor) = next_page.get_untracked() else {
+            return;
+        };
+        set_loading.set(true);
+        let word_list = word_list.get_untracked();
+        leptos::task::spawn_local(async move {
+            match fetch_page(word_list.as_deref(), Some(cursor)).await {
+                Some(page) => {
+                    // Words added here may already be listed.
+                    list.update(|list| {
+                        let words: Vec<String> = page
+                            .words
+                            .into_iter()
+                            .filter(|w| !list.contains(w))
+                            .collect();
+                        list.extend(words);
+                    });
+                    next_page.set(page.next_page);
+                }
+                None => toasts.show(ToastKind::Warning, "Failed to load more words"),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    view! {
+        <Show when=move || next_page.read().is_some()>
+            <button
+                type="button"
+                class="btn btn-ghost btn-sm w-full"
+                on:click=load
+                disabled=move || loading.get()
+            >
+                {move || if loading.get() { "loading..." } else { "load more" }}
+            </button>
+        </Show>
+    }
+}
+
+/// A page of words along with the cursors of its neighbouring pages. Search
+/// results aren't paginated.
+#[derive(Clone, Default)]
+struct WordPage {
     words: Vec<String>,
+    next_page: Option<String>,
+    prev_page: Option<String>,
+}
+
+async fn search_words(search: Result<WordSearch, ParamsError>) -> Option<WordPage> {
+    let search = search.ok()?;
+    let word_list = search.word_list.as_deref();
+    if search.flagged.unwrap_or_default() {
+        let json = api(word_list).flagged_words().await.ok()?;
+
+        Some(WordPage {
+            words: json.words.into_iter().map(|w| w.word).collect(),
+            ..Default::default()
+        })
+    } else if let Some(term) = search.q
+        && term != ""
+    {
+        let mode = search.mode.unwrap_or_default();
+        let json = api(word_list).search_words(&term, mode).await.ok()?;
+
+        Some(WordPage {
+            words: json.words,
+            ..Default::default()
+        })
+    } else {
+        fetch_page(word_list, search.cursor).await
+    }
+}
+
+/// The page of the words of `word_list` starting at `cursor`, or the first
+/// one.
+async fn fetch_page(word_list: Option<&str>, cursor: Option<String>) -> Option<WordPage> {
+    let json = api(word_list).list_words(cursor.as_deref()).await.ok()?;
+
+    Some(WordPage {
+        words: json.words.into_iter().map(|w| w.text).collect(),
+        next_page: json.pagination.next_page.map(|c| c.0),
+        prev_page: json.pagination.prev_page.map(|c| c.0),
+    })
 }
 
 enum SearchError {
     Fetch(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn checks_new_words_like_the_server() {
+        assert_eq!(
+            Ok(vec!["apple".to_owned(), "bread".to_owned()]),
+            parse_new_words("Bread, apple  bread")
+        );
+        assert!(parse_new_words(" ").is_err());
+        assert!(parse_new_words("apple cr4ne").is_err());
+        assert!(parse_new_words("café").is_err());
+        assert!(parse_new_words("bee").is_err());
+    }
+}
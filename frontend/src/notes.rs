@@ -0,0 +1,84 @@
+//! Notes players jot down about a puzzle, kept in local storage by puzzle date
+//! and synced with their account while they're logged in.
+
+use leptos::prelude::*;
+use puzzle_config::{MAX_NOTE_CHARS, Note};
+
+use crate::account::use_account;
+
+/// Notes on the puzzle for `date`, formatted like `2024-01-31`.
+///
+/// On login the note saved with the account replaces an empty local one, and a
+/// local one is sent to the account otherwise. Edits are sent as they're made.
+#[component]
+pub(crate) fn PuzzleNotes(date: String) -> impl IntoView {
+    let (note, set_note, _) = leptos_use::storage::use_local_storage::<
+        String,
+        codee::string::JsonSerdeCodec,
+    >(format!("notes/{}", date));
+    let (user, _) = use_account();
+    let date = StoredValue::new(date);
+
+    Effect::watch(
+        move || user.read().is_some(),
+        move |logged_in, _, _| {
+            if !*logged_in {
+                return;
+            }
+            let date = date.get_value();
+            let local = note.get_untracked();
+            leptos::task::spawn_local(async move {
+                let synced = if local.is_empty() {
+                    fetch_note(&date)
+                        .await
+                        .map(|remote| set_note.set(remote.text))
+                } else {
+                    put_note(&date, &Note { text: local }).await
+                };
+                if let Err(e) = synced {
+                    leptos::logging::error!("Failed to sync note: {}", e);
+                }
+            });
+        },
+        true,
+    );
+
+    let save = move |text: String| {
+        set_note.set(text.clone());
+        if user.read_untracked().is_none() {
+            return;
+        }
+        let date = date.get_value();
+        leptos::task::spawn_local(async move {
+            if let Err(e) = put_note(&date, &Note { text }).await {
+                leptos::logging::error!("Failed to save note: {}", e);
+            }
+        });
+    };
+
+    view! {
+        <label class="flex flex-col gap-1">
+            "Notes"
+            <textarea
+                class="textarea w-full"
+                maxlength=MAX_NOTE_CHARS
+                placeholder="Thoughts on today's puzzle"
+                prop:value=move || note.get()
+                on:change:target=move |e| save(e.target().value())
+            ></textarea>
+        </label>
+    }
+}
+
+async fn fetch_note(date: &str) -> Result<Note, String> {
+    crate::api::get_json(&format!("/api/notes/{}", date), &[])
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn put_note(date: &str, note: &Note) -> Result<(), String> {
+    api_client::Client::default()
+        .save_note(date, note)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,72 @@
+//! Playing without a connection.
+//!
+//! The service worker built by `build/pwa.mjs` serves the app itself from its
+//! cache, and the day's puzzle comes from local storage, so the game starts and
+//! plays offline. Progress is sent once the connection is back.
+
+use leptos::prelude::*;
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::settings::use_settings;
+
+/// Whether the browser has a network connection.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Online(pub(crate) ReadSignal<bool>);
+
+pub(crate) fn provide_online() {
+    let (online, set_online) = signal(window().navigator().on_line());
+    let _ = window_event_listener(leptos::ev::online, move |_| set_online.set(true));
+    let _ = window_event_listener(leptos::ev::offline, move |_| set_online.set(false));
+    provide_context(Online(online));
+}
+
+pub(crate) fn use_online() -> ReadSignal<bool> {
+    use_context::<Online>()
+        .expect("No online status provided")
+        .0
+}
+
+/// Register the service worker, where the browser supports them.
+pub(crate) fn register_service_worker() {
+    let container = js_sys::Reflect::get(&window().navigator(), &"serviceWorker".into())
+        .ok()
+        .filter(|container| !container.is_undefined());
+    let Some(container) = container else {
+        return;
+    };
+    let register = js_sys::Reflect::get(&container, &"register".into())
+        .ok()
+        .and_then(|register| register.dyn_into::<js_sys::Function>().ok());
+    let promise = register
+        .and_then(|register| register.call1(&container, &"/sw.js".into()).ok())
+        .and_then(|promise| promise.dyn_into::<js_sys::Promise>().ok());
+    if let Some(promise) = promise {
+        leptos::task::spawn_local(async move {
+            if let Err(e) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                leptos::logging::warn!("failed to register service worker: {:?}", e);
+            }
+        });
+    }
+}
+
+/// Tells the player they're playing a saved puzzle while the server can't be
+/// reached.
+#[component]
+pub(crate) fn OfflineBanner() -> impl IntoView {
+    let online = use_online();
+    let degraded = crate::api::degraded();
+    let (settings, _) = use_settings();
+    let shown = move || {
+        (!online.get() || degraded.get())
+            && crate::storage::load_config(settings.read().dictionary, settings.read().difficulty)
+                .is_some()
+    };
+
+    view! {
+        <Show when=shown>
+            <div role="status" class="alert alert-warning rounded-none justify-center">
+                "You're offline. Playing the saved puzzle; progress syncs when you reconnect."
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,102 @@
+use leptos::prelude::*;
+use leptos_router::{
+    NavigateOptions,
+    hooks::{use_navigate, use_query},
+    params::Params,
+};
+use puzzle_config::Difficulty;
+
+use crate::game::{AppError, Puzzle, daily_key, fetch_random_config};
+use crate::settings::use_settings;
+
+#[derive(Debug, PartialEq, Params, Clone)]
+struct PracticeQuery {
+    seed: Option<u64>,
+}
+
+/// Play puzzles generated from random seeds rather than the date, as many as
+/// the player likes. The seed is kept in the URL, so reloading or sharing the
+/// page gives the same puzzle, and progress is stored apart from the daily
+/// puzzle's.
+#[component]
+pub(crate) fn Practice() -> impl IntoView {
+    let query = use_query::<PracticeQuery>();
+    let seed = Memo::new(move |_| query.get().ok().and_then(|q| q.seed));
+    let navigate = use_navigate();
+    Effect::new({
+        let navigate = navigate.clone();
+        move |_| {
+            if seed.get().is_none() {
+                navigate(
+                    &practice_path(random_seed()),
+                    NavigateOptions {
+                        replace: true,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    });
+
+    let (settings, _) = use_settings();
+    let difficulty = Memo::new(move |_| settings.read().difficulty);
+    let config = LocalResource::new(move || {
+        let seed = seed.get();
+        let dictionary = settings.read().dictionary;
+        let difficulty = difficulty.get();
+        async move {
+            match seed {
+                Some(seed) => fetch_random_config(seed, dictionary, difficulty).await,
+                None => Err(AppError::ConfigLoadError("no puzzle picked yet".into())),
+            }
+        }
+    });
+
+    let reroll = move |_| {
+        if let Some(seed) = seed.get_untracked() {
+            forget_progress(&practice_key(seed, difficulty.get_untracked()));
+        }
+        navigate(&practice_path(random_seed()), Default::default());
+    };
+
+    view! {
+        <div class="container px-4 pt-4 flex flex-row justify-between items-center">
+            <h1 class="text-xl font-bold">"Practice puzzle"</h1>
+            <button type="button" class="btn btn-sm btn-primary" on:click=reroll>
+                "new puzzle"
+            </button>
+        </div>
+        {move || {
+            seed.get()
+                .map(|seed| {
+                    view! { <Puzzle storage_key=practice_key(seed, difficulty.get()) config /> }
+                })
+        }}
+    }
+}
+
+fn practice_path(seed: u64) -> String {
+    format!("/practice?seed={}", seed)
+}
+
+/// Key the progress on the practice puzzle of `difficulty` from `seed` is
+/// stored under, apart from the daily puzzles.
+fn practice_key(seed: u64, difficulty: Difficulty) -> String {
+    format!("practice/{}", daily_key(seed, difficulty))
+}
+
+/// A seed small enough to survive the round trip through a JavaScript number.
+fn random_seed() -> u64 {
+    (js_sys::Math::random() * (1u64 << 53) as f64) as u64
+}
+
+/// Drop the progress stored under `key`, since a practice puzzle is seldom
+/// played again once rerolled.
+fn forget_progress(key: &str) {
+    let Ok(storage) = crate::storage::get_storage() else {
+        return;
+    };
+    for field in ["score", "submitted", "started"] {
+        let _ = storage.remove_item(&format!("{}/{}", key, field));
+    }
+}
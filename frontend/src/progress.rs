@@ -0,0 +1,71 @@
+use std::collections::HashSet;
+
+use leptos::prelude::*;
+use puzzle_config::{Difficulty, Progress, ScoringRules, Word};
+
+use crate::account::use_account;
+use crate::game::get_current_tz;
+use crate::rules::merge_submitted;
+
+/// Keep the daily puzzle's progress in sync with the server while a user is
+/// logged in.
+///
+/// Local progress is sent whenever it changes or the user logs in. The server
+/// responds with the union of every device's words, and any words found on
+/// other devices are added to local progress with the score recalculated.
+///
+/// Nothing is sent while offline. Progress only grows, so sending the latest
+/// once back online catches the server up on everything found meanwhile.
+pub(crate) fn sync_progress(
+    valid_words: HashSet<Word>,
+    scoring: ScoringRules,
+    score: Signal<u32>,
+    set_score: WriteSignal<u32>,
+    submitted: Signal<Vec<String>>,
+    set_submitted: WriteSignal<Vec<String>>,
+) {
+    let (user, _) = use_account();
+    let (settings, _) = crate::settings::use_settings();
+    let online = crate::offline::use_online();
+    Effect::new(move |_| {
+        if user.read().is_none() || !online.get() {
+            return;
+        }
+        let progress = Progress {
+            submitted: submitted.get(),
+            score: score.get_untracked(),
+        };
+        let difficulty = settings.get_untracked().difficulty;
+        let valid_words = valid_words.clone();
+        let scoring = scoring.clone();
+        leptos::task::spawn_local(async move {
+            let remote = match put_progress(&progress, difficulty).await {
+                Ok(remote) => remote,
+                Err(e) => {
+                    leptos::logging::error!("Failed to sync progress: {}", e);
+                    return;
+                }
+            };
+
+            // Merge into the current words rather than the ones sent, which may
+            // have changed while the request was in flight.
+            if let Some((merged, merged_score)) = merge_submitted(
+                &submitted.get_untracked(),
+                remote.submitted,
+                &valid_words,
+                &scoring,
+            ) {
+                set_score.set(merged_score);
+                set_submitted.set(merged);
+            }
+        });
+    });
+}
+
+async fn put_progress(progress: &Progress, difficulty: Difficulty) -> Result<Progress, String> {
+    let tz = get_current_tz().map_err(|e| e.to_string())?;
+    api_client::Client::default()
+        .save_progress(&tz, difficulty, progress)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,164 @@
+use codee::string::JsonSerdeCodec;
+use leptos::prelude::*;
+use leptos_router::{
+    hooks::{use_navigate, use_params},
+    params::Params,
+};
+use leptos_use::{UseWebSocketReturn, core::ConnectionReadyState, use_websocket};
+use puzzle_config::{OpponentEvent, RaceProgress};
+
+use crate::game::{Puzzle, daily_key, day_64, load};
+use crate::settings::use_settings;
+
+#[derive(Debug, PartialEq, Params, Clone)]
+struct RaceParams {
+    room: Option<String>,
+}
+
+/// What's known of the other player of a room.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Opponent {
+    Waiting,
+    Racing(RaceProgress),
+    Left,
+}
+
+/// Pick a room to race a friend in on today's puzzle. Whoever opens the same
+/// room first waits for the other.
+#[component]
+pub(crate) fn RaceLobby() -> impl IntoView {
+    let (room, set_room) = signal(random_room());
+    let navigate = use_navigate();
+
+    let submit = move |e: web_sys::SubmitEvent| {
+        e.prevent_default();
+        navigate(&format!("/race/{}", room.get()), Default::default());
+    };
+
+    view! {
+        <main class="container p-4 flex flex-col items-center gap-4">
+            <h1 class="text-3xl">"Race a friend"</h1>
+            <p>"Share the room name with a friend and both join it to race on today's puzzle."</p>
+            <form on:submit=submit class="flex flex-col gap-2 w-full max-w-xs">
+                <label class="flex flex-col gap-1">
+                    "room"
+                    <input
+                        type="text"
+                        class="input w-full"
+                        maxlength="32"
+                        pattern="[A-Za-z0-9\\-]+"
+                        required
+                        bind:value=(room, set_room)
+                    />
+                </label>
+                <button type="submit" class="btn btn-primary">
+                    "join"
+                </button>
+            </form>
+        </main>
+    }
+}
+
+/// Play today's puzzle against the other player of a room, whose progress is
+/// shown above the board as they find words.
+#[component]
+pub(crate) fn Race() -> impl IntoView {
+    let room = use_params::<RaceParams>()
+        .get_untracked()
+        .ok()
+        .and_then(|p| p.room)
+        .unwrap_or_default();
+
+    let (settings, _) = use_settings();
+    let difficulty = settings.get_untracked().difficulty;
+    let storage_key = daily_key(day_64(), difficulty);
+    let config = LocalResource::new(move || load(settings.read().dictionary, difficulty));
+
+    // The same progress the puzzle below stores, read back to send to the room.
+    let (score, _, _) = leptos_use::storage::use_local_storage::<u32, JsonSerdeCodec>(format!(
+        "{}/score",
+        storage_key
+    ));
+    let (submitted, _, _) = leptos_use::storage::use_local_storage::<Vec<String>, JsonSerdeCodec>(
+        format!("{}/submitted", storage_key),
+    );
+
+    let UseWebSocketReturn {
+        ready_state,
+        message,
+        send,
+        ..
+    } = use_websocket::<RaceProgress, OpponentEvent, JsonSerdeCodec>(&crate::api::websocket_url(
+        &format!("/api/puzzle/daily/rooms/{}", room),
+    ));
+
+    let (opponent, set_opponent) = signal(Opponent::Waiting);
+    Effect::new(move |_| match message.get() {
+        Some(OpponentEvent::Joined) => set_opponent.set(Opponent::Racing(RaceProgress::default())),
+        Some(OpponentEvent::Progress(progress)) => set_opponent.set(Opponent::Racing(progress)),
+        Some(OpponentEvent::Left) => set_opponent.set(Opponent::Left),
+        None => {}
+    });
+    Effect::new(move |_| {
+        let progress = RaceProgress {
+            words: submitted.read().len(),
+            score: score.get(),
+        };
+        if ready_state.get() == ConnectionReadyState::Open {
+            send(&progress);
+        }
+    });
+
+    let total_words = move || {
+        config.with(|config| match config {
+            Some(Ok(config)) => config.valid_words.len(),
+            _ => 0,
+        })
+    };
+
+    view! {
+        <div class="container px-4 pt-4 flex flex-col gap-2">
+            <h1 class="text-xl font-bold">{format!("Race in room {}", room)}</h1>
+            <div aria-live="polite">
+                {move || match (ready_state.get(), opponent.get()) {
+                    (ConnectionReadyState::Closed, _) => {
+                        view! {
+                            <p class="text-error">
+                                "Disconnected from the room. It may already have two players."
+                            </p>
+                        }
+                            .into_any()
+                    }
+                    (_, Opponent::Waiting) => {
+                        view! { <p>"Waiting for an opponent to join..."</p> }.into_any()
+                    }
+                    (_, Opponent::Left) => view! { <p>"Your opponent left."</p> }.into_any(),
+                    (_, Opponent::Racing(progress)) => {
+                        view! {
+                            <label class="flex flex-col gap-1">
+                                {format!(
+                                    "Opponent: {} words, {} points",
+                                    progress.words,
+                                    progress.score,
+                                )}
+                                <progress
+                                    class="progress progress-secondary w-full"
+                                    value=progress.words.to_string()
+                                    max=move || total_words().to_string()
+                                ></progress>
+                            </label>
+                        }
+                            .into_any()
+                    }
+                }}
+            </div>
+        </div>
+        <Puzzle storage_key config daily=true />
+    }
+}
+
+/// A room name unlikely to be taken, to share with a friend.
+fn random_room() -> String {
+    let n = (js_sys::Math::random() * (1u64 << 32) as f64) as u64;
+    format!("bee-{:x}", n)
+}
@@ -0,0 +1,438 @@
+//! Puzzle rules and bookkeeping kept out of the components so they can be
+//! tested on their own.
+
+use std::collections::HashSet;
+
+use puzzle_config::{Alphabet, Letter, ScoreBuckets, ScoringRules, Word, WordFilter};
+pub(crate) use validation::ValidationError;
+use validation::normalize_guess;
+
+/// Normalize a deep-linked word, dropping it unless every letter is in the puzzle.
+pub(crate) fn prefill_word(
+    word: &str,
+    alphabet: &Alphabet,
+    required_letter: &Letter,
+    other_letters: &[Letter],
+) -> Option<String> {
+    let word = alphabet.fold(&normalize_guess(word));
+    let is_playable = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| required_letter.0 == c || other_letters.contains(&Letter::new(c)));
+    is_playable.then_some(word)
+}
+
+/// Check a guess against the puzzle's rules and the words `submitted` so far,
+/// returning the scored word if it is accepted. The guess is normalized and
+/// folded into the puzzle's `alphabet` first, so the returned word is what
+/// should be recorded as submitted.
+///
+/// When playing the copy saved for offline play, only the words found so far
+/// are known and others are checked against its `filter`.
+pub(crate) fn check_word(
+    word: &str,
+    alphabet: &Alphabet,
+    required_letter: &Letter,
+    other_letters: &[Letter],
+    valid_words: &HashSet<Word>,
+    filter: Option<&WordFilter>,
+    submitted: &[String],
+) -> Result<Word, ValidationError> {
+    let guess =
+        validation::validate_guess_with(alphabet, required_letter, other_letters, word, |word| {
+            valid_words.contains(&Word::new(word, false))
+                || filter.is_some_and(|f| f.contains(word))
+        })?;
+    if submitted.contains(&guess.word.word) {
+        return Err(ValidationError::AlreadyGuessed);
+    }
+    Ok(guess.word)
+}
+
+/// Length of the longest word in the puzzle. Nothing longer can be accepted,
+/// so input is capped there.
+pub(crate) fn max_word_length(valid_words: &HashSet<Word>) -> usize {
+    valid_words
+        .iter()
+        .map(|w| w.word.chars().count())
+        .max()
+        .unwrap_or_default()
+}
+
+/// Append `letter` to `word` unless it is already `max_len` letters long.
+/// Returns whether the letter was added.
+pub(crate) fn push_letter(word: &mut String, letter: char, max_len: usize) -> bool {
+    if word.chars().count() >= max_len {
+        return false;
+    }
+    word.push(letter);
+    true
+}
+
+/// Drops repeats of an action that come within `window_ms` of the last one let
+/// through, so a double-tap on a hex only adds its letter once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Debounce {
+    window_ms: f64,
+    last_ms: Option<f64>,
+}
+
+impl Debounce {
+    pub(crate) fn new(window_ms: f64) -> Self {
+        Self {
+            window_ms,
+            last_ms: None,
+        }
+    }
+
+    /// Whether an action at `now_ms` should go ahead, remembering it if so.
+    pub(crate) fn ready(&mut self, now_ms: f64) -> bool {
+        if self
+            .last_ms
+            .is_some_and(|last| now_ms - last < self.window_ms)
+        {
+            return false;
+        }
+        self.last_ms = Some(now_ms);
+        true
+    }
+}
+
+/// Label of the highest bucket reached by `score`.
+pub(crate) fn current_rank(buckets: &ScoreBuckets, score: u32) -> String {
+    buckets
+        .iter()
+        .rfind(|(_label, thresh)| score >= *thresh)
+        .map(|(label, _score)| label.clone())
+        .unwrap_or_else(|| buckets[8].0.clone())
+}
+
+/// Milliseconds since the epoch at the start of `date`'s day in the local
+/// timezone, which identifies the day's puzzle in local storage.
+pub(crate) fn daydex(date: &js_sys::Date) -> u64 {
+    let midnight = js_sys::Date::new(date);
+    midnight.set_hours(0);
+    midnight.set_minutes(0);
+    midnight.set_seconds(0);
+    midnight.set_milliseconds(0);
+    midnight.get_time() as u64
+}
+
+/// Add words found on other devices to the local ones, keeping local words
+/// first and in order. Returns the merged words and their score recalculated
+/// by `scoring`, or `None` if `remote` adds nothing.
+pub(crate) fn merge_submitted(
+    local: &[String],
+    remote: Vec<String>,
+    valid_words: &HashSet<Word>,
+    scoring: &ScoringRules,
+) -> Option<(Vec<String>, u32)> {
+    let mut merged = local.to_vec();
+    for word in remote {
+        if !merged.contains(&word) {
+            merged.push(word);
+        }
+    }
+    if merged.len() == local.len() {
+        return None;
+    }
+
+    let score = merged
+        .iter()
+        .filter_map(|w| valid_words.get(&Word::new(w, false)))
+        .map(|w| w.score(scoring))
+        .sum();
+    Some((merged, score))
+}
+
+/// Position in a paged list whose length can change between reads, such as
+/// the guessed words.
+///
+/// The page is clamped to the list's current length on every read, so
+/// navigation never points past the last page even after the list shrinks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Paginator {
+    page: usize,
+    page_size: usize,
+}
+
+impl Paginator {
+    pub(crate) fn new(page_size: usize) -> Self {
+        Self {
+            page: 0,
+            page_size: page_size.max(1),
+        }
+    }
+
+    /// Number of pages in a list of `len` items. Empty lists have no pages.
+    pub(crate) fn page_count(&self, len: usize) -> usize {
+        len.div_ceil(self.page_size)
+    }
+
+    /// Index of the page shown for a list of `len` items.
+    pub(crate) fn current(&self, len: usize) -> usize {
+        self.page.min(self.page_count(len).saturating_sub(1))
+    }
+
+    /// The items on the current page of `items`.
+    pub(crate) fn page_of<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        let start = self.current(items.len()) * self.page_size;
+        let end = (start + self.page_size).min(items.len());
+        &items[start..end]
+    }
+
+    pub(crate) fn has_prev(&self, len: usize) -> bool {
+        self.current(len) > 0
+    }
+
+    pub(crate) fn has_next(&self, len: usize) -> bool {
+        self.current(len) + 1 < self.page_count(len)
+    }
+
+    pub(crate) fn prev(&mut self, len: usize) {
+        self.page = self.current(len).saturating_sub(1);
+    }
+
+    pub(crate) fn next(&mut self, len: usize) {
+        self.page = (self.current(len) + 1).min(self.page_count(len).saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn letters() -> (Letter, Vec<Letter>) {
+        (
+            Letter::new('b'),
+            "acdekl".chars().map(Letter::new).collect(),
+        )
+    }
+
+    fn valid_words() -> HashSet<Word> {
+        ["bake", "beak", "backed", "blacked"]
+            .into_iter()
+            .map(|w| Word::new(w, w == "blacked"))
+            .collect()
+    }
+
+    fn check(word: &str, submitted: &[&str]) -> Result<Word, ValidationError> {
+        let (required, others) = letters();
+        let submitted: Vec<String> = submitted.iter().map(|w| w.to_string()).collect();
+        check_word(
+            word,
+            &Alphabet::LATIN,
+            &required,
+            &others,
+            &valid_words(),
+            None,
+            &submitted,
+        )
+    }
+
+    #[wasm_bindgen_test]
+    fn accepts_valid_words() {
+        let word = check("bake", &[]).unwrap();
+        assert_eq!("bake", word.word);
+        assert!(!word.is_pangram);
+        assert_eq!(1, word.score(&ScoringRules::default()));
+    }
+
+    #[wasm_bindgen_test]
+    fn marks_pangrams() {
+        let word = check("blacked", &[]).unwrap();
+        assert!(word.is_pangram);
+        assert_eq!(14, word.score(&ScoringRules::default()));
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_invalid_guesses() {
+        assert_eq!(Err(ValidationError::TooShort), check("bak", &[]));
+        assert_eq!(
+            Err(ValidationError::AlreadyGuessed),
+            check("bake", &["bake"])
+        );
+        assert_eq!(
+            Err(ValidationError::MissingRequiredLetter),
+            check("lace", &[])
+        );
+        assert_eq!(Err(ValidationError::BadLetters), check("bike", &[]));
+        assert_eq!(Err(ValidationError::NotInList), check("blab", &[]));
+    }
+
+    #[wasm_bindgen_test]
+    fn checks_unknown_words_against_offline_filters() {
+        let (required, others) = letters();
+        let filter = WordFilter::new(["bake", "blab"].into_iter(), 3);
+
+        let word = check_word(
+            "blab",
+            &Alphabet::LATIN,
+            &required,
+            &others,
+            &HashSet::new(),
+            Some(&filter),
+            &[],
+        );
+        assert_eq!("blab", word.unwrap().word);
+        assert_eq!(
+            Err(ValidationError::NotInList),
+            check_word(
+                "bead",
+                &Alphabet::LATIN,
+                &required,
+                &others,
+                &HashSet::new(),
+                Some(&filter),
+                &[]
+            )
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn normalizes_guesses() {
+        assert_eq!("bake", check("  BAKE\n", &[]).unwrap().word);
+        assert_eq!(
+            Err(ValidationError::AlreadyGuessed),
+            check("Bake", &["bake"])
+        );
+        assert_eq!("café", normalize_guess(" Cafe\u{301} "));
+    }
+
+    #[wasm_bindgen_test]
+    fn prefills_playable_words_only() {
+        let (required, others) = letters();
+        assert_eq!(
+            Some("bake".to_owned()),
+            prefill_word(" BAKE ", &Alphabet::LATIN, &required, &others)
+        );
+        assert_eq!(
+            Some("backe".to_owned()),
+            prefill_word("bäcke", &Alphabet::GERMAN, &required, &others)
+        );
+        assert_eq!(
+            None,
+            prefill_word("bike", &Alphabet::LATIN, &required, &others)
+        );
+        assert_eq!(None, prefill_word("", &Alphabet::LATIN, &required, &others));
+    }
+
+    #[wasm_bindgen_test]
+    fn caps_input_at_longest_word() {
+        let max_len = max_word_length(&valid_words());
+        assert_eq!(7, max_len);
+        assert_eq!(0, max_word_length(&HashSet::new()));
+
+        let mut word = "blacke".to_owned();
+        assert!(push_letter(&mut word, 'd', max_len));
+        assert!(!push_letter(&mut word, 'd', max_len));
+        assert_eq!("blacked", word);
+    }
+
+    #[wasm_bindgen_test]
+    fn debounces_repeated_presses() {
+        let mut debounce = Debounce::new(100.0);
+        assert!(debounce.ready(1000.0));
+        assert!(!debounce.ready(1050.0));
+        assert!(debounce.ready(1100.0));
+        assert!(!debounce.ready(1199.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn ranks_scores() {
+        let buckets: ScoreBuckets = [
+            ("Beginner".to_owned(), 0),
+            ("Good Start".to_owned(), 2),
+            ("Moving Up".to_owned(), 5),
+            ("Good".to_owned(), 8),
+            ("Solid".to_owned(), 15),
+            ("Nice".to_owned(), 25),
+            ("Great".to_owned(), 40),
+            ("Amazing".to_owned(), 50),
+            ("Genius".to_owned(), 70),
+        ];
+        assert_eq!("Beginner", current_rank(&buckets, 0));
+        assert_eq!("Good Start", current_rank(&buckets, 4));
+        assert_eq!("Genius", current_rank(&buckets, 100));
+    }
+
+    #[wasm_bindgen_test]
+    fn daydex_is_local_midnight() {
+        let morning = js_sys::Date::new_with_year_month_day_hr_min_sec(2025, 6, 4, 9, 30, 15);
+        let evening = js_sys::Date::new_with_year_month_day_hr_min_sec(2025, 6, 4, 23, 59, 59);
+        let next_day = js_sys::Date::new_with_year_month_day(2025, 6, 5);
+        let midnight = js_sys::Date::new_with_year_month_day(2025, 6, 4);
+
+        assert_eq!(midnight.get_time() as u64, daydex(&morning));
+        assert_eq!(daydex(&morning), daydex(&evening));
+        assert_ne!(daydex(&evening), daydex(&next_day));
+    }
+
+    #[wasm_bindgen_test]
+    fn merges_remote_progress() {
+        let local = vec!["bake".to_owned()];
+        let remote = vec!["blacked".to_owned(), "bake".to_owned()];
+
+        assert_eq!(
+            Some((vec!["bake".to_owned(), "blacked".to_owned()], 15)),
+            merge_submitted(&local, remote, &valid_words(), &ScoringRules::default())
+        );
+        assert_eq!(
+            None,
+            merge_submitted(
+                &local,
+                vec!["bake".to_owned()],
+                &valid_words(),
+                &ScoringRules::default()
+            )
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn paginates_words() {
+        let words = ["a", "b", "c", "d", "e"];
+        let mut paginator = Paginator::new(2);
+
+        assert_eq!(3, paginator.page_count(words.len()));
+        assert_eq!(&["a", "b"], paginator.page_of(&words));
+        assert!(!paginator.has_prev(words.len()));
+        paginator.prev(words.len());
+        assert_eq!(0, paginator.current(words.len()));
+
+        paginator.next(words.len());
+        paginator.next(words.len());
+        assert_eq!(&["e"], paginator.page_of(&words));
+        assert!(!paginator.has_next(words.len()));
+        paginator.next(words.len());
+        assert_eq!(2, paginator.current(words.len()));
+    }
+
+    #[wasm_bindgen_test]
+    fn clamps_page_when_list_shrinks() {
+        let mut paginator = Paginator::new(2);
+        paginator.next(5);
+        paginator.next(5);
+
+        let shrunk = ["a", "b", "c"];
+        assert_eq!(1, paginator.current(shrunk.len()));
+        assert_eq!(&["c"], paginator.page_of(&shrunk));
+        paginator.prev(shrunk.len());
+        assert_eq!(&["a", "b"], paginator.page_of(&shrunk));
+    }
+
+    #[wasm_bindgen_test]
+    fn handles_empty_lists() {
+        let mut paginator = Paginator::new(2);
+        let empty: [&str; 0] = [];
+
+        assert_eq!(0, paginator.page_count(0));
+        assert!(paginator.page_of(&empty).is_empty());
+        assert!(!paginator.has_prev(0));
+        assert!(!paginator.has_next(0));
+        paginator.prev(0);
+        paginator.next(0);
+        assert_eq!(0, paginator.current(0));
+    }
+}
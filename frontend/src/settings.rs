@@ -0,0 +1,238 @@
+use accounts::Dictionary;
+use leptos::prelude::*;
+use puzzle_config::Difficulty;
+use serde::{Deserialize, Serialize};
+
+use crate::theme::{Palette, Theme};
+
+/// Player preferences persisted in local storage.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub(crate) theme: Theme,
+    pub(crate) palette: Palette,
+    pub(crate) screen_reader_mode: bool,
+    pub(crate) layout: Layout,
+    /// Opt-in consent to send anonymous error reports.
+    pub(crate) error_reporting: bool,
+    /// Which words count. Saved with the account of logged in players.
+    pub(crate) dictionary: Dictionary,
+    /// Which of the day's puzzles to play.
+    pub(crate) difficulty: Difficulty,
+}
+
+/// Arrangement of the board controls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Layout {
+    /// Swap the delete and submit buttons.
+    pub(crate) left_handed: bool,
+    /// Keep the input and buttons low on tall screens for one-handed play.
+    pub(crate) thumb_reach: bool,
+}
+
+const SETTINGS_KEY: &str = "settings";
+
+/// Load settings from local storage and share them with the rest of the app.
+pub(crate) fn provide_settings() {
+    let (settings, set_settings, _) = leptos_use::storage::use_local_storage::<
+        Settings,
+        codee::string::JsonSerdeCodec,
+    >(SETTINGS_KEY);
+    provide_context((settings, set_settings));
+}
+
+/// Read settings straight from local storage, for code that runs outside the
+/// reactive system such as the panic hook.
+pub(crate) fn load_settings() -> Settings {
+    crate::storage::get_storage()
+        .ok()
+        .and_then(|storage| storage.get(SETTINGS_KEY).ok().flatten())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn use_settings() -> (Signal<Settings>, WriteSignal<Settings>) {
+    use_context::<(Signal<Settings>, WriteSignal<Settings>)>().expect("No settings provided")
+}
+
+fn difficulty_label(difficulty: Difficulty) -> &'static str {
+    match difficulty {
+        Difficulty::Easy => "Easy: fewer, more common words",
+        Difficulty::Medium => "Medium",
+        Difficulty::Hard => "Hard: more words and pangrams",
+    }
+}
+
+#[component]
+pub(crate) fn SettingsButton() -> impl IntoView {
+    view! {
+        <button type="button" class="btn btn-ghost btn-sm" onclick="settings.showModal()">
+            settings
+        </button>
+        <SettingsModal />
+    }
+}
+
+#[component]
+pub(crate) fn SettingsModal() -> impl IntoView {
+    let (settings, set_settings) = use_settings();
+
+    view! {
+        <dialog id="settings" class="modal">
+            <section class="modal-box flex flex-col gap-4">
+                <h1 class="text-3xl">Settings</h1>
+                <label class="flex flex-col gap-1">
+                    Theme
+                    <select
+                        class="select"
+                        on:change:target=move |e| {
+                            if let Some(theme) = Theme::from_id(&e.target().value()) {
+                                set_settings.write().theme = theme;
+                            }
+                        }
+                    >
+                        {Theme::ALL
+                            .into_iter()
+                            .map(|theme| {
+                                view! {
+                                    <option
+                                        value=theme.id()
+                                        selected=move || settings.read().theme == theme
+                                    >
+                                        {theme.label()}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                <label class="flex flex-col gap-1">
+                    Color palette
+                    <select
+                        class="select"
+                        on:change:target=move |e| {
+                            if let Some(palette) = Palette::from_id(&e.target().value()) {
+                                set_settings.write().palette = palette;
+                            }
+                        }
+                    >
+                        {Palette::ALL
+                            .into_iter()
+                            .map(|palette| {
+                                view! {
+                                    <option
+                                        value=palette.id()
+                                        selected=move || settings.read().palette == palette
+                                    >
+                                        {palette.label()}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                <label class="flex flex-col gap-1">
+                    Dictionary
+                    <select
+                        class="select"
+                        on:change:target=move |e| {
+                            if let Ok(dictionary) = e.target().value().parse() {
+                                set_settings.write().dictionary = dictionary;
+                            }
+                        }
+                    >
+                        <option
+                            value=Dictionary::Permissive.as_str()
+                            selected=move || settings.read().dictionary == Dictionary::Permissive
+                        >
+                            "Permissive: every word in the list"
+                        </option>
+                        <option
+                            value=Dictionary::Strict.as_str()
+                            selected=move || settings.read().dictionary == Dictionary::Strict
+                        >
+                            "Strict: common words only"
+                        </option>
+                    </select>
+                </label>
+                <label class="flex flex-col gap-1">
+                    Difficulty
+                    <select
+                        class="select"
+                        on:change:target=move |e| {
+                            if let Ok(difficulty) = e.target().value().parse() {
+                                set_settings.write().difficulty = difficulty;
+                            }
+                        }
+                    >
+                        {Difficulty::ALL
+                            .into_iter()
+                            .map(|difficulty| {
+                                view! {
+                                    <option
+                                        value=difficulty.as_str()
+                                        selected=move || settings.read().difficulty == difficulty
+                                    >
+                                        {difficulty_label(difficulty)}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                <label class="label">
+                    <input
+                        type="checkbox"
+                        class="toggle"
+                        prop:checked=move || settings.read().screen_reader_mode
+                        on:change:target=move |e| {
+                            set_settings.write().screen_reader_mode = e.target().checked();
+                        }
+                    />
+                    Screen reader mode
+                </label>
+                <label class="label">
+                    <input
+                        type="checkbox"
+                        class="toggle"
+                        prop:checked=move || settings.read().layout.left_handed
+                        on:change:target=move |e| {
+                            set_settings.write().layout.left_handed = e.target().checked();
+                        }
+                    />
+                    Left-handed buttons
+                </label>
+                <label class="label">
+                    <input
+                        type="checkbox"
+                        class="toggle"
+                        prop:checked=move || settings.read().layout.thumb_reach
+                        on:change:target=move |e| {
+                            set_settings.write().layout.thumb_reach = e.target().checked();
+                        }
+                    />
+                    Thumb-reach layout
+                </label>
+                <label class="label">
+                    <input
+                        type="checkbox"
+                        class="toggle"
+                        prop:checked=move || settings.read().error_reporting
+                        on:change:target=move |e| {
+                            set_settings.write().error_reporting = e.target().checked();
+                        }
+                    />
+                    Send anonymous error reports
+                </label>
+                <div class="modal-action">
+                    <form method="dialog">
+                        <button type="submit" class="btn btn-primary">
+                            close
+                        </button>
+                    </form>
+                </div>
+            </section>
+        </dialog>
+    }
+}
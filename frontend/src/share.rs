@@ -0,0 +1,165 @@
+//! Spoiler-free summaries of a player's progress for sharing, like:
+//!
+//! ```text
+//! Bee 2025-06-01
+//! Great
+//! 🟨🟨🟨🟨🟨🟨🟨⬜⬜
+//! Words: 23/54
+//! Pangrams: 1/2
+//! ```
+
+use leptos::prelude::*;
+use puzzle_config::ScoreBuckets;
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::rules::current_rank;
+use crate::toast::{ToastKind, use_toasts};
+
+const REACHED: &str = "🟨";
+const UNREACHED: &str = "⬜";
+
+/// What the summary reveals about the player's progress.
+#[derive(Debug, Clone, PartialEq)]
+struct Summary {
+    date: String,
+    score: u32,
+    words_found: usize,
+    words: usize,
+    pangrams_found: usize,
+    pangrams: usize,
+}
+
+impl Summary {
+    fn text(&self, buckets: &ScoreBuckets) -> String {
+        let bar: String = buckets
+            .iter()
+            .map(|(_, threshold)| {
+                if self.score >= *threshold {
+                    REACHED
+                } else {
+                    UNREACHED
+                }
+            })
+            .collect();
+        format!(
+            "Bee {}\n{}\n{}\nWords: {}/{}\nPangrams: {}/{}",
+            self.date,
+            current_rank(buckets, self.score),
+            bar,
+            self.words_found,
+            self.words,
+            self.pangrams_found,
+            self.pangrams,
+        )
+    }
+}
+
+/// The `YYYY-MM-DD` date of the day starting at `day`, in milliseconds since
+/// the epoch as returned by [`crate::game::day_64`].
+fn day_date(day: u64) -> String {
+    let date = js_sys::Date::new(&(day as f64).into());
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date()
+    )
+}
+
+/// Copy the summary of today's puzzle to the clipboard.
+#[component]
+pub(crate) fn ShareButton(
+    buckets: ScoreBuckets,
+    /// Every word of the puzzle and whether it's a pangram.
+    words: Vec<(String, bool)>,
+) -> impl IntoView {
+    let (score, _) =
+        use_context::<(Signal<u32>, WriteSignal<u32>)>().expect("No writable score provided");
+    let (submitted, _) = use_context::<(Signal<Vec<String>>, WriteSignal<Vec<String>>)>()
+        .expect("No writable submittion list provided");
+    let toasts = use_toasts();
+    let buckets = StoredValue::new(buckets);
+    let words = StoredValue::new(words);
+
+    let share = move |_| {
+        let summary = words.with_value(|words| {
+            let submitted = submitted.read_untracked();
+            let found = words.iter().filter(|(word, _)| submitted.contains(word));
+            Summary {
+                date: day_date(crate::game::day_64()),
+                score: score.get_untracked(),
+                words_found: found.clone().count(),
+                words: words.len(),
+                pangrams_found: found.filter(|(_, is_pangram)| *is_pangram).count(),
+                pangrams: words.iter().filter(|(_, is_pangram)| *is_pangram).count(),
+            }
+        });
+        let text = buckets.with_value(|buckets| summary.text(buckets));
+        leptos::task::spawn_local(async move {
+            match copy_to_clipboard(&text).await {
+                Ok(()) => toasts.show(ToastKind::Success, "Results copied to the clipboard."),
+                Err(e) => {
+                    leptos::logging::warn!("failed to copy results: {}", e);
+                    toasts.show(
+                        ToastKind::Warning,
+                        "Couldn't copy results to the clipboard.",
+                    );
+                }
+            }
+        });
+    };
+
+    view! {
+        <button type="button" class="btn btn-ghost btn-sm" on:click=share>
+            share
+        </button>
+    }
+}
+
+/// Write `text` to the clipboard through `navigator.clipboard`, which is
+/// missing outside secure contexts.
+pub(crate) async fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let navigator = web_sys::window().ok_or("no window")?.navigator();
+    let clipboard = js_sys::Reflect::get(&navigator, &"clipboard".into())
+        .ok()
+        .filter(|clipboard| !clipboard.is_undefined())
+        .ok_or("clipboard unavailable")?;
+    let write_text = js_sys::Reflect::get(&clipboard, &"writeText".into())
+        .ok()
+        .and_then(|write_text| write_text.dyn_into::<js_sys::Function>().ok())
+        .ok_or("clipboard can't write text")?;
+    let promise = write_text
+        .call1(&clipboard, &text.into())
+        .map_err(|e| format!("{:?}", e))?
+        .dyn_into::<js_sys::Promise>()
+        .map_err(|e| format!("{:?}", e))?;
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("{:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn summarizes_progress_without_spoilers() {
+        let buckets: ScoreBuckets = std::array::from_fn(|i| (format!("Rank {}", i), i as u32 * 10));
+        let summary = Summary {
+            date: "2025-06-01".to_owned(),
+            score: 35,
+            words_found: 12,
+            words: 40,
+            pangrams_found: 1,
+            pangrams: 2,
+        };
+
+        assert_eq!(
+            "Bee 2025-06-01\nRank 3\n🟨🟨🟨🟨⬜⬜⬜⬜⬜\nWords: 12/40\nPangrams: 1/2",
+            summary.text(&buckets)
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Statistics on the words a player has found so far.
+
+use std::collections::{BTreeMap, HashSet};
+
+use leptos::prelude::*;
+use puzzle_config::{ScoreBuckets, Word};
+
+/// Progress through a puzzle, derived from its words and those submitted.
+#[derive(Debug, Clone, PartialEq)]
+struct Stats {
+    words_found: usize,
+    words: usize,
+    pangrams_found: usize,
+    pangrams: usize,
+    /// Found and total words by length, shortest first.
+    by_length: Vec<(usize, usize, usize)>,
+}
+
+impl Stats {
+    fn new(valid_words: &HashSet<Word>, submitted: &[String]) -> Self {
+        let mut by_length = BTreeMap::<usize, (usize, usize)>::new();
+        let (mut words_found, mut pangrams_found, mut pangrams) = (0, 0, 0);
+        for word in valid_words {
+            let found = submitted.contains(&word.word);
+            let (length_found, length_total) = by_length.entry(word.len()).or_default();
+            *length_total += 1;
+            if found {
+                *length_found += 1;
+                words_found += 1;
+            }
+            if word.is_pangram {
+                pangrams += 1;
+                pangrams_found += usize::from(found);
+            }
+        }
+
+        Self {
+            words_found,
+            words: valid_words.len(),
+            pangrams_found,
+            pangrams,
+            by_length: by_length
+                .into_iter()
+                .map(|(length, (found, total))| (length, found, total))
+                .collect(),
+        }
+    }
+}
+
+/// Points needed to reach the rank after the one `score` is at, and its name,
+/// or `None` at the top rank.
+fn next_rank(buckets: &ScoreBuckets, score: u32) -> Option<(String, u32)> {
+    buckets
+        .iter()
+        .find(|(_, threshold)| *threshold > score)
+        .map(|(label, threshold)| (label.clone(), threshold - score))
+}
+
+#[component]
+pub(crate) fn StatsButton(valid_words: HashSet<Word>, buckets: ScoreBuckets) -> impl IntoView {
+    view! {
+        <button type="button" class="btn btn-ghost btn-sm" onclick="stats.showModal()">
+            stats
+        </button>
+        <StatsModal valid_words buckets />
+    }
+}
+
+#[component]
+fn StatsModal(valid_words: HashSet<Word>, buckets: ScoreBuckets) -> impl IntoView {
+    let (score, _) =
+        use_context::<(Signal<u32>, WriteSignal<u32>)>().expect("No writable score provided");
+    let (submitted, _) = use_context::<(Signal<Vec<String>>, WriteSignal<Vec<String>>)>()
+        .expect("No writable submittion list provided");
+    let (valid_words, _) = signal(valid_words);
+    let (buckets, _) = signal(buckets);
+    let stats = Memo::new(move |_| Stats::new(&valid_words.read(), &submitted.read()));
+    let upcoming = Memo::new(move |_| next_rank(&buckets.read(), score.get()));
+
+    view! {
+        <dialog id="stats" class="modal">
+            <section class="modal-box flex flex-col gap-4">
+                <h1 class="text-3xl">Stats</h1>
+                <dl class="grid grid-cols-2 gap-2">
+                    <dt>words found</dt>
+                    <dd>{move || format!("{} of {}", stats.read().words_found, stats.read().words)}</dd>
+                    <dt>pangrams found</dt>
+                    <dd>
+                        {move || format!("{} of {}", stats.read().pangrams_found, stats.read().pangrams)}
+                    </dd>
+                    <dt>next rank</dt>
+                    <dd>
+                        {move || match upcoming.get() {
+                            Some((rank, points)) => format!("{} points to {}", points, rank),
+                            None => "Top rank reached".to_owned(),
+                        }}
+                    </dd>
+                </dl>
+                <table class="table table-xs text-center">
+                    <thead>
+                        <tr>
+                            <th>length</th>
+                            <th>found</th>
+                            <th>total</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || stats.read().by_length.clone()
+                            key=|row| *row
+                            children=|(length, found, total)| {
+                                view! {
+                                    <tr>
+                                        <th>{length}</th>
+                                        <td>{found}</td>
+                                        <td>{total}</td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+                <div class="modal-action">
+                    <form method="dialog">
+                        <button type="submit" class="btn btn-primary">
+                            close
+                        </button>
+                    </form>
+                </div>
+            </section>
+        </dialog>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn counts_found_words_by_length() {
+        let valid_words: HashSet<Word> = [
+            ("bake", false),
+            ("beak", false),
+            ("backed", false),
+            ("blacked", true),
+        ]
+        .into_iter()
+        .map(|(word, is_pangram)| Word::new(word, is_pangram))
+        .collect();
+        let submitted = vec!["beak".to_owned(), "blacked".to_owned()];
+
+        assert_eq!(
+            Stats {
+                words_found: 2,
+                words: 4,
+                pangrams_found: 1,
+                pangrams: 1,
+                by_length: vec![(4, 1, 2), (6, 0, 1), (7, 1, 1)],
+            },
+            Stats::new(&valid_words, &submitted)
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn finds_points_to_next_rank() {
+        let buckets: ScoreBuckets = std::array::from_fn(|i| (format!("Rank {}", i), i as u32 * 10));
+
+        assert_eq!(Some(("Rank 4".to_owned(), 5)), next_rank(&buckets, 35));
+        assert_eq!(Some(("Rank 1".to_owned(), 10)), next_rank(&buckets, 0));
+        assert_eq!(None, next_rank(&buckets, 80));
+    }
+}
@@ -0,0 +1,200 @@
+//! Puzzle configs saved in local storage for playing offline.
+//!
+//! Only an [`OfflinePuzzleConfig`] is saved, so that the day's words can't be
+//! read from storage. It's stored as deflated JSON encoded with base64, which
+//! takes a fraction of the space of the JSON as days accumulate. Entries
+//! written as plain JSON or with every word by older versions are still read.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use accounts::Dictionary;
+use base64::Engine as _;
+use puzzle_config::{Difficulty, OfflinePuzzleConfig, PuzzleConfig};
+
+use crate::game::{AppError, day_64};
+
+/// Marks compressed entries, and the format they're in, so that other entries
+/// can be read as JSON.
+const COMPRESSED_PREFIX: &str = "z1:";
+
+/// Decompressed configs larger than this are treated as corrupt.
+const MAX_CONFIG_BYTES: usize = 4 * 1024 * 1024;
+
+thread_local! {
+    /// Saved configs being played because the server couldn't be reached, by
+    /// storage key.
+    static PLAYING: RefCell<HashMap<String, OfflinePuzzleConfig>> = RefCell::new(HashMap::new());
+}
+
+pub(crate) fn store_config(
+    config: &PuzzleConfig,
+    dictionary: Dictionary,
+    difficulty: Difficulty,
+) -> Result<(), AppError> {
+    let storage = get_storage()?;
+    let salt = (js_sys::Math::random() * u64::MAX as f64) as u64;
+    let data = encode_config(&OfflinePuzzleConfig::new(config, salt))?;
+    let key = config_key(dictionary, difficulty);
+    PLAYING.with_borrow_mut(|playing| playing.remove(&key));
+    storage.set(&key, &data).map_err(AppError::from)
+}
+
+pub(crate) fn load_config(
+    dictionary: Dictionary,
+    difficulty: Difficulty,
+) -> Option<OfflinePuzzleConfig> {
+    let storage = get_storage().ok()?;
+    let data = storage
+        .get(&config_key(dictionary, difficulty))
+        .ok()
+        .flatten()?;
+
+    decode_config(&data)
+}
+
+/// Play the saved config, if there is one. The puzzle comes without words;
+/// guesses are checked against the filter from [`playing_offline`] instead.
+pub(crate) fn play_offline(dictionary: Dictionary, difficulty: Difficulty) -> Option<PuzzleConfig> {
+    let offline = load_config(dictionary, difficulty)?;
+    let config = PuzzleConfig {
+        score_buckets: offline.score_buckets.clone(),
+        scoring: offline.scoring.clone(),
+        alphabet: offline.alphabet,
+        required_letter: offline.required_letter.clone(),
+        other_letters: offline.other_letters.clone(),
+        valid_words: Default::default(),
+    };
+    PLAYING.with_borrow_mut(|playing| playing.insert(config_key(dictionary, difficulty), offline));
+    Some(config)
+}
+
+/// The saved config being played since the server couldn't be reached, if
+/// it is.
+pub(crate) fn playing_offline(
+    dictionary: Dictionary,
+    difficulty: Difficulty,
+) -> Option<OfflinePuzzleConfig> {
+    PLAYING.with_borrow(|playing| playing.get(&config_key(dictionary, difficulty)).cloned())
+}
+
+pub(crate) fn get_storage() -> Result<web_sys::Storage, AppError> {
+    let window =
+        web_sys::window().ok_or_else(|| AppError::ConfigLoadError("Window unavailable".into()))?;
+    window
+        .local_storage()
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::ConfigLoadError("Local storage unavailable".into()))
+}
+
+/// Configs served for the strict dictionary or a difficulty other than medium
+/// are stored apart from the full medium ones, which keep the key they always
+/// had.
+pub(crate) fn config_key(dictionary: Dictionary, difficulty: Difficulty) -> String {
+    let mut key = match dictionary {
+        Dictionary::Permissive => format!("puzzle-storage/{}", day_64()),
+        Dictionary::Strict => format!("puzzle-storage/{}/strict", day_64()),
+    };
+    if difficulty != Difficulty::Medium {
+        key.push('/');
+        key.push_str(difficulty.as_str());
+    }
+    key
+}
+
+fn encode_config(config: &OfflinePuzzleConfig) -> Result<String, AppError> {
+    let json = serde_json::to_vec(config).map_err(|e| AppError::ConfigLoadError(e.to_string()))?;
+    let compressed = miniz_oxide::deflate::compress_to_vec(&json, 6);
+    Ok(format!(
+        "{}{}",
+        COMPRESSED_PREFIX,
+        base64::engine::general_purpose::STANDARD_NO_PAD.encode(compressed)
+    ))
+}
+
+/// Read a stored config, compressed or not. Entries that can't be read are
+/// ignored so that the config is fetched again.
+fn decode_config(data: &str) -> Option<OfflinePuzzleConfig> {
+    let Some(encoded) = data.strip_prefix(COMPRESSED_PREFIX) else {
+        return parse_config(data.as_bytes());
+    };
+    let compressed = base64::engine::general_purpose::STANDARD_NO_PAD
+        .decode(encoded)
+        .ok()?;
+    let json =
+        miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_CONFIG_BYTES).ok()?;
+    parse_config(&json)
+}
+
+/// Parse an offline config, or a config with every word as older versions
+/// stored, which is filtered as it would be now.
+fn parse_config(json: &[u8]) -> Option<OfflinePuzzleConfig> {
+    serde_json::from_slice(json).ok().or_else(|| {
+        let config: PuzzleConfig = serde_json::from_slice(json).ok()?;
+        Some(OfflinePuzzleConfig::new(&config, 0))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use puzzle_config::{Letter, Word};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn config() -> PuzzleConfig {
+        let valid_words = ["bake", "beak", "backed", "blacked", "blackballed"]
+            .into_iter()
+            .map(|w| Word::new(w, w == "blacked"))
+            .collect();
+        PuzzleConfig {
+            score_buckets: std::array::from_fn(|i| (format!("rank {}", i), i as u32)),
+            scoring: Default::default(),
+            alphabet: Default::default(),
+            required_letter: Letter::new('b'),
+            other_letters: "acdekl".chars().map(Letter::new).collect(),
+            valid_words,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn round_trips_compressed_configs() {
+        let offline = OfflinePuzzleConfig::new(&config(), 5);
+        let encoded = encode_config(&offline).unwrap();
+
+        assert!(encoded.starts_with(COMPRESSED_PREFIX));
+        assert_eq!(Some(offline), decode_config(&encoded));
+    }
+
+    #[wasm_bindgen_test]
+    fn reads_json_configs() {
+        let offline = OfflinePuzzleConfig::new(&config(), 5);
+        let json = serde_json::to_string(&offline).unwrap();
+        assert_eq!(Some(offline), decode_config(&json));
+    }
+
+    #[wasm_bindgen_test]
+    fn filters_configs_stored_with_their_words() {
+        let json = serde_json::to_string(&config()).unwrap();
+        let offline = decode_config(&json).unwrap();
+
+        assert_eq!(5, offline.word_count);
+        assert!(offline.word("blacked").is_some());
+        assert!(offline.word("cake").is_none());
+
+        let compressed = miniz_oxide::deflate::compress_to_vec(json.as_bytes(), 6);
+        let encoded = format!(
+            "{}{}",
+            COMPRESSED_PREFIX,
+            base64::engine::general_purpose::STANDARD_NO_PAD.encode(compressed)
+        );
+        assert_eq!(Some(offline), decode_config(&encoded));
+    }
+
+    #[wasm_bindgen_test]
+    fn ignores_corrupt_entries() {
+        assert_eq!(None, decode_config("z1:not base64!"));
+        assert_eq!(None, decode_config("z1:AAAA"));
+        assert_eq!(None, decode_config("{"));
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{HashMap, HashSet};
+
+use codee::string::JsonSerdeCodec;
+use leptos::prelude::*;
+use leptos_router::{
+    hooks::{use_navigate, use_params, use_query},
+    params::Params,
+};
+use leptos_use::{UseWebSocketReturn, core::ConnectionReadyState, use_websocket};
+use puzzle_config::{TeamEvent, TeamSubmission, TeamWord};
+
+use crate::game::{Puzzle, daily_key, day_64, get_current_tz, load};
+use crate::settings::use_settings;
+use crate::toast::{ToastKind, use_toasts};
+
+#[derive(Debug, PartialEq, Params, Clone)]
+struct TeamParams {
+    team: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Params, Clone)]
+struct TeamQuery {
+    name: Option<String>,
+}
+
+/// Who found each word of a team's list, shown next to the words in
+/// [`crate::game::GuessedWords`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Contributors(pub(crate) Signal<HashMap<String, String>>);
+
+/// Pick a team to find today's words with, and the name teammates see.
+#[component]
+pub(crate) fn TeamLobby() -> impl IntoView {
+    let (team, set_team) = signal(random_team());
+    let (name, set_name) = signal(String::new());
+    let navigate = use_navigate();
+
+    let submit = move |e: web_sys::SubmitEvent| {
+        e.prevent_default();
+        let name = String::from(js_sys::encode_uri_component(&name.get()));
+        navigate(
+            &format!("/team/{}?name={}", team.get(), name),
+            Default::default(),
+        );
+    };
+
+    view! {
+        <main class="container p-4 flex flex-col items-center gap-4">
+            <h1 class="text-3xl">"Play as a team"</h1>
+            <p>"Everyone joining the same team shares one list of found words."</p>
+            <form on:submit=submit class="flex flex-col gap-2 w-full max-w-xs">
+                <label class="flex flex-col gap-1">
+                    "team"
+                    <input
+                        type="text"
+                        class="input w-full"
+                        maxlength="32"
+                        pattern="[A-Za-z0-9\\-]+"
+                        required
+                        bind:value=(team, set_team)
+                    />
+                </label>
+                <label class="flex flex-col gap-1">
+                    "your name"
+                    <input
+                        type="text"
+                        class="input w-full"
+                        maxlength="20"
+                        required
+                        bind:value=(name, set_name)
+                    />
+                </label>
+                <button type="submit" class="btn btn-primary">
+                    "join"
+                </button>
+            </form>
+        </main>
+    }
+}
+
+/// Find today's words together with the rest of a team. Words found by anyone
+/// are added to everyone's list, and the team's progress is stored apart from
+/// the player's own.
+#[component]
+pub(crate) fn Team() -> impl IntoView {
+    let team = use_params::<TeamParams>()
+        .get_untracked()
+        .ok()
+        .and_then(|p| p.team)
+        .unwrap_or_default();
+    let name = use_query::<TeamQuery>()
+        .get_untracked()
+        .ok()
+        .and_then(|q| q.name)
+        .unwrap_or_default();
+
+    let (settings, _) = use_settings();
+    let difficulty = settings.get_untracked().difficulty;
+    let storage_key = format!("team/{}/{}", team, daily_key(day_64(), difficulty));
+    let config = LocalResource::new(move || load(settings.read().dictionary, difficulty));
+
+    // The same progress the puzzle below stores, so teammates' words can be
+    // added to it.
+    let (_, set_score, _) = leptos_use::storage::use_local_storage::<u32, JsonSerdeCodec>(format!(
+        "{}/score",
+        storage_key
+    ));
+    let (submitted, set_submitted, _) = leptos_use::storage::use_local_storage::<
+        Vec<String>,
+        JsonSerdeCodec,
+    >(format!("{}/submitted", storage_key));
+
+    // A team plays the puzzle of the player who started it, which is the same
+    // one here as long as teammates share a timezone and difficulty.
+    let tz = get_current_tz().unwrap_or_else(|_| "+00:00".to_owned());
+    let UseWebSocketReturn {
+        ready_state,
+        message,
+        send,
+        ..
+    } = use_websocket::<TeamSubmission, TeamEvent, JsonSerdeCodec>(&crate::api::websocket_url(
+        &format!(
+            "/api/puzzle/daily/teams/{}?name={}&tz={}&difficulty={}",
+            team,
+            String::from(js_sys::encode_uri_component(&name)),
+            String::from(js_sys::encode_uri_component(&tz)),
+            difficulty.as_str()
+        ),
+    ));
+
+    let team_words = RwSignal::new(Vec::<TeamWord>::new());
+    let contributors = RwSignal::new(HashMap::<String, String>::new());
+    let players = RwSignal::new(Vec::<String>::new());
+    provide_context(Contributors(contributors.into()));
+    let toasts = use_toasts();
+
+    Effect::new(move |_| {
+        let Some(event) = message.get() else {
+            return;
+        };
+        let credit = |words: &[TeamWord]| {
+            contributors.update(|contributors| {
+                for word in words {
+                    contributors.insert(word.word.clone(), word.player.clone());
+                }
+            });
+        };
+        match event {
+            TeamEvent::Sync { words } => {
+                credit(&words);
+                team_words.set(words);
+            }
+            TeamEvent::Players { players: names } => players.set(names),
+            TeamEvent::Found(word) => {
+                credit(std::slice::from_ref(&word));
+                team_words.update(|words| words.push(word));
+            }
+            TeamEvent::Taken(word) => {
+                toasts.show(
+                    ToastKind::Info,
+                    format!("{} found {} first.", word.player, word.word),
+                );
+                credit(&[word]);
+            }
+        }
+    });
+
+    // Add the words teammates found to the list, once there's a puzzle to
+    // score them against.
+    Effect::new(move |_| {
+        config.with(|config| {
+            let Some(Ok(config)) = config else {
+                return;
+            };
+            let missing: Vec<String> = team_words.with(|words| {
+                submitted.with(|submitted| {
+                    words
+                        .iter()
+                        .map(|word| &word.word)
+                        .filter(|word| !submitted.contains(word))
+                        .cloned()
+                        .collect()
+                })
+            });
+            let missing: Vec<String> = missing
+                .into_iter()
+                .filter(|word| config.valid_words.iter().any(|w| &w.word == word))
+                .collect();
+            if missing.is_empty() {
+                return;
+            }
+
+            set_submitted.update(|submitted| submitted.extend(missing));
+            let score = submitted.with_untracked(|submitted| {
+                config
+                    .valid_words
+                    .iter()
+                    .filter(|word| submitted.contains(&word.word))
+                    .map(|word| word.score(&config.scoring))
+                    .sum()
+            });
+            set_score.set(score);
+        });
+    });
+
+    // Send the team the words found here that it doesn't know of yet, once
+    // per connection.
+    let sent = StoredValue::new(HashSet::<String>::new());
+    Effect::new(move |_| {
+        if ready_state.get() != ConnectionReadyState::Open {
+            sent.update_value(|sent| sent.clear());
+            return;
+        }
+        let unknown: Vec<String> = contributors.with(|contributors| {
+            submitted
+                .read()
+                .iter()
+                .filter(|word| !contributors.contains_key(*word))
+                .filter(|word| !sent.with_value(|sent| sent.contains(*word)))
+                .cloned()
+                .collect()
+        });
+        for word in unknown {
+            sent.update_value(|sent| {
+                sent.insert(word.clone());
+            });
+            send(&TeamSubmission { word });
+        }
+    });
+
+    view! {
+        <div class="container px-4 pt-4 flex flex-col gap-2">
+            <h1 class="text-xl font-bold">{format!("Team {}", team)}</h1>
+            <p aria-live="polite">
+                {move || {
+                    if ready_state.get() == ConnectionReadyState::Closed {
+                        "Disconnected from the team.".to_owned()
+                    } else {
+                        format!("Playing with {}", players.read().join(", "))
+                    }
+                }}
+            </p>
+        </div>
+        <Puzzle storage_key config />
+    }
+}
+
+/// A team name unlikely to be taken, to share with teammates.
+fn random_team() -> String {
+    let n = (js_sys::Math::random() * (1u64 << 32) as f64) as u64;
+    format!("hive-{:x}", n)
+}
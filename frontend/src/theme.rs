@@ -0,0 +1,217 @@
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use web_sys::wasm_bindgen::JsCast as _;
+
+use crate::settings::use_settings;
+
+/// daisyUI theme of the app. `System` follows the `prefers-color-scheme` media
+/// query, which the stylesheet maps to the light and dark themes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub(crate) const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Theme::System => "Same as the system",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+        }
+    }
+
+    pub(crate) fn id(&self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    pub(crate) fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.id() == id)
+    }
+
+    /// Whether the theme is dark, given whether the system prefers dark themes.
+    fn is_dark(self, prefers_dark: bool) -> bool {
+        match self {
+            Theme::System => prefers_dark,
+            Theme::Light => false,
+            Theme::Dark => true,
+        }
+    }
+
+    /// The opposite of how the theme looks, which the header toggle switches to.
+    fn toggled(self, prefers_dark: bool) -> Self {
+        if self.is_dark(prefers_dark) {
+            Theme::Light
+        } else {
+            Theme::Dark
+        }
+    }
+}
+
+/// Set the theme's `data-theme` attribute on the document root, removing it to
+/// follow the system.
+pub(crate) fn apply_theme(theme: Theme) {
+    let Some(root) = document().document_element() else {
+        leptos::logging::error!("Document root unavailable, cannot apply theme");
+        return;
+    };
+    let result = match theme {
+        Theme::System => root.remove_attribute("data-theme"),
+        _ => root.set_attribute("data-theme", theme.id()),
+    };
+    if let Err(e) = result {
+        leptos::logging::error!("Failed to apply theme: {:?}", e);
+    }
+}
+
+/// Whether the system prefers dark themes, through `matchMedia`.
+fn prefers_dark() -> bool {
+    let window = window();
+    js_sys::Reflect::get(&window, &"matchMedia".into())
+        .ok()
+        .and_then(|match_media| match_media.dyn_into::<js_sys::Function>().ok())
+        .and_then(|match_media| {
+            match_media
+                .call1(&window, &"(prefers-color-scheme: dark)".into())
+                .ok()
+        })
+        .and_then(|query| js_sys::Reflect::get(&query, &"matches".into()).ok())
+        .and_then(|matches| matches.as_bool())
+        .unwrap_or(false)
+}
+
+/// Header button switching between the light and dark themes.
+#[component]
+pub(crate) fn ThemeToggle() -> impl IntoView {
+    let (settings, set_settings) = use_settings();
+    let target = move || settings.read().theme.toggled(prefers_dark());
+
+    view! {
+        <button
+            type="button"
+            class="btn btn-ghost btn-sm"
+            aria-label=move || format!("switch to {} theme", target().id())
+            on:click=move |_| {
+                let theme = target();
+                set_settings.write().theme = theme;
+            }
+        >
+            {move || target().id()}
+        </button>
+    }
+}
+
+/// Colors used for the letter grid and score segments.
+///
+/// `Default` leaves the stylesheet's daisyUI accent colors in place; the other
+/// palettes are drawn from the Okabe-Ito set so the required letter, the other
+/// letters and filled score segments stay distinguishable for each type of
+/// color vision deficiency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Palette {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl Palette {
+    pub(crate) const ALL: [Palette; 4] = [
+        Palette::Default,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::Tritanopia,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Palette::Default => "Default",
+            Palette::Deuteranopia => "Deuteranopia (green-weak)",
+            Palette::Protanopia => "Protanopia (red-weak)",
+            Palette::Tritanopia => "Tritanopia (blue-weak)",
+        }
+    }
+
+    pub(crate) fn id(&self) -> &'static str {
+        match self {
+            Palette::Default => "default",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+            Palette::Tritanopia => "tritanopia",
+        }
+    }
+
+    pub(crate) fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.id() == id)
+    }
+
+    /// Values for the `--letter-required-color`, `--letter-other-color` and
+    /// `--segment-filled-color` CSS variables, or `None` to use the stylesheet defaults.
+    fn colors(&self) -> Option<[&'static str; 3]> {
+        match self {
+            Palette::Default => None,
+            Palette::Deuteranopia => Some(["#e69f00", "#56b4e9", "#0072b2"]),
+            Palette::Protanopia => Some(["#f0e442", "#56b4e9", "#0072b2"]),
+            Palette::Tritanopia => Some(["#d55e00", "#9ad0c2", "#cc79a7"]),
+        }
+    }
+}
+
+const PALETTE_VARIABLES: [&str; 3] = [
+    "--letter-required-color",
+    "--letter-other-color",
+    "--segment-filled-color",
+];
+
+/// Set the palette's CSS variables on the document root.
+pub(crate) fn apply_palette(palette: Palette) {
+    let Some(root) = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.document_element())
+        .and_then(|e| web_sys::wasm_bindgen::JsCast::dyn_into::<web_sys::HtmlElement>(e).ok())
+    else {
+        leptos::logging::error!("Document root unavailable, cannot apply palette");
+        return;
+    };
+
+    let style = root.style();
+    let result = match palette.colors() {
+        Some(colors) => PALETTE_VARIABLES
+            .iter()
+            .zip(colors)
+            .try_for_each(|(var, color)| style.set_property(var, color)),
+        None => PALETTE_VARIABLES
+            .iter()
+            .try_for_each(|var| style.remove_property(var).map(|_| ())),
+    };
+    if let Err(e) = result {
+        leptos::logging::error!("Failed to apply palette: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn toggles_to_the_opposite_of_how_the_theme_looks() {
+        assert_eq!(Theme::Dark, Theme::System.toggled(false));
+        assert_eq!(Theme::Light, Theme::System.toggled(true));
+        assert_eq!(Theme::Dark, Theme::Light.toggled(true));
+        assert_eq!(Theme::Light, Theme::Dark.toggled(false));
+        for theme in Theme::ALL {
+            assert_eq!(Some(theme), Theme::from_id(theme.id()));
+        }
+    }
+}
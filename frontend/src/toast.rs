@@ -0,0 +1,111 @@
+//! Short-lived notifications stacked in a corner of the screen.
+
+use std::time::Duration;
+
+use leptos::prelude::*;
+
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ToastKind {
+    Info,
+    Success,
+    Warning,
+}
+
+impl ToastKind {
+    fn class(self) -> &'static str {
+        match self {
+            Self::Info => "alert alert-info",
+            Self::Success => "alert alert-success",
+            Self::Warning => "alert alert-warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Toast {
+    id: u64,
+    kind: ToastKind,
+    message: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Toasts {
+    toasts: RwSignal<Vec<Toast>>,
+    next_id: StoredValue<u64>,
+}
+
+impl Toasts {
+    /// Show `message` for a few seconds.
+    pub(crate) fn show(&self, kind: ToastKind, message: impl Into<String>) {
+        let id = self
+            .next_id
+            .try_update_value(|id| {
+                *id += 1;
+                *id
+            })
+            .unwrap_or_default();
+        self.toasts.update(|toasts| {
+            toasts.push(Toast {
+                id,
+                kind,
+                message: message.into(),
+            })
+        });
+
+        let toasts = self.toasts;
+        set_timeout(
+            move || toasts.update(|toasts| toasts.retain(|t| t.id != id)),
+            TOAST_DURATION,
+        );
+    }
+}
+
+pub(crate) fn provide_toasts() {
+    provide_context(Toasts {
+        toasts: RwSignal::new(Vec::new()),
+        next_id: StoredValue::new(0),
+    });
+}
+
+pub(crate) fn use_toasts() -> Toasts {
+    use_context::<Toasts>().expect("No toasts provided")
+}
+
+#[component]
+pub(crate) fn ToastContainer() -> impl IntoView {
+    let Toasts { toasts, .. } = use_toasts();
+
+    view! {
+        <div class="toast toast-top toast-center z-50" role="status" aria-live="polite">
+            <For each=move || toasts.get() key=|toast| toast.id let(toast)>
+                <div class=toast.kind.class()>
+                    <span>{toast.message}</span>
+                </div>
+            </For>
+        </div>
+    }
+}
+
+/// Tell the player when the app starts or stops falling back on saved data
+/// because the server is failing.
+pub(crate) fn announce_degraded_mode() {
+    let toasts = use_toasts();
+    let degraded = crate::api::degraded();
+    Effect::watch(
+        move || degraded.get(),
+        move |degraded, _, _| {
+            if *degraded {
+                toasts.show(
+                    ToastKind::Warning,
+                    "Having trouble reaching the server. Showing saved data.",
+                );
+            } else {
+                toasts.show(ToastKind::Success, "Reconnected to the server.");
+            }
+        },
+        false,
+    );
+}
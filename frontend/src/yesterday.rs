@@ -0,0 +1,118 @@
+use accounts::Dictionary;
+use leptos::prelude::*;
+use puzzle_config::{Answers, Difficulty};
+
+use crate::game::{AppError, daily_key, get_current_tz};
+use crate::rules::daydex;
+use crate::settings::use_settings;
+
+/// Every word of yesterday's daily puzzle, with the ones the player missed
+/// highlighted.
+#[component]
+pub(crate) fn Yesterday() -> impl IntoView {
+    let (settings, _) = use_settings();
+    let difficulty = settings.get_untracked().difficulty;
+    let answers = LocalResource::new(move || fetch_answers(settings.read().dictionary, difficulty));
+    let submitted = yesterdays_submitted(difficulty);
+
+    view! {
+        <div class="container p-4 flex flex-col gap-4">
+            <h1 class="text-3xl">"Yesterday's answers"</h1>
+            <Suspense fallback=move || view! { <p>"Loading ..."</p> }>
+                {move || {
+                    let submitted = submitted.clone();
+                    Suspend::new(async move {
+                        match answers.await {
+                            Ok(answers) => {
+                                leptos::either::Either::Left(view! { <AnswerList answers submitted /> })
+                            }
+                            Err(e) => leptos::either::Either::Right(view! {
+                                <p class="text-error">{e.to_string()}</p>
+                            }),
+                        }
+                    })
+                }}
+            </Suspense>
+            <a class="link" href="/">
+                "Back to today's puzzle"
+            </a>
+        </div>
+    }
+}
+
+#[component]
+fn AnswerList(answers: Answers, submitted: Vec<String>) -> impl IntoView {
+    let missed = answers
+        .words
+        .iter()
+        .filter(|w| !submitted.contains(w))
+        .count();
+    let letters: String = std::iter::once(answers.required_letter.0)
+        .chain(answers.other_letters.iter().map(|l| l.0))
+        .collect();
+
+    view! {
+        <p>
+            {format!(
+                "{}: {} words, {} missed. Letters {}.",
+                answers.date,
+                answers.words.len(),
+                missed,
+                letters.to_uppercase(),
+            )}
+        </p>
+        <ul class="columns-2 sm:columns-3">
+            {answers
+                .words
+                .into_iter()
+                .map(|word| {
+                    let found = submitted.contains(&word);
+                    let pangram = answers.pangrams.contains(&word);
+                    view! {
+                        <li class:font-bold=pangram class:text-error=!found>
+                            {word}
+                            {(!found).then(|| view! { <span class="sr-only">" (missed)"</span> })}
+                        </li>
+                    }
+                })
+                .collect_view()}
+        </ul>
+    }
+}
+
+/// Words the player found in yesterday's daily puzzle of `difficulty`, as
+/// stored by the [`crate::game::Puzzle`] under that day's key.
+fn yesterdays_submitted(difficulty: Difficulty) -> Vec<String> {
+    let yesterday = js_sys::Date::new_0();
+    yesterday.set_date(yesterday.get_date() - 1);
+    crate::storage::get_storage()
+        .ok()
+        .and_then(|storage| {
+            storage
+                .get(&format!(
+                    "{}/submitted",
+                    daily_key(daydex(&yesterday), difficulty)
+                ))
+                .ok()
+                .flatten()
+        })
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+async fn fetch_answers(
+    dictionary: Dictionary,
+    difficulty: Difficulty,
+) -> Result<Answers, AppError> {
+    let tz = get_current_tz()?;
+    crate::api::get_json(
+        "/api/puzzle/yesterday/answers",
+        &[
+            ("tz", tz.as_str()),
+            ("dictionary", dictionary.as_str()),
+            ("difficulty", difficulty.as_str()),
+        ],
+    )
+    .await
+    .map_err(|e| AppError::ConfigLoadError(format!("failed to load answers: {}", e)))
+}
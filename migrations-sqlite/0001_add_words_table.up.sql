@@ -0,0 +1,8 @@
+-- The words table of migrations/ for the sqlite word services.
+create table if not exists words (
+  word text primary key
+  , letter_mask integer not null
+  , length integer not null
+  , definition text
+  , obscurity integer check (obscurity between 1 and 5)
+);
@@ -0,0 +1,3 @@
+drop table if exists word_audit;
+delete from words where deleted_at is not null;
+alter table words drop column deleted_at;
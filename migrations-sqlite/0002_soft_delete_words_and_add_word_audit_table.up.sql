@@ -0,0 +1,10 @@
+-- The soft deletes and audit log of migrations/, without the users table.
+alter table words add column deleted_at text;
+
+create table if not exists word_audit (
+  id integer primary key
+  , word text not null
+  , action text not null check (action in ('added', 'removed', 'restored'))
+  , user_id integer
+  , recorded_at text not null default current_timestamp
+);
@@ -0,0 +1,20 @@
+delete from word_audit where dictionary_id != 1;
+alter table word_audit drop column dictionary_id;
+
+create table words_by_word (
+  word text primary key
+  , letter_mask integer not null
+  , length integer not null
+  , definition text
+  , obscurity integer check (obscurity between 1 and 5)
+  , deleted_at text
+);
+
+insert into words_by_word (word, letter_mask, length, definition, obscurity, deleted_at)
+select word, letter_mask, length, definition, obscurity, deleted_at from words
+where dictionary_id = 1;
+
+drop table words;
+alter table words_by_word rename to words;
+
+drop table if exists dictionaries;
@@ -0,0 +1,28 @@
+-- The dictionaries of migrations/. SQLite can't change a primary key, so the
+-- words table is rebuilt with the dictionary in it.
+create table if not exists dictionaries (
+  id integer primary key
+  , name text not null unique
+  , created_at text not null default current_timestamp
+);
+
+insert into dictionaries (id, name) values (1, 'standard') on conflict do nothing;
+
+create table words_by_dictionary (
+  dictionary_id integer not null default 1 references dictionaries (id)
+  , word text not null
+  , letter_mask integer not null
+  , length integer not null
+  , definition text
+  , obscurity integer check (obscurity between 1 and 5)
+  , deleted_at text
+  , primary key (dictionary_id, word)
+);
+
+insert into words_by_dictionary (word, letter_mask, length, definition, obscurity, deleted_at)
+select word, letter_mask, length, definition, obscurity, deleted_at from words;
+
+drop table words;
+alter table words_by_dictionary rename to words;
+
+alter table word_audit add column dictionary_id integer not null default 1;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+create table if not exists puzzle_word_exclusions (
+  puzzle_date date not null
+  , word text not null
+  , primary key (puzzle_date, word)
+);
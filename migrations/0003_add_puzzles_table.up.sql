@@ -0,0 +1,8 @@
+-- Add up migration script here
+create table if not exists puzzles (
+  puzzle_date date not null
+  , utc_offset integer not null
+  , config jsonb not null
+  , created_at timestamptz not null default now()
+  , primary key (puzzle_date, utc_offset)
+);
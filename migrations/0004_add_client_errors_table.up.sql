@@ -0,0 +1,8 @@
+-- Add up migration script here
+create table if not exists client_errors (
+  id bigserial primary key
+  , message text not null
+  , location text
+  , route text
+  , reported_at timestamptz not null default now()
+);
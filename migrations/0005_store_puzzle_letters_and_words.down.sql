@@ -0,0 +1,13 @@
+-- Add down migration script here
+alter table puzzles
+  drop column required_letter
+  , drop column other_letters
+  , drop column words
+  , drop column pangrams
+  , add column config jsonb;
+
+-- Stored letters and words can't be converted back, so drop the puzzles and let
+-- them be regenerated.
+delete from puzzles;
+
+alter table puzzles alter column config set not null;
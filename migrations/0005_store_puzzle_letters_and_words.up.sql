@@ -0,0 +1,26 @@
+-- Add up migration script here
+alter table puzzles
+  add column required_letter text
+  , add column other_letters text
+  , add column words text[]
+  , add column pangrams text[];
+
+update puzzles set
+  required_letter = config->>'required_letter'
+  , other_letters = (
+    select string_agg(letter, '') from jsonb_array_elements_text(config->'other_letters') letter
+  )
+  , words = array(
+    select w->>'word' from jsonb_array_elements(config->'valid_words') w
+  )
+  , pangrams = array(
+    select w->>'word' from jsonb_array_elements(config->'valid_words') w
+    where (w->>'is_pangram')::boolean
+  );
+
+alter table puzzles
+  alter column required_letter set not null
+  , alter column other_letters set not null
+  , alter column words set not null
+  , alter column pangrams set not null
+  , drop column config;
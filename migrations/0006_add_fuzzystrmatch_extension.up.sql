@@ -0,0 +1,2 @@
+-- Add up migration script here
+create extension if not exists fuzzystrmatch;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+create table if not exists users (
+  id bigserial primary key
+  , username text not null unique
+  , password_hash text not null
+  , created_at timestamptz not null default now()
+);
+
+create table if not exists sessions (
+  token_hash bytea primary key
+  , user_id bigint not null references users (id) on delete cascade
+  , created_at timestamptz not null default now()
+  , expires_at timestamptz not null
+);
+
+create index if not exists sessions_user_id_idx on sessions (user_id);
@@ -0,0 +1,10 @@
+-- Add up migration script here
+create table if not exists puzzle_progress (
+  user_id bigint not null references users (id) on delete cascade
+  , puzzle_date date not null
+  , utc_offset integer not null
+  , submitted text[] not null
+  , score integer not null
+  , updated_at timestamptz not null default now()
+  , primary key (user_id, puzzle_date, utc_offset)
+);
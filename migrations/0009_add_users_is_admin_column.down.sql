@@ -0,0 +1,2 @@
+-- Add down migration script here
+alter table users drop column if exists is_admin;
@@ -0,0 +1,2 @@
+-- Add up migration script here
+alter table users add column if not exists is_admin boolean not null default false;
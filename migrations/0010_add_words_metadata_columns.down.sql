@@ -0,0 +1,4 @@
+-- Add down migration script here
+alter table words
+  drop column if exists definition
+  , drop column if exists obscurity;
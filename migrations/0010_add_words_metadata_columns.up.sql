@@ -0,0 +1,4 @@
+-- Add up migration script here
+alter table words
+  add column if not exists definition text
+  , add column if not exists obscurity smallint check (obscurity between 1 and 5);
@@ -0,0 +1,8 @@
+-- Add up migration script here
+create table if not exists scheduled_puzzles (
+  puzzle_date date primary key
+  , required_letter text
+  , other_letters text
+  , event text
+  , check ((required_letter is null) = (other_letters is null))
+);
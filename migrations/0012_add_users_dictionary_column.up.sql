@@ -0,0 +1,3 @@
+-- Add up migration script here
+alter table users add column if not exists dictionary text not null default 'permissive'
+    check (dictionary in ('permissive', 'strict'));
@@ -0,0 +1,8 @@
+-- Add up migration script here
+create table if not exists puzzle_notes (
+  user_id bigint not null references users (id) on delete cascade
+  , puzzle_date date not null
+  , text text not null
+  , updated_at timestamptz not null default now()
+  , primary key (user_id, puzzle_date)
+);
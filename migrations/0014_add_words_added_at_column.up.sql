@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- Words already in the list were added at some unknown time, so the default
+-- only applies to words added from now on.
+alter table words add column if not exists added_at timestamptz;
+alter table words alter column added_at set default now();
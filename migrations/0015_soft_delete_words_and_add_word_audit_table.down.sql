@@ -0,0 +1,4 @@
+-- Add down migration script here
+drop table if exists word_audit;
+delete from words where deleted_at is not null;
+alter table words drop column if exists deleted_at;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+alter table words add column if not exists deleted_at timestamptz;
+
+create table if not exists word_audit (
+  id bigserial primary key
+  , word text not null
+  , action text not null check (action in ('added', 'removed', 'restored'))
+  , user_id bigint references users (id) on delete set null
+  , recorded_at timestamptz not null default now()
+);
+
+create index if not exists word_audit_recorded_at_idx on word_audit (recorded_at desc);
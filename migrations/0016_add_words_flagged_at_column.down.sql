@@ -0,0 +1,2 @@
+-- Add down migration script here
+alter table words drop column if exists flagged_at;
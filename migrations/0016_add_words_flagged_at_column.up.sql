@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- Flagged words stay in the list for review but are kept out of puzzles.
+alter table words add column if not exists flagged_at timestamptz;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- Reports are anonymous, like client errors.
+create table if not exists word_reports (
+  id bigserial primary key
+  , word text not null
+  , kind text not null check (kind in ('missing', 'invalid'))
+  , reported_at timestamptz not null default now()
+);
+
+create index if not exists word_reports_word_kind_idx on word_reports (word, kind);
@@ -0,0 +1,12 @@
+-- Add down migration script here
+delete from puzzle_progress where difficulty != 'medium';
+alter table puzzle_progress
+  drop constraint puzzle_progress_pkey
+  , add primary key (user_id, puzzle_date, utc_offset)
+  , drop column difficulty;
+
+delete from puzzles where difficulty != 'medium';
+alter table puzzles
+  drop constraint puzzles_pkey
+  , add primary key (puzzle_date, utc_offset)
+  , drop column difficulty;
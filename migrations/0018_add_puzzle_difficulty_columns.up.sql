@@ -0,0 +1,14 @@
+-- Add up migration script here
+alter table puzzles
+  add column difficulty text not null default 'medium'
+  check (difficulty in ('easy', 'medium', 'hard'));
+alter table puzzles
+  drop constraint puzzles_pkey
+  , add primary key (puzzle_date, utc_offset, difficulty);
+
+alter table puzzle_progress
+  add column difficulty text not null default 'medium'
+  check (difficulty in ('easy', 'medium', 'hard'));
+alter table puzzle_progress
+  drop constraint puzzle_progress_pkey
+  , add primary key (user_id, puzzle_date, utc_offset, difficulty);
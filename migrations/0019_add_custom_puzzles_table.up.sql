@@ -0,0 +1,9 @@
+-- Add up migration script here
+create table if not exists custom_puzzles (
+  id text primary key
+  , required_letter text not null
+  , other_letters text not null
+  , words text[] not null
+  , pangrams text[] not null
+  , created_at timestamptz not null default now()
+);
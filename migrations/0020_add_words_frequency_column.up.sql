@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- How often the word occurs in the frequency list it was imported from, for
+-- tuning the difficulty of puzzles.
+alter table words add column if not exists frequency bigint check (frequency >= 0);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+alter table words alter column letter_mask type integer;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Letter masks hold the letters of every alphabet, which may not fit in the
+-- bits of an integer.
+alter table words alter column letter_mask type bigint;
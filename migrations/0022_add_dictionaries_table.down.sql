@@ -0,0 +1,17 @@
+-- Add down migration script here
+delete from puzzles where dictionary_id != 1;
+alter table puzzles
+  drop constraint puzzles_pkey
+  , add primary key (puzzle_date, utc_offset, difficulty)
+  , drop column dictionary_id;
+
+delete from word_audit where dictionary_id != 1;
+alter table word_audit drop column dictionary_id;
+
+delete from words where dictionary_id != 1;
+alter table words
+  drop constraint words_pkey
+  , add primary key (word)
+  , drop column dictionary_id;
+
+drop table if exists dictionaries;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+-- Word lists the server hosts side by side. The words so far make up the
+-- standard dictionary, which is used unless another is chosen.
+create table if not exists dictionaries (
+  id serial primary key
+  , name text not null unique
+  , created_at timestamptz not null default now()
+);
+
+insert into dictionaries (id, name) values (1, 'standard') on conflict do nothing;
+select setval(pg_get_serial_sequence('dictionaries', 'id'), (select max(id) from dictionaries));
+
+alter table words
+  add column if not exists dictionary_id integer not null default 1 references dictionaries (id);
+alter table words
+  drop constraint words_pkey
+  , add primary key (dictionary_id, word);
+
+alter table word_audit
+  add column if not exists dictionary_id integer not null default 1 references dictionaries (id);
+
+alter table puzzles
+  add column if not exists dictionary_id integer not null default 1 references dictionaries (id);
+alter table puzzles
+  drop constraint puzzles_pkey
+  , add primary key (puzzle_date, utc_offset, difficulty, dictionary_id);
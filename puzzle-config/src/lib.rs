@@ -1,11 +1,20 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use serde::{Deserialize,Serialize};
+pub use words::Alphabet;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Word {
     pub word: String,
+    #[serde(serialize_with = "in_order::chars")]
     pub chars: HashSet<char>,
     pub is_pangram: bool,
+    /// Short definition shown to players once they've found the word.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
+    /// How obscure the word is, from 1 for everyday words to 5 for the
+    /// rarest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obscurity: Option<u8>,
 }
 
 impl std::hash::Hash for Word {
@@ -28,15 +37,38 @@ impl Word {
             word: word.to_owned(),
             is_pangram,
             chars: word.chars().collect(),
+            definition: None,
+            obscurity: None,
         }
     }
 
-    pub fn score(&self) -> u32 {
-        if self.word.len() == 4 {
-            1
+    pub fn score(&self, rules: &ScoringRules) -> u32 {
+        let length = self.word.chars().count() as u32;
+        if length == 4 {
+            rules.short_word_score
         } else {
-            let pangram_boost = if self.is_pangram { 7 } else { 0 };
-            self.word.len() as u32 + pangram_boost
+            let pangram_bonus = if self.is_pangram { rules.pangram_bonus } else { 0 };
+            length * rules.letter_score + pangram_bonus
+        }
+    }
+
+    /// Whether the word is a pangram using each of the puzzle's seven letters
+    /// exactly once.
+    pub fn is_perfect_pangram(&self) -> bool {
+        self.is_pangram && self.word.chars().count() == 7
+    }
+
+    /// Whether the word uses every letter of a puzzle with these letters and
+    /// nothing else.
+    pub fn is_pangram_of(&self, required_letter: &Letter, other_letters: &[Letter]) -> bool {
+        let letters = letters_mask(
+            std::iter::once(required_letter)
+                .chain(other_letters)
+                .map(|l| l.0),
+        );
+        match (letters_mask(self.word.chars()), letters) {
+            (Ok(word), Ok(letters)) => words::is_pangram_for(word, letters),
+            _ => false,
         }
     }
 
@@ -45,14 +77,16 @@ impl Word {
     }
 
     pub fn get(&self, idx: usize) -> Option<Letter> {
-        self.word
-            .get(idx..=idx)
-            .and_then(|s| s.chars().nth(0))
-            .map(Letter::new)
+        self.word.chars().nth(idx).map(Letter::new)
     }
 
+    /// The number of letters in the word.
     pub fn len(&self) -> usize {
-        self.word.len()
+        self.word.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.word.is_empty()
     }
 
     pub fn letters(&self) -> HashSet<Letter> {
@@ -71,6 +105,16 @@ impl Word {
     }
 }
 
+/// The mask of letters of any alphabet.
+fn letters_mask(
+    letters: impl IntoIterator<Item = char>,
+) -> Result<words::Bitmask, words::BitmaskError> {
+    letters
+        .into_iter()
+        .try_fold(words::Bitmask::EMPTY, |mask, l| {
+            words::Bitmask::try_from(l).map(|letter| mask | letter)
+        })
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Letter(pub char);
@@ -83,11 +127,772 @@ impl Letter {
 
 pub type ScoreBuckets = [(String, u32); 9];
 
+/// How words are scored and how much of a puzzle's maximum score each rank
+/// needs.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ScoringRules {
+    /// Points for a four letter word.
+    pub short_word_score: u32,
+    /// Points per letter of longer words.
+    pub letter_score: u32,
+    /// Points a pangram scores on top of its letters.
+    pub pangram_bonus: u32,
+    /// Rank names and the percentage of the maximum score needed to reach
+    /// each, from the lowest rank up.
+    pub bucket_percentages: [(String, u32); 9],
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        let ranks = [
+            ("Beginner", 0),
+            ("Good Start", 2),
+            ("Moving Up", 5),
+            ("Good", 8),
+            ("Solid", 15),
+            ("Nice", 25),
+            ("Great", 40),
+            ("Amazing", 50),
+            ("Genius", 70),
+        ];
+        Self {
+            short_word_score: 1,
+            letter_score: 1,
+            pangram_bonus: 7,
+            bucket_percentages: ranks.map(|(name, percentage)| (name.to_owned(), percentage)),
+        }
+    }
+}
+
+impl ScoringRules {
+    /// Points for finding every one of `words`.
+    pub fn max_score<'a>(&self, words: impl IntoIterator<Item = &'a Word>) -> u32 {
+        words.into_iter().map(|w| w.score(self)).sum()
+    }
+
+    /// The ranks of a puzzle with `words`, with the score needed for each.
+    pub fn score_buckets<'a>(&self, words: impl IntoIterator<Item = &'a Word>) -> ScoreBuckets {
+        let thresholds = self.rank_thresholds(self.max_score(words));
+        std::array::from_fn(|i| (self.bucket_percentages[i].0.clone(), thresholds[i]))
+    }
+
+    /// Score needed for each rank in a puzzle worth `max_score` points.
+    ///
+    /// Percentages of the maximum are rounded to the nearest point and then
+    /// adjusted so that every rank needs at least one point more than the one
+    /// before it and the top rank is reachable. Puzzles worth fewer points than
+    /// there are ranks can't satisfy both, so their lowest ranks share a
+    /// threshold of zero instead.
+    pub fn rank_thresholds(&self, max_score: u32) -> [u32; 9] {
+        let mut thresholds: [u32; 9] = std::array::from_fn(|i| {
+            let percentage = u64::from(self.bucket_percentages[i].1);
+            ((u64::from(max_score) * percentage + 50) / 100) as u32
+        });
+        for i in 1..thresholds.len() {
+            thresholds[i] = thresholds[i].max(thresholds[i - 1] + 1);
+        }
+        let last = thresholds.len() - 1;
+        for (i, threshold) in thresholds.iter_mut().enumerate() {
+            *threshold = (*threshold).min(max_score.saturating_sub((last - i) as u32));
+        }
+        thresholds
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct PuzzleConfig {
     pub score_buckets: ScoreBuckets,
+    /// How the puzzle's words are scored. Configs stored before the rules
+    /// were served were scored by the default rules.
+    #[serde(default)]
+    pub scoring: ScoringRules,
+    /// The alphabet the puzzle is spelled in, which guesses are folded into.
+    #[serde(default, with = "alphabet_name")]
+    pub alphabet: Alphabet,
     pub required_letter: Letter,
     pub other_letters: Vec<Letter>,
+    #[serde(serialize_with = "in_order::words")]
     pub valid_words: HashSet<Word>,
 }
 
+impl PuzzleConfig {
+    pub fn pangram_count(&self) -> u32 {
+        self.valid_words.iter().filter(|w| w.is_pangram).count() as u32
+    }
+
+    pub fn perfect_pangram_count(&self) -> u32 {
+        self.valid_words
+            .iter()
+            .filter(|w| w.is_perfect_pangram())
+            .count() as u32
+    }
+}
+
+/// Sets are sent in order, so that the same puzzle always serializes the
+/// same way and can be told apart by a hash of it.
+mod in_order {
+    use std::collections::HashSet;
+
+    use serde::Serializer;
+
+    use super::Word;
+
+    pub fn chars<S: Serializer>(chars: &HashSet<char>, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut chars: Vec<_> = chars.iter().collect();
+        chars.sort_unstable();
+        serializer.collect_seq(chars)
+    }
+
+    pub fn words<S: Serializer>(words: &HashSet<Word>, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut words: Vec<_> = words.iter().collect();
+        words.sort_unstable_by(|a, b| a.word.cmp(&b.word));
+        serializer.collect_seq(words)
+    }
+}
+
+/// Alphabets are sent by name.
+mod alphabet_name {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::Alphabet;
+
+    pub fn serialize<S: Serializer>(alphabet: &Alphabet, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(alphabet.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Alphabet, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A puzzle without its words, for playing against the server's guess
+/// endpoint so that the answers never reach the browser.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct SecurePuzzleConfig {
+    pub score_buckets: ScoreBuckets,
+    #[serde(default)]
+    pub scoring: ScoringRules,
+    /// The alphabet the puzzle is spelled in, which guesses are folded into.
+    #[serde(default, with = "alphabet_name")]
+    pub alphabet: Alphabet,
+    pub required_letter: Letter,
+    pub other_letters: Vec<Letter>,
+    pub word_count: u32,
+    pub pangram_count: u32,
+}
+
+impl From<&PuzzleConfig> for SecurePuzzleConfig {
+    fn from(config: &PuzzleConfig) -> Self {
+        Self {
+            score_buckets: config.score_buckets.clone(),
+            scoring: config.scoring.clone(),
+            alphabet: config.alphabet,
+            required_letter: config.required_letter.clone(),
+            other_letters: config.other_letters.clone(),
+            word_count: config.valid_words.len() as u32,
+            pangram_count: config.pangram_count(),
+        }
+    }
+}
+
+/// A puzzle to keep for playing offline, with its words in a [`WordFilter`]
+/// so that they can't be read from wherever it's kept.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct OfflinePuzzleConfig {
+    pub score_buckets: ScoreBuckets,
+    #[serde(default)]
+    pub scoring: ScoringRules,
+    /// The alphabet the puzzle is spelled in, which guesses are folded into.
+    #[serde(default, with = "alphabet_name")]
+    pub alphabet: Alphabet,
+    pub required_letter: Letter,
+    pub other_letters: Vec<Letter>,
+    pub word_count: u32,
+    pub pangram_count: u32,
+    /// Length of the longest word, past which input is capped.
+    pub max_word_length: usize,
+    pub words: WordFilter,
+}
+
+impl OfflinePuzzleConfig {
+    pub fn new(config: &PuzzleConfig, salt: u64) -> Self {
+        Self {
+            score_buckets: config.score_buckets.clone(),
+            scoring: config.scoring.clone(),
+            alphabet: config.alphabet,
+            required_letter: config.required_letter.clone(),
+            other_letters: config.other_letters.clone(),
+            word_count: config.valid_words.len() as u32,
+            pangram_count: config.pangram_count(),
+            max_word_length: config
+                .valid_words
+                .iter()
+                .map(|w| w.word.chars().count())
+                .max()
+                .unwrap_or_default(),
+            words: WordFilter::new(config.valid_words.iter().map(|w| w.word.as_str()), salt),
+        }
+    }
+
+    /// `word` as one of the puzzle's words, if the filter has it.
+    pub fn word(&self, word: &str) -> Option<Word> {
+        if !self.words.contains(word) {
+            return None;
+        }
+        let mut word = Word::new(word, false);
+        word.is_pangram = word.is_pangram_of(&self.required_letter, &self.other_letters);
+        Some(word)
+    }
+}
+
+/// Bits of a [`WordFilter`] for each word it holds, and bits set per word.
+/// Together they let about one in 15,000 words not in the filter through.
+const FILTER_BITS_PER_WORD: usize = 20;
+const FILTER_HASHES: u64 = 14;
+
+/// A Bloom filter of a puzzle's words, salted so that the same words make a
+/// different filter each time. It tells whether a word is one of them
+/// without holding the words themselves, at the cost of rarely taking a word
+/// for one of them when it isn't.
+///
+/// Filters serialize as the text from [`WordFilter::encode`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct WordFilter {
+    salt: u64,
+    bits: Vec<u64>,
+}
+
+impl WordFilter {
+    pub fn new<'a>(words: impl ExactSizeIterator<Item = &'a str>, salt: u64) -> Self {
+        let blocks = (words.len() * FILTER_BITS_PER_WORD).div_ceil(64).max(1);
+        let mut filter = Self {
+            salt,
+            bits: vec![0; blocks],
+        };
+        for word in words {
+            for bit in filter.bits_of(word) {
+                filter.bits[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        filter
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.bits_of(word)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// The bits set for `word`, by double hashing a salted FNV-1a hash.
+    fn bits_of(&self, word: &str) -> impl Iterator<Item = usize> + use<> {
+        let first = word.bytes().fold(0xcbf29ce484222325 ^ self.salt, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+        let second = mix(first) | 1;
+        let len = self.bits.len() as u64 * 64;
+        (0..FILTER_HASHES)
+            .map(move |i| (first.wrapping_add(i.wrapping_mul(second)) % len) as usize)
+    }
+
+    /// The salt and then the bits, as lowercase hex.
+    pub fn encode(&self) -> String {
+        std::iter::once(&self.salt)
+            .chain(&self.bits)
+            .map(|block| format!("{:016x}", block))
+            .collect()
+    }
+
+    /// Read a filter written by [`WordFilter::encode`].
+    pub fn decode(encoded: &str) -> Option<Self> {
+        if !encoded.is_ascii() || !encoded.len().is_multiple_of(16) || encoded.len() < 32 {
+            return None;
+        }
+        let mut blocks = (0..encoded.len())
+            .step_by(16)
+            .map(|i| u64::from_str_radix(&encoded[i..i + 16], 16).ok());
+        let salt = blocks.next()??;
+        let bits = blocks.collect::<Option<Vec<u64>>>()?;
+        Some(Self { salt, bits })
+    }
+}
+
+/// The finalizer of SplitMix64, spreading the bits of `hash` about.
+fn mix(mut hash: u64) -> u64 {
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+impl From<WordFilter> for String {
+    fn from(filter: WordFilter) -> Self {
+        filter.encode()
+    }
+}
+
+impl TryFrom<String> for WordFilter {
+    type Error = String;
+
+    fn try_from(encoded: String) -> Result<Self, Self::Error> {
+        Self::decode(&encoded).ok_or_else(|| "invalid word filter".to_owned())
+    }
+}
+
+/// A word a player wants checked against a puzzle kept on the server.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Guess {
+    pub word: String,
+}
+
+/// The verdict on a [`Guess`]: the word as the puzzle has it, or `None` when
+/// it isn't one of the puzzle's words.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct GuessResult {
+    pub word: Option<Word>,
+}
+
+/// How hard a player wants their daily puzzle. Each difficulty has its own
+/// puzzle every day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    /// Fewer and more common words.
+    Easy,
+    #[default]
+    Medium,
+    /// More words and pangrams, and rarer words.
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Self::Easy, Self::Medium, Self::Hard];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.as_str() == s)
+            .ok_or_else(|| format!("Unknown difficulty {:?}", s))
+    }
+}
+
+/// Letters a player picked for a puzzle of their own. `letters` holds all seven
+/// letters, including `required`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct NewCustomPuzzle {
+    pub letters: String,
+    pub required: String,
+}
+
+/// A puzzle a player made, shared by its ID.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct CustomPuzzle {
+    pub id: String,
+}
+
+/// Solution of a past puzzle, revealed once its day is over.
+///
+/// `date` is an ISO-8601 calendar date (`YYYY-MM-DD`).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Answers {
+    pub date: String,
+    pub required_letter: Letter,
+    pub other_letters: Vec<Letter>,
+    /// Every word of the puzzle, in alphabetical order.
+    pub words: Vec<String>,
+    pub pangrams: Vec<String>,
+}
+
+impl Answers {
+    pub fn new(date: String, config: &PuzzleConfig) -> Self {
+        let mut words: Vec<_> = config.valid_words.iter().map(|w| w.word.clone()).collect();
+        words.sort();
+        let mut pangrams: Vec<_> = config
+            .valid_words
+            .iter()
+            .filter(|w| w.is_pangram)
+            .map(|w| w.word.clone())
+            .collect();
+        pangrams.sort();
+        Self {
+            date,
+            required_letter: config.required_letter.clone(),
+            other_letters: config.other_letters.clone(),
+            words,
+            pangrams,
+        }
+    }
+}
+
+/// A player's progress through a puzzle, as synced between their devices.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct Progress {
+    /// Accepted words in the order they were found.
+    pub submitted: Vec<String>,
+    pub score: u32,
+}
+
+/// How far a player racing in a head-to-head room has got, without the words
+/// themselves.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
+pub struct RaceProgress {
+    pub words: usize,
+    pub score: u32,
+}
+
+/// What a head-to-head room tells a player about their opponent.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum OpponentEvent {
+    Joined,
+    Progress(RaceProgress),
+    Left,
+}
+
+/// A word a player of a team found, sent to the team's room.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct TeamSubmission {
+    pub word: String,
+}
+
+/// A word on a team's shared list, with the player who found it first.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct TeamWord {
+    pub word: String,
+    pub player: String,
+    /// What the word scored for the team.
+    pub points: u32,
+}
+
+/// What a team room tells its players.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum TeamEvent {
+    /// Everything the team has found so far, sent to a player joining it.
+    Sync { words: Vec<TeamWord> },
+    /// Names of the players in the room, sent whenever someone joins or leaves.
+    Players { players: Vec<String> },
+    /// A word added to the team's list.
+    Found(TeamWord),
+    /// A word the player submitted that a teammate had already found.
+    Taken(TeamWord),
+}
+
+/// Longest note a player can keep on a puzzle, in characters.
+pub const MAX_NOTE_CHARS: usize = 2000;
+
+/// A player's note on a puzzle, as synced between their devices.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct Note {
+    pub text: String,
+}
+
+/// Word-count hints for a puzzle that don't reveal any of its words.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Hints {
+    pub word_count: u32,
+    pub pangram_count: u32,
+    /// Pangrams using each letter exactly once, included in `pangram_count`.
+    pub perfect_pangram_count: u32,
+    /// Word lengths present in the puzzle, in ascending order.
+    pub lengths: Vec<usize>,
+    /// Counts of words by starting letter, one row per letter in alphabetical
+    /// order, with counts aligned to `lengths`.
+    pub grid: Vec<HintRow>,
+    /// Counts of words by their first two letters, in alphabetical order.
+    pub two_letter_prefixes: Vec<(String, u32)>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct HintRow {
+    pub letter: Letter,
+    pub counts: Vec<u32>,
+}
+
+impl From<&PuzzleConfig> for Hints {
+    fn from(config: &PuzzleConfig) -> Self {
+        let lengths: BTreeSet<usize> = config.valid_words.iter().map(Word::len).collect();
+        let lengths: Vec<usize> = lengths.into_iter().collect();
+
+        let mut by_letter: BTreeMap<char, Vec<u32>> = BTreeMap::new();
+        let mut prefixes: BTreeMap<String, u32> = BTreeMap::new();
+        for word in &config.valid_words {
+            let Some(first) = word.word.chars().next() else {
+                continue;
+            };
+            let counts = by_letter
+                .entry(first)
+                .or_insert_with(|| vec![0; lengths.len()]);
+            if let Ok(idx) = lengths.binary_search(&word.len()) {
+                counts[idx] += 1;
+            }
+            *prefixes
+                .entry(word.word.chars().take(2).collect())
+                .or_default() += 1;
+        }
+
+        Self {
+            word_count: config.valid_words.len() as u32,
+            pangram_count: config.pangram_count(),
+            perfect_pangram_count: config.perfect_pangram_count(),
+            lengths,
+            grid: by_letter
+                .into_iter()
+                .map(|(letter, counts)| HintRow {
+                    letter: Letter::new(letter),
+                    counts,
+                })
+                .collect(),
+            two_letter_prefixes: prefixes.into_iter().collect(),
+        }
+    }
+}
+
+/// A candidate puzzle evaluated for curators without being stored or cached.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PuzzlePreview {
+    pub required_letter: Letter,
+    pub other_letters: Vec<Letter>,
+    /// Every word in the puzzle, in alphabetical order.
+    pub words: Vec<String>,
+    pub pangrams: Vec<String>,
+    pub max_score: u32,
+    pub score_buckets: ScoreBuckets,
+    /// Problems with the letter set, including any that would keep the
+    /// generator from choosing it. Empty if it looks playable.
+    pub warnings: Vec<String>,
+}
+
+/// How well the dictionary supports generating puzzles.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct DictionaryHealth {
+    pub words: usize,
+    /// Words spelled with exactly seven distinct letters, which can be the
+    /// pangram of a puzzle, containing each letter from `a` to `z`.
+    pub pangram_words_by_letter: Vec<(char, usize)>,
+    /// Distinct sets of letters the pangram words are spelled with.
+    pub letter_sets: usize,
+    /// Letter sets that make no puzzle satisfying the generation policy,
+    /// whichever letter is required.
+    pub degenerate_letter_sets: usize,
+    /// Why puzzles can't be generated. Empty if they can.
+    pub problems: Vec<String>,
+}
+
+impl DictionaryHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Curation state of a single puzzle day as managed from the puzzle calendar.
+///
+/// `date` is an ISO-8601 calendar date (`YYYY-MM-DD`).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ScheduledPuzzle {
+    pub date: String,
+    pub required_letter: Option<Letter>,
+    pub other_letters: Vec<Letter>,
+    pub event: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Schedule {
+    pub puzzles: Vec<ScheduledPuzzle>,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 5] = ["bake", "beak", "backed", "blacked", "blackballed"];
+
+    #[test]
+    fn filters_hold_their_words() {
+        let filter = WordFilter::new(WORDS.into_iter(), 42);
+
+        assert!(WORDS.iter().all(|word| filter.contains(word)));
+        assert!(!filter.contains("cake"));
+        assert!(!filter.contains("blackball"));
+        assert!(!filter.contains(""));
+    }
+
+    #[test]
+    fn filters_rarely_hold_other_words() {
+        let words: Vec<String> = (0..100).map(|i| format!("word{}", i)).collect();
+        let filter = WordFilter::new(words.iter().map(String::as_str), 7);
+
+        let let_through = (0..10_000)
+            .filter(|i| filter.contains(&format!("other{}", i)))
+            .count();
+        assert!(let_through < 10, "{} let through", let_through);
+    }
+
+    #[test]
+    fn filters_round_trip_through_text() {
+        let filter = WordFilter::new(WORDS.into_iter(), 1234);
+        let encoded = filter.encode();
+
+        assert!(WORDS.iter().all(|word| !encoded.contains(word)));
+        assert_eq!(Some(filter.clone()), WordFilter::decode(&encoded));
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(format!("\"{}\"", encoded), json);
+        assert_eq!(filter, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn salts_change_filters() {
+        assert_ne!(
+            WordFilter::new(WORDS.into_iter(), 1).encode(),
+            WordFilter::new(WORDS.into_iter(), 2).encode()
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_filters() {
+        assert_eq!(None, WordFilter::decode(""));
+        assert_eq!(None, WordFilter::decode("0000000000000001"));
+        assert_eq!(None, WordFilter::decode("000000000000000100000000000000zz"));
+        assert_eq!(None, WordFilter::decode("0000000000000001000000000000000"));
+        assert!(serde_json::from_str::<WordFilter>("\"nope\"").is_err());
+    }
+
+    #[test]
+    fn scores_words_by_the_rules() {
+        let default = ScoringRules::default();
+        assert_eq!(1, Word::new("bake", false).score(&default));
+        assert_eq!(6, Word::new("backed", false).score(&default));
+        assert_eq!(14, Word::new("blacked", true).score(&default));
+
+        let rules = ScoringRules {
+            short_word_score: 2,
+            letter_score: 3,
+            pangram_bonus: 10,
+            ..ScoringRules::default()
+        };
+        assert_eq!(2, Word::new("bake", false).score(&rules));
+        assert_eq!(18, Word::new("backed", false).score(&rules));
+        assert_eq!(31, Word::new("blacked", true).score(&rules));
+    }
+
+    #[test]
+    fn scores_and_finds_pangrams_beyond_latin_letters() {
+        let others: Vec<Letter> = "aeloñu".chars().map(Letter::new).collect();
+        let word = Word::new("pañuelo", true);
+        assert!(word.is_pangram_of(&Letter::new('p'), &others));
+        assert!(!Word::new("pañuelos", false).is_pangram_of(&Letter::new('p'), &others));
+        assert!(!Word::new("puelo", false).is_pangram_of(&Letter::new('p'), &others));
+        assert_eq!(14, word.score(&ScoringRules::default()));
+    }
+
+    #[test]
+    fn sends_alphabets_by_name() {
+        let config = PuzzleConfig {
+            score_buckets: ScoringRules::default().score_buckets(&HashSet::new()),
+            scoring: ScoringRules::default(),
+            alphabet: Alphabet::SPANISH,
+            required_letter: Letter::new('ñ'),
+            other_letters: Vec::new(),
+            valid_words: HashSet::new(),
+        };
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!("spanish", json["alphabet"]);
+        assert_eq!(config, serde_json::from_value(json.clone()).unwrap());
+
+        let mut json = json;
+        json.as_object_mut().unwrap().remove("alphabet");
+        let config: PuzzleConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(Alphabet::LATIN, config.alphabet);
+    }
+
+    #[test]
+    fn rounds_rank_thresholds() {
+        let rules = ScoringRules::default();
+        assert_eq!([0, 2, 5, 8, 15, 25, 40, 50, 70], rules.rank_thresholds(100));
+        assert_eq!([0, 1, 3, 4, 8, 13, 20, 26, 36], rules.rank_thresholds(51));
+    }
+
+    #[test]
+    fn rank_thresholds_increase_strictly() {
+        let rules = ScoringRules::default();
+        for max_score in 8..200 {
+            let thresholds = rules.rank_thresholds(max_score);
+            assert_eq!(0, thresholds[0], "max score {}", max_score);
+            assert!(
+                thresholds.windows(2).all(|pair| pair[0] < pair[1]),
+                "max score {}: {:?}",
+                max_score,
+                thresholds
+            );
+            assert!(thresholds[8] <= max_score, "max score {}", max_score);
+        }
+    }
+
+    #[test]
+    fn small_puzzles_share_the_lowest_thresholds() {
+        let rules = ScoringRules::default();
+        assert_eq!([0, 1, 2, 3, 4, 5, 6, 7, 8], rules.rank_thresholds(8));
+        assert_eq!([0, 0, 0, 0, 0, 0, 1, 2, 3], rules.rank_thresholds(3));
+        assert_eq!([0; 9], rules.rank_thresholds(0));
+    }
+
+    #[test]
+    fn offline_configs_mark_pangrams() {
+        let config = PuzzleConfig {
+            score_buckets: std::array::from_fn(|i| (format!("rank {}", i), i as u32)),
+            scoring: ScoringRules::default(),
+            alphabet: Alphabet::default(),
+            required_letter: Letter::new('b'),
+            other_letters: "acdekl".chars().map(Letter::new).collect(),
+            valid_words: WORDS
+                .into_iter()
+                .map(|w| Word::new(w, w == "blacked"))
+                .collect(),
+        };
+        let offline = OfflinePuzzleConfig::new(&config, 9);
+
+        assert_eq!(5, offline.word_count);
+        assert_eq!(1, offline.pangram_count);
+        assert_eq!(11, offline.max_word_length);
+        assert_eq!(Some(Word::new("bake", false)), offline.word("bake"));
+        assert!(offline.word("blacked").unwrap().is_pangram);
+        assert_eq!(None, offline.word("cake"));
+    }
+
+    #[test]
+    fn serializes_words_in_order() {
+        let word = Word::new("backed", false);
+        assert_eq!(
+            r#"{"word":"backed","chars":["a","b","c","d","e","k"],"is_pangram":false}"#,
+            serde_json::to_string(&word).unwrap()
+        );
+
+        let config = |words: &[&str]| PuzzleConfig {
+            score_buckets: std::array::from_fn(|i| (format!("rank {}", i), i as u32)),
+            scoring: ScoringRules::default(),
+            alphabet: Alphabet::default(),
+            required_letter: Letter::new('b'),
+            other_letters: "acdekl".chars().map(Letter::new).collect(),
+            valid_words: words.iter().map(|w| Word::new(w, false)).collect(),
+        };
+        let mut reversed = WORDS;
+        reversed.reverse();
+        assert_eq!(
+            serde_json::to_string(&config(&WORDS)).unwrap(),
+            serde_json::to_string(&config(&reversed)).unwrap()
+        );
+    }
+}
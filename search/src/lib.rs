@@ -4,3 +4,131 @@ use serde::{Deserialize, Serialize};
 pub struct SearchedWords {
     pub words: Vec<String>,
 }
+
+/// How a search query is matched against words.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// The words closest to the query by [`distance`].
+    #[default]
+    Fuzzy,
+    /// Words starting with the query.
+    Prefix,
+    /// Words containing the query.
+    Substring,
+    /// Words matching the query as a regular expression.
+    Regex,
+    /// Words spelled only with the letters of the query, such as the words of
+    /// a puzzle with those letters.
+    Letters,
+}
+
+impl SearchMode {
+    pub const ALL: [SearchMode; 5] = [
+        Self::Fuzzy,
+        Self::Prefix,
+        Self::Substring,
+        Self::Regex,
+        Self::Letters,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fuzzy => "fuzzy",
+            Self::Prefix => "prefix",
+            Self::Substring => "substring",
+            Self::Regex => "regex",
+            Self::Letters => "letters",
+        }
+    }
+}
+
+impl std::str::FromStr for SearchMode {
+    type Err = UnknownSearchMode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.as_str() == s)
+            .ok_or_else(|| UnknownSearchMode(s.to_owned()))
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownSearchMode(pub String);
+
+impl std::fmt::Display for UnknownSearchMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown search mode {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownSearchMode {}
+
+/// Cost of inserting a letter into the query, cheaper than deleting or
+/// substituting one so that words starting with the query rank first.
+const INSERTION_COST: u32 = 1;
+const DELETION_COST: u32 = 2;
+const SUBSTITUTION_COST: u32 = 2;
+
+/// Edit distance from `query` to `word`, weighted like the
+/// `levenshtein(query, word, 1, 2, 2)` of Postgres' fuzzystrmatch.
+pub fn distance(query: &str, word: &str) -> u32 {
+    let word: Vec<char> = word.chars().collect();
+    let mut previous: Vec<u32> = (0..=word.len() as u32)
+        .map(|i| i * INSERTION_COST)
+        .collect();
+    let mut current = vec![0; word.len() + 1];
+    for (i, q) in query.chars().enumerate() {
+        current[0] = (i as u32 + 1) * DELETION_COST;
+        for (j, w) in word.iter().enumerate() {
+            let substitution = if q == *w { 0 } else { SUBSTITUTION_COST };
+            current[j + 1] = (previous[j] + substitution)
+                .min(previous[j + 1] + DELETION_COST)
+                .min(current[j] + INSERTION_COST);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[word.len()]
+}
+
+/// The `limit` words closest to `query` by [`distance`], for databases
+/// without fuzzystrmatch. Ties are broken alphabetically.
+pub fn closest(query: &str, words: impl IntoIterator<Item = String>, limit: usize) -> Vec<String> {
+    let mut scored: Vec<(u32, String)> = words
+        .into_iter()
+        .map(|word| (distance(query, &word), word))
+        .collect();
+    scored.sort();
+    scored.truncate(limit);
+    scored.into_iter().map(|(_, word)| word).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighs_edits_like_fuzzystrmatch() {
+        assert_eq!(0, distance("bee", "bee"));
+        assert_eq!(2, distance("bee", "beech"));
+        assert_eq!(4, distance("beech", "bee"));
+        assert_eq!(2, distance("bee", "bye"));
+        assert_eq!(3, distance("", "bee"));
+    }
+
+    #[test]
+    fn parses_modes_by_name() {
+        for mode in SearchMode::ALL {
+            assert_eq!(mode, mode.as_str().parse().unwrap());
+        }
+        assert!("soundex".parse::<SearchMode>().is_err());
+    }
+
+    #[test]
+    fn ranks_closest_words_first() {
+        let words = ["apple", "bead", "beech", "been", "cat"].map(str::to_owned);
+
+        assert_eq!(vec!["been", "beech", "bead"], closest("bee", words, 3));
+    }
+}
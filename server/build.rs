@@ -1,5 +1,5 @@
 // generated by `sqlx migrate build-script`
 fn main() {
     // trigger recompilation when a new migration is added
-    println!("cargo:rerun-if-changed=migrations");
+    println!("cargo:rerun-if-changed=../migrations");
 }
@@ -0,0 +1,105 @@
+//! Cache headers for the frontend.
+//!
+//! The frontend's build names its WASM, JavaScript and CSS after a hash of
+//! their contents and lists those files in `asset-manifest.json`. A new build
+//! brings new names, so listed files can be cached for good, while everything
+//! else, `index.html` linking to the current build above all, is revalidated on
+//! every load so that players pick up new builds as soon as they're deployed.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+
+/// Name of the manifest in the assets directory.
+const MANIFEST: &str = "asset-manifest.json";
+
+/// Where the assets are served from.
+pub(crate) const ASSETS_PATH: &str = "/assets";
+
+const IMMUTABLE: HeaderValue = HeaderValue::from_static("public, max-age=31536000, immutable");
+const NO_CACHE: HeaderValue = HeaderValue::from_static("no-cache");
+
+/// Assets whose names carry a hash of their contents.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AssetManifest {
+    fingerprinted: Arc<HashSet<String>>,
+}
+
+#[derive(Deserialize)]
+struct ManifestFile {
+    fingerprinted: Vec<String>,
+}
+
+impl AssetManifest {
+    /// Read the manifest of the build in `assets_dir`. Without one, nothing is
+    /// cached for good.
+    pub(crate) fn load(assets_dir: &Path) -> Self {
+        let path = assets_dir.join(MANIFEST);
+        let manifest = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| {
+                serde_json::from_slice::<ManifestFile>(&data).map_err(|e| e.to_string())
+            });
+        match manifest {
+            Ok(manifest) => Self {
+                fingerprinted: Arc::new(manifest.fingerprinted.into_iter().collect()),
+            },
+            Err(e) => {
+                tracing::info!(path = %path.display(), error = %e, "no asset manifest, assets won't be cached");
+                Self::default()
+            }
+        }
+    }
+
+    fn is_fingerprinted(&self, path: &str) -> bool {
+        path.strip_prefix(ASSETS_PATH)
+            .and_then(|path| path.strip_prefix('/'))
+            .is_some_and(|file| self.fingerprinted.contains(file))
+    }
+}
+
+/// Let fingerprinted assets be cached for good and have everything else
+/// revalidated.
+pub(crate) async fn cache_control(
+    State(manifest): State<AssetManifest>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_fingerprinted = manifest.is_fingerprinted(request.uri().path());
+    let mut response = next.run(request).await;
+    if response.status().is_success() {
+        let value = if is_fingerprinted {
+            IMMUTABLE
+        } else {
+            NO_CACHE
+        };
+        response.headers_mut().insert(header::CACHE_CONTROL, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_fingerprinted_assets() {
+        let manifest = AssetManifest {
+            fingerprinted: Arc::new(HashSet::from(["frontend-1f2e3d4c.js".to_owned()])),
+        };
+
+        assert!(manifest.is_fingerprinted("/assets/frontend-1f2e3d4c.js"));
+        assert!(!manifest.is_fingerprinted("/assets/frontend-00000000.js"));
+        assert!(!manifest.is_fingerprinted("/frontend-1f2e3d4c.js"));
+        assert!(!manifest.is_fingerprinted("/assetsfrontend-1f2e3d4c.js"));
+        assert!(!manifest.is_fingerprinted("/"));
+    }
+}
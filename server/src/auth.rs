@@ -0,0 +1,248 @@
+//! Password hashing, session cookies and the extractor identifying the user
+//! making a request.
+
+use std::num::NonZeroU32;
+use std::sync::LazyLock;
+
+use axum::{
+    extract::FromRequestParts,
+    http::{HeaderMap, header, request::Parts},
+};
+use base64::Engine as _;
+use ring::rand::SecureRandom as _;
+
+use crate::responses::AppError;
+use crate::services::users::{FindSession as _, User, pg::Sessions};
+
+pub(crate) const SESSION_COOKIE: &str = "bee_session";
+
+/// How long a session stays valid after logging in.
+pub(crate) const SESSION_TTL: chrono::Duration = chrono::Duration::days(30);
+
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const HASH_SCHEME: &str = "pbkdf2-sha256";
+const SALT_LEN: usize = 16;
+const TOKEN_LEN: usize = 32;
+
+/// Hash of a password no one has, checked when logging in as a user that
+/// doesn't exist so that it takes as long as logging in as one that does.
+static UNKNOWN_USER_HASH: LazyLock<String> =
+    LazyLock::new(|| derive_hash(&String::from_utf8_lossy(&random_bytes::<TOKEN_LEN>())));
+
+/// Hash `password` with a random salt into a self-describing string of the
+/// form `pbkdf2-sha256$<iterations>$<salt>$<hash>`.
+///
+/// Hashing takes hundreds of milliseconds by design, so it runs on the
+/// blocking thread pool rather than holding up other requests.
+pub(crate) async fn hash_password(password: String) -> Result<String, tokio::task::JoinError> {
+    tokio::task::spawn_blocking(move || derive_hash(&password)).await
+}
+
+/// Check `password` against the hash `stored` by [`hash_password`], or
+/// against a dummy hash for users without one, which never matches. Malformed
+/// hashes never match either.
+pub(crate) async fn verify_password(
+    password: String,
+    stored: Option<String>,
+) -> Result<bool, tokio::task::JoinError> {
+    tokio::task::spawn_blocking(move || match stored {
+        Some(stored) => matches_hash(&password, &stored),
+        None => {
+            matches_hash(&password, &UNKNOWN_USER_HASH);
+            false
+        }
+    })
+    .await
+}
+
+fn derive_hash(password: &str) -> String {
+    let salt = random_bytes::<SALT_LEN>();
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).unwrap();
+    let mut hash = [0u8; ring::digest::SHA256_OUTPUT_LEN];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        &salt,
+        password.as_bytes(),
+        &mut hash,
+    );
+
+    let b64 = base64::engine::general_purpose::STANDARD_NO_PAD;
+    format!(
+        "{}${}${}${}",
+        HASH_SCHEME,
+        iterations,
+        b64.encode(salt),
+        b64.encode(hash)
+    )
+}
+
+fn matches_hash(password: &str, stored: &str) -> bool {
+    let b64 = base64::engine::general_purpose::STANDARD_NO_PAD;
+    let mut parts = stored.split('$');
+    let (Some(HASH_SCHEME), Some(iterations), Some(salt), Some(hash), None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return false;
+    };
+    let (Ok(iterations), Ok(salt), Ok(hash)) = (
+        iterations.parse::<NonZeroU32>(),
+        b64.decode(salt),
+        b64.decode(hash),
+    ) else {
+        return false;
+    };
+
+    ring::pbkdf2::verify(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        &salt,
+        password.as_bytes(),
+        &hash,
+    )
+    .is_ok()
+}
+
+/// Generate a new random session token to hand to the client.
+pub(crate) fn new_session_token() -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(random_bytes::<TOKEN_LEN>())
+}
+
+/// Hash a session token for storage and lookup.
+pub(crate) fn hash_token(token: &str) -> Vec<u8> {
+    ring::digest::digest(&ring::digest::SHA256, token.as_bytes())
+        .as_ref()
+        .to_vec()
+}
+
+/// `Set-Cookie` value storing `token` in the browser for the session lifetime.
+pub(crate) fn session_cookie(token: &str) -> String {
+    format!(
+        "{}={}; Path=/; Max-Age={}; HttpOnly; Secure; SameSite=Lax",
+        SESSION_COOKIE,
+        token,
+        SESSION_TTL.num_seconds()
+    )
+}
+
+/// `Set-Cookie` value removing the session cookie from the browser.
+pub(crate) fn expired_session_cookie() -> String {
+    format!(
+        "{}=; Path=/; Max-Age=0; HttpOnly; Secure; SameSite=Lax",
+        SESSION_COOKIE
+    )
+}
+
+/// The session token sent by the client, if any.
+pub(crate) fn session_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, token)| token)
+        .filter(|token| !token.is_empty())
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    ring::rand::SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("Failed to generate random bytes");
+    bytes
+}
+
+/// The logged in user making a request. Rejects requests without a valid
+/// session with 401.
+///
+/// Sessions are looked up through the [`Sessions`] service added to the
+/// router as an extension.
+pub(crate) struct CurrentUser(pub(crate) User);
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(token) = session_token(&parts.headers) else {
+            return Err(not_logged_in());
+        };
+        let Some(sessions) = parts.extensions.get::<Sessions>() else {
+            return Err(AppError::Internal("Sessions are not configured".to_owned()));
+        };
+
+        sessions
+            .find_session(&hash_token(token))
+            .await?
+            .map(Self)
+            .ok_or_else(not_logged_in)
+    }
+}
+
+/// Hash of the token granting admin access to scripts without a session,
+/// added to the router as an extension. `None` when no token is configured.
+#[derive(Clone)]
+pub(crate) struct AdminToken(pub(crate) Option<Vec<u8>>);
+
+impl AdminToken {
+    pub(crate) fn new(token: Option<&str>) -> Self {
+        Self(token.map(hash_token))
+    }
+}
+
+/// The token sent in an `Authorization: Bearer` header, if any.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// The logged in admin making a request, or `None` for a script holding the
+/// [`AdminToken`]. Rejects requests without a valid session with 401 and
+/// requests from other users with 403.
+pub(crate) struct RequireAdmin(pub(crate) Option<User>);
+
+impl RequireAdmin {
+    /// Who is editing the word list, for its audit log.
+    pub(crate) fn editor(&self) -> crate::services::words::Editor {
+        self.0.as_ref().map(|user| user.id)
+    }
+}
+
+impl<S> FromRequestParts<S> for RequireAdmin
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let (Some(AdminToken(Some(expected))), Some(token)) = (
+            parts.extensions.get::<AdminToken>(),
+            bearer_token(&parts.headers),
+        ) && hash_token(token) == *expected
+        {
+            return Ok(Self(None));
+        }
+
+        let CurrentUser(user) = CurrentUser::from_request_parts(parts, state).await?;
+        if !user.is_admin {
+            return Err(AppError::Forbidden("Admin access required".to_owned()));
+        }
+        Ok(Self(Some(user)))
+    }
+}
+
+fn not_logged_in() -> AppError {
+    AppError::Unauthorized("Not logged in".to_owned())
+}
@@ -0,0 +1,350 @@
+use std::time::Duration;
+
+/// Shared key/value store for data that every server instance should agree on,
+/// such as generated daily puzzles.
+pub(crate) trait CacheBackend {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error>;
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), Error>;
+
+    /// Add one to the counter at `key` and return its count. A new counter
+    /// starts from zero and expires after `ttl`. Counters aren't values to
+    /// [`CacheBackend::get`].
+    async fn incr(&self, key: &str, ttl: Duration) -> Result<u64, Error>;
+}
+
+/// The cache backend selected at startup.
+///
+/// Defaults to an in-process store holding up to `CACHE_MAX_ENTRIES` entries;
+/// when built with the `redis` feature and `REDIS_URL` is set, entries are
+/// shared between replicas through Redis.
+#[derive(Clone)]
+pub enum Backend {
+    Memory(memory::Memory),
+    #[cfg(feature = "redis")]
+    Redis(redis::Redis),
+}
+
+impl Backend {
+    pub async fn from_env() -> Result<Self, Error> {
+        #[cfg(feature = "redis")]
+        if let Ok(url) = dotenvy::var("REDIS_URL") {
+            tracing::info!("using redis cache backend");
+            return redis::Redis::connect(&url).await.map(Self::Redis);
+        }
+
+        let max_entries = match dotenvy::var("CACHE_MAX_ENTRIES") {
+            Ok(value) => value
+                .parse()
+                .ok()
+                .filter(|max| *max > 0)
+                .ok_or(Error::InvalidMaxEntries(value))?,
+            Err(_) => memory::DEFAULT_MAX_ENTRIES,
+        };
+        Ok(Self::Memory(memory::Memory::with_max_entries(max_entries)))
+    }
+}
+
+impl std::fmt::Debug for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Memory(_) => write!(f, "Backend::Memory"),
+            #[cfg(feature = "redis")]
+            Self::Redis(_) => write!(f, "Backend::Redis"),
+        }
+    }
+}
+
+impl CacheBackend for Backend {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error> {
+        let value = match self {
+            Self::Memory(cache) => cache.get(key).await,
+            #[cfg(feature = "redis")]
+            Self::Redis(cache) => cache.get(key).await,
+        }?;
+        let result = if value.is_some() { "hit" } else { "miss" };
+        crate::metrics::increment("bee_cache_lookups_total", &[("result", result)], 1);
+        Ok(value)
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), Error> {
+        match self {
+            Self::Memory(cache) => cache.set(key, value, ttl).await,
+            #[cfg(feature = "redis")]
+            Self::Redis(cache) => cache.set(key, value, ttl).await,
+        }
+    }
+
+    async fn incr(&self, key: &str, ttl: Duration) -> Result<u64, Error> {
+        match self {
+            Self::Memory(cache) => cache.incr(key, ttl).await,
+            #[cfg(feature = "redis")]
+            Self::Redis(cache) => cache.incr(key, ttl).await,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    #[cfg_attr(not(feature = "redis"), allow(dead_code))]
+    BackendError(Box<dyn std::error::Error + Send + Sync>),
+    InvalidMaxEntries(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BackendError(cause) => write!(f, "Cache backend error: {}", cause),
+            Self::InvalidMaxEntries(value) => write!(
+                f,
+                "Invalid CACHE_MAX_ENTRIES {:?}, expected a positive number",
+                value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub mod memory {
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use dashmap::DashMap;
+
+    /// Entries kept unless `CACHE_MAX_ENTRIES` says otherwise. Puzzles are
+    /// cached per day and UTC offset, so this is a few weeks' worth.
+    pub(crate) const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+    /// Number of counters above which expired ones are swept on the next count.
+    const SWEEP_THRESHOLD: usize = 10_000;
+
+    /// In-process cache. Entries are only visible to the current server instance.
+    ///
+    /// Expired entries are evicted whenever an entry is set, and once the cache
+    /// is full the entries closest to expiring make room for new ones.
+    /// Counters are kept apart from the entries and don't count towards
+    /// `max_entries`.
+    #[derive(Clone)]
+    pub struct Memory {
+        entries: Arc<DashMap<String, Entry>>,
+        counters: Arc<DashMap<String, Counter>>,
+        max_entries: usize,
+    }
+
+    struct Entry {
+        value: String,
+        expires_at: Instant,
+    }
+
+    struct Counter {
+        count: u64,
+        expires_at: Instant,
+    }
+
+    impl Default for Memory {
+        fn default() -> Self {
+            Self::with_max_entries(DEFAULT_MAX_ENTRIES)
+        }
+    }
+
+    impl Memory {
+        pub(crate) fn with_max_entries(max_entries: usize) -> Self {
+            Self {
+                entries: Arc::new(DashMap::new()),
+                counters: Arc::new(DashMap::new()),
+                max_entries,
+            }
+        }
+
+        /// Drop expired entries, then the entries closest to expiring until
+        /// there is room for one more.
+        fn evict(&self, now: Instant) {
+            let before = self.entries.len();
+            self.entries.retain(|_, entry| entry.expires_at > now);
+            let expired = before - self.entries.len();
+
+            let excess = (self.entries.len() + 1).saturating_sub(self.max_entries);
+            if excess > 0 {
+                let mut by_expiry: Vec<(Instant, String)> = self
+                    .entries
+                    .iter()
+                    .map(|entry| (entry.expires_at, entry.key().clone()))
+                    .collect();
+                by_expiry.sort();
+                for (_, key) in by_expiry.into_iter().take(excess) {
+                    self.entries.remove(&key);
+                }
+            }
+
+            for (reason, count) in [("expired", expired), ("capacity", excess)] {
+                if count > 0 {
+                    crate::metrics::increment(
+                        "bee_cache_evictions_total",
+                        &[("reason", reason)],
+                        count as u64,
+                    );
+                }
+            }
+        }
+    }
+
+    impl super::CacheBackend for Memory {
+        async fn get(&self, key: &str) -> Result<Option<String>, super::Error> {
+            let now = Instant::now();
+            match self.entries.get(key) {
+                Some(entry) if entry.expires_at > now => return Ok(Some(entry.value.clone())),
+                Some(_) => {}
+                None => return Ok(None),
+            }
+
+            self.entries
+                .remove_if(key, |_, entry| entry.expires_at <= now);
+            Ok(None)
+        }
+
+        async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), super::Error> {
+            let now = Instant::now();
+            if !self.entries.contains_key(key) {
+                self.evict(now);
+            }
+            self.entries.insert(
+                key.to_owned(),
+                Entry {
+                    value: value.to_owned(),
+                    expires_at: now + ttl,
+                },
+            );
+            Ok(())
+        }
+
+        async fn incr(&self, key: &str, ttl: Duration) -> Result<u64, super::Error> {
+            let now = Instant::now();
+            if self.counters.len() > SWEEP_THRESHOLD {
+                self.counters.retain(|_, counter| counter.expires_at > now);
+            }
+
+            let fresh = || Counter {
+                count: 0,
+                expires_at: now + ttl,
+            };
+            let mut counter = self.counters.entry(key.to_owned()).or_insert_with(fresh);
+            if counter.expires_at <= now {
+                *counter = fresh();
+            }
+            counter.count += 1;
+            Ok(counter.count)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::time::Duration;
+
+        use super::Memory;
+        use crate::cache::CacheBackend as _;
+
+        #[tokio::test]
+        async fn evicts_expired_entries_then_those_closest_to_expiring() {
+            let cache = Memory::with_max_entries(2);
+            cache.set("expired", "0", Duration::ZERO).await.unwrap();
+            cache
+                .set("soon", "1", Duration::from_secs(60))
+                .await
+                .unwrap();
+            cache
+                .set("later", "2", Duration::from_secs(120))
+                .await
+                .unwrap();
+            assert_eq!(2, cache.entries.len());
+            assert!(!cache.entries.contains_key("expired"));
+
+            cache
+                .set("latest", "3", Duration::from_secs(180))
+                .await
+                .unwrap();
+            assert_eq!(2, cache.entries.len());
+            assert_eq!(None, cache.get("soon").await.unwrap());
+            assert_eq!(Some("2".to_owned()), cache.get("later").await.unwrap());
+
+            // Replacing an entry doesn't evict another.
+            cache
+                .set("later", "4", Duration::from_secs(60))
+                .await
+                .unwrap();
+            assert_eq!(Some("3".to_owned()), cache.get("latest").await.unwrap());
+        }
+
+        #[tokio::test]
+        async fn counts_until_the_counter_expires() {
+            let cache = Memory::with_max_entries(1);
+            let minute = Duration::from_secs(60);
+            assert_eq!(1, cache.incr("a", minute).await.unwrap());
+            assert_eq!(2, cache.incr("a", minute).await.unwrap());
+            assert_eq!(1, cache.incr("b", minute).await.unwrap());
+            assert_eq!(None, cache.get("a").await.unwrap());
+
+            assert_eq!(1, cache.incr("expired", Duration::ZERO).await.unwrap());
+            assert_eq!(1, cache.incr("expired", Duration::ZERO).await.unwrap());
+        }
+    }
+}
+
+#[cfg(feature = "redis")]
+pub mod redis {
+    use std::time::Duration;
+
+    use ::redis::AsyncCommands as _;
+
+    /// Redis-backed cache shared between every server instance pointed at the same Redis.
+    #[derive(Clone)]
+    pub struct Redis(::redis::aio::ConnectionManager);
+
+    impl Redis {
+        pub(crate) async fn connect(url: &str) -> Result<Self, super::Error> {
+            let client =
+                ::redis::Client::open(url).map_err(|e| super::Error::BackendError(Box::new(e)))?;
+            ::redis::aio::ConnectionManager::new(client)
+                .await
+                .map(Self)
+                .map_err(|e| super::Error::BackendError(Box::new(e)))
+        }
+    }
+
+    impl super::CacheBackend for Redis {
+        async fn get(&self, key: &str) -> Result<Option<String>, super::Error> {
+            let mut conn = self.0.clone();
+            conn.get(key)
+                .await
+                .map_err(|e| super::Error::BackendError(Box::new(e)))
+        }
+
+        async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), super::Error> {
+            let mut conn = self.0.clone();
+            conn.set_ex(key, value, ttl.as_secs().max(1))
+                .await
+                .map_err(|e| super::Error::BackendError(Box::new(e)))
+        }
+
+        /// Starts the counter with its expiry and increments it in one
+        /// transaction, so no counter is left without one.
+        async fn incr(&self, key: &str, ttl: Duration) -> Result<u64, super::Error> {
+            let mut conn = self.0.clone();
+            let (count,): (u64,) = ::redis::pipe()
+                .atomic()
+                .set_options(
+                    key,
+                    0,
+                    ::redis::SetOptions::default()
+                        .conditional_set(::redis::ExistenceCheck::NX)
+                        .with_expiration(::redis::SetExpiry::EX(ttl.as_secs().max(1))),
+                )
+                .ignore()
+                .incr(key, 1)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| super::Error::BackendError(Box::new(e)))?;
+            Ok(count)
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Settings for running the server, read from the environment at boot.
+//!
+//! Settings of the puzzles themselves are read by the modules they belong to,
+//! e.g. [`crate::puzzle_config::PuzzleGenerationPolicy::from_env`].
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+const DEFAULT_BIND_ADDRESS: &str = "0.0.0.0:3000";
+const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+const DEFAULT_MAX_WORDS_PER_REQUEST: usize = 10_000;
+/// Longer words can't be the answer of any puzzle.
+const DEFAULT_MAX_WORD_LEN: usize = 32;
+/// What `tracing_subscriber` falls back on without a filter.
+const DEFAULT_LOG_LEVEL: &str = "error";
+/// Admin tokens shorter than this are too easy to guess.
+const MIN_ADMIN_TOKEN_LEN: usize = 32;
+
+#[derive(Clone)]
+pub struct ServerConfig {
+    /// Address to listen on, from `BEE_BIND_ADDRESS`.
+    pub bind_address: SocketAddr,
+    /// Postgres connection string, from `DATABASE_URL`. Only needed with a
+    /// database, see [`ServerConfig::database_url`].
+    pub database_url: Option<String>,
+    /// Most connections the pool opens, from `DATABASE_MAX_CONNECTIONS`.
+    pub max_connections: u32,
+    /// `tracing_subscriber` filter directives, from `BEE_LOG_LEVEL`.
+    pub log_level: String,
+    /// Page served for every route of the app, from `BEE_INDEX_FILE`.
+    pub index_file: PathBuf,
+    /// Directory of the built frontend, from `BEE_ASSETS_DIR`.
+    pub assets_dir: PathBuf,
+    /// Bearer token granting admin access without a session, from
+    /// `BEE_ADMIN_TOKEN`. Unset disables it.
+    pub admin_token: Option<String>,
+    /// Most words a request may add, remove or restore at once, from
+    /// `BEE_MAX_WORDS_PER_REQUEST`. Longer lists can be imported instead.
+    pub max_words_per_request: usize,
+    /// Longest word that can be added to a dictionary, in characters, from
+    /// `BEE_MAX_WORD_LEN`.
+    pub max_word_len: usize,
+}
+
+impl std::fmt::Debug for ServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The database URL may hold a password, so only the settings without
+        // secrets are shown.
+        f.debug_struct("ServerConfig")
+            .field("bind_address", &self.bind_address)
+            .field("max_connections", &self.max_connections)
+            .field("log_level", &self.log_level)
+            .field("index_file", &self.index_file)
+            .field("assets_dir", &self.assets_dir)
+            .field(
+                "admin_token",
+                &self.admin_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("max_words_per_request", &self.max_words_per_request)
+            .field("max_word_len", &self.max_word_len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerConfig {
+    /// Read the settings from the environment, using the defaults for any
+    /// that are unset.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| dotenvy::var(name).ok())
+    }
+
+    /// The Postgres connection string, which must be set unless the server
+    /// runs without a database.
+    pub fn database_url(&self) -> Result<&str, ConfigError> {
+        self.database_url
+            .as_deref()
+            .ok_or(ConfigError::Missing("DATABASE_URL"))
+    }
+
+    fn from_vars(var: impl Fn(&'static str) -> Option<String>) -> Result<Self, ConfigError> {
+        let var = |name| var(name).filter(|value| !value.trim().is_empty());
+
+        let bind_address = match var("BEE_BIND_ADDRESS") {
+            Some(value) => value.parse().map_err(|_| ConfigError::Invalid {
+                name: "BEE_BIND_ADDRESS",
+                value,
+                expected: "an address and port such as 0.0.0.0:3000",
+            })?,
+            None => DEFAULT_BIND_ADDRESS.parse().unwrap(),
+        };
+
+        let database_url = var("DATABASE_URL");
+        if let Some(url) = database_url
+            .as_ref()
+            .filter(|url| !url.starts_with("postgres://") && !url.starts_with("postgresql://"))
+        {
+            return Err(ConfigError::Invalid {
+                name: "DATABASE_URL",
+                value: url.clone(),
+                expected: "a postgres:// connection string",
+            });
+        }
+
+        let max_connections = positive_integer(
+            "DATABASE_MAX_CONNECTIONS",
+            var("DATABASE_MAX_CONNECTIONS"),
+            DEFAULT_MAX_CONNECTIONS,
+        )?;
+        let max_words_per_request = positive_integer(
+            "BEE_MAX_WORDS_PER_REQUEST",
+            var("BEE_MAX_WORDS_PER_REQUEST"),
+            DEFAULT_MAX_WORDS_PER_REQUEST,
+        )?;
+        let max_word_len = positive_integer(
+            "BEE_MAX_WORD_LEN",
+            var("BEE_MAX_WORD_LEN"),
+            DEFAULT_MAX_WORD_LEN,
+        )?;
+
+        let log_level = var("BEE_LOG_LEVEL").unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_owned());
+        if tracing_subscriber::EnvFilter::try_new(&log_level).is_err() {
+            return Err(ConfigError::Invalid {
+                name: "BEE_LOG_LEVEL",
+                value: log_level,
+                expected: "a log level such as info, or filter directives such as server=debug",
+            });
+        }
+
+        let admin_token = var("BEE_ADMIN_TOKEN");
+        if admin_token
+            .as_ref()
+            .is_some_and(|token| token.len() < MIN_ADMIN_TOKEN_LEN)
+        {
+            return Err(ConfigError::ShortAdminToken);
+        }
+
+        Ok(Self {
+            bind_address,
+            database_url,
+            max_connections,
+            log_level,
+            index_file: var("BEE_INDEX_FILE")
+                .unwrap_or_else(|| "index.html".to_owned())
+                .into(),
+            assets_dir: var("BEE_ASSETS_DIR")
+                .unwrap_or_else(|| "assets".to_owned())
+                .into(),
+            admin_token,
+            max_words_per_request,
+            max_word_len,
+        })
+    }
+}
+
+/// The positive integer `value` of the variable `name`, or `default` if it's
+/// unset.
+fn positive_integer<T>(
+    name: &'static str,
+    value: Option<String>,
+    default: T,
+) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr + PartialOrd + Default,
+{
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    value
+        .parse()
+        .ok()
+        .filter(|n| *n > T::default())
+        .ok_or(ConfigError::Invalid {
+            name,
+            value,
+            expected: "a positive integer",
+        })
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Missing(&'static str),
+    Invalid {
+        name: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    ShortAdminToken,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Missing(name) => write!(f, "{} must be set", name),
+            Self::Invalid {
+                name,
+                value,
+                expected,
+            } => write!(f, "{} must be {}, got {:?}", name, expected, value),
+            Self::ShortAdminToken => write!(
+                f,
+                "BEE_ADMIN_TOKEN must be at least {} characters long",
+                MIN_ADMIN_TOKEN_LEN
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> Result<ServerConfig, ConfigError> {
+        ServerConfig::from_vars(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| (*value).to_owned())
+        })
+    }
+
+    #[test]
+    fn defaults_everything_but_the_database() {
+        let without_database = config(&[]).unwrap();
+        assert_eq!(
+            "DATABASE_URL must be set",
+            without_database.database_url().unwrap_err().to_string()
+        );
+
+        let config = config(&[("DATABASE_URL", "postgres://bee@localhost/bee")]).unwrap();
+        assert_eq!(
+            Ok("postgres://bee@localhost/bee"),
+            config.database_url().map_err(|_| ())
+        );
+
+        assert_eq!("0.0.0.0:3000", config.bind_address.to_string());
+        assert_eq!(10, config.max_connections);
+        assert_eq!("error", config.log_level);
+        assert_eq!(PathBuf::from("index.html"), config.index_file);
+        assert_eq!(PathBuf::from("assets"), config.assets_dir);
+        assert_eq!(None, config.admin_token);
+        assert_eq!(10_000, config.max_words_per_request);
+        assert_eq!(32, config.max_word_len);
+    }
+
+    #[test]
+    fn explains_invalid_settings() {
+        let error = |vars: &[(&str, &str)]| config(vars).unwrap_err().to_string();
+        let db = ("DATABASE_URL", "postgres://bee@localhost/bee");
+
+        assert_eq!(
+            "DATABASE_URL must be a postgres:// connection string, got \"mysql://localhost\"",
+            error(&[("DATABASE_URL", "mysql://localhost")])
+        );
+        assert_eq!(
+            "BEE_BIND_ADDRESS must be an address and port such as 0.0.0.0:3000, got \"localhost\"",
+            error(&[db, ("BEE_BIND_ADDRESS", "localhost")])
+        );
+        assert_eq!(
+            "DATABASE_MAX_CONNECTIONS must be a positive integer, got \"0\"",
+            error(&[db, ("DATABASE_MAX_CONNECTIONS", "0")])
+        );
+        assert_eq!(
+            "BEE_MAX_WORD_LEN must be a positive integer, got \"long\"",
+            error(&[db, ("BEE_MAX_WORD_LEN", "long")])
+        );
+        assert!(error(&[db, ("BEE_LOG_LEVEL", "server=loud")]).starts_with("BEE_LOG_LEVEL"));
+        assert_eq!(
+            "BEE_ADMIN_TOKEN must be at least 32 characters long",
+            error(&[db, ("BEE_ADMIN_TOKEN", "hunter2")])
+        );
+    }
+}
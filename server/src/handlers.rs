@@ -1,3 +1,12 @@
+pub(crate) mod auth;
+pub(crate) mod client_errors;
+pub(crate) mod custom;
+pub(crate) mod dictionaries;
+pub(crate) mod embedded;
+pub(crate) mod feedback;
 pub(crate) mod management;
+pub(crate) mod metrics;
+pub(crate) mod notes;
+pub(crate) mod progress;
 pub(crate) mod puzzle_config;
 pub(crate) mod words;
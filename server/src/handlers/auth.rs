@@ -0,0 +1,158 @@
+use std::net::SocketAddr;
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+use crate::auth::CurrentUser;
+use crate::rate_limit::RateLimiter;
+use crate::responses::AppError;
+use crate::services::users::{
+    CreateSession, CreateUser, DeleteSession, FindCredentials, SetPreferences, User,
+};
+
+const MIN_PASSWORD_LEN: usize = 8;
+const MAX_PASSWORD_LEN: usize = 256;
+
+#[derive(Clone)]
+pub(crate) struct AuthState<Users, Sessions> {
+    pub(crate) users: Users,
+    pub(crate) sessions: Sessions,
+    pub(crate) limiter: RateLimiter,
+}
+
+pub(crate) async fn register<Users, Sessions>(
+    State(state): State<AuthState<Users, Sessions>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(credentials): Json<accounts::Credentials>,
+) -> Result<Response, AppError>
+where
+    Users: CreateUser,
+    Sessions: CreateSession,
+{
+    if !state.limiter.check(addr.ip()).await {
+        return Err(too_many_attempts());
+    }
+
+    let username = credentials.username.trim().to_lowercase();
+    if !valid_username(&username) {
+        return Err(AppError::Validation(
+            "Usernames must be 3 to 32 letters, numbers, dashes or underscores.".to_owned(),
+        ));
+    }
+    if !(MIN_PASSWORD_LEN..=MAX_PASSWORD_LEN).contains(&credentials.password.len()) {
+        return Err(AppError::Validation(format!(
+            "Passwords must be {} to {} characters long.",
+            MIN_PASSWORD_LEN, MAX_PASSWORD_LEN
+        )));
+    }
+
+    let password_hash = crate::auth::hash_password(credentials.password).await?;
+    // Converted right away so the non-`Send` error is dropped before the
+    // session is created.
+    let user = state.users.create_user(&username, &password_hash).await?;
+
+    start_session(&state.sessions, user, StatusCode::CREATED).await
+}
+
+pub(crate) async fn login<Users, Sessions>(
+    State(state): State<AuthState<Users, Sessions>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(credentials): Json<accounts::Credentials>,
+) -> Result<Response, AppError>
+where
+    Users: FindCredentials,
+    Sessions: CreateSession,
+{
+    if !state.limiter.check(addr.ip()).await {
+        return Err(too_many_attempts());
+    }
+
+    let username = credentials.username.trim().to_lowercase();
+    // Unknown usernames are checked against a dummy hash, so that how long
+    // logging in takes doesn't reveal which usernames exist.
+    let (user, password_hash) = state.users.find_credentials(&username).await?.unzip();
+    let verified = crate::auth::verify_password(credentials.password, password_hash).await?;
+    let user = user
+        .filter(|_| verified)
+        .ok_or_else(|| AppError::Unauthorized("Incorrect username or password".to_owned()))?;
+
+    start_session(&state.sessions, user, StatusCode::OK).await
+}
+
+pub(crate) async fn logout<Sessions>(
+    State(sessions): State<Sessions>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError>
+where
+    Sessions: DeleteSession,
+{
+    if let Some(token) = crate::auth::session_token(&headers) {
+        sessions
+            .delete_session(&crate::auth::hash_token(token))
+            .await?;
+    }
+
+    Ok((
+        StatusCode::NO_CONTENT,
+        [(header::SET_COOKIE, crate::auth::expired_session_cookie())],
+    ))
+}
+
+pub(crate) async fn me(CurrentUser(user): CurrentUser) -> Json<accounts::User> {
+    Json(accounts::User {
+        username: user.username,
+        dictionary: user.dictionary,
+    })
+}
+
+pub(crate) async fn set_preferences<Users>(
+    State(users): State<Users>,
+    CurrentUser(user): CurrentUser,
+    Json(preferences): Json<accounts::Preferences>,
+) -> Result<Json<accounts::Preferences>, AppError>
+where
+    Users: SetPreferences,
+{
+    users.set_preferences(user.id, &preferences).await?;
+    Ok(Json(preferences))
+}
+
+async fn start_session<Sessions>(
+    sessions: &Sessions,
+    user: User,
+    status: StatusCode,
+) -> Result<Response, AppError>
+where
+    Sessions: CreateSession,
+{
+    let token = crate::auth::new_session_token();
+    let expires_at = chrono::Utc::now() + crate::auth::SESSION_TTL;
+    sessions
+        .create_session(user.id, &crate::auth::hash_token(&token), expires_at)
+        .await?;
+
+    Ok((
+        status,
+        [(header::SET_COOKIE, crate::auth::session_cookie(&token))],
+        Json(accounts::User {
+            username: user.username,
+            dictionary: user.dictionary,
+        }),
+    )
+        .into_response())
+}
+
+fn valid_username(username: &str) -> bool {
+    (3..=32).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn too_many_attempts() -> AppError {
+    AppError::TooManyRequests("Too many attempts. Try again later.".to_owned())
+}
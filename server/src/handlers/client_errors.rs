@@ -0,0 +1,67 @@
+use std::net::SocketAddr;
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, State},
+    http::StatusCode,
+};
+
+use crate::rate_limit::RateLimiter;
+use crate::responses::AppError;
+use crate::services::client_errors::{ListClientErrors, ReportClientError};
+
+const MAX_MESSAGE_LEN: usize = 4096;
+const MAX_FIELD_LEN: usize = 512;
+const LIST_LIMIT: i64 = 200;
+
+#[derive(Clone)]
+pub(crate) struct ReportState<Service> {
+    pub(crate) service: Service,
+    pub(crate) limiter: RateLimiter,
+}
+
+pub(crate) async fn report<Service>(
+    State(state): State<ReportState<Service>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(mut report): Json<client_errors::ClientErrorReport>,
+) -> Result<StatusCode, AppError>
+where
+    Service: ReportClientError,
+{
+    if !state.limiter.check(addr.ip()).await {
+        return Err(AppError::TooManyRequests(
+            "Too many error reports".to_owned(),
+        ));
+    }
+
+    truncate(&mut report.message, MAX_MESSAGE_LEN);
+    for field in [&mut report.location, &mut report.route]
+        .into_iter()
+        .flatten()
+    {
+        truncate(field, MAX_FIELD_LEN);
+    }
+
+    state.service.report(&report).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(crate) async fn list<Service>(
+    State(service): State<Service>,
+) -> Result<Json<client_errors::ReportedErrors>, AppError>
+where
+    Service: ListClientErrors,
+{
+    let errors = service.list(LIST_LIMIT).await?;
+    Ok(Json(client_errors::ReportedErrors { errors }))
+}
+
+fn truncate(s: &mut String, max_len: usize) {
+    if s.len() > max_len {
+        let mut end = max_len;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        s.truncate(end);
+    }
+}
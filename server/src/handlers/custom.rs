@@ -0,0 +1,77 @@
+use std::net::SocketAddr;
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Path, Query, State},
+    http::StatusCode,
+};
+use puzzle_config::{CustomPuzzle, NewCustomPuzzle};
+
+use crate::handlers::puzzle_config::{DictionaryQuery, puzzle_letters};
+use crate::puzzle_config::{ConfigProvider, restrict_to};
+use crate::rate_limit::RateLimiter;
+use crate::responses::AppError;
+use crate::services::puzzles::CustomPuzzleRepository;
+
+#[derive(Clone)]
+pub(crate) struct CustomPuzzleState<Repository> {
+    pub(crate) repository: Repository,
+    pub(crate) configs: ConfigProvider,
+    pub(crate) limiter: RateLimiter,
+}
+
+/// Build a puzzle from the letters a player picked and store it under a new
+/// ID for them to share. Its words are fixed once stored, like those of daily
+/// puzzles.
+pub(crate) async fn create_custom_puzzle<Repository>(
+    State(state): State<CustomPuzzleState<Repository>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(form): Json<NewCustomPuzzle>,
+) -> Result<(StatusCode, Json<CustomPuzzle>), AppError>
+where
+    Repository: CustomPuzzleRepository,
+{
+    if !state.limiter.check(addr.ip()).await {
+        return Err(AppError::TooManyRequests(
+            "Too many custom puzzles".to_owned(),
+        ));
+    }
+
+    let (required, letters) =
+        puzzle_letters(state.configs.alphabet(), &form.letters, &form.required).ok_or_else(
+            || {
+                AppError::Validation(
+                    "Letters must be seven distinct letters from a to z, including the required \
+                    letter."
+                        .to_owned(),
+                )
+            },
+        )?;
+
+    // The non-`Send` error is converted as soon as it is returned, so that it
+    // is dropped before the next await.
+    let config = state.configs.build_custom(required, &letters).await?;
+    if config.valid_words.is_empty() {
+        return Err(AppError::Validation(
+            "These letters don't spell any words with the required letter.".to_owned(),
+        ));
+    }
+
+    let id = state.repository.create(&config).await?;
+    Ok((StatusCode::CREATED, Json(CustomPuzzle { id })))
+}
+
+pub(crate) async fn custom_puzzle_config<Repository>(
+    State(repository): State<Repository>,
+    Path(id): Path<String>,
+    Query(DictionaryQuery { dictionary }): Query<DictionaryQuery>,
+) -> Result<Json<puzzle_config::PuzzleConfig>, AppError>
+where
+    Repository: CustomPuzzleRepository,
+{
+    let config = repository
+        .get(&id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("No custom puzzle has the ID {:?}", id)))?;
+    Ok(Json(restrict_to(config, dictionary)))
+}
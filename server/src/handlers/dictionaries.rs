@@ -0,0 +1,91 @@
+use axum::{
+    Json,
+    extract::{FromRequestParts, Query, State},
+    http::{StatusCode, request::Parts},
+};
+use serde::Deserialize;
+
+use crate::responses::AppError;
+use crate::services::dictionaries::{Dictionaries as _, DictionaryId, pg::Dictionaries};
+
+/// Longest name a dictionary can be given.
+const MAX_NAME_LEN: usize = 64;
+
+/// Every dictionary with its count of words.
+pub(crate) async fn list_dictionaries<Service>(
+    State(service): State<Service>,
+) -> Result<Json<words_list::Dictionaries>, AppError>
+where
+    Service: crate::services::dictionaries::Dictionaries,
+{
+    let dictionaries = service.list().await?;
+    Ok(Json(words_list::Dictionaries { dictionaries }))
+}
+
+/// Create an empty dictionary, which words can then be added to with the
+/// `word_list` query parameter. Creating one that exists already does nothing.
+pub(crate) async fn create_dictionary<Service>(
+    State(service): State<Service>,
+    Json(form): Json<words_list::NewDictionary>,
+) -> Result<StatusCode, AppError>
+where
+    Service: crate::services::dictionaries::Dictionaries,
+{
+    let name = form.name.trim().to_lowercase();
+    if !is_valid_name(&name) {
+        return Err(AppError::Validation(format!(
+            "Dictionary names must be 1 to {} lowercase letters, digits or dashes.",
+            MAX_NAME_LEN
+        )));
+    }
+
+    service.create(&name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Names are kept to what can go in a query string as is.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[derive(Deserialize)]
+struct WordListQuery {
+    word_list: Option<String>,
+}
+
+/// The dictionary named by the `word_list` query parameter, the standard one
+/// unless asked. Rejects requests naming a dictionary that doesn't exist with
+/// 404.
+pub(crate) struct SelectedDictionary(pub(crate) DictionaryId);
+
+impl<S> FromRequestParts<S> for SelectedDictionary
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let name = Query::<WordListQuery>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(query)| query.word_list)
+            .filter(|name| !name.is_empty());
+        let Some(name) = name else {
+            return Ok(Self(DictionaryId::STANDARD));
+        };
+        let not_found = || AppError::NotFound(format!("There is no dictionary called {:?}", name));
+        // Servers without a database only have the standard dictionary.
+        let Some(dictionaries) = parts.extensions.get::<Dictionaries>() else {
+            return Err(not_found());
+        };
+
+        dictionaries
+            .find(&name)
+            .await?
+            .map(Self)
+            .ok_or_else(not_found)
+    }
+}
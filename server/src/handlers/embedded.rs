@@ -0,0 +1,21 @@
+//! Answers for the routes a server without Postgres can't serve.
+
+use axum::http::StatusCode;
+
+use crate::responses::AppError;
+
+/// Adding or removing words, which the embedded dictionary doesn't allow.
+pub(crate) async fn read_only_words() -> AppError {
+    AppError::Status(
+        StatusCode::NOT_IMPLEMENTED,
+        "The word list is read-only when serving an embedded dictionary.".to_owned(),
+    )
+}
+
+/// Any route that needs Postgres.
+pub(crate) async fn unavailable() -> AppError {
+    AppError::Status(
+        StatusCode::NOT_IMPLEMENTED,
+        "Not available when running without Postgres.".to_owned(),
+    )
+}
@@ -0,0 +1,60 @@
+use std::net::SocketAddr;
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, State},
+    http::StatusCode,
+};
+
+use crate::rate_limit::RateLimiter;
+use crate::responses::AppError;
+use crate::services::feedback::{ListWordReports, ReportWord};
+
+/// Longer words can't be the answer of any puzzle.
+const MAX_WORD_LEN: usize = 32;
+const LIST_LIMIT: i64 = 200;
+
+#[derive(Clone)]
+pub(crate) struct ReportState<Service> {
+    pub(crate) service: Service,
+    pub(crate) limiter: RateLimiter,
+}
+
+/// Store a player's report of a word missing from the list or wrongly in it.
+pub(crate) async fn report_word<Service>(
+    State(state): State<ReportState<Service>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(mut report): Json<words_list::WordReport>,
+) -> Result<StatusCode, AppError>
+where
+    Service: ReportWord,
+{
+    if !state.limiter.check(addr.ip()).await {
+        return Err(AppError::TooManyRequests(
+            "Too many word reports".to_owned(),
+        ));
+    }
+
+    report.word = report.word.trim().to_lowercase();
+    if !(4..=MAX_WORD_LEN).contains(&report.word.len())
+        || !report.word.chars().all(|c| c.is_ascii_lowercase())
+    {
+        return Err(AppError::Validation(format!(
+            "Reported words must be 4 to {} letters from a to z.",
+            MAX_WORD_LEN
+        )));
+    }
+
+    state.service.report_word(&report).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(crate) async fn list_word_reports<Service>(
+    State(service): State<Service>,
+) -> Result<Json<words_list::ReportedWords>, AppError>
+where
+    Service: ListWordReports,
+{
+    let words = service.list_word_reports(LIST_LIMIT).await?;
+    Ok(Json(words_list::ReportedWords { words }))
+}
@@ -1,103 +1,416 @@
+use std::collections::HashSet;
+
 use axum::{
     Json,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
 };
 use base64::Engine as _;
+use chrono::{NaiveDate, Utc};
 use serde::Deserialize;
 
+use crate::handlers::dictionaries::SelectedDictionary;
+use crate::responses::AppError;
+
 pub(crate) async fn list_words<Service>(
     State(service): State<Service>,
     Query(query): Query<ListQuery>,
-) -> impl IntoResponse
+    SelectedDictionary(dictionary): SelectedDictionary,
+) -> Result<Json<words_list::Words>, AppError>
 where
     Service: crate::services::words::ListWords,
 {
-    let Ok(cursor) = query
-        .cursor
-        .map(cursor_from_url)
-        .unwrap_or_else(|| Ok(Default::default()))
-    else {
-        return crate::responses::Error::new(
-            StatusCode::UNPROCESSABLE_ENTITY,
-            "Invalid cursor".to_owned(),
-        )
-        .into_response();
+    let cursor = match query.cursor {
+        Some(cursor) => cursor_from_url(cursor)
+            .map_err(|_| AppError::Validation("Invalid cursor".to_owned()))?,
+        None => Default::default(),
     };
 
-    match service.list(&cursor, None).await {
-        Err(e) => crate::responses::Error::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-            .into_response(),
-        Ok(crate::services::words::ListedWords { words, next_page }) => {
-            (
-                StatusCode::OK,
-                [("content-type", "application/json")],
-                Json(words_list::Words {
-                    words: words
-                        .into_iter()
-                        .map(|w| words_list::Word {
-                            text: w.text,
-                            cursor: words_list::Cursor(cursor_to_url(&w.cursor).unwrap()),
-                        })
-                        .collect(),
-                    pagination: words_list::Pagination {
-                        next_page: next_page
-                            .and_then(|np| cursor_to_url(&np).map(|c| words_list::Cursor(c)).ok()),
-                        prev_page: None,
-                    },
-                }),
-            )
-        }
-        .into_response(),
-    }
+    let limit = query.limit.map(|limit| limit.clamp(1, MAX_PAGE_SIZE));
+    let crate::services::words::ListedWords {
+        words,
+        next_page,
+        prev_page,
+    } = service.list(dictionary, &cursor, limit).await?;
+    Ok(Json(words_list::Words {
+        words: words
+            .into_iter()
+            .map(|w| words_list::Word {
+                text: w.text,
+                cursor: words_list::Cursor(cursor_to_url(&w.cursor)),
+            })
+            .collect(),
+        pagination: words_list::Pagination {
+            next_page: next_page.map(|np| words_list::Cursor(cursor_to_url(&np))),
+            prev_page: prev_page.map(|pp| words_list::Cursor(cursor_to_url(&pp))),
+        },
+    }))
 }
 
+const MAX_PAGE_SIZE: usize = 200;
+
 #[derive(Deserialize)]
 pub(crate) struct ListQuery {
     cursor: Option<String>,
+    limit: Option<usize>,
 }
 
-fn cursor_to_url(
-    cursor: &crate::services::words::ListCursor,
-) -> Result<String, Box<dyn std::error::Error>> {
-    let mut output = String::new();
-    base64::engine::general_purpose::URL_SAFE.encode_string(cursor.after.as_bytes(), &mut output);
-    Ok(output)
+/// Cursors are encoded as `after:<word>` or `before:<word>` in URL-safe base64.
+fn cursor_to_url(cursor: &crate::services::words::ListCursor) -> String {
+    let raw = match cursor {
+        crate::services::words::ListCursor::After(word) => format!("after:{}", word),
+        crate::services::words::ListCursor::Before(word) => format!("before:{}", word),
+    };
+    base64::engine::general_purpose::URL_SAFE.encode(raw.as_bytes())
 }
 
 fn cursor_from_url(
     param: String,
 ) -> Result<crate::services::words::ListCursor, Box<dyn std::error::Error>> {
-    let after = base64::engine::general_purpose::URL_SAFE
+    let raw = base64::engine::general_purpose::URL_SAFE
         .decode(&param)
         .map_err(Box::new)?;
 
-    let after = String::from_utf8(after).map_err(Box::new)?;
-    Ok(crate::services::words::ListCursor { after })
+    let raw = String::from_utf8(raw).map_err(Box::new)?;
+    match raw.split_once(':') {
+        Some(("after", word)) => Ok(crate::services::words::ListCursor::After(word.to_owned())),
+        Some(("before", word)) => Ok(crate::services::words::ListCursor::Before(word.to_owned())),
+        _ => Err(format!("Unknown cursor {:?}", raw).into()),
+    }
 }
 
 pub(crate) async fn search<Service>(
     State(service): State<Service>,
     Query(query): Query<SearchQuery>,
-) -> impl IntoResponse
+    SelectedDictionary(dictionary): SelectedDictionary,
+) -> Result<Json<search::SearchedWords>, AppError>
 where
     Service: crate::services::words::SearchWords,
 {
-    match service.search(&query.query).await {
-        Err(e) => crate::responses::Error::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-            .into_response(),
-        Ok(results) => (
-            StatusCode::OK,
-            [("content-type", "application/json")],
-            Json(search::SearchedWords { words: results }),
-        )
-            .into_response(),
-    }
+    let limit = query
+        .limit
+        .map_or(DEFAULT_SEARCH_LIMIT, |limit| limit.clamp(1, MAX_PAGE_SIZE));
+    let words = service
+        .search(dictionary, &query.query, query.mode, limit)
+        .await?;
+    Ok(Json(search::SearchedWords { words }))
+}
+
+#[derive(Clone)]
+pub(crate) struct ExcludedWordsState<Service> {
+    pub(crate) service: Service,
+    pub(crate) morphology: crate::morphology::MorphologyPolicy,
+}
+
+/// Words in the list that the morphology policy keeps out of puzzles.
+pub(crate) async fn excluded_words<Service>(
+    State(state): State<ExcludedWordsState<Service>>,
+    SelectedDictionary(dictionary): SelectedDictionary,
+) -> Result<Json<words_list::ExcludedWords>, AppError>
+where
+    Service: crate::services::words::ListWords,
+{
+    let words = state.service.all(dictionary).await?;
+
+    let known: HashSet<&str> = words.iter().map(String::as_str).collect();
+    let excluded = words
+        .iter()
+        .filter_map(|word| {
+            state
+                .morphology
+                .exclusion(word, |stem| known.contains(stem))
+        })
+        .map(|exclusion| words_list::ExcludedWord {
+            word: exclusion.word,
+            stem: exclusion.stem,
+            inflection: exclusion.inflection.as_str().to_owned(),
+        })
+        .collect();
+    Ok(Json(words_list::ExcludedWords { words: excluded }))
 }
 
 #[derive(Deserialize)]
 pub(crate) struct SearchQuery {
     #[serde(alias = "q")]
     query: String,
+    #[serde(default)]
+    mode: search::SearchMode,
+    limit: Option<usize>,
+}
+
+const DEFAULT_SEARCH_LIMIT: usize = 15;
+
+const INVALID_DATE: &str = "Invalid date. Dates must be formatted as YYYY-MM-DD.";
+
+fn parse_date(date: &str) -> Result<NaiveDate, AppError> {
+    date.parse()
+        .map_err(|_| AppError::Validation(INVALID_DATE.to_owned()))
+}
+
+pub(crate) async fn list_exclusions<Service>(
+    State(service): State<Service>,
+    Path(date): Path<String>,
+) -> Result<Json<search::SearchedWords>, AppError>
+where
+    Service: crate::services::puzzles::ListExclusions,
+{
+    let date = parse_date(&date)?;
+    let words = service.list_exclusions(date).await?;
+    Ok(Json(search::SearchedWords { words }))
+}
+
+pub(crate) async fn exclude_words<Service>(
+    State(service): State<Service>,
+    Path(date): Path<String>,
+    Json(form): Json<ExcludeWordsForm>,
+) -> Result<StatusCode, AppError>
+where
+    Service: crate::services::puzzles::ExcludeWords,
+{
+    let date = parse_date(&date)?;
+    let words: Vec<String> = form.words.iter().map(|w| w.to_lowercase()).collect();
+    service.exclude_words(date, &words).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ExcludeWordsForm {
+    pub(crate) words: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ScheduleQuery {
+    from: String,
+    to: String,
+}
+
+/// The days scheduled from `from` to `to` inclusive.
+pub(crate) async fn list_schedule<Repository>(
+    State(repository): State<Repository>,
+    Query(query): Query<ScheduleQuery>,
+) -> Result<Json<puzzle_config::Schedule>, AppError>
+where
+    Repository: crate::services::puzzles::ScheduleRepository,
+{
+    let (from, to) = (parse_date(&query.from)?, parse_date(&query.to)?);
+
+    let puzzles = repository.list(from, to).await?;
+    Ok(Json(puzzle_config::Schedule {
+        puzzles: puzzles
+            .into_iter()
+            .map(|puzzle| puzzle_config::ScheduledPuzzle {
+                date: puzzle.date.to_string(),
+                required_letter: puzzle
+                    .letters
+                    .as_ref()
+                    .map(|l| puzzle_config::Letter::new(l.required)),
+                other_letters: puzzle
+                    .letters
+                    .map(|l| {
+                        l.others
+                            .into_iter()
+                            .map(puzzle_config::Letter::new)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                event: puzzle.event,
+            })
+            .collect(),
+    }))
+}
+
+/// Pin letters, an event or both to a day. Letters can only be pinned to days
+/// that haven't started anywhere yet, since puzzles already generated for a
+/// day are kept.
+pub(crate) async fn schedule_puzzle<Repository>(
+    State(repository): State<Repository>,
+    Json(puzzle): Json<puzzle_config::ScheduledPuzzle>,
+) -> Result<StatusCode, AppError>
+where
+    Repository: crate::services::puzzles::ScheduleRepository,
+{
+    let puzzle = scheduled_puzzle(puzzle)?;
+    repository.save(&puzzle).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn scheduled_puzzle(
+    puzzle: puzzle_config::ScheduledPuzzle,
+) -> Result<crate::services::puzzles::ScheduledPuzzle, AppError> {
+    let invalid = |message: &str| AppError::Validation(message.to_owned());
+    let date = parse_date(&puzzle.date)?;
+    let letters = match (puzzle.required_letter, puzzle.other_letters) {
+        (None, others) if others.is_empty() => None,
+        (Some(required), others) => {
+            let required = required.0.to_ascii_lowercase();
+            let others: Vec<char> = others.iter().map(|l| l.0.to_ascii_lowercase()).collect();
+            let distinct: HashSet<char> = others.iter().copied().chain([required]).collect();
+            if others.len() != 6
+                || distinct.len() != 7
+                || !distinct.iter().all(char::is_ascii_lowercase)
+            {
+                return Err(invalid(
+                    "Scheduled letters must be a required letter and six other letters, \
+                    all distinct letters from a to z.",
+                ));
+            }
+            // The earliest timezones start the next day while it is still today in UTC.
+            if date <= Utc::now().date_naive() + chrono::Days::new(1) {
+                return Err(invalid(
+                    "Letters can only be scheduled for days that haven't started yet.",
+                ));
+            }
+            Some(crate::services::puzzles::ScheduledLetters { required, others })
+        }
+        (None, _) => return Err(invalid("Scheduled letters need a required letter.")),
+    };
+
+    Ok(crate::services::puzzles::ScheduledPuzzle {
+        date,
+        letters,
+        event: puzzle
+            .event
+            .map(|e| e.trim().to_owned())
+            .filter(|e| !e.is_empty()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::response::{IntoResponse as _, Response};
+
+    use super::*;
+    use crate::services::dictionaries::DictionaryId;
+    use crate::services::words::memory::{Unavailable, Words};
+
+    const STANDARD: DictionaryId = DictionaryId::STANDARD;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    async fn list(
+        service: impl crate::services::words::ListWords,
+        cursor: Option<&str>,
+        limit: Option<usize>,
+    ) -> Response {
+        list_words(
+            State(service),
+            Query(ListQuery {
+                cursor: cursor.map(str::to_owned),
+                limit,
+            }),
+            SelectedDictionary(STANDARD),
+        )
+        .await
+        .into_response()
+    }
+
+    async fn search_for(
+        service: impl crate::services::words::SearchWords,
+        query: &str,
+        mode: search::SearchMode,
+    ) -> Response {
+        search(
+            State(service),
+            Query(SearchQuery {
+                query: query.to_owned(),
+                mode,
+                limit: None,
+            }),
+            SelectedDictionary(STANDARD),
+        )
+        .await
+        .into_response()
+    }
+
+    #[tokio::test]
+    async fn pages_through_words_with_cursors() {
+        let store = Words::of(STANDARD, &["apple", "bread", "crane"]);
+
+        let response = list(store.clone(), None, Some(2)).await;
+        assert_eq!(StatusCode::OK, response.status());
+        let first = body(response).await;
+        assert_eq!("apple", first["words"][0]["text"]);
+        assert_eq!("bread", first["words"][1]["text"]);
+        assert!(first["pagination"]["prev_page"].is_null());
+
+        let next = first["pagination"]["next_page"].as_str().unwrap();
+        let second = body(list(store.clone(), Some(next), Some(2)).await).await;
+        assert_eq!(1, second["words"].as_array().unwrap().len());
+        assert_eq!("crane", second["words"][0]["text"]);
+        assert!(second["pagination"]["next_page"].is_null());
+
+        let prev = second["pagination"]["prev_page"].as_str().unwrap();
+        let again = body(list(store, Some(prev), Some(2)).await).await;
+        assert_eq!(first["words"], again["words"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_cursors() {
+        let store = Words::of(STANDARD, &["apple"]);
+        for cursor in ["not base64!", "c2lkZXdheXM6YXBwbGU="] {
+            let response = list(store.clone(), Some(cursor), None).await;
+            assert_eq!(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                response.status(),
+                "{}",
+                cursor
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn searches_words() {
+        let store = Words::of(STANDARD, &["apple", "bread", "crane"]);
+
+        let response = search_for(store.clone(), "br", search::SearchMode::Prefix).await;
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(serde_json::json!(["bread"]), body(response).await["words"]);
+
+        let found = body(search_for(store.clone(), "aple", search::SearchMode::Fuzzy).await).await;
+        assert_eq!("apple", found["words"][0]);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_search_patterns() {
+        let store = Words::of(STANDARD, &["apple"]);
+        for (query, mode) in [
+            ("(", search::SearchMode::Regex),
+            ("BREAD", search::SearchMode::Letters),
+        ] {
+            let response = search_for(store.clone(), query, mode).await;
+            assert_eq!(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                response.status(),
+                "{}",
+                query
+            );
+            let message = body(response).await["message"].as_str().unwrap().to_owned();
+            assert!(message.starts_with("Invalid search pattern"), "{}", message);
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_service_failures_as_server_errors() {
+        let response = list(Unavailable, None, None).await;
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+
+        let response = search_for(Unavailable, "apple", search::SearchMode::Fuzzy).await;
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+
+        let response = excluded_words(
+            State(ExcludedWordsState {
+                service: Unavailable,
+                morphology: Default::default(),
+            }),
+            SelectedDictionary(STANDARD),
+        )
+        .await
+        .into_response();
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+    }
 }
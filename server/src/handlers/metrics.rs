@@ -0,0 +1,18 @@
+use axum::{extract::State, http::header, response::IntoResponse};
+
+pub(crate) async fn metrics(State(pool): State<sqlx::PgPool>) -> impl IntoResponse {
+    crate::telemetry::record_pool(&pool);
+    rendered()
+}
+
+/// Metrics of a server running without a database, so without a pool.
+pub(crate) async fn embedded_metrics() -> impl IntoResponse {
+    rendered()
+}
+
+fn rendered() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::render(),
+    )
+}
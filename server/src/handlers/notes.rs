@@ -0,0 +1,58 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::NaiveDate;
+use puzzle_config::{MAX_NOTE_CHARS, Note};
+
+use crate::auth::CurrentUser;
+use crate::responses::AppError;
+use crate::services::notes::NotesRepository;
+
+/// The current user's note on the puzzle for `date`, empty if they haven't
+/// written one.
+pub(crate) async fn get_note<Repository>(
+    State(repository): State<Repository>,
+    CurrentUser(user): CurrentUser,
+    Path(date): Path<String>,
+) -> Result<Json<Note>, AppError>
+where
+    Repository: NotesRepository,
+{
+    let date = parse_date(&date)?;
+    let note = repository.get(user.id, date).await?;
+    Ok(Json(note.unwrap_or_default()))
+}
+
+/// Replace the current user's note on the puzzle for `date`. Blank notes are
+/// deleted.
+pub(crate) async fn put_note<Repository>(
+    State(repository): State<Repository>,
+    CurrentUser(user): CurrentUser,
+    Path(date): Path<String>,
+    Json(note): Json<Note>,
+) -> Result<Json<Note>, AppError>
+where
+    Repository: NotesRepository,
+{
+    let date = parse_date(&date)?;
+    if note.text.chars().count() > MAX_NOTE_CHARS {
+        return Err(AppError::Validation(format!(
+            "Notes must be at most {} characters long.",
+            MAX_NOTE_CHARS
+        )));
+    }
+
+    if note.text.trim().is_empty() {
+        repository.delete(user.id, date).await?;
+    } else {
+        repository.save(user.id, date, &note).await?;
+    }
+    Ok(Json(note))
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, AppError> {
+    date.parse().map_err(|_| {
+        AppError::Validation("Invalid date. Dates must be formatted as YYYY-MM-DD.".to_owned())
+    })
+}
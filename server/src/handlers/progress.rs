@@ -0,0 +1,95 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::{NaiveDate, Utc};
+use puzzle_config::{Progress, PuzzleConfig};
+
+use crate::auth::CurrentUser;
+use crate::handlers::puzzle_config::{DifficultyQuery, Timezone};
+use crate::puzzle_config::ConfigProvider;
+use crate::responses::AppError;
+use crate::services::dictionaries::DictionaryId;
+use crate::services::progress::ProgressRepository;
+use crate::timezone::Zone;
+
+#[derive(Clone)]
+pub(crate) struct ProgressState<Repository> {
+    pub(crate) repository: Repository,
+    pub(crate) configs: ConfigProvider,
+}
+
+pub(crate) async fn get_progress<Repository>(
+    State(state): State<ProgressState<Repository>>,
+    CurrentUser(user): CurrentUser,
+    Timezone(tz): Timezone,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+) -> Result<Json<Progress>, AppError>
+where
+    Repository: ProgressRepository,
+{
+    let (date, utc_offset) = today(&tz);
+
+    let stored = state
+        .repository
+        .get(user.id, date, utc_offset, difficulty)
+        .await?;
+    Ok(Json(stored.unwrap_or_default()))
+}
+
+/// Merge the submitted progress into the stored progress for the day and
+/// respond with the result. Words that aren't valid in the day's puzzle are
+/// dropped and the score is recalculated from the merged words.
+pub(crate) async fn put_progress<Repository>(
+    State(state): State<ProgressState<Repository>>,
+    CurrentUser(user): CurrentUser,
+    Timezone(tz): Timezone,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+    Json(progress): Json<Progress>,
+) -> Result<Json<Progress>, AppError>
+where
+    Repository: ProgressRepository,
+{
+    let (date, utc_offset) = today(&tz);
+
+    // The non-`Send` errors are converted as soon as they are returned, so
+    // that they are dropped before the next await. Progress is only kept for
+    // the puzzles of the standard dictionary.
+    let config = state
+        .configs
+        .get_config(&tz, difficulty, DictionaryId::STANDARD)
+        .await?;
+    let stored = state
+        .repository
+        .get(user.id, date, utc_offset, difficulty)
+        .await?
+        .unwrap_or_default();
+
+    let merged = merge(stored, progress, &config);
+    state
+        .repository
+        .save(user.id, date, utc_offset, difficulty, &merged)
+        .await?;
+    Ok(Json(merged))
+}
+
+/// Today's date in `tz` and the UTC offset its puzzle is stored under.
+fn today(tz: &Zone) -> (NaiveDate, i32) {
+    let date = tz.date_at(Utc::now());
+    (date, tz.day_offset(date).local_minus_utc())
+}
+
+fn merge(stored: Progress, incoming: Progress, config: &PuzzleConfig) -> Progress {
+    let mut submitted: Vec<String> = Vec::new();
+    let mut score = 0;
+    for word in stored.submitted.iter().chain(&incoming.submitted) {
+        let Ok(guess) = validation::validate_guess(config, word) else {
+            continue;
+        };
+        if !submitted.contains(&guess.word.word) {
+            score += guess.word.score(&config.scoring);
+            submitted.push(guess.word.word);
+        }
+    }
+    Progress { submitted, score }
+}
@@ -1,30 +1,346 @@
 use axum::{
-    extract::{Query, State},
-    http,
-    response::IntoResponse,
+    Json,
+    extract::{FromRequestParts, Path, Query, State},
+    http::{self, HeaderMap, header, request::Parts},
+    response::{IntoResponse, Response},
 };
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
 
+use ::puzzle_config::{
+    Answers, DictionaryHealth, Difficulty, Guess, GuessResult, Hints, OfflinePuzzleConfig,
+    PuzzleConfig, PuzzlePreview, SecurePuzzleConfig,
+};
+use accounts::Dictionary;
 use serde::Deserialize;
 
+use crate::handlers::dictionaries::SelectedDictionary;
 use crate::puzzle_config;
+use crate::responses::AppError;
+use crate::timezone::Zone;
+
+/// Bytes of the hash kept in an ETag, plenty to tell versions of a puzzle
+/// apart.
+const ETAG_HASH_LEN: usize = 16;
 
+/// The day's puzzle, which browsers may keep until the player's midnight and
+/// revalidate with its ETag after that, or when the player refreshes.
 pub async fn puzzle_config(
     State(configs): State<puzzle_config::ConfigProvider>,
-    Query(query): Query<TimezoneQuery>,
-) -> impl IntoResponse {
-    let config = configs
-        .get_config(&query.tz.parse().unwrap())
-        .await
-        .unwrap();
-    let body = serde_json::to_string(&config).unwrap();
-    (
+    Timezone(tz): Timezone,
+    Query(DictionaryQuery { dictionary }): Query<DictionaryQuery>,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+    SelectedDictionary(word_list): SelectedDictionary,
+    Query(ConfigModeQuery { mode }): Query<ConfigModeQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let config = configs.get_config(&tz, difficulty, word_list).await?;
+    let config = puzzle_config::restrict_to(config, dictionary);
+    let now = Utc::now();
+    let full = serde_json::to_string(&config)?;
+    let etag = etag(mode, &full);
+    let cache_control = format!(
+        "private, max-age={}",
+        (tz.next_midnight(now) - now).num_seconds().max(0)
+    );
+    if if_none_match(&headers, &etag) {
+        return Ok((
+            http::StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, cache_control),
+                (header::VARY, header::ACCEPT_ENCODING.to_string()),
+            ],
+        )
+            .into_response());
+    }
+
+    let body = match mode {
+        ConfigMode::Full => full,
+        ConfigMode::Secure => serde_json::to_string(&SecurePuzzleConfig::from(&config))?,
+        ConfigMode::Offline => {
+            serde_json::to_string(&OfflinePuzzleConfig::new(&config, rand::random()))?
+        }
+    };
+    Ok((
         http::StatusCode::OK,
-        [("content-type", "application/json")],
+        [
+            (header::CONTENT_TYPE, "application/json".to_owned()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, cache_control),
+            // Compressed and uncompressed bodies share the ETag.
+            (header::VARY, header::ACCEPT_ENCODING.to_string()),
+        ],
         body,
     )
+        .into_response())
+}
+
+/// ETag of a config served in `mode`, from a hash of the config in full.
+/// Secure and offline configs are built from it, the offline ones salted
+/// afresh each time, so it stands for them too.
+fn etag(mode: ConfigMode, config: &str) -> String {
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    context.update(mode.as_str().as_bytes());
+    context.update(b"\n");
+    context.update(config.as_bytes());
+    format!(
+        "\"{}\"",
+        URL_SAFE_NO_PAD.encode(&context.finish().as_ref()[..ETAG_HASH_LEN])
+    )
+}
+
+/// Whether the request's `If-None-Match` lists `etag`, compared weakly as
+/// RFC 9110 asks of it.
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+pub async fn daily_hints(
+    State(configs): State<puzzle_config::ConfigProvider>,
+    Timezone(tz): Timezone,
+    Query(DictionaryQuery { dictionary }): Query<DictionaryQuery>,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+    SelectedDictionary(word_list): SelectedDictionary,
+) -> Result<Json<Hints>, AppError> {
+    let config = configs.get_config(&tz, difficulty, word_list).await?;
+    Ok(Json(Hints::from(&puzzle_config::restrict_to(
+        config, dictionary,
+    ))))
+}
+
+/// Check a word against today's puzzle, for players given the config without
+/// its words.
+pub async fn daily_guess(
+    State(configs): State<puzzle_config::ConfigProvider>,
+    Timezone(tz): Timezone,
+    Query(DictionaryQuery { dictionary }): Query<DictionaryQuery>,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+    SelectedDictionary(word_list): SelectedDictionary,
+    Json(Guess { word }): Json<Guess>,
+) -> Result<Json<GuessResult>, AppError> {
+    let config = configs.get_config(&tz, difficulty, word_list).await?;
+    let mut config = puzzle_config::restrict_to(config, dictionary);
+    let word = validation::validate_guess(&config, &word)
+        .ok()
+        .and_then(|guess| config.valid_words.take(&guess.word));
+    Ok(Json(GuessResult { word }))
+}
+
+#[derive(Deserialize)]
+pub struct RandomQuery {
+    seed: u64,
+}
+
+/// A practice puzzle generated from the `seed` query parameter rather than the
+/// date, so players can play as many as they like.
+pub async fn random_puzzle_config(
+    State(configs): State<puzzle_config::ConfigProvider>,
+    Query(RandomQuery { seed }): Query<RandomQuery>,
+    Query(DictionaryQuery { dictionary }): Query<DictionaryQuery>,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+    SelectedDictionary(word_list): SelectedDictionary,
+) -> Result<Json<PuzzleConfig>, AppError> {
+    let config = configs
+        .get_random_config(seed, difficulty, word_list)
+        .await?;
+    Ok(Json(puzzle_config::restrict_to(config, dictionary)))
+}
+
+/// The fixed demo puzzle, which doesn't need the database.
+pub async fn demo_config() -> impl IntoResponse {
+    Json(puzzle_config::demo_config())
+}
+
+#[derive(Deserialize)]
+struct TimezoneQuery {
+    tz: String,
+}
+
+/// The player's timezone from the `tz` query parameter, either an offset like
+/// `+09:00` or an IANA zone name like `Asia/Tokyo`. Rejects requests without a
+/// known timezone with 422.
+pub(crate) struct Timezone(pub(crate) Zone);
+
+impl<S> FromRequestParts<S> for Timezone
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Query::<TimezoneQuery>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(query)| Zone::parse(&query.tz))
+            .map(Self)
+            .ok_or_else(|| {
+                AppError::Validation(
+                    "Invalid timezone. Use an offset like +09:00 or a zone like Asia/Tokyo."
+                        .to_owned(),
+                )
+            })
+    }
+}
+
+/// The dictionary a player validates words against, permissive unless asked.
+#[derive(Deserialize)]
+pub struct DictionaryQuery {
+    #[serde(default)]
+    pub(crate) dictionary: Dictionary,
 }
 
+/// Which of the day's puzzles a player wants, medium unless asked.
 #[derive(Deserialize)]
-pub struct TimezoneQuery {
-    pub(crate) tz: String,
+pub struct DifficultyQuery {
+    #[serde(default)]
+    pub(crate) difficulty: Difficulty,
+}
+
+/// How much of the puzzle a player gets: every word, only what's needed to
+/// play against [`daily_guess`] so that the answers can't be read from the
+/// browser, or the words in a filter that guesses can be checked against
+/// offline without giving them away.
+#[derive(Clone, Copy, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigMode {
+    #[default]
+    Full,
+    Secure,
+    Offline,
+}
+
+impl ConfigMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Secure => "secure",
+            Self::Offline => "offline",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ConfigModeQuery {
+    #[serde(default)]
+    mode: ConfigMode,
+}
+
+/// A past day's puzzle with all of its words. Today's puzzle is only in the
+/// archive once the day is over in `tz`, so that its words can't be read
+/// while it's still being played.
+pub async fn archived_puzzle_config(
+    State(configs): State<puzzle_config::ConfigProvider>,
+    Path(date): Path<String>,
+    Timezone(tz): Timezone,
+    Query(DictionaryQuery { dictionary }): Query<DictionaryQuery>,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+    SelectedDictionary(word_list): SelectedDictionary,
+) -> Result<Json<PuzzleConfig>, AppError> {
+    let date = date.parse().map_err(|_| {
+        AppError::Validation("Invalid date. Dates must be formatted as YYYY-MM-DD.".to_owned())
+    })?;
+    if date >= tz.date_at(Utc::now()) {
+        return Err(puzzle_config::Error::Unavailable(date).into());
+    }
+
+    let config = configs
+        .get_config_for_date(&tz, date, difficulty, word_list)
+        .await?;
+    Ok(Json(puzzle_config::restrict_to(config, dictionary)))
+}
+
+/// Every word of yesterday's puzzle in the player's timezone, which is over
+/// everywhere sharing its puzzle.
+pub async fn yesterday_answers(
+    State(configs): State<puzzle_config::ConfigProvider>,
+    Timezone(tz): Timezone,
+    Query(DictionaryQuery { dictionary }): Query<DictionaryQuery>,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+    SelectedDictionary(word_list): SelectedDictionary,
+) -> Result<Json<Answers>, AppError> {
+    let today = tz.date_at(chrono::Utc::now());
+    let yesterday = today
+        .pred_opt()
+        .ok_or_else(|| AppError::NotFound("There is no puzzle before today's.".to_owned()))?;
+
+    let config = configs
+        .get_config_for_date(&tz, yesterday, difficulty, word_list)
+        .await?;
+    Ok(Json(Answers::new(
+        yesterday.to_string(),
+        &puzzle_config::restrict_to(config, dictionary),
+    )))
+}
+
+#[derive(Deserialize)]
+pub struct PreviewQuery {
+    letters: String,
+    required: String,
+}
+
+/// How well the dictionary supports generating puzzles.
+pub async fn dictionary_health(
+    State(configs): State<puzzle_config::ConfigProvider>,
+    SelectedDictionary(word_list): SelectedDictionary,
+) -> Result<Json<DictionaryHealth>, AppError> {
+    Ok(Json(configs.dictionary_health(word_list).await?))
+}
+
+/// Evaluate a candidate puzzle for curators without caching or storing it.
+/// `letters` holds all seven letters of the puzzle, including `required`.
+pub async fn preview_puzzle(
+    State(configs): State<puzzle_config::ConfigProvider>,
+    Query(query): Query<PreviewQuery>,
+    SelectedDictionary(word_list): SelectedDictionary,
+) -> Result<Json<PuzzlePreview>, AppError> {
+    let (required, letters) = puzzle_letters(configs.alphabet(), &query.letters, &query.required)
+        .ok_or_else(|| {
+        AppError::Validation(
+            "Letters must be seven distinct letters from a to z, including the required \
+                letter."
+                .to_owned(),
+        )
+    })?;
+
+    Ok(Json(configs.preview(required, &letters, word_list).await?))
+}
+
+/// Generate the puzzles stored for a day again from the current words and
+/// schedule, for when they were generated before a curator's changes.
+pub async fn regenerate_puzzle(
+    State(configs): State<puzzle_config::ConfigProvider>,
+    Path(date): Path<String>,
+) -> Result<http::StatusCode, AppError> {
+    let date = date.parse().map_err(|_| {
+        AppError::Validation("Invalid date. Dates must be formatted as YYYY-MM-DD.".to_owned())
+    })?;
+    let regenerated = configs.regenerate(date).await?;
+    tracing::info!(%date, regenerated, "regenerated stored puzzles");
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+/// The required letter and all seven letters of a puzzle, if `letters` are
+/// seven distinct letters of `alphabet` including `required`.
+pub(crate) fn puzzle_letters(
+    alphabet: words::Alphabet,
+    letters: &str,
+    required: &str,
+) -> Option<(char, Vec<char>)> {
+    let letters: Vec<char> = letters.to_lowercase().chars().collect();
+    let required = match required.to_lowercase().chars().collect::<Vec<_>>()[..] {
+        [required] => required,
+        _ => return None,
+    };
+    let distinct: std::collections::HashSet<_> = letters.iter().collect();
+    let is_valid = letters.len() == 7
+        && distinct.len() == 7
+        && letters.iter().all(|l| alphabet.contains(*l))
+        && letters.contains(&required);
+    is_valid.then_some((required, letters))
 }
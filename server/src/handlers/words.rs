@@ -1,32 +1,97 @@
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
-use serde::Deserialize;
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{FromRequest as _, Multipart, Path, Query, Request, State},
+    http::{StatusCode, header},
+};
+use futures_util::StreamExt as _;
+use serde::{Deserialize, Deserializer};
+use unicode_normalization::UnicodeNormalization as _;
 
-use crate::services::words::{AddWords, RemoveWords};
+use crate::auth::RequireAdmin;
+use crate::handlers::dictionaries::SelectedDictionary;
+use crate::import::Importer;
+use crate::language::Language;
+use crate::responses::AppError;
+use crate::services::dictionaries::DictionaryId;
+use crate::services::words::{
+    AddWords, DescribeWord, FlagWords, ListWordAudit, RemoveWords, UpdateWord, WordUpdate,
+};
+
+/// Largest word list accepted by [`import_words`].
+const MAX_IMPORT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Audit log entries returned by [`word_audit`] when no limit is given.
+const DEFAULT_AUDIT_LIMIT: i64 = 100;
+const MAX_AUDIT_LIMIT: i64 = 1000;
+
+/// Limits on the words of a request to [`add_words`], [`remove_words`] or
+/// [`restore_words`], from the server's config.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct WordLimits {
+    pub(crate) max_words: usize,
+    /// Longest word that can be added, in characters.
+    pub(crate) max_word_len: usize,
+}
+
+impl WordLimits {
+    /// `words` in the form they're stored in, if there are few enough.
+    fn normalized(&self, words: Vec<String>) -> Result<Vec<String>, AppError> {
+        if words.len() > self.max_words {
+            return Err(AppError::Status(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "At most {} words can be sent at once. Import longer lists instead.",
+                    self.max_words
+                ),
+            ));
+        }
+        Ok(words.iter().map(|word| normalized_word(word)).collect())
+    }
+}
+
+/// `word` trimmed and lowercased, with its accents composed (NFC) so that
+/// they're stored the same however they were typed.
+fn normalized_word(word: &str) -> String {
+    word.trim().nfc().collect::<String>().to_lowercase()
+}
+
+#[derive(Clone)]
+pub(crate) struct EditWordsState<Service> {
+    pub(crate) service: Service,
+    pub(crate) limits: WordLimits,
+}
 
 pub(crate) async fn add_words<Service>(
-    State(service): State<Service>,
+    State(state): State<EditWordsState<Service>>,
+    admin: RequireAdmin,
+    SelectedDictionary(dictionary): SelectedDictionary,
     Json(form): Json<AddWordsForm>,
-) -> impl IntoResponse
+) -> Result<StatusCode, AppError>
 where
     Service: AddWords,
 {
-    if form.words.iter().any(|w| w.len() < 4 || !w.is_ascii()) {
-        return crate::responses::Error::new(
-            StatusCode::UNPROCESSABLE_ENTITY,
-            "Invalid words detected. Words must be >= 4 ascii alphabetic characters long."
-                .to_owned(),
-        )
-        .into_response();
+    let words = state.limits.normalized(form.words)?;
+    if words.iter().any(|w| w.chars().count() < 4) {
+        return Err(AppError::Validation(
+            "Invalid words detected. Words must be at least 4 characters long.".to_owned(),
+        ));
     }
-
-    match service
-        .add_words(form.words.into_iter().map(|s| s.to_lowercase()).collect())
-        .await
+    if let Some(word) = words
+        .iter()
+        .find(|w| w.chars().count() > state.limits.max_word_len)
     {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
-        Err(e) => crate::responses::Error::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-            .into_response(),
+        return Err(AppError::Validation(format!(
+            "Invalid word {:?}: words must be at most {} characters long.",
+            word, state.limits.max_word_len
+        )));
     }
+
+    state
+        .service
+        .add_words(dictionary, words, admin.editor())
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,21 +99,446 @@ pub(crate) struct AddWordsForm {
     pub(crate) words: Vec<String>,
 }
 
+/// Remove words from the list. They're kept marked as removed, so that
+/// [`restore_words`] can undo it.
 pub(crate) async fn remove_words<Service>(
-    State(service): State<Service>,
+    State(state): State<EditWordsState<Service>>,
+    admin: RequireAdmin,
+    SelectedDictionary(dictionary): SelectedDictionary,
     Json(form): Json<RemoveWordsForm>,
-) -> impl IntoResponse
+) -> Result<StatusCode, AppError>
 where
     Service: RemoveWords,
 {
-    match service.remove_words(&form.words).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
-        Err(e) => crate::responses::Error::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-            .into_response(),
-    }
+    let words = state.limits.normalized(form.words)?;
+    state
+        .service
+        .remove_words(dictionary, &words, admin.editor())
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct RemoveWordsForm {
     pub(crate) words: Vec<String>,
 }
+
+pub(crate) async fn restore_words<Service>(
+    State(state): State<EditWordsState<Service>>,
+    admin: RequireAdmin,
+    SelectedDictionary(dictionary): SelectedDictionary,
+    Json(form): Json<RemoveWordsForm>,
+) -> Result<StatusCode, AppError>
+where
+    Service: RemoveWords,
+{
+    let words = state.limits.normalized(form.words)?;
+    state
+        .service
+        .restore_words(dictionary, &words, admin.editor())
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct WordAuditQuery {
+    limit: Option<i64>,
+}
+
+/// The latest additions, removals and restorations of words.
+pub(crate) async fn word_audit<Service>(
+    State(service): State<Service>,
+    Query(query): Query<WordAuditQuery>,
+    SelectedDictionary(dictionary): SelectedDictionary,
+) -> Result<Json<words_list::WordAudit>, AppError>
+where
+    Service: ListWordAudit,
+{
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_LIMIT)
+        .clamp(1, MAX_AUDIT_LIMIT);
+    let entries = service.list_audit(dictionary, limit).await?;
+    Ok(Json(words_list::WordAudit { entries }))
+}
+
+/// Edit the definition and obscurity of a word. Fields missing from the body
+/// are left alone and `null` clears them.
+pub(crate) async fn update_word<Service>(
+    State(service): State<Service>,
+    Path(word): Path<String>,
+    SelectedDictionary(dictionary): SelectedDictionary,
+    Json(form): Json<UpdateWordForm>,
+) -> Result<Json<words_list::WordMetadata>, AppError>
+where
+    Service: UpdateWord,
+{
+    if let Some(Some(obscurity)) = form.obscurity
+        && !(1..=5).contains(&obscurity)
+    {
+        return Err(AppError::Validation(
+            "Obscurity must be between 1 and 5.".to_owned(),
+        ));
+    }
+
+    let update = WordUpdate {
+        // A blank definition is the same as none.
+        definition: form.definition.map(|definition| {
+            definition
+                .map(|d| d.trim().to_owned())
+                .filter(|d| !d.is_empty())
+        }),
+        obscurity: form.obscurity,
+    };
+    service
+        .update_word(dictionary, &word.to_lowercase(), &update)
+        .await?
+        .map(Json)
+        .ok_or_else(|| not_in_word_list(&word))
+}
+
+pub(crate) async fn describe_word<Service>(
+    State(service): State<Service>,
+    Path(word): Path<String>,
+    SelectedDictionary(dictionary): SelectedDictionary,
+) -> Result<Json<words_list::WordDetail>, AppError>
+where
+    Service: DescribeWord,
+{
+    service
+        .describe_word(dictionary, &word.to_lowercase())
+        .await?
+        .map(Json)
+        .ok_or_else(|| not_in_word_list(&word))
+}
+
+/// Keep a word out of generated puzzles until it's reviewed.
+pub(crate) async fn flag_word<Service>(
+    State(service): State<Service>,
+    Path(word): Path<String>,
+    SelectedDictionary(dictionary): SelectedDictionary,
+) -> Result<StatusCode, AppError>
+where
+    Service: FlagWords,
+{
+    set_flagged(service, dictionary, word, true).await
+}
+
+pub(crate) async fn unflag_word<Service>(
+    State(service): State<Service>,
+    Path(word): Path<String>,
+    SelectedDictionary(dictionary): SelectedDictionary,
+) -> Result<StatusCode, AppError>
+where
+    Service: FlagWords,
+{
+    set_flagged(service, dictionary, word, false).await
+}
+
+async fn set_flagged<Service>(
+    service: Service,
+    dictionary: DictionaryId,
+    word: String,
+    flagged: bool,
+) -> Result<StatusCode, AppError>
+where
+    Service: FlagWords,
+{
+    if service
+        .set_flagged(dictionary, &word.to_lowercase(), flagged)
+        .await?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_in_word_list(&word))
+    }
+}
+
+pub(crate) async fn flagged_words<Service>(
+    State(service): State<Service>,
+    SelectedDictionary(dictionary): SelectedDictionary,
+) -> Result<Json<words_list::FlaggedWords>, AppError>
+where
+    Service: FlagWords,
+{
+    let words = service.flagged_words(dictionary).await?;
+    Ok(Json(words_list::FlaggedWords { words }))
+}
+
+fn not_in_word_list(word: &str) -> AppError {
+    AppError::NotFound(format!("{} is not in the word list", word))
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdateWordForm {
+    #[serde(default, deserialize_with = "present")]
+    pub(crate) definition: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub(crate) obscurity: Option<Option<u8>>,
+}
+
+/// Deserialize a field that is present in the body, telling `null` apart from
+/// a missing field, which `#[serde(default)]` turns into `None`.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+/// Import a newline-delimited word list, uploaded either as the `words` field of
+/// a multipart form or as the raw request body, inserting it in batches as it
+/// streams in. Responds with counts of inserted, skipped and rejected words.
+///
+/// Batches are committed as they fill, so a failed or oversized upload keeps the
+/// words imported before it stopped.
+pub(crate) async fn import_words<Service>(
+    State(state): State<ImportState<Service>>,
+    admin: RequireAdmin,
+    SelectedDictionary(dictionary): SelectedDictionary,
+    request: Request,
+) -> Result<Json<words_list::ImportedWords>, AppError>
+where
+    Service: AddWords,
+{
+    let is_multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("multipart/form-data"));
+    let mut importer = Importer::new(
+        &state.service,
+        dictionary,
+        state.language,
+        &state.morphology,
+        admin.editor(),
+    );
+    let mut received = 0;
+
+    if is_multipart {
+        let mut multipart = Multipart::from_request(request, &())
+            .await
+            .map_err(|rejection| AppError::Status(rejection.status(), rejection.body_text()))?;
+        while let Some(mut field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| invalid_upload(e.body_text()))?
+        {
+            if field.name() != Some("words") {
+                continue;
+            }
+            while let Some(chunk) = field
+                .chunk()
+                .await
+                .map_err(|e| invalid_upload(e.body_text()))?
+            {
+                import_chunk(&mut importer, &mut received, chunk).await?;
+            }
+        }
+    } else {
+        let mut body = request.into_body().into_data_stream();
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk.map_err(|e| invalid_upload(e.to_string()))?;
+            import_chunk(&mut importer, &mut received, chunk).await?;
+        }
+    }
+
+    Ok(Json(importer.finish().await?))
+}
+
+#[derive(Clone)]
+pub(crate) struct ImportState<Service> {
+    pub(crate) service: Service,
+    /// Language imported words must belong to, if any.
+    pub(crate) language: Option<Language>,
+    pub(crate) morphology: crate::morphology::MorphologyPolicy,
+}
+
+async fn import_chunk<Service>(
+    importer: &mut Importer<'_, Service>,
+    received: &mut usize,
+    chunk: Bytes,
+) -> Result<(), AppError>
+where
+    Service: AddWords,
+{
+    *received += chunk.len();
+    if *received > MAX_IMPORT_BYTES {
+        return Err(AppError::Status(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "Word lists are limited to {} MiB. Words before the limit were imported.",
+                MAX_IMPORT_BYTES / 1024 / 1024
+            ),
+        ));
+    }
+
+    Ok(importer.push_chunk(&chunk).await?)
+}
+
+fn invalid_upload(cause: String) -> AppError {
+    AppError::Status(
+        StatusCode::BAD_REQUEST,
+        format!("Failed to read uploaded word list: {}", cause),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::response::{IntoResponse as _, Response};
+
+    use super::*;
+    use crate::services::words::memory::{Unavailable, Words};
+
+    const STANDARD: DictionaryId = DictionaryId::STANDARD;
+    const LIMITS: WordLimits = WordLimits {
+        max_words: 3,
+        max_word_len: 8,
+    };
+
+    fn editing<Service>(service: Service) -> State<EditWordsState<Service>> {
+        State(EditWordsState {
+            service,
+            limits: LIMITS,
+        })
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| (*w).to_owned()).collect()
+    }
+
+    async fn add(service: impl AddWords, added: &[&str]) -> Response {
+        add_words(
+            editing(service),
+            RequireAdmin(None),
+            SelectedDictionary(STANDARD),
+            Json(AddWordsForm {
+                words: words(added),
+            }),
+        )
+        .await
+        .into_response()
+    }
+
+    async fn message(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        body["message"].as_str().unwrap().to_owned()
+    }
+
+    #[tokio::test]
+    async fn adds_words_in_lowercase() {
+        let store = Words::of(STANDARD, &["apple"]);
+        let response = add(store.clone(), &[" Bread\t", "apple"]).await;
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert_eq!(words(&["apple", "bread"]), store.listed(STANDARD));
+        assert!(store.listed(DictionaryId(2)).is_empty());
+    }
+
+    #[test]
+    fn composes_accents_before_lowercasing() {
+        assert_eq!("caf\u{e9}", normalized_word("CAFE\u{301}"));
+        assert_eq!("caf\u{e9}", normalized_word("caf\u{e9}"));
+    }
+
+    #[tokio::test]
+    async fn limits_the_words_of_a_request() {
+        let store = Words::default();
+
+        let response = add(store.clone(), &["apple", "bread", "crane", "dance"]).await;
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+        assert!(message(response).await.contains("At most 3 words"));
+
+        let response = add(store.clone(), &["apple", "elephants"]).await;
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+        assert_eq!(
+            "Invalid word \"elephants\": words must be at most 8 characters long.",
+            message(response).await
+        );
+        assert!(store.listed(STANDARD).is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_words_without_adding_any() {
+        let store = Words::default();
+
+        let response = add(store.clone(), &["bread", "abc"]).await;
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+        assert!(message(response).await.contains("at least 4 characters"));
+
+        let response = add(store.clone(), &["bread", "cr4ne"]).await;
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+        assert_eq!(
+            "Invalid word \"cr4ne\": '4' is not a letter from a to z",
+            message(response).await
+        );
+        assert!(store.listed(STANDARD).is_empty());
+    }
+
+    #[tokio::test]
+    async fn removes_and_restores_words() {
+        let store = Words::of(STANDARD, &["apple", "bread"]);
+        let form = || {
+            Json(RemoveWordsForm {
+                words: words(&["apple", "crane"]),
+            })
+        };
+
+        let response = remove_words(
+            editing(store.clone()),
+            RequireAdmin(None),
+            SelectedDictionary(STANDARD),
+            form(),
+        )
+        .await
+        .into_response();
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert_eq!(words(&["bread"]), store.listed(STANDARD));
+
+        let response = restore_words(
+            editing(store.clone()),
+            RequireAdmin(None),
+            SelectedDictionary(STANDARD),
+            form(),
+        )
+        .await
+        .into_response();
+        assert_eq!(StatusCode::NO_CONTENT, response.status());
+        assert_eq!(words(&["apple", "bread"]), store.listed(STANDARD));
+    }
+
+    #[tokio::test]
+    async fn reports_service_failures_as_server_errors() {
+        let response = add(Unavailable, &["apple"]).await;
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        assert!(message(response).await.contains("connection refused"));
+
+        for response in [
+            remove_words(
+                editing(Unavailable),
+                RequireAdmin(None),
+                SelectedDictionary(STANDARD),
+                Json(RemoveWordsForm {
+                    words: words(&["apple"]),
+                }),
+            )
+            .await
+            .into_response(),
+            restore_words(
+                editing(Unavailable),
+                RequireAdmin(None),
+                SelectedDictionary(STANDARD),
+                Json(RemoveWordsForm {
+                    words: words(&["apple"]),
+                }),
+            )
+            .await
+            .into_response(),
+        ] {
+            assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Batched import of newline-delimited word lists, shared by seeding on boot
+//! and the word import endpoint.
+
+use words_list::ImportedWords;
+
+use crate::language::Language;
+use crate::morphology::MorphologyPolicy;
+use crate::services::dictionaries::DictionaryId;
+use crate::services::words::{AddWords, AddWordsError, Editor};
+
+const BATCH_SIZE: usize = 1000;
+
+/// Foreign words reported in [`ImportedWords::foreign_examples`].
+const FOREIGN_EXAMPLES: usize = 10;
+
+/// Share of foreign words above which an import is taken for a word list in
+/// another language and stopped. Checked before each batch is inserted, so a
+/// foreign list is stopped before any of it is.
+const MAX_FOREIGN_SHARE: f64 = 0.1;
+
+/// Words needed before judging the share of foreign words, so that a short
+/// list with a single foreign word isn't stopped.
+const MIN_LANGUAGE_SAMPLE: u64 = 100;
+
+/// Normalize a line of a word list, or `None` if it could never be a valid
+/// answer (fewer than 4 characters or containing anything other than ascii
+/// letters), matching `build-word-db`.
+pub(crate) fn importable_word(line: &str) -> Option<String> {
+    let word = line.trim();
+    (word.len() >= 4 && word.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| word.to_ascii_lowercase())
+}
+
+/// Normalize a line that's spelled only with letters, in any alphabet, to
+/// check its language.
+fn spelled_word(line: &str) -> Option<String> {
+    let word = line.trim();
+    (word.chars().count() >= 4 && word.chars().all(char::is_alphabetic))
+        .then(|| word.to_lowercase())
+}
+
+/// Feeds a word list to [`AddWords`] in batches as it arrives, counting what
+/// happened to each line.
+pub(crate) struct Importer<'a, Service> {
+    service: &'a Service,
+    /// Dictionary the words are added to.
+    dictionary: DictionaryId,
+    /// Language the words must belong to, if any.
+    language: Option<Language>,
+    morphology: &'a MorphologyPolicy,
+    /// Who the words are added by, for the audit log.
+    editor: Editor,
+    batch: Vec<String>,
+    /// Trailing bytes of the last chunk that aren't a complete line yet.
+    partial: Vec<u8>,
+    counts: ImportedWords,
+}
+
+impl<'a, Service> Importer<'a, Service>
+where
+    Service: AddWords,
+{
+    pub(crate) fn new(
+        service: &'a Service,
+        dictionary: DictionaryId,
+        language: Option<Language>,
+        morphology: &'a MorphologyPolicy,
+        editor: Editor,
+    ) -> Self {
+        Self {
+            service,
+            dictionary,
+            language,
+            morphology,
+            editor,
+            batch: Vec::with_capacity(BATCH_SIZE),
+            partial: Vec::new(),
+            counts: ImportedWords::default(),
+        }
+    }
+
+    /// Import the complete lines in `chunk`, holding back a trailing partial
+    /// line until the next chunk or [`Importer::finish`].
+    pub(crate) async fn push_chunk(&mut self, chunk: &[u8]) -> Result<(), ImportError> {
+        self.partial.extend_from_slice(chunk);
+        let Some(end) = self.partial.iter().rposition(|b| *b == b'\n') else {
+            return Ok(());
+        };
+
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        for line in complete.split(|b| *b == b'\n') {
+            self.push_line(line).await?;
+        }
+        Ok(())
+    }
+
+    /// Import whatever is left and report the counts.
+    pub(crate) async fn finish(mut self) -> Result<ImportedWords, ImportError> {
+        let last = std::mem::take(&mut self.partial);
+        self.push_line(&last).await?;
+        self.flush().await?;
+        Ok(self.counts)
+    }
+
+    async fn push_line(&mut self, line: &[u8]) -> Result<(), ImportError> {
+        let Ok(line) = std::str::from_utf8(line) else {
+            self.counts.rejected += 1;
+            return Ok(());
+        };
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        if let Some(language) = self.language
+            && let Some(word) = spelled_word(line)
+            && language.is_foreign(&word)
+        {
+            self.counts.foreign += 1;
+            if self.counts.foreign_examples.len() < FOREIGN_EXAMPLES {
+                self.counts.foreign_examples.push(word);
+            }
+            return Ok(());
+        }
+        match importable_word(line) {
+            Some(word) => self.batch.push(word),
+            None => self.counts.rejected += 1,
+        }
+
+        if self.batch.len() >= BATCH_SIZE {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), ImportError> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+
+        let examined = self.counts.inserted
+            + self.counts.skipped
+            + self.counts.foreign
+            + self.batch.len() as u64;
+        if examined >= MIN_LANGUAGE_SAMPLE
+            && self.counts.foreign as f64 > examined as f64 * MAX_FOREIGN_SHARE
+        {
+            return Err(ImportError::MixedLanguage {
+                foreign: self.counts.foreign,
+                examined,
+            });
+        }
+
+        let mut batch = std::mem::replace(&mut self.batch, Vec::with_capacity(BATCH_SIZE));
+        if self.morphology.is_active() {
+            batch = self.exclude_inflections(batch).await?;
+        }
+        let submitted = batch.len() as u64;
+        let inserted = self
+            .service
+            .add_words(self.dictionary, batch, self.editor)
+            .await?;
+        self.counts.inserted += inserted;
+        self.counts.skipped += submitted - inserted;
+        Ok(())
+    }
+
+    /// Drop the inflected forms of words in the table or the batch. Stems later
+    /// in the list aren't known yet, but word lists are usually sorted so stems
+    /// come before their inflections.
+    async fn exclude_inflections(
+        &mut self,
+        batch: Vec<String>,
+    ) -> Result<Vec<String>, ImportError> {
+        let stems: Vec<String> = batch
+            .iter()
+            .flat_map(|word| self.morphology.stems(word))
+            .map(|(_, stem)| stem)
+            .collect();
+        let mut known = self.service.existing_words(self.dictionary, &stems).await?;
+        known.extend(batch.iter().cloned());
+
+        let (inflected, kept): (Vec<_>, Vec<_>) = batch.into_iter().partition(|word| {
+            self.morphology
+                .exclusion(word, |stem| known.contains(stem))
+                .is_some()
+        });
+        self.counts.inflected += inflected.len() as u64;
+        Ok(kept)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ImportError {
+    /// Too many of the words looked foreign for the list to be in the word
+    /// list's language.
+    MixedLanguage {
+        foreign: u64,
+        examined: u64,
+    },
+    AddWords(AddWordsError),
+}
+
+impl From<AddWordsError> for ImportError {
+    fn from(error: AddWordsError) -> Self {
+        Self::AddWords(error)
+    }
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MixedLanguage { foreign, examined } => write!(
+                f,
+                "{} of {} words look like they're from another language, stopped importing. \
+                Words before this point were imported.",
+                foreign, examined
+            ),
+            Self::AddWords(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
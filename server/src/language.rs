@@ -0,0 +1,141 @@
+//! Heuristic check that imported words belong to the language of the word list,
+//! so that a stray dictionary in another language isn't absorbed into it.
+//!
+//! Words spelled with letters outside the language's alphabet are foreign
+//! outright. Others are scored with a letter trigram model trained on the
+//! bundled word list: the average log probability of each letter following the
+//! two before it, which is low for words whose spelling patterns are rare in
+//! the language.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Words scoring below this are foreign. About 0.5% of the bundled English list
+/// scores below it, mostly loanwords and place names.
+const MIN_SCORE: f64 = -3.5;
+
+/// Pads the start and end of words so that trigrams also capture which
+/// letters words start and end with.
+const BOUNDARY: u8 = b'^';
+
+static ENGLISH: LazyLock<TrigramModel> =
+    LazyLock::new(|| TrigramModel::train(include_str!("../data/words.txt").lines()));
+
+/// Language of the word list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    English,
+}
+
+impl Language {
+    /// Read the language from `WORD_LIST_LANGUAGE`, English by default. `any`
+    /// accepts words from every language.
+    pub fn from_env() -> Result<Option<Self>, LanguageError> {
+        match dotenvy::var("WORD_LIST_LANGUAGE") {
+            Err(_) => Ok(Some(Self::English)),
+            Ok(value) => match value.as_str() {
+                "en" => Ok(Some(Self::English)),
+                "any" => Ok(None),
+                _ => Err(LanguageError::Unsupported(value)),
+            },
+        }
+    }
+
+    /// Whether `word`, lowercase and made of letters, is unlikely to belong to
+    /// the language.
+    pub(crate) fn is_foreign(self, word: &str) -> bool {
+        match self {
+            Self::English => {
+                !word.chars().all(|c| c.is_ascii_lowercase()) || ENGLISH.score(word) < MIN_SCORE
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LanguageError {
+    Unsupported(String),
+}
+
+impl std::fmt::Display for LanguageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Unsupported(value) => write!(
+                f,
+                "Unsupported WORD_LIST_LANGUAGE {:?}, expected \"en\" or \"any\"",
+                value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LanguageError {}
+
+struct TrigramModel {
+    trigrams: HashMap<[u8; 3], u32>,
+    /// How often each pair of letters is followed by another letter.
+    bigrams: HashMap<[u8; 2], u32>,
+}
+
+impl TrigramModel {
+    fn train<'a>(words: impl Iterator<Item = &'a str>) -> Self {
+        let mut model = Self {
+            trigrams: HashMap::new(),
+            bigrams: HashMap::new(),
+        };
+        for word in words.map(str::trim) {
+            if word.is_empty() || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+                continue;
+            }
+            for trigram in trigrams(&word.to_ascii_lowercase()) {
+                *model.trigrams.entry(trigram).or_default() += 1;
+                *model.bigrams.entry([trigram[0], trigram[1]]).or_default() += 1;
+            }
+        }
+        model
+    }
+
+    /// Average log probability of the letters of `word`, with add-one
+    /// smoothing so that unseen trigrams aren't impossible.
+    fn score(&self, word: &str) -> f64 {
+        let trigrams = trigrams(word);
+        let total: f64 = trigrams
+            .iter()
+            .map(|trigram| {
+                let count = self.trigrams.get(trigram).copied().unwrap_or(0);
+                let context = self
+                    .bigrams
+                    .get(&[trigram[0], trigram[1]])
+                    .copied()
+                    .unwrap_or(0);
+                // 26 letters and the end of the word can follow.
+                ((count + 1) as f64 / (context + 27) as f64).ln()
+            })
+            .sum();
+        total / trigrams.len() as f64
+    }
+}
+
+fn trigrams(word: &str) -> Vec<[u8; 3]> {
+    let padded: Vec<u8> = [BOUNDARY, BOUNDARY]
+        .into_iter()
+        .chain(word.bytes())
+        .chain([BOUNDARY])
+        .collect();
+    padded.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Language;
+
+    #[test]
+    fn flags_words_from_other_languages() {
+        for word in ["oeuf", "jeux", "aujourdhui", "café", "straße"] {
+            assert!(Language::English.is_foreign(word), "{}", word);
+        }
+        for word in ["honey", "rhythm", "queue", "strength", "awkward", "yacht"] {
+            assert!(!Language::English.is_foreign(word), "{}", word);
+        }
+    }
+}
@@ -0,0 +1,602 @@
+//! Spelling bee API and static asset server.
+//!
+//! The binary reads its settings from the environment and serves [`app`], or
+//! [`embedded_app`] without a database, or, with the `sqlite` feature,
+//! [`sqlite_app`] with the word list in SQLite. They are exposed so that the
+//! routes can be driven by tests the way clients see them.
+
+use axum::{
+    Extension, Router,
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{any, get, patch, post, put},
+};
+
+use tower_http::{
+    catch_panic::CatchPanicLayer,
+    compression::{
+        CompressionLayer,
+        predicate::{NotForContentType, Predicate as _, SizeAbove},
+    },
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    services::{ServeDir, ServeFile},
+    trace::{DefaultOnResponse, TraceLayer},
+};
+
+mod assets;
+mod auth;
+pub mod cache;
+pub mod config;
+mod handlers;
+mod import;
+pub mod language;
+mod metrics;
+pub mod morphology;
+pub mod puzzle_config;
+mod rate_limit;
+mod realtime;
+mod responses;
+pub mod seed;
+mod services;
+mod telemetry;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+#[cfg(test)]
+mod tests;
+mod timezone;
+pub mod word_index;
+
+/// Migrations of the database schema, from `migrations/`.
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../migrations");
+
+/// What the routes of a server with a database are built from.
+#[derive(Clone)]
+pub struct AppState {
+    pub config: crate::config::ServerConfig,
+    pub dbpool: sqlx::PgPool,
+    pub configs: crate::puzzle_config::ConfigProvider,
+    /// Language imported word lists must be in, or `None` for any.
+    pub language: Option<crate::language::Language>,
+    pub morphology: crate::morphology::MorphologyPolicy,
+}
+
+/// Open the SQLite word database at `path` for [`sqlite_app`], creating and
+/// migrating it if need be.
+#[cfg(feature = "sqlite")]
+pub async fn open_sqlite_words(
+    path: &std::path::Path,
+) -> Result<sqlx::SqlitePool, Box<dyn std::error::Error>> {
+    let options = sqlx::sqlite::SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect_with(options)
+        .await?;
+    crate::services::words::sqlite::migrate(&pool).await?;
+    Ok(pool)
+}
+
+/// Make the user with `username` an admin, returning false if there is no
+/// such user.
+pub async fn grant_admin(
+    dbpool: &sqlx::PgPool,
+    username: &str,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    use crate::services::users::GrantAdmin as _;
+    Ok(crate::services::users::pg::Users(dbpool.clone())
+        .grant_admin(username)
+        .await?)
+}
+
+/// Routes adding, importing, listing, searching and removing words, served by
+/// the word services of `services::words::$store` over `$pool`. A macro
+/// since the services of each store are different types.
+macro_rules! word_routes {
+    ($store:ident, $pool:expr, $alphabet:expr, $limits:expr, $language:expr, $morphology:expr) => {
+        Router::new()
+            .route(
+                "/api/words",
+                post(handlers::words::add_words::<crate::services::words::$store::AddWords>)
+                    .with_state(handlers::words::EditWordsState {
+                        service: crate::services::words::$store::AddWords($pool.clone(), $alphabet),
+                        limits: $limits,
+                    })
+                    .get(
+                        handlers::management::list_words::<
+                            crate::services::words::$store::ListWords,
+                        >,
+                    )
+                    .with_state(crate::services::words::$store::ListWords($pool.clone())),
+            )
+            .route(
+                "/api/words/import",
+                post(handlers::words::import_words::<crate::services::words::$store::AddWords>)
+                    .with_state(handlers::words::ImportState {
+                        service: crate::services::words::$store::AddWords($pool.clone(), $alphabet),
+                        language: $language,
+                        morphology: $morphology.clone(),
+                    })
+                    .layer(DefaultBodyLimit::disable()),
+            )
+            .route(
+                "/api/words/restore",
+                post(handlers::words::restore_words::<crate::services::words::$store::RemoveWords>)
+                    .with_state(handlers::words::EditWordsState {
+                        service: crate::services::words::$store::RemoveWords($pool.clone()),
+                        limits: $limits,
+                    }),
+            )
+            .route(
+                "/api/words/search",
+                get(handlers::management::search::<crate::services::words::$store::SearchWords>)
+                    .with_state(crate::services::words::$store::SearchWords($pool.clone())),
+            )
+            .route(
+                "/api/words/excluded",
+                get(handlers::management::excluded_words::<
+                    crate::services::words::$store::ListWords,
+                >)
+                .with_state(handlers::management::ExcludedWordsState {
+                    service: crate::services::words::$store::ListWords($pool.clone()),
+                    morphology: $morphology,
+                }),
+            )
+            .route(
+                "/api/words/remove",
+                post(handlers::words::remove_words::<crate::services::words::$store::RemoveWords>)
+                    .with_state(handlers::words::EditWordsState {
+                        service: crate::services::words::$store::RemoveWords($pool.clone()),
+                        limits: $limits,
+                    }),
+            )
+    };
+}
+
+/// Every route of a server with a database, alongside the frontend.
+pub fn app(state: AppState) -> Router {
+    let AppState {
+        config,
+        dbpool,
+        configs,
+        language,
+        morphology,
+    } = state;
+    let cache = configs.cache().clone();
+    let auth = handlers::auth::AuthState {
+        users: crate::services::users::pg::Users(dbpool.clone()),
+        sessions: crate::services::users::pg::Sessions(dbpool.clone()),
+        limiter: rate_limit::RateLimiter::new(
+            "auth",
+            10,
+            std::time::Duration::from_secs(60),
+            cache.clone(),
+        ),
+    };
+    let word_limits = handlers::words::WordLimits {
+        max_words: config.max_words_per_request,
+        max_word_len: config.max_word_len,
+    };
+    // Routes changing the word list, curating puzzles or reporting on the
+    // server are only open to admins.
+    let admin = Router::new()
+        .route(
+            "/api/words/{word}",
+            patch(handlers::words::update_word::<crate::services::words::pg::UpdateWord>)
+                .with_state(crate::services::words::pg::UpdateWord(dbpool.clone()))
+                .get(handlers::words::describe_word::<crate::services::words::pg::DescribeWord>)
+                .with_state(crate::services::words::pg::DescribeWord(dbpool.clone())),
+        )
+        .route(
+            "/api/words/flagged",
+            get(handlers::words::flagged_words::<crate::services::words::pg::FlagWords>)
+                .with_state(crate::services::words::pg::FlagWords(dbpool.clone())),
+        )
+        .route(
+            "/api/words/{word}/flag",
+            post(handlers::words::flag_word::<crate::services::words::pg::FlagWords>)
+                .with_state(crate::services::words::pg::FlagWords(dbpool.clone())),
+        )
+        .route(
+            "/api/words/{word}/unflag",
+            post(handlers::words::unflag_word::<crate::services::words::pg::FlagWords>)
+                .with_state(crate::services::words::pg::FlagWords(dbpool.clone())),
+        )
+        .route(
+            "/api/words/audit",
+            get(handlers::words::word_audit::<crate::services::words::pg::WordAudit>)
+                .with_state(crate::services::words::pg::WordAudit(dbpool.clone())),
+        )
+        .route(
+            "/api/admin/dictionaries",
+            get(handlers::dictionaries::list_dictionaries::<
+                crate::services::dictionaries::pg::Dictionaries,
+            >)
+            .post(
+                handlers::dictionaries::create_dictionary::<
+                    crate::services::dictionaries::pg::Dictionaries,
+                >,
+            )
+            .with_state(crate::services::dictionaries::pg::Dictionaries(
+                dbpool.clone(),
+            )),
+        )
+        .route(
+            "/api/admin/puzzle/schedule",
+            get(handlers::management::list_schedule::<
+                crate::services::puzzles::pg::ScheduleRepository,
+            >)
+            .post(
+                handlers::management::schedule_puzzle::<
+                    crate::services::puzzles::pg::ScheduleRepository,
+                >,
+            )
+            .with_state(crate::services::puzzles::pg::ScheduleRepository(
+                dbpool.clone(),
+            )),
+        )
+        .route(
+            "/api/admin/feedback/words",
+            get(
+                handlers::feedback::list_word_reports::<crate::services::feedback::pg::WordReports>,
+            )
+            .with_state(crate::services::feedback::pg::WordReports(dbpool.clone())),
+        )
+        .route(
+            "/api/admin/puzzle/preview",
+            get(handlers::puzzle_config::preview_puzzle).with_state(configs.clone()),
+        )
+        .route(
+            "/api/admin/puzzle/{date}/regenerate",
+            post(handlers::puzzle_config::regenerate_puzzle).with_state(configs.clone()),
+        )
+        .route(
+            "/api/admin/dictionary/health",
+            get(handlers::puzzle_config::dictionary_health).with_state(configs.clone()),
+        )
+        .route(
+            "/api/admin/puzzle/{date}/exclusions",
+            get(handlers::management::list_exclusions::<
+                crate::services::puzzles::pg::ListExclusions,
+            >)
+            .with_state(crate::services::puzzles::pg::ListExclusions(dbpool.clone()))
+            .post(handlers::management::exclude_words::<crate::services::puzzles::pg::ExcludeWords>)
+            .with_state(crate::services::puzzles::pg::ExcludeWords(dbpool.clone())),
+        )
+        .route(
+            "/api/client-errors",
+            get(handlers::client_errors::list::<crate::services::client_errors::pg::ClientErrors>)
+                .with_state(crate::services::client_errors::pg::ClientErrors(
+                    dbpool.clone(),
+                )),
+        )
+        .route(
+            "/metrics",
+            get(handlers::metrics::metrics).with_state(dbpool.clone()),
+        )
+        .merge(word_routes!(
+            pg,
+            dbpool,
+            configs.alphabet(),
+            word_limits,
+            language,
+            morphology
+        ))
+        .route_layer(middleware::from_extractor::<auth::RequireAdmin>());
+
+    let api =
+        Router::new()
+            .route(
+                "/api/puzzle/daily/config",
+                get(handlers::puzzle_config::puzzle_config).with_state(configs.clone()),
+            )
+            .route(
+                "/api/puzzle/daily/hints",
+                get(handlers::puzzle_config::daily_hints).with_state(configs.clone()),
+            )
+            .route(
+                "/api/puzzle/daily/guess",
+                post(handlers::puzzle_config::daily_guess).with_state(configs.clone()),
+            )
+            .route(
+                "/api/puzzle/daily/rooms/{room}",
+                get(realtime::race::join_room).with_state(realtime::race::Rooms::default()),
+            )
+            .route(
+                "/api/puzzle/daily/teams/{team}",
+                get(realtime::team::join_team)
+                    .with_state(realtime::team::Teams::new(configs.clone())),
+            )
+            .route(
+                "/api/puzzle/daily/progress",
+                get(handlers::progress::get_progress::<
+                    crate::services::progress::pg::ProgressRepository,
+                >)
+                .put(
+                    handlers::progress::put_progress::<
+                        crate::services::progress::pg::ProgressRepository,
+                    >,
+                )
+                .with_state(handlers::progress::ProgressState {
+                    repository: crate::services::progress::pg::ProgressRepository(dbpool.clone()),
+                    configs: configs.clone(),
+                }),
+            )
+            .route(
+                "/api/notes/{date}",
+                get(handlers::notes::get_note::<crate::services::notes::pg::NotesRepository>)
+                    .put(handlers::notes::put_note::<crate::services::notes::pg::NotesRepository>)
+                    .with_state(crate::services::notes::pg::NotesRepository(dbpool.clone())),
+            )
+            .route(
+                "/api/puzzle/demo/config",
+                get(handlers::puzzle_config::demo_config),
+            )
+            .route(
+                "/api/puzzle/random/config",
+                get(handlers::puzzle_config::random_puzzle_config).with_state(configs.clone()),
+            )
+            .route(
+                "/api/puzzle/yesterday/answers",
+                get(handlers::puzzle_config::yesterday_answers).with_state(configs.clone()),
+            )
+            .route(
+                "/api/puzzle/archive/{date}",
+                get(handlers::puzzle_config::archived_puzzle_config).with_state(configs.clone()),
+            )
+            .route(
+                "/api/client-errors",
+                post(
+                    handlers::client_errors::report::<
+                        crate::services::client_errors::pg::ClientErrors,
+                    >,
+                )
+                .with_state(handlers::client_errors::ReportState {
+                    service: crate::services::client_errors::pg::ClientErrors(dbpool.clone()),
+                    limiter: rate_limit::RateLimiter::new(
+                        "client-errors",
+                        10,
+                        std::time::Duration::from_secs(60),
+                        cache.clone(),
+                    ),
+                }),
+            )
+            .route(
+                "/api/puzzle/custom",
+                post(
+                    handlers::custom::create_custom_puzzle::<
+                        crate::services::puzzles::pg::CustomPuzzleRepository,
+                    >,
+                )
+                .with_state(handlers::custom::CustomPuzzleState {
+                    repository: crate::services::puzzles::pg::CustomPuzzleRepository(
+                        dbpool.clone(),
+                    ),
+                    configs,
+                    limiter: rate_limit::RateLimiter::new(
+                        "custom-puzzles",
+                        10,
+                        std::time::Duration::from_secs(60),
+                        cache.clone(),
+                    ),
+                }),
+            )
+            .route(
+                "/api/puzzle/custom/{id}/config",
+                get(handlers::custom::custom_puzzle_config::<
+                    crate::services::puzzles::pg::CustomPuzzleRepository,
+                >)
+                .with_state(crate::services::puzzles::pg::CustomPuzzleRepository(
+                    dbpool.clone(),
+                )),
+            )
+            .route(
+                "/api/feedback/words",
+                post(handlers::feedback::report_word::<crate::services::feedback::pg::WordReports>)
+                    .with_state(handlers::feedback::ReportState {
+                        service: crate::services::feedback::pg::WordReports(dbpool.clone()),
+                        limiter: rate_limit::RateLimiter::new(
+                            "word-reports",
+                            10,
+                            std::time::Duration::from_secs(60),
+                            cache.clone(),
+                        ),
+                    }),
+            )
+            .route(
+                "/api/auth/register",
+                post(
+                    handlers::auth::register::<
+                        crate::services::users::pg::Users,
+                        crate::services::users::pg::Sessions,
+                    >,
+                )
+                .with_state(auth.clone()),
+            )
+            .route(
+                "/api/auth/login",
+                post(
+                    handlers::auth::login::<
+                        crate::services::users::pg::Users,
+                        crate::services::users::pg::Sessions,
+                    >,
+                )
+                .with_state(auth),
+            )
+            .route(
+                "/api/auth/logout",
+                post(handlers::auth::logout::<crate::services::users::pg::Sessions>)
+                    .with_state(crate::services::users::pg::Sessions(dbpool.clone())),
+            )
+            .route("/api/auth/me", get(handlers::auth::me))
+            .route(
+                "/api/auth/me/preferences",
+                put(handlers::auth::set_preferences::<crate::services::users::pg::Users>)
+                    .with_state(crate::services::users::pg::Users(dbpool.clone())),
+            )
+            .merge(admin);
+
+    let api = api
+        .layer(Extension(crate::services::users::pg::Sessions(
+            dbpool.clone(),
+        )))
+        .layer(Extension(crate::services::dictionaries::pg::Dictionaries(
+            dbpool.clone(),
+        )))
+        .layer(Extension(auth::AdminToken::new(
+            config.admin_token.as_deref(),
+        )));
+    with_frontend(&config, api)
+}
+
+/// Routes of a server running without a database. Puzzles come from the
+/// embedded dictionary of `configs` and everything else under `/api` answers
+/// 501. Metrics are only served to requests with the admin token.
+pub fn embedded_app(
+    config: &crate::config::ServerConfig,
+    configs: crate::puzzle_config::ConfigProvider,
+) -> Router {
+    let api = puzzle_routes(configs)
+        .route(
+            "/api/words",
+            post(handlers::embedded::read_only_words).fallback(handlers::embedded::unavailable),
+        )
+        .route(
+            "/api/words/remove",
+            post(handlers::embedded::read_only_words).fallback(handlers::embedded::unavailable),
+        )
+        .route("/api/{*path}", any(handlers::embedded::unavailable))
+        .merge(
+            Router::new()
+                .route("/metrics", get(handlers::metrics::embedded_metrics))
+                .route_layer(middleware::from_extractor::<auth::RequireAdmin>()),
+        )
+        .layer(Extension(auth::AdminToken::new(
+            config.admin_token.as_deref(),
+        )));
+    with_frontend(config, api)
+}
+
+/// Routes of a server keeping its word list in the SQLite database `words`
+/// rather than Postgres, such as for curating one locally. Puzzles are
+/// generated from `configs`, which should read the same database, the word
+/// routes manage its standard dictionary and everything else under `/api`
+/// answers 501. Without accounts, the word routes and metrics are only served
+/// to requests with the admin token.
+#[cfg(feature = "sqlite")]
+pub fn sqlite_app(
+    config: &crate::config::ServerConfig,
+    configs: crate::puzzle_config::ConfigProvider,
+    words: sqlx::SqlitePool,
+    language: Option<crate::language::Language>,
+    morphology: crate::morphology::MorphologyPolicy,
+) -> Router {
+    let word_limits = handlers::words::WordLimits {
+        max_words: config.max_words_per_request,
+        max_word_len: config.max_word_len,
+    };
+    let admin = word_routes!(
+        sqlite,
+        words,
+        configs.alphabet(),
+        word_limits,
+        language,
+        morphology
+    )
+    .route("/metrics", get(handlers::metrics::embedded_metrics))
+    .route_layer(middleware::from_extractor::<auth::RequireAdmin>());
+
+    let api = puzzle_routes(configs)
+        .route("/api/{*path}", any(handlers::embedded::unavailable))
+        .merge(admin)
+        .layer(Extension(auth::AdminToken::new(
+            config.admin_token.as_deref(),
+        )));
+    with_frontend(config, api)
+}
+
+/// Routes serving the puzzles of `configs` to players, which need no
+/// database of their own.
+fn puzzle_routes(configs: crate::puzzle_config::ConfigProvider) -> Router {
+    Router::new()
+        .route(
+            "/api/puzzle/daily/config",
+            get(handlers::puzzle_config::puzzle_config).with_state(configs.clone()),
+        )
+        .route(
+            "/api/puzzle/daily/hints",
+            get(handlers::puzzle_config::daily_hints).with_state(configs.clone()),
+        )
+        .route(
+            "/api/puzzle/daily/guess",
+            post(handlers::puzzle_config::daily_guess).with_state(configs.clone()),
+        )
+        .route(
+            "/api/puzzle/daily/rooms/{room}",
+            get(realtime::race::join_room).with_state(realtime::race::Rooms::default()),
+        )
+        .route(
+            "/api/puzzle/daily/teams/{team}",
+            get(realtime::team::join_team).with_state(realtime::team::Teams::new(configs.clone())),
+        )
+        .route(
+            "/api/puzzle/demo/config",
+            get(handlers::puzzle_config::demo_config),
+        )
+        .route(
+            "/api/puzzle/random/config",
+            get(handlers::puzzle_config::random_puzzle_config).with_state(configs.clone()),
+        )
+        .route(
+            "/api/puzzle/yesterday/answers",
+            get(handlers::puzzle_config::yesterday_answers).with_state(configs.clone()),
+        )
+        .route(
+            "/api/puzzle/archive/{date}",
+            get(handlers::puzzle_config::archived_puzzle_config).with_state(configs),
+        )
+}
+
+/// Bodies smaller than this gain too little to be worth compressing.
+const MIN_COMPRESSED_BYTES: u16 = 1024;
+
+/// `api` alongside the frontend, with the layers every route shares.
+fn with_frontend(config: &crate::config::ServerConfig, api: Router) -> Router {
+    let index = ServeFile::new(&config.index_file);
+    // The frontend's build writes compressed copies of its largest assets,
+    // which are served as they are to clients accepting them.
+    let assets = ServeDir::new(&config.assets_dir)
+        .precompressed_br()
+        .precompressed_gzip();
+    // The service worker is built into the assets but served from the root so
+    // that it controls every page.
+    let service_worker = ServeFile::new(config.assets_dir.join("sw.js"));
+
+    let frontend = Router::new()
+        .route_service("/sw.js", service_worker)
+        .nest_service(assets::ASSETS_PATH, assets)
+        .fallback_service(index)
+        .layer(middleware::from_fn_with_state(
+            assets::AssetManifest::load(&config.assets_dir),
+            assets::cache_control,
+        ));
+
+    // Responses are compressed for clients accepting it, which mostly saves on
+    // puzzle configs and word lists. Responses that are already encoded, like
+    // the precompressed assets, are sent as they are, and so are small ones and
+    // images and fonts other than SVG, which gain too little.
+    let compressible = SizeAbove::new(MIN_COMPRESSED_BYTES)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::const_new("font/"));
+    api.merge(frontend)
+        .layer(CompressionLayer::new().compress_when(compressible))
+        .layer(CatchPanicLayer::custom(responses::panic_response))
+        .layer(middleware::from_fn(telemetry::record_request))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(telemetry::make_span)
+                .on_response(DefaultOnResponse::new().level(tracing::Level::INFO)),
+        )
+        // Set outermost so the id is on the span and every response, panics included.
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
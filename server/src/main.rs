@@ -1,63 +1,171 @@
-use axum::{
-    Router,
-    routing::{get, post},
-};
-
-use tower_http::services::{ServeDir, ServeFile};
+use clap::Parser as _;
+use server::{AppState, cache, config, language, morphology, puzzle_config, seed, word_index};
 use tracing_subscriber::{layer::SubscriberExt as _, util::SubscriberInitExt};
 
-mod handlers;
-mod puzzle_config;
-mod responses;
-mod services;
+/// Spelling bee API and static asset server.
+#[derive(Debug, clap::Parser)]
+struct Opts {
+    /// Filepath of a newline-delimited word list used to populate the words table on
+    /// first boot. Ignored if the table already contains words.
+    #[arg(long)]
+    seed_words: Option<std::path::PathBuf>,
+
+    /// Username of a registered user to make an admin on boot.
+    #[arg(long)]
+    grant_admin: Option<String>,
+
+    /// Apply pending database migrations on boot, before seeding or serving.
+    #[arg(long)]
+    migrate: bool,
+
+    /// Serve puzzles from a word list held in memory instead of connecting to
+    /// a database, the bundled list unless a path is given. The word list
+    /// can't be changed and routes needing the database answer 501.
+    #[arg(
+        long,
+        value_name = "WORD_LIST",
+        conflicts_with_all = ["seed_words", "grant_admin", "migrate"],
+    )]
+    embedded_dictionary: Option<Option<std::path::PathBuf>>,
+
+    /// Serve puzzles from a word list kept in the SQLite database at this
+    /// path, created if missing, instead of connecting to Postgres. The list
+    /// is managed under /api/words with the admin token and other routes
+    /// needing a database answer 501.
+    #[cfg(feature = "sqlite")]
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["embedded_dictionary", "seed_words", "grant_admin", "migrate"],
+    )]
+    sqlite_words: Option<std::path::PathBuf>,
+}
 
 #[tokio::main]
 async fn main() {
+    let opts = Opts::parse();
+
+    if let Err(e) = dotenvy::dotenv() {
+        eprintln!("Failed to load dotenv file: {}", e);
+    }
+
+    let config = config::ServerConfig::from_env()
+        .unwrap_or_else(|e| panic!("Failed to read server config: {}", e));
+
     if let Err(e) = tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer())
-        .with(tracing_subscriber::EnvFilter::from_env("BEE_LOG_LEVEL"))
+        .with(tracing_subscriber::EnvFilter::new(&config.log_level))
         .try_init()
     {
         eprintln!("Failed to init tracing: {}", e);
     }
 
-    if let Err(e) = dotenvy::dotenv() {
-        eprintln!("Failed to load dotenv file: {}", e);
-    }
+    let morphology =
+        morphology::MorphologyPolicy::from_env().expect("Failed to read morphology policy");
+
+    let cache = cache::Backend::from_env()
+        .await
+        .expect("Failed to connect to cache backend");
+
+    let policy = puzzle_config::PuzzleGenerationPolicy::from_env()
+        .expect("Failed to read puzzle generation policy");
+
+    #[cfg(feature = "sqlite")]
+    let sqlite_words = opts.sqlite_words;
+    #[cfg(not(feature = "sqlite"))]
+    let sqlite_words: Option<std::path::PathBuf> = None;
+
+    let app = match (opts.embedded_dictionary, sqlite_words) {
+        (Some(word_list), _) => {
+            let index = match word_list {
+                Some(path) => word_index::WordIndex::read(&path).unwrap_or_else(|e| {
+                    panic!("Failed to read word list {}: {}", path.display(), e)
+                }),
+                None => word_index::WordIndex::bundled(),
+            };
+            tracing::info!(
+                words = index.len(),
+                "serving puzzles from an embedded dictionary"
+            );
+            let configs = puzzle_config::ConfigProvider::embedded(index, cache, policy, morphology);
+            server::embedded_app(&config, configs)
+        }
+        #[cfg(feature = "sqlite")]
+        (None, Some(path)) => {
+            let words = server::open_sqlite_words(&path).await.unwrap_or_else(|e| {
+                panic!("Failed to open word database {}: {}", path.display(), e)
+            });
+            tracing::info!(path = %path.display(), "serving puzzles from a SQLite word list");
+            let configs = puzzle_config::ConfigProvider::sqlite(
+                words.clone(),
+                cache,
+                policy,
+                morphology.clone(),
+            );
+            let language =
+                language::Language::from_env().expect("Failed to read word list language");
+            server::sqlite_app(&config, configs, words, language, morphology)
+        }
+        (None, _) => {
+            let database_url = config
+                .database_url()
+                .unwrap_or_else(|e| panic!("Failed to read server config: {}", e));
+            let dbpool = sqlx::postgres::PgPoolOptions::new()
+                .max_connections(config.max_connections)
+                .connect(database_url)
+                .await
+                .expect("Failed to connect to postgres instance");
+
+            if opts.migrate {
+                server::MIGRATOR
+                    .run(&dbpool)
+                    .await
+                    .expect("Failed to run database migrations");
+                tracing::info!("database migrations applied");
+            }
+
+            if let Some(seed_words) = opts.seed_words {
+                seed::seed_words(&dbpool, &seed_words, &morphology, policy.alphabet)
+                    .await
+                    .expect("Failed to seed words table");
+            }
+
+            if let Some(username) = opts.grant_admin {
+                let granted = server::grant_admin(&dbpool, &username)
+                    .await
+                    .expect("Failed to grant admin");
+                if !granted {
+                    panic!("Failed to grant admin: no user named {:?}", username);
+                }
+            }
+
+            let configs = puzzle_config::ConfigProvider::new(
+                dbpool.clone(),
+                cache,
+                policy,
+                morphology.clone(),
+            );
+
+            let language =
+                language::Language::from_env().expect("Failed to read word list language");
 
-    let pool_url = dotenvy::var("DATABASE_URL").expect("Failed to get database url from env");
+            server::app(AppState {
+                config: config.clone(),
+                dbpool,
+                configs,
+                language,
+                morphology,
+            })
+        }
+    };
 
-    let dbpool = sqlx::PgPool::connect(&pool_url)
+    let listener = tokio::net::TcpListener::bind(config.bind_address)
         .await
-        .expect("Failed to connect to postgres instance");
-    let index = ServeFile::new("index.html");
-    let assets = ServeDir::new("assets");
-    let app = Router::new()
-        .route(
-            "/api/puzzle/daily/config",
-            get(handlers::puzzle_config::puzzle_config)
-                .with_state(crate::puzzle_config::ConfigProvider::new(dbpool.clone())),
-        )
-        .route(
-            "/api/words",
-            post(handlers::words::add_words::<crate::services::words::pg::AddWords>)
-                .with_state(crate::services::words::pg::AddWords(dbpool.clone()))
-                .get(handlers::management::list_words::<crate::services::words::pg::ListWords>)
-                .with_state(crate::services::words::pg::ListWords(dbpool.clone())),
-        )
-        .route(
-            "/api/words/search",
-            get(handlers::management::search::<crate::services::words::pg::SearchWords>)
-                .with_state(crate::services::words::pg::SearchWords(dbpool.clone())),
-        )
-        .route(
-            "/api/words/remove",
-            post(handlers::words::remove_words::<crate::services::words::pg::RemoveWords>)
-                .with_state(crate::services::words::pg::RemoveWords(dbpool.clone())),
-        )
-        .nest_service("/assets", assets)
-        .fallback_service(index);
-
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+        .unwrap_or_else(|e| panic!("Failed to bind {}: {}", config.bind_address, e));
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
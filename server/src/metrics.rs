@@ -0,0 +1,200 @@
+//! Process-wide counters, gauges and histograms, rendered in the Prometheus text format
+//! by the `/metrics` route.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{LazyLock, Mutex};
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::default);
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Default)]
+struct Registry {
+    counters: Mutex<BTreeMap<&'static str, BTreeMap<Labels, u64>>>,
+    gauges: Mutex<BTreeMap<&'static str, BTreeMap<Labels, f64>>>,
+    histograms: Mutex<BTreeMap<&'static str, BTreeMap<Labels, Histogram>>>,
+}
+
+struct Histogram {
+    /// Upper bounds of the buckets, in ascending order.
+    bounds: &'static [f64],
+    /// Observations per bucket, not cumulative.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// Add `by` to the counter `name` with `labels`.
+pub(crate) fn increment(name: &'static str, labels: &[(&'static str, &str)], by: u64) {
+    let mut counters = REGISTRY.counters.lock().unwrap_or_else(|e| e.into_inner());
+    *counters
+        .entry(name)
+        .or_default()
+        .entry(to_labels(labels))
+        .or_default() += by;
+}
+
+/// Set the gauge `name` with `labels` to `value`.
+pub(crate) fn set(name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+    let mut gauges = REGISTRY.gauges.lock().unwrap_or_else(|e| e.into_inner());
+    gauges
+        .entry(name)
+        .or_default()
+        .insert(to_labels(labels), value);
+}
+
+/// Record `value` in the histogram `name` with `labels`. `bounds` are the
+/// upper bounds of its buckets and must be the same on every call.
+pub(crate) fn observe(
+    name: &'static str,
+    labels: &[(&'static str, &str)],
+    bounds: &'static [f64],
+    value: f64,
+) {
+    let mut histograms = REGISTRY
+        .histograms
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let histogram = histograms
+        .entry(name)
+        .or_default()
+        .entry(to_labels(labels))
+        .or_insert_with(|| Histogram {
+            bounds,
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        });
+    if let Some(bucket) = histogram.bounds.iter().position(|bound| value <= *bound) {
+        histogram.counts[bucket] += 1;
+    }
+    histogram.sum += value;
+    histogram.count += 1;
+}
+
+/// Every metric recorded so far in the Prometheus text exposition format.
+pub(crate) fn render() -> String {
+    let mut output = String::new();
+
+    let counters = REGISTRY.counters.lock().unwrap_or_else(|e| e.into_inner());
+    for (name, series) in counters.iter() {
+        let _ = writeln!(output, "# TYPE {} counter", name);
+        for (labels, value) in series {
+            let _ = writeln!(output, "{}{} {}", name, format_labels(labels, None), value);
+        }
+    }
+    drop(counters);
+
+    let gauges = REGISTRY.gauges.lock().unwrap_or_else(|e| e.into_inner());
+    for (name, series) in gauges.iter() {
+        let _ = writeln!(output, "# TYPE {} gauge", name);
+        for (labels, value) in series {
+            let _ = writeln!(output, "{}{} {}", name, format_labels(labels, None), value);
+        }
+    }
+    drop(gauges);
+
+    let histograms = REGISTRY
+        .histograms
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    for (name, series) in histograms.iter() {
+        let _ = writeln!(output, "# TYPE {} histogram", name);
+        for (labels, histogram) in series {
+            let mut cumulative = 0;
+            for (bound, count) in histogram.bounds.iter().zip(&histogram.counts) {
+                cumulative += count;
+                let _ = writeln!(
+                    output,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some(&bound.to_string())),
+                    cumulative
+                );
+            }
+            let _ = writeln!(
+                output,
+                "{}_bucket{} {}",
+                name,
+                format_labels(labels, Some("+Inf")),
+                histogram.count
+            );
+            let _ = writeln!(
+                output,
+                "{}_sum{} {}",
+                name,
+                format_labels(labels, None),
+                histogram.sum
+            );
+            let _ = writeln!(
+                output,
+                "{}_count{} {}",
+                name,
+                format_labels(labels, None),
+                histogram.count
+            );
+        }
+    }
+
+    output
+}
+
+fn to_labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(name, value)| (*name, (*value).to_owned()))
+        .collect()
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .chain(le.map(|le| ("le", le)))
+        .map(|(name, value)| {
+            format!(
+                "{}=\"{}\"",
+                name,
+                value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n")
+            )
+        })
+        .collect();
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_prometheus_text() {
+        increment("test_render_total", &[("reason", "a \"quoted\" reason")], 2);
+        set("test_render_level", &[], 3.0);
+        set("test_render_level", &[], 4.0);
+        observe("test_render_seconds", &[], &[0.1, 1.0], 0.5);
+        observe("test_render_seconds", &[], &[0.1, 1.0], 5.0);
+
+        let rendered = render();
+        assert!(rendered.contains(
+            "# TYPE test_render_total counter\n\
+            test_render_total{reason=\"a \\\"quoted\\\" reason\"} 2\n"
+        ));
+        assert!(rendered.contains("# TYPE test_render_level gauge\ntest_render_level 4\n"));
+        assert!(rendered.contains(
+            "# TYPE test_render_seconds histogram\n\
+            test_render_seconds_bucket{le=\"0.1\"} 0\n\
+            test_render_seconds_bucket{le=\"1\"} 1\n\
+            test_render_seconds_bucket{le=\"+Inf\"} 2\n\
+            test_render_seconds_sum 5.5\n\
+            test_render_seconds_count 2\n"
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+//! Policy excluding regularly inflected forms, such as plurals and `-ing` or
+//! `-ed` forms, of words that are in the list themselves. Raw dictionaries are
+//! full of them and they make puzzles tedious.
+//!
+//! A word is only excluded if its stem is a word too, so irregular forms and
+//! words that merely end like an inflection, such as `bring`, are kept.
+
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MorphologyPolicy {
+    pub disallow_plurals: bool,
+    /// Disallow `-ing` and `-ed` forms.
+    pub disallow_verb_forms: bool,
+    /// Words kept even if they are inflected forms.
+    pub allowed: HashSet<String>,
+}
+
+impl MorphologyPolicy {
+    /// Read the policy from the environment, allowing every form by default.
+    pub fn from_env() -> Result<Self, MorphologyError> {
+        Ok(Self {
+            disallow_plurals: read_flag("MORPHOLOGY_DISALLOW_PLURALS")?,
+            disallow_verb_forms: read_flag("MORPHOLOGY_DISALLOW_VERB_FORMS")?,
+            allowed: dotenvy::var("MORPHOLOGY_ALLOWED_WORDS")
+                .map(|words| {
+                    words
+                        .split(',')
+                        .map(|w| w.trim().to_lowercase())
+                        .filter(|w| !w.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.disallow_plurals || self.disallow_verb_forms
+    }
+
+    /// The words `word` would be a disallowed inflection of, if they are words.
+    pub fn stems(&self, word: &str) -> Vec<(Inflection, String)> {
+        if self.allowed.contains(word) {
+            return Vec::new();
+        }
+
+        let mut stems = Vec::new();
+        if self.disallow_plurals {
+            if let Some(stem) = word.strip_suffix("ies") {
+                stems.push((Inflection::Plural, format!("{}y", stem)));
+            }
+            if let Some(stem) = word.strip_suffix("es") {
+                stems.push((Inflection::Plural, stem.to_owned()));
+            }
+            if let Some(stem) = word.strip_suffix('s')
+                && !stem.ends_with(['s', 'u', 'i'])
+            {
+                stems.push((Inflection::Plural, stem.to_owned()));
+            }
+        }
+        if self.disallow_verb_forms {
+            if let Some(stem) = word.strip_suffix("ing") {
+                push_verb_stems(&mut stems, Inflection::Participle, stem);
+            }
+            if let Some(stem) = word.strip_suffix("ied") {
+                stems.push((Inflection::PastTense, format!("{}y", stem)));
+            }
+            if let Some(stem) = word.strip_suffix("ed") {
+                push_verb_stems(&mut stems, Inflection::PastTense, stem);
+            }
+        }
+        stems
+    }
+
+    /// Why `word` is excluded, given which words are in the list.
+    pub fn exclusion(&self, word: &str, is_word: impl Fn(&str) -> bool) -> Option<Exclusion> {
+        self.stems(word)
+            .into_iter()
+            .find(|(_, stem)| is_word(stem))
+            .map(|(inflection, stem)| Exclusion {
+                word: word.to_owned(),
+                stem,
+                inflection,
+            })
+    }
+}
+
+/// Stems of a verb form with its suffix removed: as is (`jumped`), with a
+/// silent `e` restored (`baked`) and with a doubled consonant undone
+/// (`stopped`).
+fn push_verb_stems(stems: &mut Vec<(Inflection, String)>, inflection: Inflection, stem: &str) {
+    stems.push((inflection, stem.to_owned()));
+    stems.push((inflection, format!("{}e", stem)));
+    let mut letters = stem.chars().rev();
+    if let (Some(last), Some(before)) = (letters.next(), letters.next())
+        && last == before
+    {
+        stems.push((inflection, stem[..stem.len() - 1].to_owned()));
+    }
+}
+
+fn read_flag(name: &'static str) -> Result<bool, MorphologyError> {
+    match dotenvy::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| MorphologyError::InvalidValue(name, value)),
+        Err(_) => Ok(false),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Inflection {
+    Plural,
+    /// An `-ing` form.
+    Participle,
+    /// An `-ed` form.
+    PastTense,
+}
+
+impl Inflection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Plural => "plural",
+            Self::Participle => "participle",
+            Self::PastTense => "past_tense",
+        }
+    }
+}
+
+/// A word excluded as an inflection of `stem`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exclusion {
+    pub word: String,
+    pub stem: String,
+    pub inflection: Inflection,
+}
+
+#[derive(Debug)]
+pub enum MorphologyError {
+    InvalidValue(&'static str, String),
+}
+
+impl std::fmt::Display for MorphologyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidValue(name, value) => write!(
+                f,
+                "Invalid value {:?} for {}, expected true or false",
+                value, name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MorphologyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Inflection, MorphologyPolicy};
+
+    const WORDS: &[&str] = &[
+        "bake", "jump", "stop", "pony", "box", "bring", "glass", "bus", "walk",
+    ];
+
+    fn excluded(policy: &MorphologyPolicy, word: &str) -> Option<(Inflection, String)> {
+        policy
+            .exclusion(word, |stem| WORDS.contains(&stem))
+            .map(|e| (e.inflection, e.stem))
+    }
+
+    #[test]
+    fn excludes_regular_inflections_of_words() {
+        let policy = MorphologyPolicy {
+            disallow_plurals: true,
+            disallow_verb_forms: true,
+            ..Default::default()
+        };
+
+        let stem = |inflection, stem: &str| Some((inflection, stem.to_owned()));
+        assert_eq!(
+            stem(Inflection::Plural, "pony"),
+            excluded(&policy, "ponies")
+        );
+        assert_eq!(stem(Inflection::Plural, "box"), excluded(&policy, "boxes"));
+        assert_eq!(stem(Inflection::Plural, "bake"), excluded(&policy, "bakes"));
+        assert_eq!(
+            stem(Inflection::Participle, "bake"),
+            excluded(&policy, "baking")
+        );
+        assert_eq!(
+            stem(Inflection::Participle, "stop"),
+            excluded(&policy, "stopping")
+        );
+        assert_eq!(
+            stem(Inflection::PastTense, "jump"),
+            excluded(&policy, "jumped")
+        );
+        assert_eq!(
+            stem(Inflection::PastTense, "bake"),
+            excluded(&policy, "baked")
+        );
+
+        for word in ["bring", "glass", "bus", "walk"] {
+            assert_eq!(None, excluded(&policy, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn applies_only_enabled_rules_and_allows_listed_words() {
+        let policy = MorphologyPolicy {
+            disallow_verb_forms: true,
+            allowed: ["walked".to_owned()].into(),
+            ..Default::default()
+        };
+
+        assert_eq!(None, excluded(&policy, "walks"));
+        assert_eq!(None, excluded(&policy, "walked"));
+        assert!(excluded(&policy, "walking").is_some());
+        assert!(!MorphologyPolicy::default().is_active());
+    }
+}
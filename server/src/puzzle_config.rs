@@ -1,41 +1,43 @@
 use std::collections::HashSet;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 
-use chrono::{DateTime, Duration, FixedOffset, Timelike, Utc};
-use dashmap::DashMap;
-use puzzle_config::{Letter, PuzzleConfig, Word};
+use accounts::Dictionary;
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, Utc};
+use puzzle_config::{
+    DictionaryHealth, Difficulty, Letter, PuzzleConfig, PuzzlePreview, ScoringRules, Word,
+};
 use rand::{Rng, SeedableRng};
-use serde::Serialize;
 
-struct CachedConfig {
-    config: PuzzleConfig,
-    ttl: DateTime<FixedOffset>,
-}
-
-pub struct ConfigHandle<'a>(
-    dashmap::mapref::one::MappedRef<'a, FixedOffset, CachedConfig, PuzzleConfig>,
-);
-
-impl<'a> std::ops::Deref for ConfigHandle<'a> {
-    type Target = PuzzleConfig;
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
+use crate::cache::CacheBackend as _;
+use crate::morphology::MorphologyPolicy;
+use crate::services::dictionaries::DictionaryId;
+use crate::services::puzzles::{ListExclusions as _, PuzzleRepository as _, ScheduledLetters};
+use crate::timezone::Zone;
+use crate::word_index::WordIndex;
 
-impl<'a> Serialize for ConfigHandle<'a> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        (**self).serialize(serializer)
-    }
+#[derive(Clone)]
+pub struct ConfigProvider {
+    cache: crate::cache::Backend,
+    source: Source,
+    policy: PuzzleGenerationPolicy,
+    morphology: MorphologyPolicy,
 }
 
+/// Where puzzles are generated from.
 #[derive(Clone)]
-pub struct ConfigProvider {
-    cache: Arc<DashMap<FixedOffset, CachedConfig>>,
-    pool: sqlx::PgPool,
+enum Source {
+    /// The words table. Generated puzzles are stored so they never change and
+    /// curators can schedule puzzles and exclude words.
+    Db(sqlx::PgPool),
+    /// A word list in memory. Puzzles only live in the cache, but since
+    /// generation is seeded by the date they come out the same after a restart
+    /// as long as the list doesn't change.
+    Embedded(Arc<WordIndex>),
+    /// The words table of a SQLite database, for serving a word list curated
+    /// locally without Postgres. Puzzles are only cached, like embedded ones,
+    /// so edits to the words show in them once they expire.
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlx::SqlitePool),
 }
 
 impl std::fmt::Debug for ConfigProvider {
@@ -45,127 +47,1678 @@ impl std::fmt::Debug for ConfigProvider {
 }
 
 impl ConfigProvider {
-    pub fn new(pool: sqlx::PgPool) -> Self {
+    pub fn new(
+        pool: sqlx::PgPool,
+        cache: crate::cache::Backend,
+        policy: PuzzleGenerationPolicy,
+        morphology: MorphologyPolicy,
+    ) -> Self {
         Self {
-            cache: Arc::new(DashMap::new()),
-            pool,
+            cache,
+            source: Source::Db(pool),
+            policy,
+            morphology,
         }
     }
 
-    pub async fn get_config<'cache>(
-        &'cache self,
-        tz: &FixedOffset,
-    ) -> Result<ConfigHandle<'cache>, Error> {
-        let now = Utc::now().with_timezone(tz);
-        if let Some(cached) = self.cache.get(tz)
-            && cached.ttl >= now
-        {
-            return Ok(ConfigHandle(cached.map(|cached| &cached.config)));
-        }
-
-        let ttl = next_midnight(&now);
-        let config = self.fetch().await?;
-        Ok(ConfigHandle(
-            self.cache
-                .entry(tz.clone())
-                .insert_entry(CachedConfig { config, ttl })
-                .into_ref()
-                .downgrade()
-                .map(|cached| &cached.config),
-        ))
+    /// Serve puzzles generated from `index` rather than the words table.
+    pub fn embedded(
+        index: WordIndex,
+        cache: crate::cache::Backend,
+        policy: PuzzleGenerationPolicy,
+        morphology: MorphologyPolicy,
+    ) -> Self {
+        Self {
+            cache,
+            source: Source::Embedded(Arc::new(index)),
+            policy,
+            morphology,
+        }
+    }
+
+    /// Serve puzzles generated from the words of the SQLite database `pool`
+    /// rather than the words table.
+    #[cfg(feature = "sqlite")]
+    pub fn sqlite(
+        pool: sqlx::SqlitePool,
+        cache: crate::cache::Backend,
+        policy: PuzzleGenerationPolicy,
+        morphology: MorphologyPolicy,
+    ) -> Self {
+        Self {
+            cache,
+            source: Source::Sqlite(pool),
+            policy,
+            morphology,
+        }
+    }
+
+    /// The cache puzzles are kept in, shared with the rest of the server.
+    pub(crate) fn cache(&self) -> &crate::cache::Backend {
+        &self.cache
+    }
+
+    /// The alphabet puzzles are made in.
+    pub(crate) fn alphabet(&self) -> words::Alphabet {
+        self.policy.alphabet
     }
 
-    #[tracing::instrument]
-    async fn fetch(&self) -> Result<PuzzleConfig, Error> {
-        let mut conn = self
-            .pool
-            .acquire()
+    /// Get the puzzle of `difficulty` for the current day in `tz` from
+    /// `dictionary`, generating it on a cache miss.
+    ///
+    /// Cache failures are logged and treated as misses so that an unavailable
+    /// cache backend degrades to generating puzzles locally. Words excluded from
+    /// the day's puzzle are removed on every call, so exclusions apply immediately
+    /// even to cached puzzles.
+    pub(crate) async fn get_config(
+        &self,
+        tz: &Zone,
+        difficulty: Difficulty,
+        dictionary: DictionaryId,
+    ) -> Result<PuzzleConfig, Error> {
+        let today = tz.date_at(Utc::now());
+        self.get_config_for_date(tz, today, difficulty, dictionary)
             .await
-            .map_err(|e| Error::DbError(Box::new(e)))?;
-        let mut rng = rand::rngs::StdRng::seed_from_u64(day_64());
-        let mut letter_mask = 0i32;
-        loop {
-            let required_char = rng.random_range('a'..='z');
-            let required_mask = words::letters::bitmask(&required_char);
-            for i in 0..6 {
-                loop {
-                    let letter = words::letters::bitmask(&rng.random_range('a'..='z'));
-                    if letter & (required_mask | letter_mask) == 0 {
-                        tracing::debug!(
-                            letter = ?words::vec_from_bitmask(&letter),
-                            required_mask = ?words::vec_from_bitmask(&required_mask),
-                            optional_letters = ?words::vec_from_bitmask(&letter_mask),
-                            "new letter found",
-                        );
-                        letter_mask |= letter;
-
-                        break;
-                    }
-                }
-                tracing::debug!(i, mask_so_far = ?words::vec_from_bitmask(&letter_mask));
+    }
+
+    /// Get the puzzle of `difficulty` for `date` in `tz` from `dictionary`.
+    /// Puzzles for days that have not started yet in `tz` are unavailable.
+    ///
+    /// Puzzles are identified by the date, the offset `tz` has on that day, the
+    /// difficulty and the dictionary, so zones share puzzles with the offsets
+    /// they're at.
+    #[tracing::instrument(skip(self, tz))]
+    pub(crate) async fn get_config_for_date(
+        &self,
+        tz: &Zone,
+        date: NaiveDate,
+        difficulty: Difficulty,
+        dictionary: DictionaryId,
+    ) -> Result<PuzzleConfig, Error> {
+        let now = Utc::now();
+        if date > tz.date_at(now) {
+            return Err(Error::Unavailable(date));
+        }
+
+        let ttl = tz.next_midnight(now) - now;
+        let mut config = self
+            .get_or_generate(date, tz.day_offset(date), difficulty, dictionary, ttl)
+            .await?;
+        self.apply_exclusions(date, &mut config).await?;
+        Ok(config)
+    }
+
+    async fn get_or_generate(
+        &self,
+        date: NaiveDate,
+        offset: FixedOffset,
+        difficulty: Difficulty,
+        dictionary: DictionaryId,
+        ttl: Duration,
+    ) -> Result<PuzzleConfig, Error> {
+        let key = daily_key(date, offset, difficulty, dictionary);
+        let seed = PuzzleSeed::new(date, &offset, difficulty);
+        if let Some(config) = self.get_cached(&key).await {
+            record_lookup("cache");
+            return Ok(config);
+        }
+        let ttl = ttl.to_std().unwrap_or(std::time::Duration::ZERO);
+
+        let pool = match &self.source {
+            Source::Db(pool) => pool,
+            Source::Embedded(index) => {
+                let config = self
+                    .fetch(&mut index.as_ref(), dictionary, seed, difficulty)
+                    .await?;
+                record_lookup("generated");
+                self.set_cached(&key, &config, ttl).await;
+                return Ok(config);
+            }
+            #[cfg(feature = "sqlite")]
+            Source::Sqlite(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = SqliteWords {
+                    conn: &mut conn,
+                    dictionary,
+                };
+                let config = self.fetch(&mut table, dictionary, seed, difficulty).await?;
+                record_lookup("generated");
+                self.set_cached(&key, &config, ttl).await;
+                return Ok(config);
             }
+        };
 
-            tracing::debug!(
-                required = ?words::letters::from_bitmask(&required_mask),
-                letters = ?words::vec_from_bitmask(&letter_mask)
-            );
-            let words = sqlx::query_as!(
-                WordRow,
-                r#"select word, letter_mask & $2 = $2 as "is_pangram!"
-                from words
-                where letter_mask & $1 = $1
-                and letter_mask | $2 = $2
-                "#r,
-                required_mask,
-                letter_mask | required_mask,
+        // Replicas that miss the cache at the same time serialize on a lock keyed by
+        // the puzzle so only the first one generates; the rest find its result in the
+        // cache once the lock is released.
+        let mut tx = lock_puzzle(pool, &key).await?;
+        if let Some(config) = self.get_cached(&key).await {
+            record_lookup("cache");
+            return Ok(config);
+        }
+
+        // Generated puzzles are stored so that later dictionary changes can't alter
+        // a puzzle players have already seen. Everything is done on the locked
+        // transaction, since waiting on another connection while holding the lock
+        // could exhaust the pool when many requests miss the cache at once.
+        let utc_offset = offset.local_minus_utc();
+        let stored = crate::services::puzzles::pg::stored_puzzle(
+            &mut tx, date, utc_offset, difficulty, dictionary,
+        )
+        .await
+        .map_err(|e| Error::DbError(Box::new(e)))?;
+        let config = match stored {
+            Some(config) => {
+                record_lookup("stored");
+                // Stored puzzles don't record their alphabet or scoring rules,
+                // which are the ones they were generated with.
+                let mut config = PuzzleConfig {
+                    alphabet: self.policy.alphabet,
+                    scoring: self.policy.scoring.clone(),
+                    ..config
+                };
+                config.score_buckets = config.scoring.score_buckets(&config.valid_words);
+                config
+            }
+            None => {
+                let config = self
+                    .generate_daily(&mut tx, date, seed, difficulty, dictionary)
+                    .await?;
+                crate::services::puzzles::pg::store_puzzle(
+                    &mut tx, date, utc_offset, difficulty, dictionary, &config,
+                )
+                .await
+                .map_err(|e| Error::DbError(Box::new(e)))?;
+                record_lookup("generated");
+                config
+            }
+        };
+        self.set_cached(&key, &config, ttl).await;
+        tx.commit().await.map_err(|e| Error::DbError(Box::new(e)))?;
+        Ok(config)
+    }
+
+    /// Generate every puzzle stored for `date` again from the current words and
+    /// schedule, replacing the stored and cached puzzles, and return how many
+    /// were regenerated. Players who have already seen one of the day's puzzles
+    /// will find it changed.
+    ///
+    /// Puzzles that don't come from the words table aren't stored, so there's
+    /// nothing to regenerate.
+    #[tracing::instrument(skip(self))]
+    pub async fn regenerate(&self, date: NaiveDate) -> Result<usize, Error> {
+        let Source::Db(pool) = &self.source else {
+            return Ok(0);
+        };
+
+        let stored = crate::services::puzzles::pg::PuzzleRepository(pool.clone())
+            .list_stored(date)
+            .await
+            .map_err(|e| Error::DbError(Box::new(e)))?;
+        let now = Utc::now();
+        for &(utc_offset, difficulty, dictionary) in &stored {
+            let offset = FixedOffset::east_opt(utc_offset).ok_or_else(|| {
+                Error::DbError(format!("Invalid UTC offset {}", utc_offset).into())
+            })?;
+            let key = daily_key(date, offset, difficulty, dictionary);
+            let seed = PuzzleSeed::new(date, &offset, difficulty);
+
+            // Hold the lock readers take on a cache miss, so none of them stores
+            // or caches the old puzzle in the meantime.
+            let mut tx = lock_puzzle(pool, &key).await?;
+            let config = self
+                .generate_daily(&mut tx, date, seed, difficulty, dictionary)
+                .await?;
+            crate::services::puzzles::pg::replace_puzzle(
+                &mut tx, date, utc_offset, difficulty, dictionary, &config,
             )
-            .fetch_all(&mut *conn)
             .await
             .map_err(|e| Error::DbError(Box::new(e)))?;
 
-            tracing::debug!(words = ?words);
-
-            if words.len() > 10 && words.iter().any(|w| w.is_pangram) {
-                let valid_words: HashSet<_> = words
-                    .into_iter()
-                    .map(|w| Word::new(&w.word, w.is_pangram))
-                    .collect();
-                let max_score = valid_words.iter().map(|w| w.score()).sum::<u32>() as f32;
-                let score_buckets = [
-                    ("Beginner".to_owned(), (max_score * 0.0).trunc() as u32),
-                    ("Good Start".to_owned(), (max_score * 0.02).trunc() as u32),
-                    ("Moving Up".to_owned(), (max_score * 0.05).trunc() as u32),
-                    ("Good".to_owned(), (max_score * 0.08).trunc() as u32),
-                    ("Solid".to_owned(), (max_score * 0.15).trunc() as u32),
-                    ("Nice".to_owned(), (max_score * 0.25).trunc() as u32),
-                    ("Great".to_owned(), (max_score * 0.4).trunc() as u32),
-                    ("Amazing".to_owned(), (max_score * 0.5).trunc() as u32),
-                    ("Genius".to_owned(), (max_score * 0.7).trunc() as u32),
-                ];
-                return Ok(PuzzleConfig {
-                    valid_words,
-                    score_buckets,
-                    required_letter: Letter::new(words::letters::from_bitmask(&required_mask)),
-                    other_letters: words::vec_from_bitmask(&letter_mask)
-                        .into_iter()
-                        .map(|l| Letter::new(l))
-                        .collect(),
+            let day_end = (date + Duration::days(1))
+                .and_time(chrono::NaiveTime::MIN)
+                .and_local_timezone(offset)
+                .single()
+                .map(|end| end.with_timezone(&Utc));
+            let ttl = day_end
+                .and_then(|end| (end - now).to_std().ok())
+                .unwrap_or(std::time::Duration::ZERO);
+            self.set_cached(&key, &config, ttl).await;
+            tx.commit().await.map_err(|e| Error::DbError(Box::new(e)))?;
+        }
+        Ok(stored.len())
+    }
+
+    /// Generate the puzzle of `difficulty` for `date` from `dictionary` within
+    /// `tx`, using the letters curators scheduled for the day if any.
+    async fn generate_daily(
+        &self,
+        tx: &mut sqlx::PgConnection,
+        date: NaiveDate,
+        seed: PuzzleSeed,
+        difficulty: Difficulty,
+        dictionary: DictionaryId,
+    ) -> Result<PuzzleConfig, Error> {
+        // Curators schedule the medium puzzle, the others are always generated.
+        let scheduled = match difficulty {
+            Difficulty::Medium => crate::services::puzzles::pg::scheduled_puzzles(tx, date, date)
+                .await
+                .map_err(|e| Error::DbError(Box::new(e)))?
+                .into_iter()
+                .next()
+                .and_then(|puzzle| puzzle.letters),
+            Difficulty::Easy | Difficulty::Hard => None,
+        };
+        let mut table = WordsTable {
+            conn: tx,
+            dictionary,
+        };
+        match scheduled {
+            Some(letters) => self.fetch_scheduled(&mut table, &letters).await,
+            None => self.fetch(&mut table, dictionary, seed, difficulty).await,
+        }
+    }
+
+    /// Get the practice puzzle of `difficulty` generated from `seed` with the
+    /// words of `dictionary`, which belongs to no day. It's only cached, so the
+    /// same seed gives the same puzzle as long as the words don't change.
+    #[tracing::instrument(skip(self))]
+    pub(crate) async fn get_random_config(
+        &self,
+        seed: u64,
+        difficulty: Difficulty,
+        dictionary: DictionaryId,
+    ) -> Result<PuzzleConfig, Error> {
+        let key = format!(
+            "puzzle/random/{}/{}/{}",
+            seed,
+            difficulty.as_str(),
+            dictionary
+        );
+        if let Some(config) = self.get_cached(&key).await {
+            return Ok(config);
+        }
+
+        // Mixed like daily seeds, so that nearby seeds don't give similar puzzles.
+        let seed = PuzzleSeed(splitmix64(seed));
+        let config = match &self.source {
+            Source::Db(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = WordsTable {
+                    conn: &mut conn,
+                    dictionary,
+                };
+                self.fetch(&mut table, dictionary, seed, difficulty).await?
+            }
+            Source::Embedded(index) => {
+                self.fetch(&mut index.as_ref(), dictionary, seed, difficulty)
+                    .await?
+            }
+            #[cfg(feature = "sqlite")]
+            Source::Sqlite(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = SqliteWords {
+                    conn: &mut conn,
+                    dictionary,
+                };
+                self.fetch(&mut table, dictionary, seed, difficulty).await?
+            }
+        };
+        self.set_cached(&key, &config, RANDOM_TTL).await;
+        Ok(config)
+    }
+
+    async fn apply_exclusions(
+        &self,
+        date: NaiveDate,
+        config: &mut PuzzleConfig,
+    ) -> Result<(), Error> {
+        let Source::Db(pool) = &self.source else {
+            return Ok(());
+        };
+        let excluded = crate::services::puzzles::pg::ListExclusions(pool.clone())
+            .list_exclusions(date)
+            .await
+            .map_err(|e| Error::DbError(Box::new(e)))?;
+        if excluded.is_empty() {
+            return Ok(());
+        }
+
+        config.valid_words.retain(|w| !excluded.contains(&w.word));
+        config.score_buckets = config.scoring.score_buckets(&config.valid_words);
+        Ok(())
+    }
+
+    async fn get_cached<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        match self.cache.get(key).await {
+            Ok(Some(cached)) => match serde_json::from_str(&cached) {
+                Ok(value) => return Some(value),
+                Err(e) => tracing::warn!(error = %e, key, "discarding malformed cached value"),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::warn!(error = %e, key, "failed to read cached value"),
+        }
+        None
+    }
+
+    async fn set_cached<T: serde::Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl: std::time::Duration,
+    ) {
+        match serde_json::to_string(value) {
+            Ok(serialized) => {
+                if let Err(e) = self.cache.set(key, &serialized, ttl).await {
+                    tracing::warn!(error = %e, key, "failed to cache value");
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, key, "failed to serialize value for caching"),
+        }
+    }
+
+    /// How well `dictionary` supports puzzle generation, cached for a few
+    /// minutes since it's computed from all its words.
+    #[tracing::instrument(skip(self))]
+    pub(crate) async fn dictionary_health(
+        &self,
+        dictionary: DictionaryId,
+    ) -> Result<DictionaryHealth, Error> {
+        let key = health_key(dictionary);
+        if let Some(health) = self.get_cached(&key).await {
+            return Ok(health);
+        }
+
+        let health = match &self.source {
+            Source::Db(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = WordsTable {
+                    conn: &mut conn,
+                    dictionary,
+                };
+                dictionary_health(&table.dictionary().await?, &self.policy)
+            }
+            Source::Embedded(index) => dictionary_health(index.dictionary(), &self.policy),
+            #[cfg(feature = "sqlite")]
+            Source::Sqlite(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = SqliteWords {
+                    conn: &mut conn,
+                    dictionary,
+                };
+                dictionary_health(&table.dictionary().await?, &self.policy)
+            }
+        };
+        self.set_cached(&key, &health, HEALTH_TTL).await;
+        Ok(health)
+    }
+
+    #[tracing::instrument(skip(self, source), fields(attempts, words_fetched))]
+    async fn fetch(
+        &self,
+        source: &mut impl WordSource,
+        dictionary: DictionaryId,
+        seed: PuzzleSeed,
+        difficulty: Difficulty,
+    ) -> Result<PuzzleConfig, Error> {
+        let started = std::time::Instant::now();
+        let mut stats = GenerationStats::default();
+        let result = self
+            .generate(
+                source,
+                dictionary,
+                seed,
+                &self.policy.for_difficulty(difficulty),
+                &mut stats,
+            )
+            .await;
+        stats.record(&result, started.elapsed());
+        result
+    }
+
+    /// Build the puzzle a curator scheduled. The letters were chosen on purpose,
+    /// so the generation policy is only checked to log a warning.
+    async fn fetch_scheduled(
+        &self,
+        table: &mut WordsTable<'_>,
+        letters: &ScheduledLetters,
+    ) -> Result<PuzzleConfig, Error> {
+        let required_mask = words::Bitmask::from_iter([letters.required]);
+        let letter_mask: words::Bitmask = letters.others.iter().copied().collect();
+        let words = table
+            .words(&self.morphology, required_mask, letter_mask)
+            .await?;
+        if let Err(rejection) = self.policy.check(&words) {
+            tracing::warn!(
+                required = %letters.required,
+                others = %letters.others.iter().collect::<String>(),
+                reason = rejection.message(),
+                "scheduled puzzle doesn't satisfy the generation policy"
+            );
+        }
+        Ok(build_config(
+            words,
+            required_mask,
+            letter_mask,
+            &self.policy,
+        ))
+    }
+
+    async fn generate(
+        &self,
+        source: &mut impl WordSource,
+        id: DictionaryId,
+        seed: PuzzleSeed,
+        policy: &PuzzleGenerationPolicy,
+        stats: &mut GenerationStats,
+    ) -> Result<PuzzleConfig, Error> {
+        let dictionary = source.dictionary().await?;
+        // Searching a dictionary that can't satisfy the policy would only use up
+        // every attempt, so give up straight away and say why.
+        // The health is judged by the medium policy, since it's shared by every
+        // difficulty.
+        let health = dictionary_health(&dictionary, &self.policy);
+        self.set_cached(&health_key(id), &health, HEALTH_TTL).await;
+        if !health.is_healthy() {
+            return Err(Error::UnhealthyDictionary(health.problems));
+        }
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed.0);
+        // Every letter set is the letters of a pangram, so each puzzle tried has
+        // at least one and only letter sets the dictionary can spell are tried.
+        let pangram_sets = policy.letter_weights.shuffle(
+            &mut rng,
+            dictionary
+                .letter_sets(PUZZLE_LETTERS)
+                .map(|(mask, _)| mask)
+                .filter(|mask| policy.check_letters(*mask).is_ok()),
+        );
+        // The first acceptable puzzle, used if none with a perfect pangram turns up
+        // within the policy's attempts.
+        let mut fallback = None;
+        for (attempt, letters) in pangram_sets
+            .into_iter()
+            .take(MAX_GENERATION_ATTEMPTS)
+            .enumerate()
+        {
+            stats.attempts += 1;
+            // Letter sets that can't satisfy the policy are skipped without fetching
+            // their words, trying each of their letters as the required one.
+            let quality = words::letterset::quality(letters, &dictionary);
+            let required = self
+                .policy
+                .letter_weights
+                .within(letters)
+                .sample(&mut rng, PUZZLE_LETTERS)
+                .into_iter()
+                .find(|required| match policy.screen(&quality, *required) {
+                    Ok(()) => true,
+                    Err(rejection) => {
+                        rejection.record();
+                        false
+                    }
                 });
+
+            if let Some(required_char) = required {
+                let required_mask = words::Bitmask::from_iter([required_char]);
+                let letter_mask: words::Bitmask =
+                    letters.letters().filter(|l| *l != required_char).collect();
+                tracing::debug!(
+                    required = ?required_char,
+                    letters = ?self.policy.alphabet.letters_of(letter_mask)
+                );
+
+                let words = source
+                    .words(&self.morphology, required_mask, letter_mask)
+                    .await?;
+                tracing::debug!(words = ?words);
+                stats.words_fetched += words.len();
+
+                match policy.check(&words) {
+                    Err(rejection) => rejection.record(),
+                    Ok(()) => {
+                        let config = build_config(words, required_mask, letter_mask, policy);
+                        if config.perfect_pangram_count() > 0 {
+                            return Ok(config);
+                        }
+                        Rejection::NoPerfectPangram.record();
+                        fallback.get_or_insert(config);
+                    }
+                }
+            }
+            if attempt + 1 >= policy.perfect_pangram_attempts
+                && let Some(config) = fallback.take()
+            {
+                tracing::debug!(attempt, "settling for a puzzle without a perfect pangram");
+                return Ok(config);
+            }
+        }
+
+        Err(Error::NoAcceptablePuzzle(Box::new(policy.clone())))
+    }
+
+    /// Evaluate the puzzle `letters` would make from `dictionary` with
+    /// `required` as its required letter, selecting words the same way as
+    /// generated puzzles. Nothing is stored or cached.
+    pub(crate) async fn preview(
+        &self,
+        required: char,
+        letters: &[char],
+        dictionary: DictionaryId,
+    ) -> Result<PuzzlePreview, Error> {
+        match &self.source {
+            Source::Db(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = WordsTable {
+                    conn: &mut conn,
+                    dictionary,
+                };
+                self.preview_from(&mut table, required, letters).await
+            }
+            Source::Embedded(index) => {
+                self.preview_from(&mut index.as_ref(), required, letters)
+                    .await
+            }
+            #[cfg(feature = "sqlite")]
+            Source::Sqlite(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = SqliteWords {
+                    conn: &mut conn,
+                    dictionary,
+                };
+                self.preview_from(&mut table, required, letters).await
+            }
+        }
+    }
+
+    /// Build the puzzle `letters` make with `required` as its required letter,
+    /// selecting words the same way as generated puzzles, for a puzzle a player
+    /// made. Players make puzzles from the standard dictionary. Nothing is
+    /// stored or cached.
+    pub async fn build_custom(
+        &self,
+        required: char,
+        letters: &[char],
+    ) -> Result<PuzzleConfig, Error> {
+        let required_mask = words::Bitmask::from_iter([required]);
+        let letter_mask: words::Bitmask =
+            letters.iter().copied().filter(|l| *l != required).collect();
+        let words = match &self.source {
+            Source::Db(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = WordsTable {
+                    conn: &mut conn,
+                    dictionary: DictionaryId::STANDARD,
+                };
+                table
+                    .words(&self.morphology, required_mask, letter_mask)
+                    .await?
+            }
+            Source::Embedded(index) => {
+                index
+                    .as_ref()
+                    .words(&self.morphology, required_mask, letter_mask)
+                    .await?
+            }
+            #[cfg(feature = "sqlite")]
+            Source::Sqlite(pool) => {
+                let mut conn = pool
+                    .acquire()
+                    .await
+                    .map_err(|e| Error::DbError(Box::new(e)))?;
+                let mut table = SqliteWords {
+                    conn: &mut conn,
+                    dictionary: DictionaryId::STANDARD,
+                };
+                table
+                    .words(&self.morphology, required_mask, letter_mask)
+                    .await?
+            }
+        };
+        Ok(build_config(
+            words,
+            required_mask,
+            letter_mask,
+            &self.policy,
+        ))
+    }
+
+    async fn preview_from(
+        &self,
+        source: &mut impl WordSource,
+        required: char,
+        letters: &[char],
+    ) -> Result<PuzzlePreview, Error> {
+        let required_mask = words::Bitmask::from_iter([required]);
+        let letter_mask: words::Bitmask =
+            letters.iter().copied().filter(|l| *l != required).collect();
+
+        let dictionary = source.dictionary().await?;
+        let mut warnings =
+            words::letterset::quality(letter_mask | required_mask, &dictionary).warnings();
+        let words = source
+            .words(&self.morphology, required_mask, letter_mask)
+            .await?;
+        for rejection in [
+            self.policy.check_letters(letter_mask | required_mask),
+            self.policy.check(&words),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        {
+            warnings.push(rejection.message().to_owned());
+        }
+        let config = build_config(words, required_mask, letter_mask, &self.policy);
+        if self.policy.perfect_pangram_attempts > 0 && config.perfect_pangram_count() == 0 {
+            warnings.push(Rejection::NoPerfectPangram.message().to_owned());
+        }
+
+        let mut words: Vec<_> = config.valid_words.iter().map(|w| w.word.clone()).collect();
+        words.sort();
+        let mut pangrams: Vec<_> = config
+            .valid_words
+            .iter()
+            .filter(|w| w.is_pangram)
+            .map(|w| w.word.clone())
+            .collect();
+        pangrams.sort();
+        Ok(PuzzlePreview {
+            max_score: config.scoring.max_score(&config.valid_words),
+            required_letter: config.required_letter,
+            other_letters: config.other_letters,
+            score_buckets: config.score_buckets,
+            words,
+            pangrams,
+            warnings,
+        })
+    }
+}
+
+/// Letters in a puzzle, every one of which its pangrams use.
+const PUZZLE_LETTERS: usize = 7;
+
+/// Cache key of the health of `dictionary`, which doesn't depend on the
+/// timezone.
+fn health_key(dictionary: DictionaryId) -> String {
+    format!("dictionary/health/{}", dictionary)
+}
+
+/// How long practice puzzles are cached, long enough to outlast a game.
+const RANDOM_TTL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// How long the dictionary health is cached. Generation recomputes it from the
+/// words it loads, so a stale value is only ever served to curators.
+const HEALTH_TTL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Compute how well `dictionary` supports generating puzzles under `policy`.
+fn dictionary_health(
+    dictionary: &words::letterset::Dictionary,
+    policy: &PuzzleGenerationPolicy,
+) -> DictionaryHealth {
+    let letter_sets: Vec<_> = dictionary.letter_sets(PUZZLE_LETTERS).collect();
+    let pangram_words_by_letter = policy
+        .alphabet
+        .letters()
+        .iter()
+        .map(|&letter| {
+            let count = letter_sets
+                .iter()
+                .filter(|(mask, _)| mask.contains(letter))
+                .map(|(_, count)| count)
+                .sum();
+            (letter, count)
+        })
+        .collect();
+    let degenerate_letter_sets = letter_sets
+        .iter()
+        .filter(|(mask, _)| {
+            if policy.check_letters(*mask).is_err() {
+                return true;
             }
-            letter_mask = 0i32;
+            let quality = words::letterset::quality(*mask, dictionary);
+            mask.letters()
+                .all(|required| policy.screen(&quality, required).is_err())
+        })
+        .count();
+
+    let words = dictionary.len();
+    let problem = if words == 0 {
+        Some("The dictionary has no words".to_owned())
+    } else if words < policy.min_words {
+        Some(format!(
+            "The dictionary has only {} words, fewer than a puzzle needs",
+            words
+        ))
+    } else if policy.min_pangrams > 0 && letter_sets.is_empty() {
+        Some("No word has seven distinct letters, so no puzzle can have a pangram".to_owned())
+    } else if policy.min_pangrams > 0 && degenerate_letter_sets == letter_sets.len() {
+        Some(format!(
+            "None of the {} letter sets with a pangram makes a puzzle satisfying the generation policy",
+            letter_sets.len()
+        ))
+    } else {
+        None
+    };
+
+    DictionaryHealth {
+        words,
+        pangram_words_by_letter,
+        letter_sets: letter_sets.len(),
+        degenerate_letter_sets,
+        problems: problem.into_iter().collect(),
+    }
+}
+
+/// Letter sets tried before giving up on finding one that satisfies the
+/// generation policy.
+const MAX_GENERATION_ATTEMPTS: usize = 1000;
+
+/// Constraints a generated letter set's word list must satisfy for it to be
+/// used as a puzzle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuzzleGenerationPolicy {
+    pub min_words: usize,
+    pub max_words: Option<usize>,
+    pub min_pangrams: usize,
+    pub min_four_letter_words: usize,
+    /// Letter sets tried looking for a puzzle with a perfect pangram before
+    /// settling for the first acceptable one. Zero disables the preference.
+    pub perfect_pangram_attempts: usize,
+    /// Vowels a letter set needs, since sets with fewer rarely spell enough
+    /// words.
+    pub min_vowels: usize,
+    /// The letters puzzles are made of.
+    pub alphabet: words::Alphabet,
+    /// How likely each letter is to be picked for a letter set.
+    pub letter_weights: LetterWeights,
+    /// Bounds on the average obscurity of a puzzle's rated words, from 1 to 5.
+    /// Puzzles without rated words satisfy any bounds.
+    pub min_average_obscurity: Option<u8>,
+    pub max_average_obscurity: Option<u8>,
+    /// How the puzzles' words are scored and ranked.
+    pub scoring: ScoringRules,
+}
+
+impl Default for PuzzleGenerationPolicy {
+    fn default() -> Self {
+        Self {
+            min_words: 11,
+            max_words: None,
+            min_pangrams: 1,
+            min_four_letter_words: 0,
+            perfect_pangram_attempts: 100,
+            min_vowels: 2,
+            alphabet: words::Alphabet::LATIN,
+            letter_weights: LetterWeights::default(),
+            min_average_obscurity: None,
+            max_average_obscurity: None,
+            scoring: ScoringRules::default(),
         }
     }
 }
 
+impl PuzzleGenerationPolicy {
+    /// Read the policy from `PUZZLE_MIN_WORDS`, `PUZZLE_MAX_WORDS`,
+    /// `PUZZLE_MIN_PANGRAMS`, `PUZZLE_MIN_FOUR_LETTER_WORDS`,
+    /// `PUZZLE_PERFECT_PANGRAM_ATTEMPTS`, `PUZZLE_MIN_VOWELS`, `PUZZLE_ALPHABET`
+    /// and `PUZZLE_LETTER_WEIGHTS`, using the defaults for any that are unset.
+    ///
+    /// `PUZZLE_ALPHABET` names the alphabet of the word list, `latin` by
+    /// default. `PUZZLE_LETTER_WEIGHTS` overrides the weights of some letters
+    /// with comma-separated `letter:weight` pairs, such as `q:0,z:1`.
+    pub fn from_env() -> Result<Self, PolicyError> {
+        let defaults = Self::default();
+        let alphabet = match dotenvy::var("PUZZLE_ALPHABET") {
+            Ok(value) => value.parse().map_err(PolicyError::InvalidAlphabet)?,
+            Err(_) => defaults.alphabet,
+        };
+        let letter_weights = LetterWeights::for_alphabet(alphabet);
+        let letter_weights = match dotenvy::var("PUZZLE_LETTER_WEIGHTS") {
+            Ok(value) => letter_weights
+                .with_overrides(&value)
+                .filter(|weights| weights.letters() >= PUZZLE_LETTERS)
+                .ok_or(PolicyError::InvalidLetterWeights(value))?,
+            Err(_) => letter_weights,
+        };
+        let policy = Self {
+            min_words: read_var("PUZZLE_MIN_WORDS")?.unwrap_or(defaults.min_words),
+            max_words: read_var("PUZZLE_MAX_WORDS")?.or(defaults.max_words),
+            min_pangrams: read_var("PUZZLE_MIN_PANGRAMS")?.unwrap_or(defaults.min_pangrams),
+            min_four_letter_words: read_var("PUZZLE_MIN_FOUR_LETTER_WORDS")?
+                .unwrap_or(defaults.min_four_letter_words),
+            perfect_pangram_attempts: read_var("PUZZLE_PERFECT_PANGRAM_ATTEMPTS")?
+                .unwrap_or(defaults.perfect_pangram_attempts),
+            min_vowels: read_var("PUZZLE_MIN_VOWELS")?.unwrap_or(defaults.min_vowels),
+            alphabet,
+            letter_weights,
+            ..defaults
+        };
+
+        if policy.max_words.is_some_and(|max| {
+            max < policy.min_words
+                || max < policy.min_pangrams
+                || max < policy.min_four_letter_words
+        }) || policy.min_vowels > VOWELS.len()
+        {
+            return Err(PolicyError::Unsatisfiable(Box::new(policy)));
+        }
+        Ok(policy)
+    }
+
+    /// This policy, which medium puzzles are generated by, tuned for puzzles of
+    /// `difficulty`.
+    pub fn for_difficulty(&self, difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Medium => self.clone(),
+            Difficulty::Easy => Self {
+                max_words: Some(
+                    self.max_words
+                        .map_or(EASY_MAX_WORDS, |max| max.min(EASY_MAX_WORDS))
+                        .max(self.min_words),
+                ),
+                max_average_obscurity: Some(EASY_MAX_AVERAGE_OBSCURITY),
+                ..self.clone()
+            },
+            Difficulty::Hard => {
+                let cap = |min: usize| self.max_words.map_or(min, |max| min.min(max));
+                Self {
+                    min_words: self.min_words.max(cap(HARD_MIN_WORDS)),
+                    min_pangrams: self.min_pangrams.max(cap(HARD_MIN_PANGRAMS)),
+                    min_average_obscurity: Some(HARD_MIN_AVERAGE_OBSCURITY),
+                    ..self.clone()
+                }
+            }
+        }
+    }
+
+    /// Check whether the letters of a letter set suit a puzzle, before looking
+    /// up any of its words.
+    fn check_letters(&self, letters: words::Bitmask) -> Result<(), Rejection> {
+        let vowels = letters.letters().filter(|l| VOWELS.contains(l)).count();
+        if vowels < self.min_vowels {
+            Err(Rejection::TooFewVowels)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check whether a letter set requiring `required` could satisfy the
+    /// policy, judged from the counts of its words in the dictionary.
+    fn screen(&self, quality: &words::letterset::Quality, required: char) -> Result<(), Rejection> {
+        self.check_counts(quality.words_with(required), quality.pangrams)
+    }
+
+    fn check(&self, words: &[WordRow]) -> Result<(), Rejection> {
+        let pangrams = words.iter().filter(|w| w.is_pangram).count();
+        let four_letter_words = words.iter().filter(|w| w.word.chars().count() == 4).count();
+        self.check_counts(words.len(), pangrams)?;
+        if four_letter_words < self.min_four_letter_words {
+            return Err(Rejection::TooFewFourLetterWords);
+        }
+
+        let rated: Vec<_> = words.iter().filter_map(|w| w.obscurity).collect();
+        if rated.is_empty() {
+            return Ok(());
+        }
+        // Compared as totals so that an average of 2.5 is above a bound of 2.
+        let total: i64 = rated.iter().map(|o| i64::from(*o)).sum();
+        let bound = |obscurity: u8| i64::from(obscurity) * rated.len() as i64;
+        if self
+            .min_average_obscurity
+            .is_some_and(|min| total < bound(min))
+        {
+            Err(Rejection::TooCommon)
+        } else if self
+            .max_average_obscurity
+            .is_some_and(|max| total > bound(max))
+        {
+            Err(Rejection::TooObscure)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_counts(&self, words: usize, pangrams: usize) -> Result<(), Rejection> {
+        if words < self.min_words {
+            Err(Rejection::TooFewWords)
+        } else if self.max_words.is_some_and(|max| words > max) {
+            Err(Rejection::TooManyWords)
+        } else if pangrams < self.min_pangrams {
+            Err(Rejection::TooFewPangrams)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+/// Most words an easy puzzle has, however many the policy allows.
+const EASY_MAX_WORDS: usize = 30;
+const EASY_MAX_AVERAGE_OBSCURITY: u8 = 2;
+/// Fewest words and pangrams a hard puzzle has, unless the policy allows fewer
+/// words than that.
+const HARD_MIN_WORDS: usize = 40;
+const HARD_MIN_PANGRAMS: usize = 2;
+const HARD_MIN_AVERAGE_OBSCURITY: u8 = 2;
+
+/// Weight of the letters alphabets add to the latin ones, about as often as
+/// `ñ` appears in Spanish text.
+const EXTRA_LETTER_WEIGHT: u32 = 30;
+
+/// How often each letter appears in English text, in hundredths of a percent.
+const ENGLISH_LETTER_WEIGHTS: [u32; 26] = [
+    817, 149, 278, 425, 1270, 223, 202, 609, 697, 15, 77, 403, 241, 675, 751, 193, 10, 599, 633,
+    906, 276, 98, 236, 15, 197, 7,
+];
+
+/// How likely each letter of the alphabet is to be picked for a generated
+/// puzzle, relative to the others, both among the letter sets of pangrams and
+/// as the required letter. Letters weighing nothing are never picked.
+/// Defaults to the frequencies of letters in English, so that sets of q, x and
+/// z are rarely tried.
+///
+/// Weights are kept by the bit of their letter in masks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LetterWeights([u32; words::alphabet::LAYOUT_LETTERS]);
+
+impl Default for LetterWeights {
+    fn default() -> Self {
+        Self::for_alphabet(words::Alphabet::LATIN)
+    }
+}
+
+impl LetterWeights {
+    /// The default weights of the letters of `alphabet`, with other letters
+    /// weighing nothing.
+    pub fn for_alphabet(alphabet: words::Alphabet) -> Self {
+        let mut weights = [0; words::alphabet::LAYOUT_LETTERS];
+        for letter in alphabet.letters() {
+            let offset = words::alphabet::offset(*letter).expect("letters have a bit") as usize;
+            weights[offset] = ENGLISH_LETTER_WEIGHTS
+                .get(offset)
+                .copied()
+                .unwrap_or(EXTRA_LETTER_WEIGHT);
+        }
+        Self(weights)
+    }
+
+    fn weight_mut(&mut self, letter: char) -> Option<&mut u32> {
+        words::alphabet::offset(letter).map(|offset| &mut self.0[offset as usize])
+    }
+
+    /// These weights with some replaced by comma-separated `letter:weight`
+    /// pairs, or `None` if `pairs` can't be parsed.
+    fn with_overrides(mut self, pairs: &str) -> Option<Self> {
+        for pair in pairs.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (letter, weight) = pair.split_once(':')?;
+            let mut chars = letter.trim().chars();
+            let (Some(letter), None) = (chars.next(), chars.next()) else {
+                return None;
+            };
+            *self.weight_mut(letter)? = weight.trim().parse().ok()?;
+        }
+        Some(self)
+    }
+
+    /// How many letters can be picked.
+    fn letters(&self) -> usize {
+        self.0.iter().filter(|w| **w > 0).count()
+    }
+
+    /// These weights with every letter outside of `mask` weighing nothing.
+    fn within(mut self, mask: words::Bitmask) -> Self {
+        for (offset, weight) in self.0.iter_mut().enumerate() {
+            if mask.bits() & (1 << offset) == 0 {
+                *weight = 0;
+            }
+        }
+        self
+    }
+
+    /// How likely the letters of `mask` are to be picked together, or zero if
+    /// any of them can't be picked.
+    fn of(&self, mask: words::Bitmask) -> f64 {
+        mask.letters()
+            .map(|letter| {
+                let offset = words::alphabet::offset(letter).expect("letters have a bit");
+                f64::from(self.0[offset as usize])
+            })
+            .product()
+    }
+
+    /// Letter sets in a random order, the sets more likely by [`Self::of`]
+    /// tending to come first. Sets that can't be picked are left out.
+    fn shuffle(
+        &self,
+        rng: &mut impl Rng,
+        masks: impl Iterator<Item = words::Bitmask>,
+    ) -> Vec<words::Bitmask> {
+        let mut masks: Vec<_> = masks.collect();
+        // Sorted first, so that the order only depends on the seed of `rng` and
+        // not on where the sets came from.
+        masks.sort();
+        // Weighted sampling without replacement by Efraimidis and Spirakis: each
+        // set is keyed by a random number raised to the inverse of its weight.
+        let mut keyed: Vec<_> = masks
+            .into_iter()
+            .map(|mask| (mask, self.of(mask)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|(mask, weight)| (rng.random::<f64>().ln() / weight, mask))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        keyed.into_iter().map(|(_, mask)| mask).collect()
+    }
+
+    /// Pick up to `count` distinct letters, each with a chance proportional to
+    /// its weight among the letters not picked yet.
+    fn sample(&self, rng: &mut impl Rng, count: usize) -> Vec<char> {
+        let mut weights = self.0;
+        let mut letters = Vec::with_capacity(count);
+        while letters.len() < count {
+            let total: u32 = weights.iter().sum();
+            if total == 0 {
+                break;
+            }
+            let mut pick = rng.random_range(0..total);
+            let index = weights
+                .iter()
+                .position(|w| {
+                    if pick < *w {
+                        true
+                    } else {
+                        pick -= w;
+                        false
+                    }
+                })
+                .expect("the pick is below the total weight");
+            weights[index] = 0;
+            letters.push(
+                words::alphabet::letter_at(index as u32).expect("weighted letters have a bit"),
+            );
+        }
+        letters
+    }
+}
+
+/// Why a generated letter set wasn't used for a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rejection {
+    TooFewWords,
+    TooManyWords,
+    TooFewPangrams,
+    TooFewFourLetterWords,
+    TooFewVowels,
+    TooCommon,
+    TooObscure,
+    /// Acceptable, but kept only as a fallback while looking for a puzzle with a
+    /// perfect pangram.
+    NoPerfectPangram,
+}
+
+impl Rejection {
+    /// Explanation shown to curators previewing a letter set.
+    fn message(self) -> &'static str {
+        match self {
+            Self::TooFewWords => "The puzzle has fewer words than the generation policy allows",
+            Self::TooManyWords => "The puzzle has more words than the generation policy allows",
+            Self::TooFewPangrams => {
+                "The puzzle has fewer pangrams than the generation policy requires"
+            }
+            Self::TooFewFourLetterWords => {
+                "The puzzle has fewer four letter words than the generation policy requires"
+            }
+            Self::TooFewVowels => "The puzzle has fewer vowels than the generation policy requires",
+            Self::TooCommon => {
+                "The puzzle's words are more common than the generation policy allows"
+            }
+            Self::TooObscure => {
+                "The puzzle's words are more obscure than the generation policy allows"
+            }
+            Self::NoPerfectPangram => "The puzzle has no perfect pangram",
+        }
+    }
+
+    fn record(self) {
+        let reason = match self {
+            Self::TooFewWords => "too_few_words",
+            Self::TooManyWords => "too_many_words",
+            Self::TooFewPangrams => "too_few_pangrams",
+            Self::TooFewFourLetterWords => "too_few_four_letter_words",
+            Self::TooFewVowels => "too_few_vowels",
+            Self::TooCommon => "too_common",
+            Self::TooObscure => "too_obscure",
+            Self::NoPerfectPangram => "no_perfect_pangram",
+        };
+        crate::metrics::increment(
+            "bee_puzzle_generation_rejections_total",
+            &[("reason", reason)],
+            1,
+        );
+    }
+}
+
+/// The cache key of a day's puzzle, which is also what replicas lock on while
+/// generating it.
+fn daily_key(
+    date: NaiveDate,
+    offset: FixedOffset,
+    difficulty: Difficulty,
+    dictionary: DictionaryId,
+) -> String {
+    format!(
+        "puzzle/daily/{}/{}/{}/{}",
+        offset,
+        date,
+        difficulty.as_str(),
+        dictionary
+    )
+}
+
+/// Begin a transaction holding the advisory lock on `key` until it ends.
+async fn lock_puzzle(
+    pool: &sqlx::PgPool,
+    key: &str,
+) -> Result<sqlx::Transaction<'static, sqlx::Postgres>, Error> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| Error::DbError(Box::new(e)))?;
+    sqlx::query!(
+        "select 1 as locked from pg_advisory_xact_lock(hashtext($1))",
+        key
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| Error::DbError(Box::new(e)))?;
+    Ok(tx)
+}
+
+/// Count a daily puzzle found in the cache, the puzzles table or neither,
+/// where `source` says which.
+fn record_lookup(source: &'static str) {
+    crate::metrics::increment("bee_puzzle_lookups_total", &[("source", source)], 1);
+}
+
+/// What a single puzzle generation took, recorded once it finishes.
+#[derive(Debug, Default)]
+struct GenerationStats {
+    attempts: usize,
+    words_fetched: usize,
+}
+
+impl GenerationStats {
+    fn record(&self, result: &Result<PuzzleConfig, Error>, elapsed: std::time::Duration) {
+        let outcome = match result {
+            Ok(_) => "generated",
+            Err(Error::NoAcceptablePuzzle(_)) => "no_acceptable_puzzle",
+            Err(Error::UnhealthyDictionary(_)) => "unhealthy_dictionary",
+            Err(_) => "error",
+        };
+        let span = tracing::Span::current();
+        span.record("attempts", self.attempts);
+        span.record("words_fetched", self.words_fetched);
+        tracing::info!(
+            outcome,
+            elapsed_ms = elapsed.as_millis() as u64,
+            "puzzle generation finished"
+        );
+
+        crate::metrics::increment("bee_puzzle_generations_total", &[("outcome", outcome)], 1);
+        crate::metrics::increment(
+            "bee_puzzle_generation_words_fetched_total",
+            &[],
+            self.words_fetched as u64,
+        );
+        crate::metrics::observe(
+            "bee_puzzle_generation_attempts",
+            &[],
+            &[1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0],
+            self.attempts as f64,
+        );
+        crate::metrics::observe(
+            "bee_puzzle_generation_seconds",
+            &[],
+            &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0],
+            elapsed.as_secs_f64(),
+        );
+    }
+}
+
+fn read_var(name: &'static str) -> Result<Option<usize>, PolicyError> {
+    match dotenvy::var(name) {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| PolicyError::InvalidValue(name, value)),
+        Err(_) => Ok(None),
+    }
+}
+
+#[derive(Debug)]
+pub enum PolicyError {
+    InvalidValue(&'static str, String),
+    InvalidLetterWeights(String),
+    /// `PUZZLE_ALPHABET` isn't a supported alphabet, with why.
+    InvalidAlphabet(String),
+    Unsatisfiable(Box<PuzzleGenerationPolicy>),
+}
+
+impl std::fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidValue(name, value) => {
+                write!(
+                    f,
+                    "{} must be a non-negative integer, got {:?}",
+                    name, value
+                )
+            }
+            Self::InvalidLetterWeights(value) => write!(
+                f,
+                "PUZZLE_LETTER_WEIGHTS must be comma-separated letter:weight pairs leaving at least {} letters a weight, got {:?}",
+                PUZZLE_LETTERS, value
+            ),
+            Self::InvalidAlphabet(e) => write!(f, "PUZZLE_ALPHABET: {}", e),
+            Self::Unsatisfiable(policy) => write!(
+                f,
+                "PUZZLE_MAX_WORDS is lower than one of the minimums, or PUZZLE_MIN_VOWELS is more than {}, in {:?}",
+                VOWELS.len(),
+                policy
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+/// `config` with only the words `dictionary` allows, ranked by the score they
+/// add up to.
+pub(crate) fn restrict_to(mut config: PuzzleConfig, dictionary: Dictionary) -> PuzzleConfig {
+    if dictionary != Dictionary::Permissive {
+        config
+            .valid_words
+            .retain(|word| dictionary.allows(word.obscurity));
+        config.score_buckets = config.scoring.score_buckets(&config.valid_words);
+    }
+    config
+}
+
+/// The words puzzles are generated from.
+trait WordSource {
+    /// Count of words by letter mask.
+    async fn dictionary(&mut self) -> Result<words::letterset::Dictionary, Error>;
+
+    /// Every word spelled only with the letters in `letter_mask` and the
+    /// required letter, which it must contain, apart from those `morphology`
+    /// excludes.
+    async fn words(
+        &mut self,
+        morphology: &MorphologyPolicy,
+        required_mask: words::Bitmask,
+        letter_mask: words::Bitmask,
+    ) -> Result<Vec<WordRow>, Error>;
+}
+
+/// The words of one dictionary in the words table.
+struct WordsTable<'c> {
+    conn: &'c mut sqlx::PgConnection,
+    dictionary: DictionaryId,
+}
+
+impl WordSource for WordsTable<'_> {
+    async fn dictionary(&mut self) -> Result<words::letterset::Dictionary, Error> {
+        load_dictionary(self.conn, self.dictionary).await
+    }
+
+    async fn words(
+        &mut self,
+        morphology: &MorphologyPolicy,
+        required_mask: words::Bitmask,
+        letter_mask: words::Bitmask,
+    ) -> Result<Vec<WordRow>, Error> {
+        fetch_words(
+            self.conn,
+            self.dictionary,
+            morphology,
+            required_mask,
+            letter_mask,
+        )
+        .await
+    }
+}
+
+impl WordSource for &WordIndex {
+    async fn dictionary(&mut self) -> Result<words::letterset::Dictionary, Error> {
+        Ok(WordIndex::dictionary(self).clone())
+    }
+
+    async fn words(
+        &mut self,
+        morphology: &MorphologyPolicy,
+        required_mask: words::Bitmask,
+        letter_mask: words::Bitmask,
+    ) -> Result<Vec<WordRow>, Error> {
+        let all = required_mask | letter_mask;
+        Ok(self
+            .spelled_with(required_mask, letter_mask)
+            .filter(|(word, _)| {
+                morphology
+                    .exclusion(word, |stem| self.contains(stem))
+                    .is_none()
+            })
+            .map(|(word, mask)| WordRow {
+                word: word.to_owned(),
+                is_pangram: words::is_pangram_for(mask, all),
+                definition: None,
+                obscurity: None,
+            })
+            .collect())
+    }
+}
+
+/// The words of one dictionary in a SQLite word database, which has no flags.
+#[cfg(feature = "sqlite")]
+struct SqliteWords<'c> {
+    conn: &'c mut sqlx::SqliteConnection,
+    dictionary: DictionaryId,
+}
+
+#[cfg(feature = "sqlite")]
+impl WordSource for SqliteWords<'_> {
+    async fn dictionary(&mut self) -> Result<words::letterset::Dictionary, Error> {
+        let rows: Vec<(i64, i64)> = sqlx::query_as(
+            "select letter_mask, count(*)
+            from words
+            where dictionary_id = ? and deleted_at is null
+            group by letter_mask",
+        )
+        .bind(self.dictionary.0)
+        .fetch_all(&mut *self.conn)
+        .await
+        .map_err(|e| Error::DbError(Box::new(e)))?;
+
+        let mut dictionary = words::letterset::Dictionary::new();
+        for (letter_mask, count) in rows {
+            match words::Bitmask::try_from(letter_mask) {
+                Ok(mask) => dictionary.insert(mask, count as usize),
+                Err(e) => tracing::warn!(error = %e, "skipping words with an invalid letter mask"),
+            }
+        }
+        Ok(dictionary)
+    }
+
+    async fn words(
+        &mut self,
+        morphology: &MorphologyPolicy,
+        required_mask: words::Bitmask,
+        letter_mask: words::Bitmask,
+    ) -> Result<Vec<WordRow>, Error> {
+        let all = letter_mask | required_mask;
+        let rows: Vec<(String, i64, Option<String>, Option<i16>)> = sqlx::query_as(
+            "select word, letter_mask, definition, obscurity
+            from words
+            where dictionary_id = ?1
+            and letter_mask & ?2 = ?2
+            and letter_mask | ?3 = ?3
+            and deleted_at is null",
+        )
+        .bind(self.dictionary.0)
+        .bind(i64::from(required_mask))
+        .bind(i64::from(all))
+        .fetch_all(&mut *self.conn)
+        .await
+        .map_err(|e| Error::DbError(Box::new(e)))?;
+        let words: Vec<WordRow> = rows
+            .into_iter()
+            .map(|(word, mask, definition, obscurity)| WordRow {
+                is_pangram: words::Bitmask::try_from(mask)
+                    .is_ok_and(|mask| words::is_pangram_for(mask, all)),
+                word,
+                definition,
+                obscurity,
+            })
+            .collect();
+
+        if !morphology.is_active() {
+            return Ok(words);
+        }
+        // As with the words table, stems are looked up in the whole dictionary.
+        let stems: Vec<String> = words
+            .iter()
+            .flat_map(|w| morphology.stems(&w.word))
+            .map(|(_, stem)| stem)
+            .collect();
+        let known: HashSet<String> = sqlx::query_scalar(
+            "select word from words
+            where dictionary_id = ? and deleted_at is null
+            and word in (select value from json_each(?))",
+        )
+        .bind(self.dictionary.0)
+        .bind(serde_json::to_string(&stems).expect("Failed to serialize stems"))
+        .fetch_all(&mut *self.conn)
+        .await
+        .map_err(|e| Error::DbError(Box::new(e)))?
+        .into_iter()
+        .collect();
+        Ok(words
+            .into_iter()
+            .filter(|w| {
+                morphology
+                    .exclusion(&w.word, |stem| known.contains(stem))
+                    .is_none()
+            })
+            .collect())
+    }
+}
+
+/// Index every word of `dictionary` by its letter mask, leaving out removed
+/// and flagged words.
+async fn load_dictionary(
+    conn: &mut sqlx::PgConnection,
+    dictionary: DictionaryId,
+) -> Result<words::letterset::Dictionary, Error> {
+    let rows = sqlx::query!(
+        r#"select letter_mask, count(*) as "count!"
+        from words
+        where dictionary_id = $1 and deleted_at is null and flagged_at is null
+        group by letter_mask"#,
+        dictionary.0,
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(|e| Error::DbError(Box::new(e)))?;
+
+    let mut dictionary = words::letterset::Dictionary::new();
+    for row in rows {
+        match words::Bitmask::try_from(row.letter_mask) {
+            Ok(mask) => dictionary.insert(mask, row.count as usize),
+            Err(e) => tracing::warn!(error = %e, "skipping words with an invalid letter mask"),
+        }
+    }
+    Ok(dictionary)
+}
+
+/// Every word of `dictionary` spelled only with the letters in `letter_mask`
+/// and the required letter, which it must contain, apart from removed and
+/// flagged words and those `morphology` excludes.
+async fn fetch_words(
+    conn: &mut sqlx::PgConnection,
+    dictionary: DictionaryId,
+    morphology: &MorphologyPolicy,
+    required_mask: words::Bitmask,
+    letter_mask: words::Bitmask,
+) -> Result<Vec<WordRow>, Error> {
+    let all = letter_mask | required_mask;
+    let rows = sqlx::query!(
+        "select word, letter_mask, definition, obscurity
+        from words
+        where dictionary_id = $1
+        and letter_mask & $2 = $2
+        and letter_mask | $3 = $3
+        and deleted_at is null
+        and flagged_at is null",
+        dictionary.0,
+        i64::from(required_mask),
+        i64::from(all),
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| Error::DbError(Box::new(e)))?;
+    let words: Vec<WordRow> = rows
+        .into_iter()
+        .map(|row| WordRow {
+            is_pangram: words::Bitmask::try_from(row.letter_mask)
+                .is_ok_and(|mask| words::is_pangram_for(mask, all)),
+            word: row.word,
+            definition: row.definition,
+            obscurity: row.obscurity,
+        })
+        .collect();
+
+    if !morphology.is_active() {
+        return Ok(words);
+    }
+    // Stems are looked up in the whole dictionary since they may not contain
+    // the required letter.
+    let stems: Vec<String> = words
+        .iter()
+        .flat_map(|w| morphology.stems(&w.word))
+        .map(|(_, stem)| stem)
+        .collect();
+    let known: HashSet<String> = sqlx::query_scalar!(
+        "select word from words
+        where dictionary_id = $1 and word = any($2) and deleted_at is null",
+        dictionary.0,
+        &stems
+    )
+    .fetch_all(conn)
+    .await
+    .map_err(|e| Error::DbError(Box::new(e)))?
+    .into_iter()
+    .collect();
+    Ok(words
+        .into_iter()
+        .filter(|w| {
+            morphology
+                .exclusion(&w.word, |stem| known.contains(stem))
+                .is_none()
+        })
+        .collect())
+}
+
+fn build_config(
+    words: Vec<WordRow>,
+    required_mask: words::Bitmask,
+    letter_mask: words::Bitmask,
+    policy: &PuzzleGenerationPolicy,
+) -> PuzzleConfig {
+    let valid_words: HashSet<_> = words.into_iter().map(WordRow::into_word).collect();
+    PuzzleConfig {
+        score_buckets: policy.scoring.score_buckets(&valid_words),
+        scoring: policy.scoring.clone(),
+        alphabet: policy.alphabet,
+        valid_words,
+        required_letter: Letter::new(words::letters::from_bitmask(&required_mask)),
+        other_letters: policy
+            .alphabet
+            .letters_of(letter_mask)
+            .into_iter()
+            .map(Letter::new)
+            .collect(),
+    }
+}
+
+/// Word list of the demo puzzle, which is served without touching the database.
+const DEMO_WORDS: &str = include_str!("../data/demo-words.txt");
+const DEMO_REQUIRED_LETTER: char = 'o';
+const DEMO_OTHER_LETTERS: [char; 6] = ['d', 'e', 'n', 'p', 'r', 't'];
+
+/// The fixed demo puzzle, built from the bundled word list the same way
+/// puzzles are built from the words table.
+pub fn demo_config() -> &'static PuzzleConfig {
+    static DEMO: LazyLock<PuzzleConfig> = LazyLock::new(|| {
+        let required_mask = words::letters::bitmask(&DEMO_REQUIRED_LETTER);
+        let letter_mask: words::Bitmask = DEMO_OTHER_LETTERS.into_iter().collect();
+        build_config(
+            filter_words(DEMO_WORDS.lines(), required_mask, letter_mask),
+            required_mask,
+            letter_mask,
+            &PuzzleGenerationPolicy::default(),
+        )
+    });
+    &DEMO
+}
+
+/// The words of `list` that [`fetch_words`] would select from the words table.
+fn filter_words<'a>(
+    list: impl Iterator<Item = &'a str>,
+    required_mask: words::Bitmask,
+    letter_mask: words::Bitmask,
+) -> Vec<WordRow> {
+    let all = letter_mask | required_mask;
+    list.map(str::trim)
+        .filter(|word| word.len() >= 4)
+        .filter_map(|word| Some((word, words::try_bitmask(word).ok()?)))
+        .filter(|(_, mask)| words::is_subset(required_mask, *mask) && words::is_subset(*mask, all))
+        .map(|(word, mask)| WordRow {
+            word: word.to_owned(),
+            is_pangram: words::is_pangram_for(mask, all),
+            definition: None,
+            obscurity: None,
+        })
+        .collect()
+}
+
 #[derive(sqlx::FromRow, Debug)]
 struct WordRow {
     word: String,
     is_pangram: bool,
+    definition: Option<String>,
+    obscurity: Option<i16>,
+}
+
+impl WordRow {
+    fn into_word(self) -> Word {
+        Word {
+            definition: self.definition,
+            obscurity: self.obscurity.and_then(|o| u8::try_from(o).ok()),
+            ..Word::new(&self.word, self.is_pangram)
+        }
+    }
 }
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     DbError(Box<dyn std::error::Error>),
+    Unavailable(NaiveDate),
+    NoAcceptablePuzzle(Box<PuzzleGenerationPolicy>),
+    /// The dictionary can't make any puzzle satisfying the policy.
+    UnhealthyDictionary(Vec<String>),
 }
 
 impl std::fmt::Display for Error {
@@ -174,26 +1727,247 @@ impl std::fmt::Display for Error {
             Self::DbError(cause) => {
                 write!(f, "Failed to load puzzle config from database: {}", cause)
             }
+            Self::Unavailable(date) => write!(f, "No puzzle is available for {}", date),
+            Self::NoAcceptablePuzzle(policy) => write!(
+                f,
+                "No letter set satisfying {:?} was found after {} attempts",
+                policy, MAX_GENERATION_ATTEMPTS
+            ),
+            Self::UnhealthyDictionary(problems) => write!(
+                f,
+                "The dictionary can't support puzzle generation: {}",
+                problems.join("; ")
+            ),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
-// TODO: make this timezone aware using browser TZ
-fn next_midnight<Tz: chrono::TimeZone>(now: &DateTime<Tz>) -> DateTime<Tz> {
-    (now.clone() + Duration::hours(24))
-        .with_hour(0)
-        .unwrap()
-        .with_minute(0)
-        .unwrap()
-        .with_second(0)
-        .unwrap()
-        .with_nanosecond(0)
-        .unwrap()
-}
-
-// TODO: make this timezone aware using browser TZ
-fn day_64() -> u64 {
-    Utc::now().timestamp() as u64
+/// Seed for the random letter selection of a single day's puzzle.
+///
+/// Derived only from the puzzle date and timezone so that every request for the
+/// same day in the same timezone generates the same puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PuzzleSeed(u64);
+
+impl PuzzleSeed {
+    pub fn new(date: NaiveDate, tz: &FixedOffset, difficulty: Difficulty) -> Self {
+        let days = date.num_days_from_ce() as u64;
+        let offset = tz.local_minus_utc() as i64 as u64;
+        let seed = splitmix64(splitmix64(days) ^ offset);
+        // Medium puzzles keep the seeds puzzles had before there were
+        // difficulties.
+        match difficulty {
+            Difficulty::Medium => Self(seed),
+            Difficulty::Easy => Self(splitmix64(seed ^ 1)),
+            Difficulty::Hard => Self(splitmix64(seed ^ 2)),
+        }
+    }
+}
+
+// A fixed mixing function rather than `std::hash`, whose output is not
+// guaranteed to be stable across Rust releases.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use puzzle_config::Difficulty;
+
+    use super::{LetterWeights, PuzzleGenerationPolicy, WordRow, demo_config, dictionary_health};
+
+    #[test]
+    fn reports_dictionaries_that_cant_make_puzzles() {
+        let policy = PuzzleGenerationPolicy {
+            min_words: 3,
+            ..Default::default()
+        };
+        let dictionary = |list: &[&str]| list.iter().map(|w| words::bitmask(w)).collect();
+
+        let health = dictionary_health(&dictionary(&[]), &policy);
+        assert_eq!(
+            vec!["The dictionary has no words".to_owned()],
+            health.problems
+        );
+
+        let health = dictionary_health(&dictionary(&["bead", "dance", "cane"]), &policy);
+        assert_eq!(0, health.letter_sets);
+        assert!(!health.is_healthy());
+
+        let health = dictionary_health(&dictionary(&["cabined", "bead", "zebra"]), &policy);
+        assert_eq!((1, 1), (health.letter_sets, health.degenerate_letter_sets));
+        assert!(!health.is_healthy());
+
+        let health = dictionary_health(
+            &dictionary(&["cabined", "abidance", "bead", "dance"]),
+            &policy,
+        );
+        assert_eq!(0, health.degenerate_letter_sets);
+        assert_eq!(('a', 2), health.pangram_words_by_letter[0]);
+        assert_eq!(('z', 0), health.pangram_words_by_letter[25]);
+        assert!(health.is_healthy());
+    }
+
+    #[test]
+    fn picks_distinct_letters_by_weight() {
+        let weights = LetterWeights([0; words::alphabet::LAYOUT_LETTERS])
+            .with_overrides("a:1, b:1,c:1,d:5,e:1,i:1,n:1")
+            .unwrap();
+        assert_eq!(7, weights.letters());
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let mut letters = weights.sample(&mut rng, 7);
+            letters.sort();
+            assert_eq!(vec!['a', 'b', 'c', 'd', 'e', 'i', 'n'], letters);
+        }
+        assert_eq!(
+            6,
+            weights
+                .with_overrides("n:0")
+                .unwrap()
+                .sample(&mut rng, 7)
+                .len()
+        );
+
+        for invalid in ["a", "a:-1", "ab:1", "A:1", "a:1;b:2"] {
+            assert_eq!(None, weights.with_overrides(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn weighs_the_letters_of_the_alphabet() {
+        let spanish = words::Alphabet::SPANISH;
+        let weights = LetterWeights::for_alphabet(spanish);
+        assert_eq!(27, weights.letters());
+        assert_eq!(26, LetterWeights::default().letters());
+        assert_eq!(26, weights.with_overrides("ñ:0").unwrap().letters());
+
+        let pangram = spanish.try_bitmask("pañuelo").unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut letters = weights.within(pangram).sample(&mut rng, 7);
+        letters.sort();
+        assert_eq!(vec!['a', 'e', 'l', 'o', 'p', 'u', 'ñ'], letters);
+
+        let policy = PuzzleGenerationPolicy {
+            alphabet: spanish,
+            letter_weights: weights,
+            min_words: 1,
+            ..Default::default()
+        };
+        let health = dictionary_health(&[pangram].into_iter().collect(), &policy);
+        assert_eq!(27, health.pangram_words_by_letter.len());
+        assert_eq!(('ñ', 1), health.pangram_words_by_letter[14]);
+        assert_eq!(1, health.letter_sets);
+    }
+
+    #[test]
+    fn shuffles_pangram_letter_sets_by_weight() {
+        let weights = LetterWeights::default().with_overrides("q:0").unwrap();
+        let masks = ["cabined", "pangram", "quickly", "zealots"].map(words::bitmask);
+        let shuffle = |seed| {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            weights.shuffle(&mut rng, masks.into_iter())
+        };
+
+        let shuffled = shuffle(7);
+        assert_eq!(shuffled, shuffle(7));
+        assert_eq!(3, shuffled.len());
+        assert!(!shuffled.contains(&words::bitmask("quickly")));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut required = weights
+            .within(words::bitmask("cabined"))
+            .sample(&mut rng, 7);
+        required.sort();
+        assert_eq!(vec!['a', 'b', 'c', 'd', 'e', 'i', 'n'], required);
+    }
+
+    #[test]
+    fn tunes_the_policy_for_each_difficulty() {
+        let policy = PuzzleGenerationPolicy::default();
+        assert_eq!(policy, policy.for_difficulty(Difficulty::Medium));
+
+        let easy = policy.for_difficulty(Difficulty::Easy);
+        assert_eq!(Some(30), easy.max_words);
+        assert_eq!(Some(2), easy.max_average_obscurity);
+
+        let hard = policy.for_difficulty(Difficulty::Hard);
+        assert_eq!((40, 2), (hard.min_words, hard.min_pangrams));
+        assert_eq!(Some(2), hard.min_average_obscurity);
+
+        // Tuning never makes a satisfiable policy unsatisfiable.
+        let narrow = PuzzleGenerationPolicy {
+            min_words: 35,
+            max_words: Some(35),
+            ..policy
+        };
+        assert_eq!(Some(35), narrow.for_difficulty(Difficulty::Easy).max_words);
+        assert_eq!(35, narrow.for_difficulty(Difficulty::Hard).min_words);
+    }
+
+    #[test]
+    fn checks_the_average_obscurity_of_rated_words() {
+        let word = |word: &str, obscurity| WordRow {
+            word: word.to_owned(),
+            is_pangram: false,
+            definition: None,
+            obscurity,
+        };
+        let policy = PuzzleGenerationPolicy {
+            min_words: 0,
+            min_pangrams: 0,
+            ..PuzzleGenerationPolicy::default()
+        };
+        let easy = policy.for_difficulty(Difficulty::Easy);
+        let hard = policy.for_difficulty(Difficulty::Hard);
+        let hard = PuzzleGenerationPolicy {
+            min_words: 0,
+            min_pangrams: 0,
+            ..hard
+        };
+
+        let common = [
+            word("bead", Some(1)),
+            word("bade", Some(2)),
+            word("abed", None),
+        ];
+        assert!(easy.check(&common).is_ok());
+        assert!(hard.check(&common).is_err());
+
+        let obscure = [word("bead", Some(2)), word("bade", Some(4))];
+        assert!(easy.check(&obscure).is_err());
+        assert!(hard.check(&obscure).is_ok());
+
+        let unrated = [word("bead", None)];
+        assert!(easy.check(&unrated).is_ok());
+        assert!(hard.check(&unrated).is_ok());
+    }
+
+    #[test]
+    fn checks_letter_sets_have_enough_vowels() {
+        let policy = PuzzleGenerationPolicy::default();
+        assert!(policy.check_letters(words::bitmask("cabined")).is_ok());
+        assert!(policy.check_letters(words::bitmask("lynchpt")).is_err());
+        assert!(policy.check_letters(words::bitmask("bcdfgha")).is_err());
+    }
+
+    #[test]
+    fn demo_puzzle_has_a_pangram() {
+        let demo = demo_config();
+        assert_eq!('o', demo.required_letter.0);
+        assert_eq!(55, demo.valid_words.len());
+        assert!(
+            demo.valid_words
+                .iter()
+                .any(|w| w.word == "portend" && w.is_pangram)
+        );
+        assert!(demo.valid_words.iter().all(|w| w.word.contains('o')));
+    }
 }
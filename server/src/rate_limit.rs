@@ -0,0 +1,45 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::cache::CacheBackend as _;
+
+/// Fixed-window request counter keyed by client address. Requests are counted
+/// in the cache backend, so replicas sharing a Redis cache share the limit.
+#[derive(Clone)]
+pub(crate) struct RateLimiter {
+    name: &'static str,
+    limit: u32,
+    window: Duration,
+    cache: crate::cache::Backend,
+}
+
+impl RateLimiter {
+    /// Allow `limit` requests per `window` from each client. Limiters with
+    /// different `name`s count separately.
+    pub(crate) fn new(
+        name: &'static str,
+        limit: u32,
+        window: Duration,
+        cache: crate::cache::Backend,
+    ) -> Self {
+        Self {
+            name,
+            limit,
+            window,
+            cache,
+        }
+    }
+
+    /// Record a request from `client`, returning whether it is within the limit.
+    /// Requests the cache fails to count are let through.
+    pub(crate) async fn check(&self, client: IpAddr) -> bool {
+        let key = format!("rate_limit:{}:{}", self.name, client);
+        match self.cache.incr(&key, self.window).await {
+            Ok(count) => count <= u64::from(self.limit),
+            Err(e) => {
+                tracing::warn!(error = %e, key, "failed to count request");
+                true
+            }
+        }
+    }
+}
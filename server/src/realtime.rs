@@ -0,0 +1,85 @@
+//! Rooms where players of the daily puzzle play together live, each player
+//! connected over a WebSocket: head-to-head races in [`race`] and cooperative
+//! teams in [`team`]. Rooms live in memory only and disappear once empty.
+
+pub(crate) mod race;
+pub(crate) mod team;
+
+use axum::{
+    extract::ws::{Message, Utf8Bytes, WebSocket, WebSocketUpgrade},
+    http::StatusCode,
+    response::Response,
+};
+use tracing::Instrument as _;
+
+use crate::responses::AppError;
+
+/// Largest message read from a player. Rooms only exchange short JSON messages.
+const MAX_MESSAGE_SIZE: usize = 4096;
+
+const MAX_ROOM_NAME_LEN: usize = 32;
+
+fn check_room_name(room: &str) -> Result<(), AppError> {
+    let is_valid = !room.is_empty()
+        && room.len() <= MAX_ROOM_NAME_LEN
+        && room.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if is_valid {
+        Ok(())
+    } else {
+        Err(AppError::Status(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Room names are up to {} letters, digits and dashes",
+                MAX_ROOM_NAME_LEN
+            ),
+        ))
+    }
+}
+
+/// Switch protocols, handing the connection to `play` once upgraded.
+fn accept<F, Fut>(upgrade: WebSocketUpgrade, play: F) -> Response
+where
+    F: FnOnce(Socket) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), axum::Error>> + Send + 'static,
+{
+    let span = tracing::Span::current();
+    upgrade
+        .max_message_size(MAX_MESSAGE_SIZE)
+        .on_failed_upgrade(|e| tracing::warn!(error = %e, "failed to upgrade room connection"))
+        .on_upgrade(move |socket| {
+            async move {
+                if let Err(e) = play(Socket(socket)).await {
+                    tracing::debug!(error = %e, "room connection closed");
+                }
+            }
+            .instrument(span)
+        })
+}
+
+/// A player's upgraded connection.
+struct Socket(WebSocket);
+
+/// What a message from the player amounts to. Pings are answered, and closes
+/// acknowledged, by the connection itself.
+enum Received {
+    Text(Utf8Bytes),
+    Nothing,
+    Closed,
+}
+
+impl Socket {
+    /// The next message from the player, safe to race against other events.
+    async fn receive(&mut self) -> Result<Received, axum::Error> {
+        match self.0.recv().await.transpose()? {
+            Some(Message::Text(text)) => Ok(Received::Text(text)),
+            // The rooms never send binary messages, so none are expected back.
+            Some(Message::Binary(_) | Message::Ping(_) | Message::Pong(_)) => Ok(Received::Nothing),
+            Some(Message::Close(_)) | None => Ok(Received::Closed),
+        }
+    }
+
+    async fn send(&mut self, message: &impl serde::Serialize) -> Result<(), axum::Error> {
+        let text = serde_json::to_string(message).expect("room messages serialize");
+        self.0.send(Message::text(text)).await
+    }
+}
@@ -0,0 +1,147 @@
+//! Head-to-head rooms, where two players race on the same daily puzzle.
+//!
+//! Each player sends their [`RaceProgress`] as they find words, which the room
+//! relays to the other player as an [`OpponentEvent`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::{
+    extract::{Path, State, ws::WebSocketUpgrade},
+    response::Response,
+};
+use dashmap::DashMap;
+use puzzle_config::{OpponentEvent, RaceProgress};
+use tokio::sync::broadcast;
+
+use super::{Received, Socket};
+use crate::responses::AppError;
+
+const MAX_PLAYERS: usize = 2;
+/// Events a player can fall behind on before missing some. Progress is sent
+/// whole, so the next one makes up for any missed.
+const EVENT_BUFFER: usize = 16;
+
+#[derive(Clone, Default)]
+pub(crate) struct Rooms {
+    rooms: Arc<DashMap<String, Room>>,
+    next_player: Arc<AtomicU64>,
+}
+
+struct Room {
+    /// The last progress of each player in the room.
+    players: HashMap<u64, RaceProgress>,
+    events: broadcast::Sender<(u64, OpponentEvent)>,
+}
+
+/// A player's place in a room, given up when dropped.
+struct Seat {
+    rooms: Rooms,
+    room: String,
+    player: u64,
+    events: broadcast::Receiver<(u64, OpponentEvent)>,
+}
+
+impl Rooms {
+    /// Take a seat in `room` along with what's known of the player already
+    /// there, or `None` when the room is full.
+    fn join(&self, room: &str) -> Option<(Seat, Vec<OpponentEvent>)> {
+        let player = self.next_player.fetch_add(1, Ordering::Relaxed);
+        let mut entry = self.rooms.entry(room.to_owned()).or_insert_with(|| Room {
+            players: HashMap::new(),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        });
+        if entry.players.len() >= MAX_PLAYERS {
+            return None;
+        }
+
+        let opponents = entry
+            .players
+            .values()
+            .flat_map(|progress| [OpponentEvent::Joined, OpponentEvent::Progress(*progress)])
+            .collect();
+        entry.players.insert(player, RaceProgress::default());
+        let events = entry.events.subscribe();
+        let _ = entry.events.send((player, OpponentEvent::Joined));
+        Some((
+            Seat {
+                rooms: self.clone(),
+                room: room.to_owned(),
+                player,
+                events,
+            },
+            opponents,
+        ))
+    }
+}
+
+impl Seat {
+    fn report(&self, progress: RaceProgress) {
+        if let Some(mut room) = self.rooms.rooms.get_mut(&self.room) {
+            room.players.insert(self.player, progress);
+            let _ = room
+                .events
+                .send((self.player, OpponentEvent::Progress(progress)));
+        }
+    }
+}
+
+impl Drop for Seat {
+    fn drop(&mut self) {
+        if let Some(mut room) = self.rooms.rooms.get_mut(&self.room) {
+            room.players.remove(&self.player);
+            let _ = room.events.send((self.player, OpponentEvent::Left));
+        }
+        self.rooms
+            .rooms
+            .remove_if(&self.room, |_, room| room.players.is_empty());
+    }
+}
+
+/// Join `room` by upgrading the request to a WebSocket, unless the room
+/// already has two players.
+pub(crate) async fn join_room(
+    State(rooms): State<Rooms>,
+    Path(room): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    super::check_room_name(&room)?;
+    let (seat, opponents) = rooms.join(&room).ok_or_else(|| {
+        AppError::Conflict(format!("Room {} already has {} players", room, MAX_PLAYERS))
+    })?;
+    Ok(super::accept(upgrade, move |socket| {
+        race(socket, seat, opponents)
+    }))
+}
+
+/// Send the player what's known of their opponent, then pass progress both
+/// ways until either leaves.
+async fn race(
+    mut socket: Socket,
+    mut seat: Seat,
+    opponents: Vec<OpponentEvent>,
+) -> Result<(), axum::Error> {
+    for event in opponents {
+        socket.send(&event).await?;
+    }
+    loop {
+        tokio::select! {
+            received = socket.receive() => match received? {
+                Received::Text(text) => {
+                    // Anything but progress is ignored rather than ending the race.
+                    if let Ok(progress) = serde_json::from_str(&text) {
+                        seat.report(progress);
+                    }
+                }
+                Received::Nothing => {}
+                Received::Closed => return Ok(()),
+            },
+            event = seat.events.recv() => match event {
+                Ok((player, event)) if player != seat.player => socket.send(&event).await?,
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Cooperative rooms, where a team finds the words of the daily puzzle
+//! together.
+//!
+//! Players send each word they find as a [`TeamSubmission`]. The first to
+//! submit a word gets it on the team's shared list, which every player is
+//! told of, and anyone submitting it after them is told who beat them to it.
+//! Words are checked against the team's puzzle, that of the player who
+//! started the team, before they count.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::{
+    extract::{Path, Query, State, ws::WebSocketUpgrade},
+    http::StatusCode,
+    response::Response,
+};
+use dashmap::DashMap;
+use puzzle_config::{PuzzleConfig, TeamEvent, TeamSubmission, TeamWord};
+use serde::Deserialize;
+use tokio::sync::broadcast;
+
+use super::{Received, Socket};
+use crate::handlers::puzzle_config::{DifficultyQuery, Timezone};
+use crate::puzzle_config::ConfigProvider;
+use crate::responses::AppError;
+use crate::services::dictionaries::DictionaryId;
+
+const MAX_PLAYERS: usize = 8;
+const MAX_PLAYER_NAME_CHARS: usize = 20;
+const DEFAULT_PLAYER_NAME: &str = "Player";
+/// Events a player can fall behind on before missing some, after which they
+/// are sent the whole list again.
+const EVENT_BUFFER: usize = 64;
+
+#[derive(Clone)]
+pub(crate) struct Teams {
+    teams: Arc<DashMap<String, Team>>,
+    next_player: Arc<AtomicU64>,
+    configs: ConfigProvider,
+}
+
+struct Team {
+    /// The puzzle the team is playing, which submitted words are checked
+    /// against.
+    config: PuzzleConfig,
+    /// Name of each player in the room.
+    players: HashMap<u64, String>,
+    /// Every word found so far, in the order it was found.
+    words: Vec<TeamWord>,
+    events: broadcast::Sender<TeamEvent>,
+}
+
+impl Team {
+    fn players_event(&self) -> TeamEvent {
+        let mut players: Vec<String> = self.players.values().cloned().collect();
+        players.sort();
+        TeamEvent::Players { players }
+    }
+}
+
+/// Why a submitted word wasn't added to the team's list.
+enum Refusal {
+    /// A teammate found the word first.
+    Taken(TeamWord),
+    /// The word isn't one of the puzzle's.
+    Invalid(validation::ValidationError),
+    /// The team has found every word already.
+    Complete,
+}
+
+/// A player's place in a team, given up when dropped.
+struct Member {
+    teams: Teams,
+    team: String,
+    player: u64,
+    name: String,
+    events: broadcast::Receiver<TeamEvent>,
+}
+
+impl Teams {
+    pub(crate) fn new(configs: ConfigProvider) -> Self {
+        Self {
+            teams: Default::default(),
+            next_player: Default::default(),
+            configs,
+        }
+    }
+
+    /// Join `team` as `name`, or `None` when the team is full. A team that
+    /// doesn't exist yet is started playing `config`.
+    fn join(&self, team: &str, name: String, config: PuzzleConfig) -> Option<Member> {
+        let player = self.next_player.fetch_add(1, Ordering::Relaxed);
+        let mut entry = self.teams.entry(team.to_owned()).or_insert_with(|| Team {
+            config,
+            players: HashMap::new(),
+            words: Vec::new(),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        });
+        if entry.players.len() >= MAX_PLAYERS {
+            return None;
+        }
+
+        entry.players.insert(player, name.clone());
+        let events = entry.events.subscribe();
+        let _ = entry.events.send(entry.players_event());
+        Some(Member {
+            teams: self.clone(),
+            team: team.to_owned(),
+            player,
+            name,
+            events,
+        })
+    }
+}
+
+impl Member {
+    /// Everything the team has found so far.
+    fn sync(&self) -> TeamEvent {
+        let words = self
+            .teams
+            .teams
+            .get(&self.team)
+            .map(|team| team.words.clone())
+            .unwrap_or_default();
+        TeamEvent::Sync { words }
+    }
+
+    /// Add the submitted word to the team's list if it's one of the puzzle's,
+    /// unless a teammate found it first. Submissions are taken one at a time,
+    /// so when two players submit the same word at once only one gets it.
+    fn submit(&self, submission: TeamSubmission) -> Result<(), Refusal> {
+        let Some(mut team) = self.teams.teams.get_mut(&self.team) else {
+            return Ok(());
+        };
+        if team.words.len() >= team.config.valid_words.len() {
+            return Err(Refusal::Complete);
+        }
+        let guess =
+            validation::validate_guess(&team.config, &submission.word).map_err(Refusal::Invalid)?;
+        if let Some(found) = team.words.iter().find(|w| w.word == guess.word.word) {
+            return Err(Refusal::Taken(found.clone()));
+        }
+
+        let word = TeamWord {
+            points: guess.word.score(&team.config.scoring),
+            word: guess.word.word,
+            player: self.name.clone(),
+        };
+        team.words.push(word.clone());
+        let _ = team.events.send(TeamEvent::Found(word));
+        Ok(())
+    }
+}
+
+impl Drop for Member {
+    fn drop(&mut self) {
+        if let Some(mut team) = self.teams.teams.get_mut(&self.team) {
+            team.players.remove(&self.player);
+            let _ = team.events.send(team.players_event());
+        }
+        self.teams
+            .teams
+            .remove_if(&self.team, |_, team| team.players.is_empty());
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JoinQuery {
+    name: Option<String>,
+}
+
+/// Join `team` by upgrading the request to a WebSocket, unless the team is
+/// full. A player starting a team picks its puzzle with the `tz` and
+/// `difficulty` query parameters, which later players' are ignored for.
+pub(crate) async fn join_team(
+    State(teams): State<Teams>,
+    Path(team): Path<String>,
+    Timezone(tz): Timezone,
+    Query(DifficultyQuery { difficulty }): Query<DifficultyQuery>,
+    Query(query): Query<JoinQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    super::check_room_name(&team)?;
+    let name = query
+        .name
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_PLAYER_NAME.to_owned());
+    if name.chars().count() > MAX_PLAYER_NAME_CHARS || name.chars().any(char::is_control) {
+        return Err(AppError::Status(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Player names are up to {} characters",
+                MAX_PLAYER_NAME_CHARS
+            ),
+        ));
+    }
+
+    let config = teams
+        .configs
+        .get_config(&tz, difficulty, DictionaryId::STANDARD)
+        .await?;
+    let member = teams.join(&team, name, config).ok_or_else(|| {
+        AppError::Conflict(format!("Team {} already has {} players", team, MAX_PLAYERS))
+    })?;
+    Ok(super::accept(upgrade, move |socket| play(socket, member)))
+}
+
+/// Send the player the team's words, then keep them in step with the team
+/// until they leave.
+async fn play(mut socket: Socket, mut member: Member) -> Result<(), axum::Error> {
+    socket.send(&member.sync()).await?;
+    loop {
+        tokio::select! {
+            received = socket.receive() => match received? {
+                Received::Text(text) => {
+                    // Anything but a submission is ignored rather than leaving the team.
+                    if let Ok(submission) = serde_json::from_str(&text) {
+                        match member.submit(submission) {
+                            Ok(()) => {}
+                            Err(Refusal::Taken(found)) => {
+                                socket.send(&TeamEvent::Taken(found)).await?
+                            }
+                            Err(Refusal::Invalid(e)) => {
+                                tracing::debug!(error = %e, "ignoring invalid team word")
+                            }
+                            Err(Refusal::Complete) => {
+                                tracing::debug!("ignoring word submitted to a complete team")
+                            }
+                        }
+                    }
+                }
+                Received::Nothing => {}
+                Received::Closed => return Ok(()),
+            },
+            event = member.events.recv() => match event {
+                Ok(event) => socket.send(&event).await?,
+                Err(broadcast::error::RecvError::Lagged(_)) => socket.send(&member.sync()).await?,
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+        }
+    }
+}
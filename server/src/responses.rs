@@ -1,29 +1,245 @@
 use axum::{Json, http::StatusCode, response::IntoResponse};
 use serde_json::json;
 
-pub(crate) struct Error {
-    status_code: StatusCode,
-    message: String,
+/// Failure of a handler, answered with its status and a JSON body holding
+/// the message.
+#[derive(Debug)]
+pub(crate) enum AppError {
+    /// The request is well-formed but asks for something invalid: 422.
+    Validation(String),
+    /// What the request names doesn't exist: 404.
+    NotFound(String),
+    /// The request has no valid credentials: 401.
+    Unauthorized(String),
+    /// The credentials don't allow the request: 403.
+    Forbidden(String),
+    /// The request clashes with the current state, such as a taken name: 409.
+    Conflict(String),
+    /// The client sent too many requests recently: 429.
+    TooManyRequests(String),
+    /// The server failed to answer, usually because of its database: 500.
+    Internal(String),
+    /// Any other status, which few handlers answer with.
+    Status(StatusCode, String),
 }
 
-impl IntoResponse for Error {
+impl AppError {
+    pub(crate) fn status(&self) -> StatusCode {
+        match self {
+            Self::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Status(status, _) => *status,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Self::Validation(message)
+            | Self::NotFound(message)
+            | Self::Unauthorized(message)
+            | Self::Forbidden(message)
+            | Self::Conflict(message)
+            | Self::TooManyRequests(message)
+            | Self::Internal(message)
+            | Self::Status(_, message) => message,
+        }
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.status(), self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
         (
-            self.status_code,
+            self.status(),
             [("content-type", "application/json")],
             Json(json!({
-                "message": self.message,
+                "message": self.message(),
             })),
         )
             .into_response()
     }
 }
 
-impl Error {
-    pub(crate) fn new(status_code: StatusCode, message: String) -> Self {
-        Self {
-            status_code,
-            message,
+/// Errors that only happen when the server fails, such as those of services
+/// that can only fail on their database.
+macro_rules! internal_errors {
+    ($($error:ty),* $(,)?) => {
+        $(
+            impl From<$error> for AppError {
+                fn from(error: $error) -> Self {
+                    Self::Internal(error.to_string())
+                }
+            }
+        )*
+    };
+}
+
+internal_errors!(
+    sqlx::Error,
+    serde_json::Error,
+    tokio::task::JoinError,
+    crate::services::dictionaries::DictionariesError,
+    crate::services::words::WordAuditError,
+    crate::services::words::RemoveWordsError,
+    crate::services::words::UpdateWordError,
+    crate::services::words::FlagWordsError,
+    crate::services::words::DescribeWordError,
+    crate::services::words::ListWordsError,
+    crate::services::puzzles::ExcludeWordsError,
+    crate::services::puzzles::ListExclusionsError,
+    crate::services::puzzles::PuzzleRepositoryError,
+    crate::services::puzzles::ScheduleRepositoryError,
+    crate::services::puzzles::CustomPuzzleRepositoryError,
+    crate::services::client_errors::ClientErrorsError,
+    crate::services::feedback::FeedbackError,
+    crate::services::users::UsersError,
+    crate::services::users::SessionsError,
+    crate::services::progress::ProgressRepositoryError,
+    crate::services::notes::NotesRepositoryError,
+);
+
+impl From<crate::services::words::AddWordsError> for AppError {
+    fn from(error: crate::services::words::AddWordsError) -> Self {
+        match error {
+            crate::services::words::AddWordsError::InvalidWord { .. } => {
+                Self::Validation(error.to_string())
+            }
+            crate::services::words::AddWordsError::DbError(_) => Self::Internal(error.to_string()),
+        }
+    }
+}
+
+impl From<crate::services::words::SearchWordsError> for AppError {
+    fn from(error: crate::services::words::SearchWordsError) -> Self {
+        match error {
+            crate::services::words::SearchWordsError::InvalidPattern(_) => {
+                Self::Validation(error.to_string())
+            }
+            crate::services::words::SearchWordsError::DBError(_) => {
+                Self::Internal(error.to_string())
+            }
+        }
+    }
+}
+
+impl From<crate::services::users::CreateUserError> for AppError {
+    fn from(error: crate::services::users::CreateUserError) -> Self {
+        match error {
+            crate::services::users::CreateUserError::UsernameTaken => {
+                Self::Conflict(error.to_string())
+            }
+            crate::services::users::CreateUserError::DbError(_) => {
+                Self::Internal(error.to_string())
+            }
+        }
+    }
+}
+
+impl From<crate::import::ImportError> for AppError {
+    fn from(error: crate::import::ImportError) -> Self {
+        match error {
+            crate::import::ImportError::MixedLanguage { .. } => Self::Validation(error.to_string()),
+            crate::import::ImportError::AddWords(error) => error.into(),
         }
     }
 }
+
+impl From<crate::puzzle_config::Error> for AppError {
+    fn from(error: crate::puzzle_config::Error) -> Self {
+        match error {
+            crate::puzzle_config::Error::Unavailable(_) => Self::NotFound(error.to_string()),
+            _ => Self::Internal(error.to_string()),
+        }
+    }
+}
+
+/// Convert a handler panic into a 500 response. The panic is logged with a
+/// random incident id that is also returned to the client so that reports can
+/// be matched to the log entry.
+pub(crate) fn panic_response(
+    panic: Box<dyn std::any::Any + Send + 'static>,
+) -> axum::response::Response {
+    let incident_id = format!("{:016x}", rand::random::<u64>());
+    let details = if let Some(s) = panic.downcast_ref::<String>() {
+        s.as_str()
+    } else if let Some(s) = panic.downcast_ref::<&str>() {
+        s
+    } else {
+        "unknown panic payload"
+    };
+    tracing::error!(incident_id, details, "handler panicked");
+
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        [("content-type", "application/json")],
+        Json(json!({
+            "message": "Internal server error",
+            "incident_id": incident_id,
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn reports_panics_with_an_incident_id() {
+        let response = super::panic_response(Box::new("boom"));
+        assert_eq!(
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            response.status()
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!("Internal server error", body["message"]);
+        assert!(body["incident_id"].is_string());
+    }
+
+    #[tokio::test]
+    async fn answers_errors_with_their_status_and_message() {
+        use axum::response::IntoResponse as _;
+
+        let error: super::AppError = crate::services::words::AddWordsError::InvalidWord {
+            word: "cr4ne".to_owned(),
+            error: words::BitmaskError::InvalidLetter('4'),
+        }
+        .into();
+        let response = error.into_response();
+        assert_eq!(
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            response.status()
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            "Invalid word \"cr4ne\": '4' is not a letter from a to z",
+            body["message"]
+        );
+
+        let error: super::AppError = sqlx::Error::RowNotFound.into();
+        assert_eq!(
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            error.status()
+        );
+        let error: super::AppError =
+            crate::puzzle_config::Error::Unavailable(chrono::NaiveDate::MAX).into();
+        assert_eq!(axum::http::StatusCode::NOT_FOUND, error.status());
+    }
+}
@@ -0,0 +1,70 @@
+/// Populate the standard dictionary from a newline-delimited word list if it
+/// is empty.
+///
+/// Words that could never be valid answers are skipped, see
+/// [`crate::import::importable_word`]. The seed list sets the language of the
+/// word list, so its words aren't checked against it, but inflections are
+/// dropped according to `morphology`. A non-empty dictionary is left untouched
+/// so restarts never re-import the list.
+pub async fn seed_words(
+    pool: &sqlx::PgPool,
+    path: &std::path::Path,
+    morphology: &crate::morphology::MorphologyPolicy,
+    alphabet: words::Alphabet,
+) -> Result<(), Error> {
+    let has_words = sqlx::query_scalar!(
+        r#"select exists(select 1 from words where dictionary_id = $1) as "exists!""#,
+        crate::services::dictionaries::DictionaryId::STANDARD.0
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| Error::DbError(Box::new(e)))?;
+    if has_words {
+        tracing::info!("words table already populated, skipping seed");
+        return Ok(());
+    }
+
+    let contents = std::fs::read(path).map_err(|e| Error::ReadError(Box::new(e)))?;
+    let service = crate::services::words::pg::AddWords(pool.clone(), alphabet);
+    let mut importer = crate::import::Importer::new(
+        &service,
+        crate::services::dictionaries::DictionaryId::STANDARD,
+        None,
+        morphology,
+        None,
+    );
+    importer
+        .push_chunk(&contents)
+        .await
+        .map_err(|e| Error::DbError(Box::new(e)))?;
+    let counts = importer
+        .finish()
+        .await
+        .map_err(|e| Error::DbError(Box::new(e)))?;
+
+    tracing::info!(
+        inserted = counts.inserted,
+        inflected = counts.inflected,
+        rejected = counts.rejected,
+        path = %path.display(),
+        "seeded words table"
+    );
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum Error {
+    ReadError(Box<dyn std::error::Error>),
+    DbError(Box<dyn std::error::Error>),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::ReadError(cause) => write!(f, "Failed to read seed words file: {}", cause),
+            Self::DbError(cause) => write!(f, "Failed to seed words table: {}", cause),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
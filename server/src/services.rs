@@ -1,18 +1,317 @@
+/// Running database work so that it survives failures that pass by themselves.
+pub(crate) mod db {
+    use std::time::Duration;
+
+    use futures_util::future::BoxFuture;
+
+    /// Attempts made at an operation before its error is returned.
+    const MAX_ATTEMPTS: u32 = 4;
+    /// Wait before the first retry, doubled for each one after.
+    const BASE_DELAY: Duration = Duration::from_millis(20);
+
+    /// Run `operation` in a transaction, trying it again in a new one with
+    /// exponential backoff while it fails with a serialization failure, a
+    /// deadlock or a lost connection.
+    ///
+    /// `operation` may run more than once, so it should only touch the
+    /// database through the transaction it is given.
+    /// The future it returns may borrow anything that outlives `pool`.
+    pub(crate) async fn with_retry<'a, T, F>(
+        pool: &'a sqlx::PgPool,
+        mut operation: F,
+    ) -> Result<T, sqlx::Error>
+    where
+        F: for<'c> FnMut(
+            &'c mut sqlx::Transaction<'a, sqlx::Postgres>,
+        ) -> BoxFuture<'c, Result<T, sqlx::Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match in_transaction(pool, &mut operation).await {
+                Err(error) if attempt < MAX_ATTEMPTS && is_transient(&error) => {
+                    tracing::warn!(attempt, %error, "retrying database operation");
+                    tokio::time::sleep(backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn in_transaction<'a, T, F>(
+        pool: &'a sqlx::PgPool,
+        operation: &mut F,
+    ) -> Result<T, sqlx::Error>
+    where
+        F: for<'c> FnMut(
+            &'c mut sqlx::Transaction<'a, sqlx::Postgres>,
+        ) -> BoxFuture<'c, Result<T, sqlx::Error>>,
+    {
+        let mut transaction = pool.begin().await?;
+        // Dropping the transaction on error rolls it back.
+        let value = operation(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(value)
+    }
+
+    fn backoff(attempt: u32) -> Duration {
+        BASE_DELAY * 2u32.pow(attempt - 1)
+    }
+
+    /// Whether `error` may not happen again if the operation is retried.
+    fn is_transient(error: &sqlx::Error) -> bool {
+        match error {
+            sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+            // serialization_failure, deadlock_detected and the connection
+            // exception class.
+            sqlx::Error::Database(error) => error
+                .code()
+                .is_some_and(|code| code == "40001" || code == "40P01" || code.starts_with("08")),
+            _ => false,
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[sqlx::test(migrations = false)]
+        async fn retries_serialization_failures_in_a_new_transaction(pool: sqlx::PgPool) {
+            sqlx::query("create table attempts (attempt integer)")
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let mut attempts = 0;
+            let recorded = with_retry(&pool, |tx| {
+                attempts += 1;
+                let attempt = attempts;
+                Box::pin(async move {
+                    sqlx::query("insert into attempts values ($1)")
+                        .bind(attempt)
+                        .execute(&mut **tx)
+                        .await?;
+                    if attempt == 1 {
+                        sqlx::query(
+                            "do $$ begin raise exception using errcode = 'serialization_failure'; end $$",
+                        )
+                        .execute(&mut **tx)
+                        .await?;
+                    }
+                    sqlx::query_scalar::<_, i32>("select attempt from attempts")
+                        .fetch_all(&mut **tx)
+                        .await
+                })
+            })
+            .await
+            .unwrap();
+
+            assert_eq!(2, attempts);
+            assert_eq!(vec![2], recorded);
+        }
+
+        #[sqlx::test(migrations = false)]
+        async fn gives_up_on_other_errors(pool: sqlx::PgPool) {
+            let mut attempts = 0;
+            let result = with_retry(&pool, |tx| {
+                attempts += 1;
+                Box::pin(async move {
+                    sqlx::query_scalar::<_, i32>("select 1 / 0")
+                        .fetch_one(&mut **tx)
+                        .await
+                })
+            })
+            .await;
+
+            assert!(result.is_err());
+            assert_eq!(1, attempts);
+        }
+
+        #[test]
+        fn doubles_the_delay_between_attempts() {
+            assert_eq!(
+                vec![20, 40, 80],
+                (1..MAX_ATTEMPTS)
+                    .map(|attempt| backoff(attempt).as_millis())
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
+/// The word lists hosted side by side, which words, their audit log and
+/// stored puzzles each belong to one of.
+pub(crate) mod dictionaries {
+    use std::fmt::Display;
+
+    /// Id of a row of the dictionaries table.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub(crate) struct DictionaryId(pub(crate) i32);
+
+    impl DictionaryId {
+        /// The dictionary every word was in before there were several, used
+        /// unless another is chosen.
+        pub(crate) const STANDARD: Self = Self(1);
+    }
+
+    impl Display for DictionaryId {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    pub(crate) trait Dictionaries {
+        /// Every dictionary with its count of words, in order of name.
+        async fn list(&self) -> Result<Vec<words_list::Dictionary>, DictionariesError>;
+
+        /// The id of the dictionary called `name`, if there is one.
+        async fn find(&self, name: &str) -> Result<Option<DictionaryId>, DictionariesError>;
+
+        /// Create a dictionary called `name`, returning its id, or the id of
+        /// the one already called that.
+        async fn create(&self, name: &str) -> Result<DictionaryId, DictionariesError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum DictionariesError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for DictionariesError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => write!(
+                    f,
+                    "Failed to access dictionaries due to database error: {}",
+                    error
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for DictionariesError {}
+
+    pub(crate) mod pg {
+        use super::{DictionariesError, DictionaryId};
+
+        #[derive(Clone)]
+        pub(crate) struct Dictionaries(pub(crate) sqlx::PgPool);
+
+        impl super::Dictionaries for Dictionaries {
+            #[tracing::instrument(skip(self))]
+            async fn list(&self) -> Result<Vec<words_list::Dictionary>, DictionariesError> {
+                let _timer = crate::telemetry::QueryTimer::start("dictionaries.list");
+                let rows = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            r#"select dictionaries.name, count(words.word) as "words!"
+                            from dictionaries
+                            left join words on words.dictionary_id = dictionaries.id
+                            and words.deleted_at is null
+                            group by dictionaries.id
+                            order by dictionaries.name"#
+                        )
+                        .fetch_all(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| DictionariesError::DbError(Box::new(e)))?;
+
+                Ok(rows
+                    .into_iter()
+                    .map(|row| words_list::Dictionary {
+                        name: row.name,
+                        words: row.words as u64,
+                    })
+                    .collect())
+            }
+
+            #[tracing::instrument(skip(self))]
+            async fn find(&self, name: &str) -> Result<Option<DictionaryId>, DictionariesError> {
+                let _timer = crate::telemetry::QueryTimer::start("dictionaries.find");
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query_scalar!("select id from dictionaries where name = $1", name)
+                            .fetch_optional(&mut **tx)
+                            .await
+                    })
+                })
+                .await
+                .map_err(|e| DictionariesError::DbError(Box::new(e)))
+                .map(|id| id.map(DictionaryId))
+            }
+
+            #[tracing::instrument(skip(self))]
+            async fn create(&self, name: &str) -> Result<DictionaryId, DictionariesError> {
+                let _timer = crate::telemetry::QueryTimer::start("dictionaries.create");
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        // Updating the name of an existing dictionary to itself
+                        // returns its id.
+                        sqlx::query_scalar!(
+                            "insert into dictionaries (name) values ($1)
+                            on conflict (name) do update set name = excluded.name
+                            returning id",
+                            name
+                        )
+                        .fetch_one(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| DictionariesError::DbError(Box::new(e)))
+                .map(DictionaryId)
+            }
+        }
+    }
+}
+
 pub(crate) mod words {
     use std::fmt::Display;
 
+    use super::dictionaries::DictionaryId;
+
+    /// Who changed the word list, as recorded in its audit log: the id of an
+    /// admin, or `None` for the server itself and scripts holding the admin
+    /// token.
+    pub(crate) type Editor = Option<i64>;
+
     pub(crate) trait AddWords {
-        async fn add_words(&self, words: Vec<String>) -> Result<(), AddWordsError>;
+        /// Add `words` to `dictionary` on behalf of `editor`, returning how
+        /// many were new. Removed words are restored, other words already in
+        /// the dictionary are left untouched. Nothing is added if any word has
+        /// a character other than a letter of the service's alphabet.
+        async fn add_words(
+            &self,
+            dictionary: DictionaryId,
+            words: Vec<String>,
+            editor: Editor,
+        ) -> Result<u64, AddWordsError>;
+
+        /// Which of `words` are in `dictionary` already.
+        async fn existing_words(
+            &self,
+            dictionary: DictionaryId,
+            words: &[String],
+        ) -> Result<std::collections::HashSet<String>, AddWordsError>;
     }
 
     #[derive(Debug)]
     pub(crate) enum AddWordsError {
+        InvalidWord {
+            word: String,
+            error: words::BitmaskError,
+        },
         DbError(Box<dyn std::error::Error>),
     }
 
     impl Display for AddWordsError {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             match self {
+                AddWordsError::InvalidWord { word, error } => {
+                    write!(f, "Invalid word {:?}: {}", word, error)
+                }
                 AddWordsError::DbError(error) => {
                     write!(f, "Failed to add words due to database error: {}", error)
                 }
@@ -22,10 +321,55 @@ pub(crate) mod words {
 
     impl std::error::Error for AddWordsError {}
 
+    /// Removed words are kept, marked with when they were removed, so that
+    /// removals can be undone.
     pub(crate) trait RemoveWords {
-        async fn remove_words(&self, words: &[String]) -> Result<(), RemoveWordsError>;
+        async fn remove_words(
+            &self,
+            dictionary: DictionaryId,
+            words: &[String],
+            editor: Editor,
+        ) -> Result<(), RemoveWordsError>;
+
+        /// Undo the removal of `words`, returning how many were restored.
+        async fn restore_words(
+            &self,
+            dictionary: DictionaryId,
+            words: &[String],
+            editor: Editor,
+        ) -> Result<u64, RemoveWordsError>;
+    }
+
+    pub(crate) trait ListWordAudit {
+        /// The `limit` most recent changes to `dictionary`, the latest first.
+        async fn list_audit(
+            &self,
+            dictionary: DictionaryId,
+            limit: i64,
+        ) -> Result<Vec<words_list::WordAuditEntry>, WordAuditError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum WordAuditError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for WordAuditError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                WordAuditError::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to load the word audit log due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
     }
 
+    impl std::error::Error for WordAuditError {}
+
     #[derive(Debug)]
     pub(crate) enum RemoveWordsError {
         DbError(Box<dyn std::error::Error>),
@@ -41,20 +385,133 @@ pub(crate) mod words {
         }
     }
 
+    pub(crate) trait UpdateWord {
+        /// Apply `update` to `word` and return its metadata afterwards, or `None`
+        /// if the word isn't in the list.
+        async fn update_word(
+            &self,
+            dictionary: DictionaryId,
+            word: &str,
+            update: &WordUpdate,
+        ) -> Result<Option<words_list::WordMetadata>, UpdateWordError>;
+    }
+
+    /// Changes to the metadata of a word. Fields that are `None` are left as
+    /// they are and `Some(None)` clears them.
+    #[derive(Debug, Default)]
+    pub(crate) struct WordUpdate {
+        pub(crate) definition: Option<Option<String>>,
+        pub(crate) obscurity: Option<Option<u8>>,
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum UpdateWordError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for UpdateWordError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                UpdateWordError::DbError(error) => {
+                    write!(f, "Failed to update word due to database error: {}", error)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for UpdateWordError {}
+
+    /// Flagged words, such as offensive ones, stay in the list for review but
+    /// are left out of generated puzzles.
+    pub(crate) trait FlagWords {
+        /// Flag or unflag `word`, returning whether it's in the list.
+        async fn set_flagged(
+            &self,
+            dictionary: DictionaryId,
+            word: &str,
+            flagged: bool,
+        ) -> Result<bool, FlagWordsError>;
+
+        /// Every flagged word, the most recently flagged first.
+        async fn flagged_words(
+            &self,
+            dictionary: DictionaryId,
+        ) -> Result<Vec<words_list::FlaggedWord>, FlagWordsError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum FlagWordsError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for FlagWordsError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                FlagWordsError::DbError(error) => {
+                    write!(f, "Failed to flag words due to database error: {}", error)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for FlagWordsError {}
+
+    pub(crate) trait DescribeWord {
+        /// Everything known about `word`, or `None` if it isn't in the list.
+        async fn describe_word(
+            &self,
+            dictionary: DictionaryId,
+            word: &str,
+        ) -> Result<Option<words_list::WordDetail>, DescribeWordError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum DescribeWordError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for DescribeWordError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                DescribeWordError::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to describe word due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for DescribeWordError {}
+
     pub(crate) trait SearchWords {
-        async fn search(&self, query: &str) -> Result<SearchResult, SearchWordsError>;
+        /// Up to `limit` words matching `query` by `mode`, the closest first for
+        /// fuzzy searches and in word order otherwise.
+        async fn search(
+            &self,
+            dictionary: DictionaryId,
+            query: &str,
+            mode: search::SearchMode,
+            limit: usize,
+        ) -> Result<SearchResult, SearchWordsError>;
     }
 
     type SearchResult = Vec<String>;
 
     #[derive(Debug)]
     pub(crate) enum SearchWordsError {
+        /// The query can't be searched for in the mode, such as an invalid
+        /// regular expression or a non-letter in a search by letters.
+        InvalidPattern(String),
         DBError(Box<dyn std::error::Error>),
     }
 
     impl Display for SearchWordsError {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             match self {
+                Self::InvalidPattern(e) => write!(f, "Invalid search pattern: {}", e),
                 Self::DBError(e) => write!(f, "Failed to search words due to db error: {}", e),
             }
         }
@@ -62,18 +519,30 @@ pub(crate) mod words {
 
     impl std::error::Error for SearchWordsError {}
 
+    /// The letter mask of the query of a search by letters.
+    fn letter_mask(query: &str) -> Result<i64, SearchWordsError> {
+        words::try_bitmask(query)
+            .map(i64::from)
+            .map_err(|e| SearchWordsError::InvalidPattern(e.to_string()))
+    }
+
     pub(crate) trait ListWords {
         async fn list(
             &self,
+            dictionary: DictionaryId,
             cursor: &ListCursor,
             limit: Option<usize>,
         ) -> Result<ListedWords, ListWordsError>;
+
+        /// Every word in `dictionary` in alphabetical order.
+        async fn all(&self, dictionary: DictionaryId) -> Result<Vec<String>, ListWordsError>;
     }
 
     #[derive(Debug)]
     pub(crate) struct ListedWords {
         pub(crate) words: Vec<Word>,
         pub(crate) next_page: Option<ListCursor>,
+        pub(crate) prev_page: Option<ListCursor>,
     }
 
     #[derive(Debug)]
@@ -82,16 +551,18 @@ pub(crate) mod words {
         pub(crate) cursor: ListCursor,
     }
 
-    #[derive(Debug)]
-    pub(crate) struct ListCursor {
-        pub(crate) after: String,
+    /// Position in the alphabetical list of words to list a page from.
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) enum ListCursor {
+        /// The page of words following the word.
+        After(String),
+        /// The page of words preceding the word.
+        Before(String),
     }
 
     impl std::default::Default for ListCursor {
         fn default() -> Self {
-            Self {
-                after: "".to_owned(),
-            }
+            Self::After("".to_owned())
         }
     }
 
@@ -111,33 +582,95 @@ pub(crate) mod words {
     impl std::error::Error for ListWordsError {}
 
     pub(crate) mod pg {
-        use super::{AddWordsError, RemoveWordsError};
+        use super::{AddWordsError, DictionaryId, RemoveWordsError};
+
+        /// Rows added per insert. Each takes four bound parameters, and
+        /// Postgres allows at most 65535 in a statement.
+        const INSERT_CHUNK: usize = 10_000;
 
         #[derive(Clone)]
-        pub(crate) struct AddWords(pub(crate) sqlx::PgPool);
+        /// Adds words spelled with the letters of the alphabet.
+        pub(crate) struct AddWords(pub(crate) sqlx::PgPool, pub(crate) words::Alphabet);
 
         impl super::AddWords for AddWords {
-            async fn add_words(&self, words: Vec<String>) -> Result<(), super::AddWordsError> {
-                let mut builder =
-                    sqlx::QueryBuilder::new("insert into words (word, letter_mask, length) ");
-                builder.push_values(words, |mut b, word| {
-                    let mask = words::bitmask(&word);
-                    let length = word.len();
-                    b.push_bind(word).push_bind(mask).push_bind(length as i32);
-                });
-                builder.push("on conflict do nothing");
+            #[tracing::instrument(skip_all, fields(%dictionary, words = words.len()))]
+            async fn add_words(
+                &self,
+                dictionary: DictionaryId,
+                words: Vec<String>,
+                editor: super::Editor,
+            ) -> Result<u64, super::AddWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.add");
+                let mut rows = words
+                    .into_iter()
+                    .map(|word| match self.1.try_bitmask(&word) {
+                        Ok(mask) => Ok((word, mask)),
+                        Err(error) => Err(AddWordsError::InvalidWord { word, error }),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                // An upsert can't touch the same row twice.
+                rows.sort();
+                rows.dedup();
 
-                let mut conn = self
-                    .0
-                    .acquire()
-                    .await
-                    .map_err(|e| AddWordsError::DbError(Box::new(e)))?;
-                builder
-                    .build()
-                    .execute(&mut *conn)
-                    .await
-                    .map_err(|e| AddWordsError::DbError(Box::new(e)))
-                    .map(|_| ())
+                // Chunks are inserted in one transaction, so that a failure
+                // leaves none of them added.
+                let rows = &rows;
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        let mut added = 0;
+                        for chunk in rows.chunks(INSERT_CHUNK) {
+                            let mut builder = sqlx::QueryBuilder::new(
+                                "with added as (
+                                insert into words (dictionary_id, word, letter_mask, length) ",
+                            );
+                            builder.push_values(chunk, |mut b, (word, mask)| {
+                                let mask = i64::from(*mask);
+                                let length = word.chars().count();
+                                b.push_bind(dictionary.0)
+                                    .push_bind(word.as_str())
+                                    .push_bind(mask)
+                                    .push_bind(length as i32);
+                            });
+                            builder.push(
+                                " on conflict (dictionary_id, word) do update set deleted_at = null
+                                where words.deleted_at is not null
+                                returning dictionary_id, word)
+                                insert into word_audit (dictionary_id, word, action, user_id)
+                                select dictionary_id, word, 'added', ",
+                            );
+                            builder.push_bind(editor);
+                            builder.push(" from added");
+                            added += builder.build().execute(&mut **tx).await?.rows_affected();
+                        }
+                        Ok(added)
+                    })
+                })
+                .await
+                .map_err(|e| AddWordsError::DbError(Box::new(e)))
+            }
+
+            #[tracing::instrument(skip_all, fields(%dictionary, words = words.len()))]
+            async fn existing_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+            ) -> Result<std::collections::HashSet<String>, AddWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.existing");
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query_scalar!(
+                            "select word from words
+                            where dictionary_id = $1 and word = any($2) and deleted_at is null",
+                            dictionary.0,
+                            words
+                        )
+                        .fetch_all(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| AddWordsError::DbError(Box::new(e)))
+                .map(|words| words.into_iter().collect())
             }
         }
 
@@ -145,113 +678,2910 @@ pub(crate) mod words {
         pub(crate) struct RemoveWords(pub(crate) sqlx::PgPool);
 
         impl super::RemoveWords for RemoveWords {
-            async fn remove_words(&self, words: &[String]) -> Result<(), RemoveWordsError> {
-                let mut conn = self
-                    .0
-                    .acquire()
-                    .await
-                    .map_err(|e| RemoveWordsError::DbError(Box::new(e)))?;
-
-                sqlx::query!(
-                    "delete from words where word in (select * from unnest($1::text[]))",
-                    words
-                )
-                .execute(&mut *conn)
+            #[tracing::instrument(skip_all, fields(%dictionary, words = words.len()))]
+            async fn remove_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+                editor: super::Editor,
+            ) -> Result<(), RemoveWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.remove");
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "with removed as (
+                                update words set deleted_at = now()
+                                where dictionary_id = $1 and word = any($2)
+                                and deleted_at is null
+                                returning dictionary_id, word
+                            )
+                            insert into word_audit (dictionary_id, word, action, user_id)
+                            select dictionary_id, word, 'removed', $3 from removed",
+                            dictionary.0,
+                            words,
+                            editor,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
                 .await
                 .map_err(|e| RemoveWordsError::DbError(Box::new(e)))
                 .map(|_| ())
             }
+
+            #[tracing::instrument(skip_all, fields(%dictionary, words = words.len()))]
+            async fn restore_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+                editor: super::Editor,
+            ) -> Result<u64, RemoveWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.restore");
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "with restored as (
+                                update words set deleted_at = null
+                                where dictionary_id = $1 and word = any($2)
+                                and deleted_at is not null
+                                returning dictionary_id, word
+                            )
+                            insert into word_audit (dictionary_id, word, action, user_id)
+                            select dictionary_id, word, 'restored', $3 from restored",
+                            dictionary.0,
+                            words,
+                            editor,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| RemoveWordsError::DbError(Box::new(e)))
+                .map(|result| result.rows_affected())
+            }
         }
 
         #[derive(Clone)]
-        pub(crate) struct SearchWords(pub(crate) sqlx::PgPool);
+        pub(crate) struct WordAudit(pub(crate) sqlx::PgPool);
 
-        impl super::SearchWords for SearchWords {
-            async fn search(
+        impl super::ListWordAudit for WordAudit {
+            #[tracing::instrument(skip(self))]
+            async fn list_audit(
                 &self,
-                query: &str,
-            ) -> Result<super::SearchResult, super::SearchWordsError> {
-                let mut conn = self
-                    .0
-                    .acquire()
-                    .await
-                    .map_err(|e| super::SearchWordsError::DBError(Box::new(e)))?;
-
-                let result = sqlx::query_as!(
-                    SearchResult,
-                    r#"select word, levenshtein($1, word, 1, 2, 2) as "score!"
-                    from words
-                    order by "score!" asc
-                    limit 15"#,
-                    query
-                )
-                .fetch_all(&mut *conn)
+                dictionary: DictionaryId,
+                limit: i64,
+            ) -> Result<Vec<words_list::WordAuditEntry>, super::WordAuditError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.audit");
+                let rows = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            r#"select word_audit.word, word_audit.action,
+                            users.username as "username?", word_audit.recorded_at
+                            from word_audit
+                            left join users on users.id = word_audit.user_id
+                            where word_audit.dictionary_id = $1
+                            order by word_audit.recorded_at desc, word_audit.id desc
+                            limit $2"#,
+                            dictionary.0,
+                            limit,
+                        )
+                        .fetch_all(&mut **tx)
+                        .await
+                    })
+                })
                 .await
-                .map_err(|e| super::SearchWordsError::DBError(Box::new(e)))?;
+                .map_err(|e| super::WordAuditError::DbError(Box::new(e)))?;
 
-                Ok(result.into_iter().map(|w| w.word).collect())
+                Ok(rows
+                    .into_iter()
+                    .map(|row| words_list::WordAuditEntry {
+                        word: row.word,
+                        action: row.action,
+                        username: row.username,
+                        recorded_at: row.recorded_at.to_rfc3339(),
+                    })
+                    .collect())
             }
         }
 
-        #[derive(sqlx::FromRow)]
-        struct SearchResult {
-            word: String,
-            score: i32,
+        #[derive(Clone)]
+        pub(crate) struct UpdateWord(pub(crate) sqlx::PgPool);
+
+        impl super::UpdateWord for UpdateWord {
+            #[tracing::instrument(skip(self, update))]
+            async fn update_word(
+                &self,
+                dictionary: DictionaryId,
+                word: &str,
+                update: &super::WordUpdate,
+            ) -> Result<Option<words_list::WordMetadata>, super::UpdateWordError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.update");
+                let row = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "update words set
+                            definition = case when $3 then $4 else definition end,
+                            obscurity = case when $5 then $6 else obscurity end
+                            where dictionary_id = $1 and word = $2 and deleted_at is null
+                            returning word, definition, obscurity",
+                            dictionary.0,
+                            word,
+                            update.definition.is_some(),
+                            update.definition.clone().flatten(),
+                            update.obscurity.is_some(),
+                            update.obscurity.flatten().map(i16::from),
+                        )
+                        .fetch_optional(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| super::UpdateWordError::DbError(Box::new(e)))?;
+
+                Ok(row.map(|row| words_list::WordMetadata {
+                    word: row.word,
+                    definition: row.definition,
+                    obscurity: row.obscurity.and_then(|o| u8::try_from(o).ok()),
+                }))
+            }
         }
 
         #[derive(Clone)]
-        pub(crate) struct ListWords(pub(crate) sqlx::PgPool);
+        pub(crate) struct DescribeWord(pub(crate) sqlx::PgPool);
 
-        impl super::ListWords for ListWords {
-            async fn list(
+        impl super::DescribeWord for DescribeWord {
+            #[tracing::instrument(skip(self))]
+            async fn describe_word(
                 &self,
-                cursor: &super::ListCursor,
-                limit: Option<usize>,
-            ) -> Result<super::ListedWords, super::ListWordsError> {
-                let mut conn = self
-                    .0
-                    .acquire()
-                    .await
-                    .map_err(|e| super::ListWordsError::DBError(Box::new(e)))?;
-
-                let limit = limit.unwrap_or(200);
-                let results = sqlx::query_as!(
-                    ListedWord,
-                    r#"
-                         select word from words
-                         where word > $1
-                         limit $2
-                     "#,
-                    cursor.after,
-                    (limit + 1) as i32
-                )
-                .fetch_all(&mut *conn)
-                .await
-                .map_err(|e| super::ListWordsError::DBError(Box::new(e)))?;
+                dictionary: DictionaryId,
+                word: &str,
+            ) -> Result<Option<words_list::WordDetail>, super::DescribeWordError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.describe");
+                let found = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        let row = sqlx::query!(
+                            "select word, letter_mask, length, definition, obscurity, added_at,
+                            flagged_at
+                            from words
+                            where dictionary_id = $1 and word = $2 and deleted_at is null",
+                            dictionary.0,
+                            word,
+                        )
+                        .fetch_optional(&mut **tx)
+                        .await?;
+                        let Some(row) = row else {
+                            return Ok(None);
+                        };
 
-                let next_page = if results.len() > limit {
-                    Some(super::ListCursor {
-                        after: results[results.len() - 1].word.clone(),
+                        let puzzles = sqlx::query!(
+                            r#"select puzzle_date, utc_offset, $2 = any(pangrams) as "pangram!"
+                            from puzzles
+                            where dictionary_id = $1 and $2 = any(words)
+                            order by puzzle_date desc, utc_offset"#,
+                            dictionary.0,
+                            word,
+                        )
+                        .fetch_all(&mut **tx)
+                        .await?;
+                        Ok(Some((row, puzzles)))
                     })
-                } else {
-                    None
+                })
+                .await
+                .map_err(|e| super::DescribeWordError::DbError(Box::new(e)))?;
+                let Some((row, puzzles)) = found else {
+                    return Ok(None);
                 };
-                Ok(super::ListedWords {
-                    words: results
+
+                let mask = words::Bitmask::try_from(row.letter_mask)
+                    .map_err(|e| super::DescribeWordError::DbError(Box::new(e)))?;
+                Ok(Some(words_list::WordDetail {
+                    word: row.word,
+                    letter_mask: mask.bits(),
+                    letters: mask.letters().collect(),
+                    length: row.length as usize,
+                    pangram_potential: words::letter_count(mask) == 7,
+                    definition: row.definition,
+                    obscurity: row.obscurity.and_then(|o| u8::try_from(o).ok()),
+                    added_at: row.added_at.map(|at| at.to_rfc3339()),
+                    flagged_at: row.flagged_at.map(|at| at.to_rfc3339()),
+                    puzzles: puzzles
                         .into_iter()
-                        .map(|w| super::Word {
-                            text: w.word.clone(),
-                            cursor: super::ListCursor { after: w.word },
+                        .map(|p| words_list::WordPuzzle {
+                            date: p.puzzle_date.to_string(),
+                            utc_offset: p.utc_offset,
+                            pangram: p.pangram,
                         })
                         .collect(),
-                    next_page,
+                }))
+            }
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct FlagWords(pub(crate) sqlx::PgPool);
+
+        impl super::FlagWords for FlagWords {
+            #[tracing::instrument(skip(self))]
+            async fn set_flagged(
+                &self,
+                dictionary: DictionaryId,
+                word: &str,
+                flagged: bool,
+            ) -> Result<bool, super::FlagWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.flag");
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        // Flagging again keeps when the word was first flagged.
+                        sqlx::query!(
+                            "update words set
+                            flagged_at = case when $3 then coalesce(flagged_at, now()) end
+                            where dictionary_id = $1 and word = $2 and deleted_at is null",
+                            dictionary.0,
+                            word,
+                            flagged,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| super::FlagWordsError::DbError(Box::new(e)))
+                .map(|result| result.rows_affected() > 0)
+            }
+
+            #[tracing::instrument(skip(self))]
+            async fn flagged_words(
+                &self,
+                dictionary: DictionaryId,
+            ) -> Result<Vec<words_list::FlaggedWord>, super::FlagWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.flagged");
+                let rows = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            r#"select word, flagged_at as "flagged_at!"
+                            from words
+                            where dictionary_id = $1
+                            and flagged_at is not null and deleted_at is null
+                            order by flagged_at desc, word"#,
+                            dictionary.0,
+                        )
+                        .fetch_all(&mut **tx)
+                        .await
+                    })
                 })
+                .await
+                .map_err(|e| super::FlagWordsError::DbError(Box::new(e)))?;
+
+                Ok(rows
+                    .into_iter()
+                    .map(|row| words_list::FlaggedWord {
+                        word: row.word,
+                        flagged_at: row.flagged_at.to_rfc3339(),
+                    })
+                    .collect())
             }
         }
 
-        #[derive(sqlx::FromRow)]
-        struct ListedWord {
-            word: String,
+        #[derive(Clone)]
+        pub(crate) struct SearchWords(pub(crate) sqlx::PgPool);
+
+        impl super::SearchWords for SearchWords {
+            #[tracing::instrument(skip(self))]
+            async fn search(
+                &self,
+                dictionary: DictionaryId,
+                query: &str,
+                mode: search::SearchMode,
+                limit: usize,
+            ) -> Result<super::SearchResult, super::SearchWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.search");
+                let limit = limit as i64;
+                let letters = (mode == search::SearchMode::Letters)
+                    .then(|| super::letter_mask(query))
+                    .transpose()?;
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        match mode {
+                            search::SearchMode::Fuzzy => {
+                                sqlx::query_scalar!(
+                                    "select word from words
+                                    where dictionary_id = $1 and deleted_at is null
+                                    order by levenshtein($2, word, 1, 2, 2), word
+                                    limit $3",
+                                    dictionary.0,
+                                    query,
+                                    limit,
+                                )
+                                .fetch_all(&mut **tx)
+                                .await
+                            }
+                            search::SearchMode::Prefix => {
+                                sqlx::query_scalar!(
+                                    "select word from words
+                                    where dictionary_id = $1 and starts_with(word, $2) and deleted_at is null
+                                    order by word
+                                    limit $3",
+                                    dictionary.0,
+                                    query,
+                                    limit,
+                                )
+                                .fetch_all(&mut **tx)
+                                .await
+                            }
+                            search::SearchMode::Substring => {
+                                sqlx::query_scalar!(
+                                    "select word from words
+                                    where dictionary_id = $1 and strpos(word, $2) > 0 and deleted_at is null
+                                    order by word
+                                    limit $3",
+                                    dictionary.0,
+                                    query,
+                                    limit,
+                                )
+                                .fetch_all(&mut **tx)
+                                .await
+                            }
+                            search::SearchMode::Regex => {
+                                sqlx::query_scalar!(
+                                    "select word from words
+                                    where dictionary_id = $1 and word ~ $2 and deleted_at is null
+                                    order by word
+                                    limit $3",
+                                    dictionary.0,
+                                    query,
+                                    limit,
+                                )
+                                .fetch_all(&mut **tx)
+                                .await
+                            }
+                            search::SearchMode::Letters => {
+                                sqlx::query_scalar!(
+                                    "select word from words
+                                    where dictionary_id = $1 and (letter_mask & ~$2::bigint) = 0
+                                    and deleted_at is null
+                                    order by word
+                                    limit $3",
+                                    dictionary.0,
+                                    letters,
+                                    limit,
+                                )
+                                .fetch_all(&mut **tx)
+                                .await
+                            }
+                        }
+                    })
+                })
+                .await
+                .map_err(|e| match e {
+                    // invalid_regular_expression
+                    sqlx::Error::Database(e) if e.code().as_deref() == Some("2201B") => {
+                        super::SearchWordsError::InvalidPattern(e.message().to_owned())
+                    }
+                    e => super::SearchWordsError::DBError(Box::new(e)),
+                })
+            }
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct ListWords(pub(crate) sqlx::PgPool);
+
+        impl super::ListWords for ListWords {
+            #[tracing::instrument(skip(self))]
+            async fn list(
+                &self,
+                dictionary: DictionaryId,
+                cursor: &super::ListCursor,
+                limit: Option<usize>,
+            ) -> Result<super::ListedWords, super::ListWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.list");
+                let limit = limit.unwrap_or(200);
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(Self::page(tx, dictionary, cursor, limit))
+                })
+                .await
+                .map_err(|e| super::ListWordsError::DBError(Box::new(e)))
+            }
+
+            #[tracing::instrument(skip(self))]
+            async fn all(
+                &self,
+                dictionary: DictionaryId,
+            ) -> Result<Vec<String>, super::ListWordsError> {
+                let _timer = crate::telemetry::QueryTimer::start("words.all");
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query_scalar!(
+                            "select word from words
+                            where dictionary_id = $1 and deleted_at is null
+                            order by word",
+                            dictionary.0,
+                        )
+                        .fetch_all(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| super::ListWordsError::DBError(Box::new(e)))
+            }
+        }
+
+        impl ListWords {
+            /// Up to `limit` words from `cursor` in word order, with the cursors
+            /// of the pages next to them.
+            async fn page(
+                conn: &mut sqlx::PgConnection,
+                dictionary: DictionaryId,
+                cursor: &super::ListCursor,
+                limit: usize,
+            ) -> Result<super::ListedWords, sqlx::Error> {
+                let mut results = match cursor {
+                    super::ListCursor::After(after) => {
+                        sqlx::query_as!(
+                            ListedWord,
+                            r#"
+                             select word from words
+                             where dictionary_id = $1 and word > $2 and deleted_at is null
+                             order by word
+                             limit $3
+                         "#,
+                            dictionary.0,
+                            after,
+                            (limit + 1) as i32
+                        )
+                        .fetch_all(&mut *conn)
+                        .await?
+                    }
+                    super::ListCursor::Before(before) => {
+                        sqlx::query_as!(
+                            ListedWord,
+                            r#"
+                             select word from words
+                             where dictionary_id = $1 and word < $2 and deleted_at is null
+                             order by word desc
+                             limit $3
+                         "#,
+                            dictionary.0,
+                            before,
+                            (limit + 1) as i32
+                        )
+                        .fetch_all(&mut *conn)
+                        .await?
+                    }
+                };
+
+                // One extra word is fetched to tell whether there is another page in
+                // the direction of the cursor. The other direction needs a lookup on
+                // the far side of the page.
+                let has_more = results.len() > limit;
+                results.truncate(limit);
+                if let super::ListCursor::Before(_) = cursor {
+                    results.reverse();
+                }
+                let (first, last) = match (results.first(), results.last()) {
+                    (Some(first), Some(last)) => (first.word.clone(), last.word.clone()),
+                    _ => {
+                        return Ok(super::ListedWords {
+                            words: vec![],
+                            next_page: None,
+                            prev_page: None,
+                        });
+                    }
+                };
+                let (next_page, prev_page) = match cursor {
+                    super::ListCursor::After(_) => (
+                        has_more.then_some(super::ListCursor::After(last)),
+                        Self::any_before(conn, dictionary, &first)
+                            .await?
+                            .then_some(super::ListCursor::Before(first)),
+                    ),
+                    super::ListCursor::Before(_) => (
+                        Self::any_after(conn, dictionary, &last)
+                            .await?
+                            .then_some(super::ListCursor::After(last)),
+                        has_more.then_some(super::ListCursor::Before(first)),
+                    ),
+                };
+
+                Ok(super::ListedWords {
+                    words: results
+                        .into_iter()
+                        .map(|w| super::Word {
+                            text: w.word.clone(),
+                            cursor: super::ListCursor::After(w.word),
+                        })
+                        .collect(),
+                    next_page,
+                    prev_page,
+                })
+            }
+
+            async fn any_before(
+                conn: &mut sqlx::PgConnection,
+                dictionary: DictionaryId,
+                word: &str,
+            ) -> Result<bool, sqlx::Error> {
+                sqlx::query_scalar!(
+                    r#"select exists(
+                        select 1 from words
+                        where dictionary_id = $1 and word < $2 and deleted_at is null
+                    ) as "exists!""#,
+                    dictionary.0,
+                    word
+                )
+                .fetch_one(conn)
+                .await
+            }
+
+            async fn any_after(
+                conn: &mut sqlx::PgConnection,
+                dictionary: DictionaryId,
+                word: &str,
+            ) -> Result<bool, sqlx::Error> {
+                sqlx::query_scalar!(
+                    r#"select exists(
+                        select 1 from words
+                        where dictionary_id = $1 and word > $2 and deleted_at is null
+                    ) as "exists!""#,
+                    dictionary.0,
+                    word
+                )
+                .fetch_one(conn)
+                .await
+            }
+        }
+
+        #[derive(sqlx::FromRow)]
+        struct ListedWord {
+            word: String,
+        }
+    }
+
+    /// Word services backed by SQLite, for keeping the word list out of
+    /// Postgres.
+    ///
+    /// SQLite has no fuzzystrmatch, so searches rank every word by
+    /// [`search::distance`] instead. Served by [`crate::sqlite_app`].
+    #[cfg(feature = "sqlite")]
+    pub(crate) mod sqlite {
+        use super::{
+            AddWordsError, DictionaryId, ListWordsError, RemoveWordsError, SearchWordsError,
+        };
+
+        /// Rows added per insert, keeping under SQLite's limit on bound
+        /// parameters.
+        const INSERT_CHUNK: usize = 1000;
+
+        /// Create or update the words table in `pool`.
+        pub(crate) async fn migrate(
+            pool: &sqlx::SqlitePool,
+        ) -> Result<(), sqlx::migrate::MigrateError> {
+            sqlx::migrate!("../migrations-sqlite").run(pool).await
+        }
+
+        /// `words` as a JSON array, which SQLite can expand with `json_each`
+        /// where Postgres takes an array.
+        fn json_array(words: &[String]) -> String {
+            serde_json::to_string(words).expect("Failed to serialize words")
+        }
+
+        #[derive(Clone)]
+        /// Adds words spelled with the letters of the alphabet.
+        pub(crate) struct AddWords(pub(crate) sqlx::SqlitePool, pub(crate) words::Alphabet);
+
+        /// Record `action` on `words` of `dictionary` by `editor` in the audit
+        /// log.
+        async fn audit(
+            conn: &mut sqlx::SqliteConnection,
+            dictionary: DictionaryId,
+            words: &[String],
+            action: &str,
+            editor: super::Editor,
+        ) -> Result<(), sqlx::Error> {
+            sqlx::query(
+                "insert into word_audit (dictionary_id, word, action, user_id)
+                select ?, value, ?, ? from json_each(?)",
+            )
+            .bind(dictionary.0)
+            .bind(action)
+            .bind(editor)
+            .bind(json_array(words))
+            .execute(conn)
+            .await
+            .map(|_| ())
+        }
+
+        impl super::AddWords for AddWords {
+            async fn add_words(
+                &self,
+                dictionary: DictionaryId,
+                words: Vec<String>,
+                editor: super::Editor,
+            ) -> Result<u64, AddWordsError> {
+                let rows = words
+                    .into_iter()
+                    .map(|word| match self.1.try_bitmask(&word) {
+                        Ok(mask) => Ok((word, mask)),
+                        Err(error) => Err(AddWordsError::InvalidWord { word, error }),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut tx = self
+                    .0
+                    .begin()
+                    .await
+                    .map_err(|e| AddWordsError::DbError(Box::new(e)))?;
+                let mut added = Vec::new();
+                for chunk in rows.chunks(INSERT_CHUNK) {
+                    let mut builder = sqlx::QueryBuilder::new(
+                        "insert into words (dictionary_id, word, letter_mask, length) ",
+                    );
+                    builder.push_values(chunk, |mut b, (word, mask)| {
+                        b.push_bind(dictionary.0)
+                            .push_bind(word)
+                            .push_bind(i64::from(*mask))
+                            .push_bind(word.chars().count() as i32);
+                    });
+                    builder.push(
+                        " on conflict (dictionary_id, word) do update set deleted_at = null
+                        where deleted_at is not null
+                        returning word",
+                    );
+                    added.extend(
+                        builder
+                            .build_query_scalar::<String>()
+                            .fetch_all(&mut *tx)
+                            .await
+                            .map_err(|e| AddWordsError::DbError(Box::new(e)))?,
+                    );
+                }
+                audit(&mut tx, dictionary, &added, "added", editor)
+                    .await
+                    .map_err(|e| AddWordsError::DbError(Box::new(e)))?;
+                tx.commit()
+                    .await
+                    .map_err(|e| AddWordsError::DbError(Box::new(e)))?;
+                Ok(added.len() as u64)
+            }
+
+            async fn existing_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+            ) -> Result<std::collections::HashSet<String>, AddWordsError> {
+                sqlx::query_scalar(
+                    "select word from words
+                    where dictionary_id = ? and word in (select value from json_each(?))
+                    and deleted_at is null",
+                )
+                .bind(dictionary.0)
+                .bind(json_array(words))
+                .fetch_all(&self.0)
+                .await
+                .map_err(|e| AddWordsError::DbError(Box::new(e)))
+                .map(|words: Vec<String>| words.into_iter().collect())
+            }
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct RemoveWords(pub(crate) sqlx::SqlitePool);
+
+        impl super::RemoveWords for RemoveWords {
+            async fn remove_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+                editor: super::Editor,
+            ) -> Result<(), RemoveWordsError> {
+                self.mark(
+                    "update words set deleted_at = current_timestamp
+                    where dictionary_id = ? and word in (select value from json_each(?))
+                    and deleted_at is null
+                    returning word",
+                    dictionary,
+                    words,
+                    "removed",
+                    editor,
+                )
+                .await
+                .map(|_| ())
+            }
+
+            async fn restore_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+                editor: super::Editor,
+            ) -> Result<u64, RemoveWordsError> {
+                self.mark(
+                    "update words set deleted_at = null
+                    where dictionary_id = ? and word in (select value from json_each(?))
+                    and deleted_at is not null
+                    returning word",
+                    dictionary,
+                    words,
+                    "restored",
+                    editor,
+                )
+                .await
+            }
+        }
+
+        impl RemoveWords {
+            /// Update `words` of `dictionary` with `sql`, recording `action` on
+            /// those it returns.
+            async fn mark(
+                &self,
+                sql: &str,
+                dictionary: DictionaryId,
+                words: &[String],
+                action: &str,
+                editor: super::Editor,
+            ) -> Result<u64, RemoveWordsError> {
+                let result = async {
+                    let mut tx = self.0.begin().await?;
+                    let marked: Vec<String> = sqlx::query_scalar(sql)
+                        .bind(dictionary.0)
+                        .bind(json_array(words))
+                        .fetch_all(&mut *tx)
+                        .await?;
+                    audit(&mut tx, dictionary, &marked, action, editor).await?;
+                    tx.commit().await?;
+                    Ok::<_, sqlx::Error>(marked.len() as u64)
+                };
+                result
+                    .await
+                    .map_err(|e| RemoveWordsError::DbError(Box::new(e)))
+            }
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct SearchWords(pub(crate) sqlx::SqlitePool);
+
+        impl super::SearchWords for SearchWords {
+            async fn search(
+                &self,
+                dictionary: DictionaryId,
+                query: &str,
+                mode: search::SearchMode,
+                limit: usize,
+            ) -> Result<super::SearchResult, SearchWordsError> {
+                match mode {
+                    search::SearchMode::Prefix => {
+                        self.matching(
+                            "select word from words where substr(word, 1, length(?2)) = ?2
+                            and dictionary_id = ?1 and deleted_at is null
+                            order by word limit ?3",
+                            dictionary,
+                            query,
+                            limit,
+                        )
+                        .await
+                    }
+                    search::SearchMode::Substring => {
+                        self.matching(
+                            "select word from words where instr(word, ?2) > 0
+                            and dictionary_id = ?1 and deleted_at is null
+                            order by word limit ?3",
+                            dictionary,
+                            query,
+                            limit,
+                        )
+                        .await
+                    }
+                    search::SearchMode::Letters => {
+                        let mask = super::letter_mask(query)?;
+                        self.matching(
+                            "select word from words where (letter_mask & ~?2) = 0
+                            and dictionary_id = ?1 and deleted_at is null
+                            order by word limit ?3",
+                            dictionary,
+                            mask,
+                            limit,
+                        )
+                        .await
+                    }
+                    // SQLite can neither rank by distance nor match regular
+                    // expressions, so every word is checked here.
+                    search::SearchMode::Fuzzy => {
+                        Ok(search::closest(query, self.all(dictionary).await?, limit))
+                    }
+                    search::SearchMode::Regex => {
+                        let pattern = regex::Regex::new(query)
+                            .map_err(|e| SearchWordsError::InvalidPattern(e.to_string()))?;
+                        Ok(self
+                            .all(dictionary)
+                            .await?
+                            .into_iter()
+                            .filter(|word| pattern.is_match(word))
+                            .take(limit)
+                            .collect())
+                    }
+                }
+            }
+        }
+
+        impl SearchWords {
+            /// Words of `dictionary` selected by `sql`, which binds the
+            /// dictionary, `param` and the limit.
+            async fn matching<'q, T>(
+                &self,
+                sql: &'q str,
+                dictionary: DictionaryId,
+                param: T,
+                limit: usize,
+            ) -> Result<Vec<String>, SearchWordsError>
+            where
+                T: 'q + sqlx::Encode<'q, sqlx::Sqlite> + sqlx::Type<sqlx::Sqlite> + Send,
+            {
+                sqlx::query_scalar(sql)
+                    .bind(dictionary.0)
+                    .bind(param)
+                    .bind(limit as i64)
+                    .fetch_all(&self.0)
+                    .await
+                    .map_err(|e| SearchWordsError::DBError(Box::new(e)))
+            }
+
+            async fn all(&self, dictionary: DictionaryId) -> Result<Vec<String>, SearchWordsError> {
+                sqlx::query_scalar(
+                    "select word from words where dictionary_id = ? and deleted_at is null
+                    order by word",
+                )
+                .bind(dictionary.0)
+                .fetch_all(&self.0)
+                .await
+                .map_err(|e| SearchWordsError::DBError(Box::new(e)))
+            }
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct ListWords(pub(crate) sqlx::SqlitePool);
+
+        impl super::ListWords for ListWords {
+            async fn list(
+                &self,
+                dictionary: DictionaryId,
+                cursor: &super::ListCursor,
+                limit: Option<usize>,
+            ) -> Result<super::ListedWords, ListWordsError> {
+                let limit = limit.unwrap_or(200);
+                let (query, word) = match cursor {
+                    super::ListCursor::After(after) => (
+                        "select word from words
+                        where dictionary_id = ? and word > ? and deleted_at is null
+                        order by word limit ?",
+                        after,
+                    ),
+                    super::ListCursor::Before(before) => (
+                        "select word from words
+                        where dictionary_id = ? and word < ? and deleted_at is null
+                        order by word desc limit ?",
+                        before,
+                    ),
+                };
+                let mut results: Vec<String> = sqlx::query_scalar(query)
+                    .bind(dictionary.0)
+                    .bind(word)
+                    .bind((limit + 1) as i64)
+                    .fetch_all(&self.0)
+                    .await
+                    .map_err(|e| ListWordsError::DBError(Box::new(e)))?;
+
+                // As with Postgres, one extra word tells whether there's another
+                // page in the direction of the cursor.
+                let has_more = results.len() > limit;
+                results.truncate(limit);
+                if let super::ListCursor::Before(_) = cursor {
+                    results.reverse();
+                }
+                let (first, last) = match (results.first(), results.last()) {
+                    (Some(first), Some(last)) => (first.clone(), last.clone()),
+                    _ => {
+                        return Ok(super::ListedWords {
+                            words: vec![],
+                            next_page: None,
+                            prev_page: None,
+                        });
+                    }
+                };
+                let (next_page, prev_page) = match cursor {
+                    super::ListCursor::After(_) => (
+                        has_more.then_some(super::ListCursor::After(last)),
+                        self.any(dictionary, "<", &first)
+                            .await?
+                            .then_some(super::ListCursor::Before(first)),
+                    ),
+                    super::ListCursor::Before(_) => (
+                        self.any(dictionary, ">", &last)
+                            .await?
+                            .then_some(super::ListCursor::After(last)),
+                        has_more.then_some(super::ListCursor::Before(first)),
+                    ),
+                };
+
+                Ok(super::ListedWords {
+                    words: results
+                        .into_iter()
+                        .map(|word| super::Word {
+                            text: word.clone(),
+                            cursor: super::ListCursor::After(word),
+                        })
+                        .collect(),
+                    next_page,
+                    prev_page,
+                })
+            }
+
+            async fn all(&self, dictionary: DictionaryId) -> Result<Vec<String>, ListWordsError> {
+                sqlx::query_scalar(
+                    "select word from words where dictionary_id = ? and deleted_at is null
+                    order by word",
+                )
+                .bind(dictionary.0)
+                .fetch_all(&self.0)
+                .await
+                .map_err(|e| ListWordsError::DBError(Box::new(e)))
+            }
+        }
+
+        impl ListWords {
+            /// Whether any word of `dictionary` compares to `word` by
+            /// `comparison`, `<` or `>`.
+            async fn any(
+                &self,
+                dictionary: DictionaryId,
+                comparison: &str,
+                word: &str,
+            ) -> Result<bool, ListWordsError> {
+                sqlx::query_scalar(&format!(
+                    "select exists(select 1 from words
+                    where dictionary_id = ? and word {} ? and deleted_at is null)",
+                    comparison
+                ))
+                .bind(dictionary.0)
+                .bind(word)
+                .fetch_one(&self.0)
+                .await
+                .map_err(|e| ListWordsError::DBError(Box::new(e)))
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use crate::services::words::{
+                AddWords as _, ListCursor, ListWords as _, RemoveWords as _, SearchWords as _,
+            };
+            use search::SearchMode;
+
+            use super::*;
+
+            const STANDARD: DictionaryId = DictionaryId::STANDARD;
+            const LATIN: words::Alphabet = words::Alphabet::LATIN;
+
+            async fn pool() -> sqlx::SqlitePool {
+                // Every connection to an in-memory database gets its own, so
+                // only one is opened.
+                let pool = sqlx::sqlite::SqlitePoolOptions::new()
+                    .max_connections(1)
+                    .connect("sqlite::memory:")
+                    .await
+                    .unwrap();
+                migrate(&pool).await.unwrap();
+                pool
+            }
+
+            fn words(words: &[&str]) -> Vec<String> {
+                words.iter().map(|w| (*w).to_owned()).collect()
+            }
+
+            #[tokio::test]
+            async fn adds_lists_and_removes_words() {
+                let pool = pool().await;
+
+                let added = AddWords(pool.clone(), LATIN)
+                    .add_words(STANDARD, words(&["crane", "apple", "bread", "apple"]), None)
+                    .await
+                    .unwrap();
+                assert_eq!(3, added);
+                assert!(matches!(
+                    AddWords(pool.clone(), LATIN)
+                        .add_words(STANDARD, words(&["Eagle"]), None)
+                        .await,
+                    Err(AddWordsError::InvalidWord { .. })
+                ));
+                assert_eq!(
+                    ["apple".to_owned()]
+                        .into_iter()
+                        .collect::<std::collections::HashSet<_>>(),
+                    AddWords(pool.clone(), LATIN)
+                        .existing_words(STANDARD, &words(&["apple", "dance"]))
+                        .await
+                        .unwrap()
+                );
+
+                let page = ListWords(pool.clone())
+                    .list(STANDARD, &ListCursor::default(), Some(2))
+                    .await
+                    .unwrap();
+                let listed: Vec<_> = page.words.iter().map(|w| w.text.as_str()).collect();
+                assert_eq!(vec!["apple", "bread"], listed);
+                assert_eq!(Some(ListCursor::After("bread".to_owned())), page.next_page);
+                assert_eq!(None, page.prev_page);
+
+                let page = ListWords(pool.clone())
+                    .list(STANDARD, &ListCursor::Before("crane".to_owned()), Some(1))
+                    .await
+                    .unwrap();
+                let listed: Vec<_> = page.words.iter().map(|w| w.text.as_str()).collect();
+                assert_eq!(vec!["bread"], listed);
+                assert_eq!(Some(ListCursor::After("bread".to_owned())), page.next_page);
+                assert_eq!(Some(ListCursor::Before("bread".to_owned())), page.prev_page);
+
+                RemoveWords(pool.clone())
+                    .remove_words(STANDARD, &words(&["bread"]), None)
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    words(&["apple", "crane"]),
+                    ListWords(pool.clone()).all(STANDARD).await.unwrap()
+                );
+
+                let restored = RemoveWords(pool.clone())
+                    .restore_words(STANDARD, &words(&["bread", "crane"]), None)
+                    .await
+                    .unwrap();
+                assert_eq!(1, restored);
+                assert_eq!(
+                    words(&["apple", "bread", "crane"]),
+                    ListWords(pool.clone()).all(STANDARD).await.unwrap()
+                );
+                let actions: Vec<String> = sqlx::query_scalar(
+                    "select action from word_audit where word = 'bread' order by id",
+                )
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+                assert_eq!(words(&["added", "removed", "restored"]), actions);
+            }
+
+            #[tokio::test]
+            async fn stores_the_length_of_words_in_letters() {
+                let pool = pool().await;
+                AddWords(pool.clone(), words::Alphabet::SPANISH)
+                    .add_words(STANDARD, words(&["pañuelo"]), None)
+                    .await
+                    .unwrap();
+
+                let length: i32 =
+                    sqlx::query_scalar("select length from words where word = 'pañuelo'")
+                        .fetch_one(&pool)
+                        .await
+                        .unwrap();
+                assert_eq!(7, length);
+            }
+
+            #[tokio::test]
+            async fn searches_in_every_mode() {
+                let pool = pool().await;
+                AddWords(pool.clone(), LATIN)
+                    .add_words(STANDARD, words(&["crane", "crate", "apple"]), None)
+                    .await
+                    .unwrap();
+
+                let search = SearchWords(pool);
+                let results = search
+                    .search(STANDARD, "cran", SearchMode::Fuzzy, 15)
+                    .await
+                    .unwrap();
+                assert_eq!(words(&["crane", "crate", "apple"]), results);
+                let results = search
+                    .search(STANDARD, "cra", SearchMode::Prefix, 1)
+                    .await
+                    .unwrap();
+                assert_eq!(words(&["crane"]), results);
+                let results = search
+                    .search(STANDARD, "pl", SearchMode::Substring, 15)
+                    .await
+                    .unwrap();
+                assert_eq!(words(&["apple"]), results);
+                let results = search
+                    .search(STANDARD, "^cr.ne$", SearchMode::Regex, 15)
+                    .await
+                    .unwrap();
+                assert_eq!(words(&["crane"]), results);
+                let results = search
+                    .search(STANDARD, "acenrt", SearchMode::Letters, 15)
+                    .await
+                    .unwrap();
+                assert_eq!(words(&["crane", "crate"]), results);
+                assert!(matches!(
+                    search.search(STANDARD, "(", SearchMode::Regex, 15).await,
+                    Err(SearchWordsError::InvalidPattern(_))
+                ));
+            }
+
+            #[tokio::test]
+            async fn keeps_dictionaries_apart() {
+                let pool = pool().await;
+                sqlx::query("insert into dictionaries (id, name) values (2, 'expanded')")
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+                let expanded = DictionaryId(2);
+
+                AddWords(pool.clone(), LATIN)
+                    .add_words(STANDARD, words(&["apple", "bread"]), None)
+                    .await
+                    .unwrap();
+                let added = AddWords(pool.clone(), LATIN)
+                    .add_words(expanded, words(&["apple", "crane"]), None)
+                    .await
+                    .unwrap();
+                assert_eq!(2, added);
+
+                RemoveWords(pool.clone())
+                    .remove_words(expanded, &words(&["apple"]), None)
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    words(&["apple", "bread"]),
+                    ListWords(pool.clone()).all(STANDARD).await.unwrap()
+                );
+                assert_eq!(
+                    words(&["crane"]),
+                    ListWords(pool.clone()).all(expanded).await.unwrap()
+                );
+            }
+        }
+    }
+
+    /// Word services holding their words in memory, for testing handlers
+    /// without a database.
+    #[cfg(test)]
+    pub(crate) mod memory {
+        use std::collections::{BTreeMap, HashMap, HashSet};
+        use std::sync::{Arc, Mutex};
+
+        use super::{
+            AddWordsError, DictionaryId, ListCursor, ListWordsError, ListedWords, RemoveWordsError,
+            SearchWordsError,
+        };
+
+        /// Words of every dictionary, each marked with whether it was removed.
+        /// Clones share their words, so one can be given to each service of a
+        /// handler.
+        #[derive(Clone, Default)]
+        pub(crate) struct Words(Arc<Mutex<HashMap<DictionaryId, BTreeMap<String, bool>>>>);
+
+        impl Words {
+            /// A dictionary of `words`.
+            pub(crate) fn of(dictionary: DictionaryId, words: &[&str]) -> Self {
+                let store = Self::default();
+                store.dictionary(dictionary, |list| {
+                    list.extend(words.iter().map(|word| ((*word).to_owned(), false)))
+                });
+                store
+            }
+
+            /// Words of `dictionary` that weren't removed, in order.
+            pub(crate) fn listed(&self, dictionary: DictionaryId) -> Vec<String> {
+                self.dictionary(dictionary, |list| {
+                    list.iter()
+                        .filter(|(_, removed)| !**removed)
+                        .map(|(word, _)| word.clone())
+                        .collect()
+                })
+            }
+
+            fn dictionary<T>(
+                &self,
+                dictionary: DictionaryId,
+                f: impl FnOnce(&mut BTreeMap<String, bool>) -> T,
+            ) -> T {
+                f(self.0.lock().unwrap().entry(dictionary).or_default())
+            }
+        }
+
+        impl super::AddWords for Words {
+            async fn add_words(
+                &self,
+                dictionary: DictionaryId,
+                words: Vec<String>,
+                _editor: super::Editor,
+            ) -> Result<u64, AddWordsError> {
+                for word in &words {
+                    if let Err(error) = words::try_bitmask(word) {
+                        return Err(AddWordsError::InvalidWord {
+                            word: word.clone(),
+                            error,
+                        });
+                    }
+                }
+                Ok(self.dictionary(dictionary, |list| {
+                    words
+                        .into_iter()
+                        .filter(|word| list.insert(word.clone(), false).is_none())
+                        .count() as u64
+                }))
+            }
+
+            async fn existing_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+            ) -> Result<HashSet<String>, AddWordsError> {
+                Ok(self.dictionary(dictionary, |list| {
+                    words
+                        .iter()
+                        .filter(|word| list.contains_key(*word))
+                        .cloned()
+                        .collect()
+                }))
+            }
+        }
+
+        impl super::RemoveWords for Words {
+            async fn remove_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+                _editor: super::Editor,
+            ) -> Result<(), RemoveWordsError> {
+                self.dictionary(dictionary, |list| {
+                    for word in words {
+                        if let Some(removed) = list.get_mut(word) {
+                            *removed = true;
+                        }
+                    }
+                });
+                Ok(())
+            }
+
+            async fn restore_words(
+                &self,
+                dictionary: DictionaryId,
+                words: &[String],
+                _editor: super::Editor,
+            ) -> Result<u64, RemoveWordsError> {
+                Ok(self.dictionary(dictionary, |list| {
+                    let mut restored = 0;
+                    for word in words {
+                        if let Some(removed) = list.get_mut(word) {
+                            restored += u64::from(std::mem::replace(removed, false));
+                        }
+                    }
+                    restored
+                }))
+            }
+        }
+
+        impl super::SearchWords for Words {
+            async fn search(
+                &self,
+                dictionary: DictionaryId,
+                query: &str,
+                mode: search::SearchMode,
+                limit: usize,
+            ) -> Result<super::SearchResult, SearchWordsError> {
+                let listed = self.listed(dictionary);
+                let matches: Vec<String> = match mode {
+                    search::SearchMode::Fuzzy => return Ok(search::closest(query, listed, limit)),
+                    search::SearchMode::Prefix => listed
+                        .into_iter()
+                        .filter(|word| word.starts_with(query))
+                        .collect(),
+                    search::SearchMode::Substring => listed
+                        .into_iter()
+                        .filter(|word| word.contains(query))
+                        .collect(),
+                    search::SearchMode::Regex => {
+                        let pattern = regex::Regex::new(query)
+                            .map_err(|e| SearchWordsError::InvalidPattern(e.to_string()))?;
+                        listed
+                            .into_iter()
+                            .filter(|word| pattern.is_match(word))
+                            .collect()
+                    }
+                    search::SearchMode::Letters => {
+                        let letters = super::letter_mask(query)?;
+                        listed
+                            .into_iter()
+                            .filter(|word| {
+                                words::try_bitmask(word)
+                                    .is_ok_and(|mask| i64::from(mask) & !letters == 0)
+                            })
+                            .collect()
+                    }
+                };
+                Ok(matches.into_iter().take(limit).collect())
+            }
+        }
+
+        impl super::ListWords for Words {
+            async fn list(
+                &self,
+                dictionary: DictionaryId,
+                cursor: &ListCursor,
+                limit: Option<usize>,
+            ) -> Result<ListedWords, ListWordsError> {
+                let limit = limit.unwrap_or(200);
+                let listed = self.listed(dictionary);
+                let page: Vec<String> = match cursor {
+                    ListCursor::After(after) => listed
+                        .iter()
+                        .filter(|word| *word > after)
+                        .take(limit)
+                        .cloned()
+                        .collect(),
+                    ListCursor::Before(before) => {
+                        let earlier: Vec<&String> =
+                            listed.iter().filter(|word| *word < before).collect();
+                        let skipped = earlier.len().saturating_sub(limit);
+                        earlier.into_iter().skip(skipped).cloned().collect()
+                    }
+                };
+                let (next_page, prev_page) = match (page.first(), page.last()) {
+                    (Some(first), Some(last)) => (
+                        listed
+                            .iter()
+                            .any(|word| word > last)
+                            .then(|| ListCursor::After(last.clone())),
+                        listed
+                            .iter()
+                            .any(|word| word < first)
+                            .then(|| ListCursor::Before(first.clone())),
+                    ),
+                    _ => (None, None),
+                };
+                Ok(ListedWords {
+                    words: page
+                        .into_iter()
+                        .map(|word| super::Word {
+                            text: word.clone(),
+                            cursor: ListCursor::After(word),
+                        })
+                        .collect(),
+                    next_page,
+                    prev_page,
+                })
+            }
+
+            async fn all(&self, dictionary: DictionaryId) -> Result<Vec<String>, ListWordsError> {
+                Ok(self.listed(dictionary))
+            }
+        }
+
+        /// Word services failing every call, as if the database were down.
+        #[derive(Clone)]
+        pub(crate) struct Unavailable;
+
+        fn unavailable() -> Box<dyn std::error::Error> {
+            "connection refused".into()
+        }
+
+        impl super::AddWords for Unavailable {
+            async fn add_words(
+                &self,
+                _dictionary: DictionaryId,
+                _words: Vec<String>,
+                _editor: super::Editor,
+            ) -> Result<u64, AddWordsError> {
+                Err(AddWordsError::DbError(unavailable()))
+            }
+
+            async fn existing_words(
+                &self,
+                _dictionary: DictionaryId,
+                _words: &[String],
+            ) -> Result<HashSet<String>, AddWordsError> {
+                Err(AddWordsError::DbError(unavailable()))
+            }
+        }
+
+        impl super::RemoveWords for Unavailable {
+            async fn remove_words(
+                &self,
+                _dictionary: DictionaryId,
+                _words: &[String],
+                _editor: super::Editor,
+            ) -> Result<(), RemoveWordsError> {
+                Err(RemoveWordsError::DbError(unavailable()))
+            }
+
+            async fn restore_words(
+                &self,
+                _dictionary: DictionaryId,
+                _words: &[String],
+                _editor: super::Editor,
+            ) -> Result<u64, RemoveWordsError> {
+                Err(RemoveWordsError::DbError(unavailable()))
+            }
+        }
+
+        impl super::SearchWords for Unavailable {
+            async fn search(
+                &self,
+                _dictionary: DictionaryId,
+                _query: &str,
+                _mode: search::SearchMode,
+                _limit: usize,
+            ) -> Result<super::SearchResult, SearchWordsError> {
+                Err(SearchWordsError::DBError(unavailable()))
+            }
+        }
+
+        impl super::ListWords for Unavailable {
+            async fn list(
+                &self,
+                _dictionary: DictionaryId,
+                _cursor: &ListCursor,
+                _limit: Option<usize>,
+            ) -> Result<ListedWords, ListWordsError> {
+                Err(ListWordsError::DBError(unavailable()))
+            }
+
+            async fn all(&self, _dictionary: DictionaryId) -> Result<Vec<String>, ListWordsError> {
+                Err(ListWordsError::DBError(unavailable()))
+            }
+        }
+    }
+}
+
+pub(crate) mod puzzles {
+    use std::fmt::Display;
+
+    use chrono::NaiveDate;
+    use puzzle_config::Difficulty;
+
+    use super::dictionaries::DictionaryId;
+
+    pub(crate) trait ExcludeWords {
+        async fn exclude_words(
+            &self,
+            date: NaiveDate,
+            words: &[String],
+        ) -> Result<(), ExcludeWordsError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum ExcludeWordsError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for ExcludeWordsError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to exclude words due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ExcludeWordsError {}
+
+    pub(crate) trait ListExclusions {
+        async fn list_exclusions(
+            &self,
+            date: NaiveDate,
+        ) -> Result<Vec<String>, ListExclusionsError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum ListExclusionsError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for ListExclusionsError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to list exclusions due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ListExclusionsError {}
+
+    /// Storage for generated puzzles, keyed by the day, the UTC offset and the
+    /// difficulty they were generated for.
+    ///
+    /// Single puzzles are read and stored with [`pg::stored_puzzle`] and its
+    /// neighbours instead, on the transaction holding the puzzle's lock.
+    pub(crate) trait PuzzleRepository {
+        /// The offset, difficulty and dictionary of every puzzle stored for `date`.
+        async fn list_stored(
+            &self,
+            date: NaiveDate,
+        ) -> Result<Vec<(i32, Difficulty, DictionaryId)>, PuzzleRepositoryError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum PuzzleRepositoryError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for PuzzleRepositoryError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to access stored puzzles due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for PuzzleRepositoryError {}
+
+    /// Letter sets and events curators have pinned to days, which take the place
+    /// of a randomly generated puzzle.
+    pub(crate) trait ScheduleRepository {
+        /// Every scheduled day from `from` to `to` inclusive, in date order.
+        async fn list(
+            &self,
+            from: NaiveDate,
+            to: NaiveDate,
+        ) -> Result<Vec<ScheduledPuzzle>, ScheduleRepositoryError>;
+
+        /// Replace whatever was scheduled for the day of `puzzle`. Scheduling
+        /// neither letters nor an event clears the day.
+        async fn save(&self, puzzle: &ScheduledPuzzle) -> Result<(), ScheduleRepositoryError>;
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct ScheduledPuzzle {
+        pub(crate) date: NaiveDate,
+        pub(crate) letters: Option<ScheduledLetters>,
+        pub(crate) event: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) struct ScheduledLetters {
+        pub(crate) required: char,
+        /// The six other letters.
+        pub(crate) others: Vec<char>,
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum ScheduleRepositoryError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for ScheduleRepositoryError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to access the puzzle schedule due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ScheduleRepositoryError {}
+
+    /// Storage for puzzles players made from letters of their choice, keyed by
+    /// a short random ID they can share.
+    pub(crate) trait CustomPuzzleRepository {
+        /// Store `config` under a new ID and return the ID.
+        async fn create(
+            &self,
+            config: &puzzle_config::PuzzleConfig,
+        ) -> Result<String, CustomPuzzleRepositoryError>;
+
+        async fn get(
+            &self,
+            id: &str,
+        ) -> Result<Option<puzzle_config::PuzzleConfig>, CustomPuzzleRepositoryError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum CustomPuzzleRepositoryError {
+        DbError(Box<dyn std::error::Error>),
+        /// Every ID tried was taken.
+        NoFreeId,
+    }
+
+    impl Display for CustomPuzzleRepositoryError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to access custom puzzles due to database error: {}",
+                        error
+                    )
+                }
+                Self::NoFreeId => write!(f, "Failed to find a free ID for the custom puzzle"),
+            }
+        }
+    }
+
+    impl std::error::Error for CustomPuzzleRepositoryError {}
+
+    pub(crate) mod pg {
+        use std::collections::HashMap;
+
+        use chrono::NaiveDate;
+
+        use puzzle_config::{Difficulty, Letter, PuzzleConfig, Word};
+
+        use super::{
+            CustomPuzzleRepositoryError, DictionaryId, ExcludeWordsError, ListExclusionsError,
+            PuzzleRepositoryError, ScheduleRepositoryError, ScheduledLetters, ScheduledPuzzle,
+        };
+
+        /// Length of custom puzzle IDs, short enough to read out but with far
+        /// more IDs than puzzles.
+        const CUSTOM_ID_LEN: usize = 8;
+        /// IDs tried before giving up, in the unlikely event they're all taken.
+        const CUSTOM_ID_ATTEMPTS: usize = 5;
+
+        #[derive(Clone)]
+        pub(crate) struct ExcludeWords(pub(crate) sqlx::PgPool);
+
+        impl super::ExcludeWords for ExcludeWords {
+            async fn exclude_words(
+                &self,
+                date: NaiveDate,
+                words: &[String],
+            ) -> Result<(), ExcludeWordsError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "insert into puzzle_word_exclusions (puzzle_date, word)
+                            select $1, * from unnest($2::text[])
+                            on conflict do nothing",
+                            date,
+                            words
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| ExcludeWordsError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct ListExclusions(pub(crate) sqlx::PgPool);
+
+        impl super::ListExclusions for ListExclusions {
+            async fn list_exclusions(
+                &self,
+                date: NaiveDate,
+            ) -> Result<Vec<String>, ListExclusionsError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query_scalar!(
+                    "select word from puzzle_word_exclusions where puzzle_date = $1 order by word",
+                    date
+                )
+                .fetch_all(&mut **tx)
+                .await
+                    })
+                })
+                .await
+                .map_err(|e| ListExclusionsError::DbError(Box::new(e)))
+            }
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct PuzzleRepository(pub(crate) sqlx::PgPool);
+
+        impl super::PuzzleRepository for PuzzleRepository {
+            async fn list_stored(
+                &self,
+                date: NaiveDate,
+            ) -> Result<Vec<(i32, Difficulty, DictionaryId)>, PuzzleRepositoryError> {
+                let rows = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "select utc_offset, difficulty, dictionary_id
+                            from puzzles
+                            where puzzle_date = $1
+                            order by utc_offset, difficulty, dictionary_id",
+                            date,
+                        )
+                        .fetch_all(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| PuzzleRepositoryError::DbError(Box::new(e)))?;
+
+                rows.into_iter()
+                    .map(|row| {
+                        let difficulty = row
+                            .difficulty
+                            .parse()
+                            .map_err(|e: String| PuzzleRepositoryError::DbError(e.into()))?;
+                        Ok((row.utc_offset, difficulty, DictionaryId(row.dictionary_id)))
+                    })
+                    .collect()
+            }
+        }
+
+        /// The puzzle stored for `date`, `utc_offset`, `difficulty` and
+        /// `dictionary`, read on `conn` so that it can be done within the
+        /// transaction holding the puzzle's lock rather than on a connection of
+        /// its own.
+        pub(crate) async fn stored_puzzle(
+            conn: &mut sqlx::PgConnection,
+            date: NaiveDate,
+            utc_offset: i32,
+            difficulty: Difficulty,
+            dictionary: DictionaryId,
+        ) -> Result<Option<PuzzleConfig>, sqlx::Error> {
+            let row = sqlx::query_as!(
+                StoredPuzzle,
+                "select required_letter, other_letters, words, pangrams
+                from puzzles
+                where puzzle_date = $1 and utc_offset = $2 and difficulty = $3
+                and dictionary_id = $4",
+                date,
+                utc_offset,
+                difficulty.as_str(),
+                dictionary.0,
+            )
+            .fetch_optional(&mut *conn)
+            .await?;
+            let Some(row) = row else {
+                return Ok(None);
+            };
+
+            // Definitions and obscurity are read from the word list rather
+            // than stored with the puzzle, so edits show up in past puzzles
+            // too.
+            let metadata = sqlx::query_as!(
+                WordMetadata,
+                "select word, definition, obscurity
+                from words
+                where dictionary_id = $1 and word = any($2)
+                and (definition is not null or obscurity is not null)",
+                dictionary.0,
+                &row.words,
+            )
+            .fetch_all(conn)
+            .await?;
+
+            Ok(Some(row.into_config(metadata)))
+        }
+
+        /// Store `config` on `conn` unless a puzzle has already been stored for
+        /// the same day, offset, difficulty and dictionary.
+        pub(crate) async fn store_puzzle(
+            conn: &mut sqlx::PgConnection,
+            date: NaiveDate,
+            utc_offset: i32,
+            difficulty: Difficulty,
+            dictionary: DictionaryId,
+            config: &PuzzleConfig,
+        ) -> Result<(), sqlx::Error> {
+            let (other_letters, words, pangrams) = stored_columns(config);
+            sqlx::query!(
+                "insert into puzzles
+                (puzzle_date, utc_offset, difficulty, dictionary_id, required_letter,
+                other_letters, words, pangrams)
+                values ($1, $2, $3, $4, $5, $6, $7, $8)
+                on conflict do nothing",
+                date,
+                utc_offset,
+                difficulty.as_str(),
+                dictionary.0,
+                config.required_letter.0.to_string(),
+                other_letters,
+                &words,
+                &pangrams,
+            )
+            .execute(conn)
+            .await
+            .map(|_| ())
+        }
+
+        /// Store `config` on `conn` in place of any puzzle stored for the same
+        /// day, offset, difficulty and dictionary.
+        pub(crate) async fn replace_puzzle(
+            conn: &mut sqlx::PgConnection,
+            date: NaiveDate,
+            utc_offset: i32,
+            difficulty: Difficulty,
+            dictionary: DictionaryId,
+            config: &PuzzleConfig,
+        ) -> Result<(), sqlx::Error> {
+            let (other_letters, words, pangrams) = stored_columns(config);
+            sqlx::query!(
+                "insert into puzzles
+                (puzzle_date, utc_offset, difficulty, dictionary_id, required_letter,
+                other_letters, words, pangrams)
+                values ($1, $2, $3, $4, $5, $6, $7, $8)
+                on conflict (puzzle_date, utc_offset, difficulty, dictionary_id)
+                do update set
+                required_letter = excluded.required_letter,
+                other_letters = excluded.other_letters,
+                words = excluded.words,
+                pangrams = excluded.pangrams",
+                date,
+                utc_offset,
+                difficulty.as_str(),
+                dictionary.0,
+                config.required_letter.0.to_string(),
+                other_letters,
+                &words,
+                &pangrams,
+            )
+            .execute(conn)
+            .await
+            .map(|_| ())
+        }
+
+        /// The other letters, words and pangrams of `config` as they're stored,
+        /// with the pangrams first among the words.
+        fn stored_columns(config: &PuzzleConfig) -> (String, Vec<String>, Vec<String>) {
+            let other_letters: String = config.other_letters.iter().map(|l| l.0).collect();
+            let (pangrams, words): (Vec<_>, Vec<_>) =
+                config.valid_words.iter().partition(|w| w.is_pangram);
+            let words: Vec<_> = pangrams
+                .iter()
+                .chain(&words)
+                .map(|w| w.word.clone())
+                .collect();
+            let pangrams: Vec<_> = pangrams.into_iter().map(|w| w.word.clone()).collect();
+            (other_letters, words, pangrams)
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct ScheduleRepository(pub(crate) sqlx::PgPool);
+
+        impl super::ScheduleRepository for ScheduleRepository {
+            async fn list(
+                &self,
+                from: NaiveDate,
+                to: NaiveDate,
+            ) -> Result<Vec<ScheduledPuzzle>, ScheduleRepositoryError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(scheduled_puzzles(tx, from, to))
+                })
+                .await
+                .map_err(|e| ScheduleRepositoryError::DbError(Box::new(e)))
+            }
+
+            async fn save(&self, puzzle: &ScheduledPuzzle) -> Result<(), ScheduleRepositoryError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        if puzzle.letters.is_none() && puzzle.event.is_none() {
+                            return sqlx::query!(
+                                "delete from scheduled_puzzles where puzzle_date = $1",
+                                puzzle.date
+                            )
+                            .execute(&mut **tx)
+                            .await;
+                        }
+
+                        sqlx::query!(
+                            "insert into scheduled_puzzles
+                            (puzzle_date, required_letter, other_letters, event)
+                            values ($1, $2, $3, $4)
+                            on conflict (puzzle_date) do update set
+                            required_letter = excluded.required_letter,
+                            other_letters = excluded.other_letters,
+                            event = excluded.event",
+                            puzzle.date,
+                            puzzle.letters.as_ref().map(|l| l.required.to_string()),
+                            puzzle
+                                .letters
+                                .as_ref()
+                                .map(|l| l.others.iter().collect::<String>()),
+                            puzzle.event,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| ScheduleRepositoryError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+        }
+
+        /// Every day scheduled from `from` to `to` inclusive, in date order,
+        /// read on `conn` so that it can be done within the transaction
+        /// holding a puzzle's lock.
+        pub(crate) async fn scheduled_puzzles(
+            conn: &mut sqlx::PgConnection,
+            from: NaiveDate,
+            to: NaiveDate,
+        ) -> Result<Vec<ScheduledPuzzle>, sqlx::Error> {
+            let rows = sqlx::query!(
+                "select puzzle_date, required_letter, other_letters, event
+                from scheduled_puzzles
+                where puzzle_date between $1 and $2
+                order by puzzle_date",
+                from,
+                to,
+            )
+            .fetch_all(conn)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| ScheduledPuzzle {
+                    date: row.puzzle_date,
+                    letters: row
+                        .required_letter
+                        .and_then(|r| r.chars().next())
+                        .zip(row.other_letters)
+                        .map(|(required, others)| ScheduledLetters {
+                            required,
+                            others: others.chars().collect(),
+                        }),
+                    event: row.event,
+                })
+                .collect())
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct CustomPuzzleRepository(pub(crate) sqlx::PgPool);
+
+        impl super::CustomPuzzleRepository for CustomPuzzleRepository {
+            async fn create(
+                &self,
+                config: &PuzzleConfig,
+            ) -> Result<String, CustomPuzzleRepositoryError> {
+                use rand::{Rng as _, distr::Alphanumeric};
+
+                let other_letters: String = config.other_letters.iter().map(|l| l.0).collect();
+                let words: Vec<_> = config.valid_words.iter().map(|w| w.word.clone()).collect();
+                let pangrams: Vec<_> = config
+                    .valid_words
+                    .iter()
+                    .filter(|w| w.is_pangram)
+                    .map(|w| w.word.clone())
+                    .collect();
+                for _ in 0..CUSTOM_ID_ATTEMPTS {
+                    let id: String = rand::rng()
+                        .sample_iter(Alphanumeric)
+                        .take(CUSTOM_ID_LEN)
+                        .map(char::from)
+                        .collect();
+                    let stored = crate::services::db::with_retry(&self.0, |tx| {
+                        let (id, other_letters, words, pangrams) =
+                            (&id, &other_letters, &words, &pangrams);
+                        Box::pin(async move {
+                            sqlx::query_scalar!(
+                                "insert into custom_puzzles
+                                (id, required_letter, other_letters, words, pangrams)
+                                values ($1, $2, $3, $4, $5)
+                                on conflict do nothing
+                                returning id",
+                                id,
+                                config.required_letter.0.to_string(),
+                                other_letters,
+                                words,
+                                pangrams,
+                            )
+                            .fetch_optional(&mut **tx)
+                            .await
+                        })
+                    })
+                    .await
+                    .map_err(|e| CustomPuzzleRepositoryError::DbError(Box::new(e)))?;
+                    if let Some(id) = stored {
+                        return Ok(id);
+                    }
+                }
+                Err(CustomPuzzleRepositoryError::NoFreeId)
+            }
+
+            async fn get(
+                &self,
+                id: &str,
+            ) -> Result<Option<PuzzleConfig>, CustomPuzzleRepositoryError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        let row = sqlx::query_as!(
+                            StoredPuzzle,
+                            "select required_letter, other_letters, words, pangrams
+                            from custom_puzzles
+                            where id = $1",
+                            id,
+                        )
+                        .fetch_optional(&mut **tx)
+                        .await?;
+                        let Some(row) = row else {
+                            return Ok(None);
+                        };
+
+                        // Custom puzzles are made from the standard dictionary.
+                        let metadata = sqlx::query_as!(
+                            WordMetadata,
+                            "select word, definition, obscurity
+                            from words
+                            where dictionary_id = $1 and word = any($2)
+                            and (definition is not null or obscurity is not null)",
+                            DictionaryId::STANDARD.0,
+                            &row.words,
+                        )
+                        .fetch_all(&mut **tx)
+                        .await?;
+
+                        Ok(Some(row.into_config(metadata)))
+                    })
+                })
+                .await
+                .map_err(|e| CustomPuzzleRepositoryError::DbError(Box::new(e)))
+            }
+        }
+
+        #[derive(sqlx::FromRow)]
+        struct StoredPuzzle {
+            required_letter: String,
+            other_letters: String,
+            words: Vec<String>,
+            pangrams: Vec<String>,
+        }
+
+        struct WordMetadata {
+            word: String,
+            definition: Option<String>,
+            obscurity: Option<i16>,
+        }
+
+        impl StoredPuzzle {
+            fn into_config(self, metadata: Vec<WordMetadata>) -> PuzzleConfig {
+                let mut metadata: HashMap<_, _> =
+                    metadata.into_iter().map(|m| (m.word.clone(), m)).collect();
+                let valid_words = self
+                    .words
+                    .iter()
+                    .map(|w| {
+                        let word = Word::new(w, self.pangrams.contains(w));
+                        match metadata.remove(w) {
+                            Some(m) => Word {
+                                definition: m.definition,
+                                obscurity: m.obscurity.and_then(|o| u8::try_from(o).ok()),
+                                ..word
+                            },
+                            None => word,
+                        }
+                    })
+                    .collect();
+                let mut config = PuzzleConfig {
+                    score_buckets: Default::default(),
+                    scoring: Default::default(),
+                    alphabet: Default::default(),
+                    required_letter: Letter::new(
+                        self.required_letter.chars().next().unwrap_or_default(),
+                    ),
+                    other_letters: self.other_letters.chars().map(Letter::new).collect(),
+                    valid_words,
+                };
+                config.score_buckets = config.scoring.score_buckets(&config.valid_words);
+                config
+            }
+        }
+    }
+}
+
+pub(crate) mod client_errors {
+    use std::fmt::Display;
+
+    pub(crate) trait ReportClientError {
+        async fn report(
+            &self,
+            report: &client_errors::ClientErrorReport,
+        ) -> Result<(), ClientErrorsError>;
+    }
+
+    pub(crate) trait ListClientErrors {
+        async fn list(
+            &self,
+            limit: i64,
+        ) -> Result<Vec<client_errors::ReportedError>, ClientErrorsError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum ClientErrorsError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for ClientErrorsError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to access client errors due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ClientErrorsError {}
+
+    pub(crate) mod pg {
+        use super::ClientErrorsError;
+
+        #[derive(Clone)]
+        pub(crate) struct ClientErrors(pub(crate) sqlx::PgPool);
+
+        impl super::ReportClientError for ClientErrors {
+            async fn report(
+                &self,
+                report: &client_errors::ClientErrorReport,
+            ) -> Result<(), ClientErrorsError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                    "insert into client_errors (message, location, route) values ($1, $2, $3)",
+                    report.message,
+                    report.location,
+                    report.route,
+                )
+                .execute(&mut **tx)
+                .await
+                    })
+                })
+                .await
+                .map_err(|e| ClientErrorsError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+        }
+
+        impl super::ListClientErrors for ClientErrors {
+            async fn list(
+                &self,
+                limit: i64,
+            ) -> Result<Vec<client_errors::ReportedError>, ClientErrorsError> {
+                let rows = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query_as!(
+                            ReportedErrorRow,
+                            "select id, message, location, route, reported_at
+                            from client_errors
+                            order by reported_at desc
+                            limit $1",
+                            limit
+                        )
+                        .fetch_all(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| ClientErrorsError::DbError(Box::new(e)))?;
+
+                Ok(rows
+                    .into_iter()
+                    .map(|row| client_errors::ReportedError {
+                        id: row.id,
+                        message: row.message,
+                        location: row.location,
+                        route: row.route,
+                        reported_at: row.reported_at.to_rfc3339(),
+                    })
+                    .collect())
+            }
+        }
+
+        #[derive(sqlx::FromRow)]
+        struct ReportedErrorRow {
+            id: i64,
+            message: String,
+            location: Option<String>,
+            route: Option<String>,
+            reported_at: chrono::DateTime<chrono::Utc>,
+        }
+    }
+}
+
+pub(crate) mod feedback {
+    use std::fmt::Display;
+
+    pub(crate) trait ReportWord {
+        async fn report_word(&self, report: &words_list::WordReport) -> Result<(), FeedbackError>;
+    }
+
+    pub(crate) trait ListWordReports {
+        /// Reports grouped by word and kind, the `limit` most reported first.
+        async fn list_word_reports(
+            &self,
+            limit: i64,
+        ) -> Result<Vec<words_list::ReportedWord>, FeedbackError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum FeedbackError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for FeedbackError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to access word reports due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for FeedbackError {}
+
+    pub(crate) mod pg {
+        use super::FeedbackError;
+
+        #[derive(Clone)]
+        pub(crate) struct WordReports(pub(crate) sqlx::PgPool);
+
+        impl super::ReportWord for WordReports {
+            async fn report_word(
+                &self,
+                report: &words_list::WordReport,
+            ) -> Result<(), FeedbackError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "insert into word_reports (word, kind) values ($1, $2)",
+                            report.word,
+                            report.kind.as_str(),
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| FeedbackError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+        }
+
+        impl super::ListWordReports for WordReports {
+            async fn list_word_reports(
+                &self,
+                limit: i64,
+            ) -> Result<Vec<words_list::ReportedWord>, FeedbackError> {
+                let rows = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        // Players report words of the puzzles they play, which
+                        // are made from the standard dictionary.
+                        sqlx::query!(
+                            r#"select word_reports.word, word_reports.kind,
+                            count(*) as "reports!",
+                            max(word_reports.reported_at) as "last_reported_at!",
+                            exists(
+                                select 1 from words
+                                where words.dictionary_id = $2
+                                and words.word = word_reports.word and deleted_at is null
+                            ) as "in_list!"
+                            from word_reports
+                            group by word_reports.word, word_reports.kind
+                            order by 3 desc, 4 desc
+                            limit $1"#,
+                            limit,
+                            crate::services::dictionaries::DictionaryId::STANDARD.0,
+                        )
+                        .fetch_all(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| FeedbackError::DbError(Box::new(e)))?;
+
+                Ok(rows
+                    .into_iter()
+                    .map(|row| words_list::ReportedWord {
+                        word: row.word,
+                        kind: match row.kind.as_str() {
+                            "missing" => words_list::WordReportKind::Missing,
+                            _ => words_list::WordReportKind::Invalid,
+                        },
+                        reports: row.reports,
+                        last_reported_at: row.last_reported_at.to_rfc3339(),
+                        in_list: row.in_list,
+                    })
+                    .collect())
+            }
+        }
+    }
+}
+
+pub(crate) mod users {
+    use std::fmt::Display;
+
+    use chrono::{DateTime, Utc};
+
+    #[derive(Debug, Clone)]
+    pub(crate) struct User {
+        pub(crate) id: i64,
+        pub(crate) username: String,
+        /// Admins may manage the word list and curate puzzles.
+        pub(crate) is_admin: bool,
+        pub(crate) dictionary: accounts::Dictionary,
+    }
+
+    pub(crate) trait CreateUser {
+        async fn create_user(
+            &self,
+            username: &str,
+            password_hash: &str,
+        ) -> Result<User, CreateUserError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum CreateUserError {
+        UsernameTaken,
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for CreateUserError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::UsernameTaken => write!(f, "That username is already taken"),
+                Self::DbError(error) => {
+                    write!(f, "Failed to create user due to database error: {}", error)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for CreateUserError {}
+
+    pub(crate) trait FindCredentials {
+        /// Find the user with `username` along with their stored password hash.
+        async fn find_credentials(
+            &self,
+            username: &str,
+        ) -> Result<Option<(User, String)>, UsersError>;
+    }
+
+    pub(crate) trait GrantAdmin {
+        /// Make the user with `username` an admin, returning false if there is no
+        /// such user.
+        async fn grant_admin(&self, username: &str) -> Result<bool, UsersError>;
+    }
+
+    pub(crate) trait SetPreferences {
+        async fn set_preferences(
+            &self,
+            user_id: i64,
+            preferences: &accounts::Preferences,
+        ) -> Result<(), UsersError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum UsersError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for UsersError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(f, "Failed to load user due to database error: {}", error)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for UsersError {}
+
+    /// Sessions are looked up by a hash of their token so that a leaked sessions
+    /// table can't be used to log in.
+    pub(crate) trait CreateSession {
+        async fn create_session(
+            &self,
+            user_id: i64,
+            token_hash: &[u8],
+            expires_at: DateTime<Utc>,
+        ) -> Result<(), SessionsError>;
+    }
+
+    pub(crate) trait FindSession {
+        /// Find the user owning an unexpired session.
+        async fn find_session(&self, token_hash: &[u8]) -> Result<Option<User>, SessionsError>;
+    }
+
+    pub(crate) trait DeleteSession {
+        async fn delete_session(&self, token_hash: &[u8]) -> Result<(), SessionsError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum SessionsError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for SessionsError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to access session due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for SessionsError {}
+
+    pub(crate) mod pg {
+        use chrono::{DateTime, Utc};
+
+        use super::{CreateUserError, SessionsError, User, UsersError};
+
+        #[derive(Clone)]
+        pub(crate) struct Users(pub(crate) sqlx::PgPool);
+
+        impl super::CreateUser for Users {
+            async fn create_user(
+                &self,
+                username: &str,
+                password_hash: &str,
+            ) -> Result<User, CreateUserError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query_as!(
+                            UserRow,
+                            "insert into users (username, password_hash)
+                            values ($1, $2)
+                            returning id, username, is_admin, dictionary",
+                            username,
+                            password_hash,
+                        )
+                        .fetch_one(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map(User::from)
+                .map_err(|e| match e {
+                    sqlx::Error::Database(e) if e.is_unique_violation() => {
+                        CreateUserError::UsernameTaken
+                    }
+                    e => CreateUserError::DbError(Box::new(e)),
+                })
+            }
+        }
+
+        impl super::FindCredentials for Users {
+            async fn find_credentials(
+                &self,
+                username: &str,
+            ) -> Result<Option<(User, String)>, UsersError> {
+                let row = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "select id, username, is_admin, dictionary, password_hash
+                            from users where username = $1",
+                            username,
+                        )
+                        .fetch_optional(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| UsersError::DbError(Box::new(e)))?;
+
+                Ok(row.map(|r| {
+                    (
+                        User::from(UserRow {
+                            id: r.id,
+                            username: r.username,
+                            is_admin: r.is_admin,
+                            dictionary: r.dictionary,
+                        }),
+                        r.password_hash,
+                    )
+                }))
+            }
+        }
+
+        impl super::GrantAdmin for Users {
+            async fn grant_admin(&self, username: &str) -> Result<bool, UsersError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "update users set is_admin = true where username = $1",
+                            username,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| UsersError::DbError(Box::new(e)))
+                .map(|result| result.rows_affected() > 0)
+            }
+        }
+
+        impl super::SetPreferences for Users {
+            async fn set_preferences(
+                &self,
+                user_id: i64,
+                preferences: &accounts::Preferences,
+            ) -> Result<(), UsersError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "update users set dictionary = $2 where id = $1",
+                            user_id,
+                            preferences.dictionary.as_str(),
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| UsersError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+        }
+
+        struct UserRow {
+            id: i64,
+            username: String,
+            is_admin: bool,
+            dictionary: String,
+        }
+
+        impl From<UserRow> for User {
+            fn from(row: UserRow) -> Self {
+                Self {
+                    id: row.id,
+                    username: row.username,
+                    is_admin: row.is_admin,
+                    // The column is constrained to known dictionaries.
+                    dictionary: row.dictionary.parse().unwrap_or_default(),
+                }
+            }
+        }
+
+        #[derive(Clone)]
+        pub(crate) struct Sessions(pub(crate) sqlx::PgPool);
+
+        impl super::CreateSession for Sessions {
+            async fn create_session(
+                &self,
+                user_id: i64,
+                token_hash: &[u8],
+                expires_at: DateTime<Utc>,
+            ) -> Result<(), SessionsError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                    "insert into sessions (token_hash, user_id, expires_at) values ($1, $2, $3)",
+                    token_hash,
+                    user_id,
+                    expires_at,
+                )
+                .execute(&mut **tx)
+                .await
+                    })
+                })
+                .await
+                .map_err(|e| SessionsError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+        }
+
+        impl super::FindSession for Sessions {
+            async fn find_session(&self, token_hash: &[u8]) -> Result<Option<User>, SessionsError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query_as!(
+                            UserRow,
+                            "select users.id, users.username, users.is_admin, users.dictionary
+                            from sessions
+                            join users on users.id = sessions.user_id
+                            where sessions.token_hash = $1 and sessions.expires_at > now()",
+                            token_hash,
+                        )
+                        .fetch_optional(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map(|row| row.map(User::from))
+                .map_err(|e| SessionsError::DbError(Box::new(e)))
+            }
+        }
+
+        impl super::DeleteSession for Sessions {
+            async fn delete_session(&self, token_hash: &[u8]) -> Result<(), SessionsError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        // Expired sessions are never used again, so clear them out while here.
+                        sqlx::query!(
+                            "delete from sessions where token_hash = $1 or expires_at <= now()",
+                            token_hash,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| SessionsError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+        }
+    }
+}
+
+pub(crate) mod progress {
+    use std::fmt::Display;
+
+    use chrono::NaiveDate;
+    use puzzle_config::{Difficulty, Progress};
+
+    /// Storage for each user's progress through the puzzles they've played,
+    /// keyed like generated puzzles by day, UTC offset and difficulty.
+    pub(crate) trait ProgressRepository {
+        async fn get(
+            &self,
+            user_id: i64,
+            date: NaiveDate,
+            utc_offset: i32,
+            difficulty: Difficulty,
+        ) -> Result<Option<Progress>, ProgressRepositoryError>;
+
+        async fn save(
+            &self,
+            user_id: i64,
+            date: NaiveDate,
+            utc_offset: i32,
+            difficulty: Difficulty,
+            progress: &Progress,
+        ) -> Result<(), ProgressRepositoryError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum ProgressRepositoryError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for ProgressRepositoryError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to access puzzle progress due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ProgressRepositoryError {}
+
+    pub(crate) mod pg {
+        use chrono::NaiveDate;
+        use puzzle_config::{Difficulty, Progress};
+
+        use super::ProgressRepositoryError;
+
+        #[derive(Clone)]
+        pub(crate) struct ProgressRepository(pub(crate) sqlx::PgPool);
+
+        impl super::ProgressRepository for ProgressRepository {
+            async fn get(
+                &self,
+                user_id: i64,
+                date: NaiveDate,
+                utc_offset: i32,
+                difficulty: Difficulty,
+            ) -> Result<Option<Progress>, ProgressRepositoryError> {
+                let row = crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "select submitted, score from puzzle_progress
+                            where user_id = $1 and puzzle_date = $2 and utc_offset = $3
+                            and difficulty = $4",
+                            user_id,
+                            date,
+                            utc_offset,
+                            difficulty.as_str(),
+                        )
+                        .fetch_optional(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| ProgressRepositoryError::DbError(Box::new(e)))?;
+
+                Ok(row.map(|r| Progress {
+                    submitted: r.submitted,
+                    score: r.score as u32,
+                }))
+            }
+
+            async fn save(
+                &self,
+                user_id: i64,
+                date: NaiveDate,
+                utc_offset: i32,
+                difficulty: Difficulty,
+                progress: &Progress,
+            ) -> Result<(), ProgressRepositoryError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "insert into puzzle_progress
+                    (user_id, puzzle_date, utc_offset, difficulty, submitted, score)
+                    values ($1, $2, $3, $4, $5, $6)
+                    on conflict (user_id, puzzle_date, utc_offset, difficulty) do update
+                    set submitted = excluded.submitted
+                    , score = excluded.score
+                    , updated_at = now()",
+                            user_id,
+                            date,
+                            utc_offset,
+                            difficulty.as_str(),
+                            &progress.submitted,
+                            progress.score as i32,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| ProgressRepositoryError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+        }
+    }
+}
+
+pub(crate) mod notes {
+    use std::fmt::Display;
+
+    use chrono::NaiveDate;
+    use puzzle_config::Note;
+
+    /// Storage for the notes users keep on puzzles, keyed by puzzle date.
+    pub(crate) trait NotesRepository {
+        async fn get(
+            &self,
+            user_id: i64,
+            date: NaiveDate,
+        ) -> Result<Option<Note>, NotesRepositoryError>;
+
+        async fn save(
+            &self,
+            user_id: i64,
+            date: NaiveDate,
+            note: &Note,
+        ) -> Result<(), NotesRepositoryError>;
+
+        async fn delete(&self, user_id: i64, date: NaiveDate) -> Result<(), NotesRepositoryError>;
+    }
+
+    #[derive(Debug)]
+    pub(crate) enum NotesRepositoryError {
+        DbError(Box<dyn std::error::Error>),
+    }
+
+    impl Display for NotesRepositoryError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::DbError(error) => {
+                    write!(
+                        f,
+                        "Failed to access puzzle notes due to database error: {}",
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for NotesRepositoryError {}
+
+    pub(crate) mod pg {
+        use chrono::NaiveDate;
+        use puzzle_config::Note;
+
+        use super::NotesRepositoryError;
+
+        #[derive(Clone)]
+        pub(crate) struct NotesRepository(pub(crate) sqlx::PgPool);
+
+        impl super::NotesRepository for NotesRepository {
+            async fn get(
+                &self,
+                user_id: i64,
+                date: NaiveDate,
+            ) -> Result<Option<Note>, NotesRepositoryError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query_as!(
+                            Note,
+                            "select text from puzzle_notes where user_id = $1 and puzzle_date = $2",
+                            user_id,
+                            date,
+                        )
+                        .fetch_optional(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| NotesRepositoryError::DbError(Box::new(e)))
+            }
+
+            async fn save(
+                &self,
+                user_id: i64,
+                date: NaiveDate,
+                note: &Note,
+            ) -> Result<(), NotesRepositoryError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "insert into puzzle_notes (user_id, puzzle_date, text)
+                            values ($1, $2, $3)
+                            on conflict (user_id, puzzle_date) do update
+                            set text = excluded.text
+                            , updated_at = now()",
+                            user_id,
+                            date,
+                            note.text,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| NotesRepositoryError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
+
+            async fn delete(
+                &self,
+                user_id: i64,
+                date: NaiveDate,
+            ) -> Result<(), NotesRepositoryError> {
+                crate::services::db::with_retry(&self.0, |tx| {
+                    Box::pin(async move {
+                        sqlx::query!(
+                            "delete from puzzle_notes where user_id = $1 and puzzle_date = $2",
+                            user_id,
+                            date,
+                        )
+                        .execute(&mut **tx)
+                        .await
+                    })
+                })
+                .await
+                .map_err(|e| NotesRepositoryError::DbError(Box::new(e)))
+                .map(|_| ())
+            }
         }
     }
 }
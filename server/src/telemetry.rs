@@ -0,0 +1,116 @@
+//! Request tracing and the metrics describing how the server is doing.
+//!
+//! Every request carries an `x-request-id`, kept from the request when a proxy
+//! already set one, which is recorded on its span and echoed on the response.
+//! Request counts and latencies are labelled with the route that matched rather
+//! than the path, so that the number of series stays bounded.
+
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use tower_http::request_id::RequestId;
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const LATENCY_BOUNDS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// The span every event logged while handling `request` is recorded in.
+pub(crate) fn make_span(request: &Request) -> tracing::Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .unwrap_or_default();
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        route = route(request),
+        uri = %request.uri(),
+        request_id,
+    )
+}
+
+/// Count requests and time their responses by method, route and status.
+pub(crate) async fn record_request(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = route(&request).to_owned();
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    crate::metrics::increment(
+        "bee_http_requests_total",
+        &[
+            ("method", method.as_str()),
+            ("route", &route),
+            ("status", response.status().as_str()),
+        ],
+        1,
+    );
+    crate::metrics::observe(
+        "bee_http_request_seconds",
+        &[("method", method.as_str()), ("route", &route)],
+        LATENCY_BOUNDS,
+        started.elapsed().as_secs_f64(),
+    );
+    response
+}
+
+fn route(request: &Request) -> &str {
+    request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str)
+        .unwrap_or("unmatched")
+}
+
+/// Record how many of the connections of `pool` are in use.
+pub(crate) fn record_pool(pool: &sqlx::PgPool) {
+    let size = pool.size();
+    let idle = u32::try_from(pool.num_idle()).unwrap_or(u32::MAX).min(size);
+    crate::metrics::set(
+        "bee_db_pool_connections",
+        &[("state", "idle")],
+        f64::from(idle),
+    );
+    crate::metrics::set(
+        "bee_db_pool_connections",
+        &[("state", "in_use")],
+        f64::from(size - idle),
+    );
+    crate::metrics::set(
+        "bee_db_pool_max_connections",
+        &[],
+        f64::from(pool.options().get_max_connections()),
+    );
+}
+
+/// Times a database operation, recording it once dropped.
+pub(crate) struct QueryTimer {
+    operation: &'static str,
+    started: Instant,
+}
+
+impl QueryTimer {
+    pub(crate) fn start(operation: &'static str) -> Self {
+        Self {
+            operation,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for QueryTimer {
+    fn drop(&mut self) {
+        crate::metrics::observe(
+            "bee_db_query_seconds",
+            &[("operation", self.operation)],
+            LATENCY_BOUNDS,
+            self.started.elapsed().as_secs_f64(),
+        );
+    }
+}
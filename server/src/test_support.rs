@@ -0,0 +1,64 @@
+//! Fixtures shared by the tests of the routes, both the ones inside the crate
+//! and the ones driving the API over HTTP from `tests/`, which reach them
+//! through the `test-support` feature.
+
+use crate::morphology::MorphologyPolicy;
+use crate::puzzle_config::{ConfigProvider, PuzzleGenerationPolicy};
+
+pub const ADMIN_TOKEN: &str = "an-admin-token-long-enough-to-be-accepted";
+
+pub const WORDS: &[&str] = &[
+    "apple", "bread", "crane", "dance", "eagle", "fable", "grape", "honey", "ideal", "jelly",
+];
+/// Words using seven letters, which puzzles are generated from.
+pub const PANGRAMS: &[&str] = &["dolphin", "kingdom"];
+
+/// The test dictionary is far too small for the default policy, so accept any
+/// letter set rather than searching for a playable one.
+pub fn permissive_policy() -> PuzzleGenerationPolicy {
+    PuzzleGenerationPolicy {
+        min_words: 0,
+        max_words: None,
+        min_pangrams: 0,
+        min_four_letter_words: 0,
+        perfect_pangram_attempts: 0,
+        min_vowels: 0,
+        ..Default::default()
+    }
+}
+
+pub fn server_config() -> crate::config::ServerConfig {
+    crate::config::ServerConfig {
+        bind_address: std::net::SocketAddr::from(([127, 0, 0, 1], 0)),
+        database_url: None,
+        max_connections: 1,
+        log_level: "error".to_owned(),
+        index_file: "index.html".into(),
+        assets_dir: "assets".into(),
+        admin_token: Some(ADMIN_TOKEN.to_owned()),
+        max_words_per_request: 50_000,
+        max_word_len: 32,
+    }
+}
+
+/// The state of an English server generating puzzles from `pool` by `policy`,
+/// caching them in memory.
+pub fn app_state(
+    pool: sqlx::PgPool,
+    policy: PuzzleGenerationPolicy,
+    morphology: MorphologyPolicy,
+) -> crate::AppState {
+    let configs = ConfigProvider::new(
+        pool.clone(),
+        crate::cache::Backend::Memory(Default::default()),
+        policy,
+        morphology.clone(),
+    );
+    crate::AppState {
+        config: server_config(),
+        dbpool: pool,
+        configs,
+        language: Some(crate::language::Language::English),
+        morphology,
+    }
+}
@@ -0,0 +1,126 @@
+//! Timezones players ask for puzzles in: fixed UTC offsets like `+09:00`, or
+//! IANA zones like `America/New_York` whose offset follows daylight saving time.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Zone {
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+impl Zone {
+    /// Look up `tz`, either an offset like `+09:00` or an IANA zone name like
+    /// `Asia/Tokyo`.
+    pub(crate) fn parse(tz: &str) -> Option<Self> {
+        if let Ok(offset) = tz.parse() {
+            return Some(Self::Fixed(offset));
+        }
+        tz.parse().ok().map(Self::Named)
+    }
+
+    pub(crate) fn offset_at(&self, instant: DateTime<Utc>) -> FixedOffset {
+        match self {
+            Self::Fixed(offset) => *offset,
+            Self::Named(tz) => tz.offset_from_utc_datetime(&instant.naive_utc()).fix(),
+        }
+    }
+
+    /// The local date at `instant`.
+    pub(crate) fn date_at(&self, instant: DateTime<Utc>) -> NaiveDate {
+        instant.with_timezone(&self.offset_at(instant)).date_naive()
+    }
+
+    /// The offset that identifies the zone's puzzle for `date`: the one in
+    /// effect at noon, clear of the night time daylight saving transitions, so
+    /// that the whole day shares one puzzle.
+    pub(crate) fn day_offset(&self, date: NaiveDate) -> FixedOffset {
+        let noon = date.and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap());
+        self.local_to_utc(noon)
+            .map(|instant| self.offset_at(instant))
+            .unwrap_or_else(|| self.offset_at(noon.and_utc()))
+    }
+
+    /// The start of the local day after the one `instant` falls on.
+    pub(crate) fn next_midnight(&self, instant: DateTime<Utc>) -> DateTime<Utc> {
+        let tomorrow = self
+            .date_at(instant)
+            .succ_opt()
+            .unwrap_or(NaiveDate::MAX)
+            .and_time(NaiveTime::MIN);
+        // Where daylight saving skips midnight, the day starts when the clocks
+        // go forward.
+        self.local_to_utc(tomorrow)
+            .or_else(|| {
+                let offset = self.offset_at(instant);
+                Some(tomorrow.and_local_timezone(offset).single()?.to_utc())
+            })
+            .unwrap_or(instant)
+    }
+
+    /// The earliest instant with the local time `local`, unless it's skipped.
+    fn local_to_utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Fixed(offset) => local
+                .and_local_timezone(*offset)
+                .earliest()
+                .map(|i| i.to_utc()),
+            Self::Named(tz) => local.and_local_timezone(*tz).earliest().map(|i| i.to_utc()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, Utc};
+
+    use super::Zone;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn follows_iana_zones() {
+        let zone = Zone::parse("America/New_York").unwrap();
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2024, 3, 9).unwrap(),
+            zone.date_at(utc("2024-03-10T04:59:59Z"))
+        );
+        assert_eq!(
+            "-04:00",
+            zone.day_offset(NaiveDate::from_ymd_opt(2024, 3, 10).unwrap())
+                .to_string()
+        );
+        assert_eq!(
+            "-05:00",
+            zone.offset_at(utc("2024-11-03T06:00:00Z")).to_string()
+        );
+        // The day daylight saving starts is an hour short.
+        assert_eq!(
+            utc("2024-03-11T04:00:00Z"),
+            zone.next_midnight(utc("2024-03-10T05:00:00Z"))
+        );
+        // Historical offsets are kept too.
+        assert_eq!(
+            "-04:00",
+            zone.offset_at(utc("1975-03-01T12:00:00Z")).to_string()
+        );
+
+        let fixed = Zone::parse("+09:00").unwrap();
+        assert_eq!(
+            utc("2024-03-11T15:00:00Z"),
+            fixed.next_midnight(utc("2024-03-10T15:00:00Z"))
+        );
+
+        for name in [
+            "../../etc/passwd",
+            "/etc/localtime",
+            "Nowhere/Land",
+            "UTC+1",
+        ] {
+            assert!(Zone::parse(name).is_none(), "{}", name);
+        }
+    }
+}
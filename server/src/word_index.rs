@@ -0,0 +1,83 @@
+//! A word list held in memory, for serving puzzles without a database.
+
+use std::collections::HashMap;
+
+/// Word list bundled into the binary, the same one the language check is
+/// trained on.
+const BUNDLED_WORDS: &str = include_str!("../data/words.txt");
+
+/// Every word of a list by its letter mask, standing in for the words table.
+#[derive(Debug)]
+pub struct WordIndex {
+    words: HashMap<String, words::Bitmask>,
+    dictionary: words::letterset::Dictionary,
+}
+
+impl WordIndex {
+    /// Index the words of a newline-delimited list that could be answers, see
+    /// [`crate::import::importable_word`].
+    pub fn new<'a>(list: impl Iterator<Item = &'a str>) -> Self {
+        let words: HashMap<String, words::Bitmask> = list
+            .filter_map(crate::import::importable_word)
+            .filter_map(|word| {
+                let mask = words::try_bitmask(&word).ok()?;
+                Some((word, mask))
+            })
+            .collect();
+
+        let mut counts = HashMap::<words::Bitmask, usize>::new();
+        for mask in words.values() {
+            *counts.entry(*mask).or_default() += 1;
+        }
+        let mut dictionary = words::letterset::Dictionary::new();
+        for (mask, count) in counts {
+            dictionary.insert(mask, count);
+        }
+
+        Self { words, dictionary }
+    }
+
+    /// Index the word list bundled into the binary.
+    pub fn bundled() -> Self {
+        Self::new(BUNDLED_WORDS.lines())
+    }
+
+    /// Index the word list at `path`.
+    pub fn read(path: &std::path::Path) -> std::io::Result<Self> {
+        let list = std::fs::read_to_string(path)?;
+        Ok(Self::new(list.lines()))
+    }
+
+    /// Count of words by letter mask, as loaded from the words table.
+    pub(crate) fn dictionary(&self) -> &words::letterset::Dictionary {
+        &self.dictionary
+    }
+
+    pub(crate) fn contains(&self, word: &str) -> bool {
+        self.words.contains_key(word)
+    }
+
+    /// Every word containing `required_mask` and spelled only with it and
+    /// `letter_mask`, with its letter mask.
+    pub(crate) fn spelled_with(
+        &self,
+        required_mask: words::Bitmask,
+        letter_mask: words::Bitmask,
+    ) -> impl Iterator<Item = (&str, words::Bitmask)> {
+        let all = required_mask | letter_mask;
+        self.words
+            .iter()
+            .filter(move |(_, mask)| {
+                words::is_subset(required_mask, **mask) && words::is_subset(**mask, all)
+            })
+            .map(|(word, mask)| (word.as_str(), *mask))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
@@ -0,0 +1,216 @@
+//! The API as clients see it: [`server::app`] served on a free port and
+//! driven over HTTP with [`api_client`].
+//!
+//! Each test gets a freshly migrated database from `#[sqlx::test]`, so
+//! `DATABASE_URL` must point at a Postgres instance where the user may create
+//! databases.
+
+use accounts::Credentials;
+use api_client::{ApiError, Client};
+use puzzle_config::{Difficulty, Letter, ScheduledPuzzle};
+use search::SearchMode;
+use server::morphology::MorphologyPolicy;
+use server::test_support::{ADMIN_TOKEN, PANGRAMS, WORDS, app_state, permissive_policy};
+
+/// Serve the app on a free port, returning its base URL.
+async fn serve(pool: sqlx::PgPool) -> String {
+    let app = server::app(app_state(
+        pool,
+        permissive_policy(),
+        MorphologyPolicy::default(),
+    ));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .unwrap()
+    });
+    format!("http://{}", addr)
+}
+
+/// A client of the server at `base_url` with admin access.
+fn admin(base_url: &str) -> Client {
+    Client::new(base_url).with_admin_token(ADMIN_TOKEN)
+}
+
+fn owned(words: &[&str]) -> Vec<String> {
+    words.iter().map(|w| (*w).to_owned()).collect()
+}
+
+/// Status of the error response to a request, if it failed with one.
+fn status<T>(result: Result<T, ApiError>) -> Option<u16> {
+    result.err().and_then(|e| e.status())
+}
+
+async fn listed_words(client: &Client) -> Vec<String> {
+    let listed = client.list_words(None).await.unwrap();
+    listed.words.into_iter().map(|w| w.text).collect()
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn serves_one_daily_puzzle_per_timezone(pool: sqlx::PgPool) {
+    let client = admin(&serve(pool.clone()).await);
+    client.add_words(&owned(WORDS)).await.unwrap();
+    client.add_words(&owned(PANGRAMS)).await.unwrap();
+
+    let daily = |tz| client.get_daily_config(tz, Default::default(), Difficulty::Medium);
+    let utc = daily("+00:00").await.unwrap();
+    assert_eq!(utc, daily("+00:00").await.unwrap());
+    assert_eq!(6, utc.other_letters.len());
+
+    // Zones share the puzzle of the offset they're at.
+    let tokyo = daily("+09:00").await.unwrap();
+    assert_eq!(tokyo, daily("Asia/Tokyo").await.unwrap());
+
+    // Puzzles are stored, so another server agrees on them.
+    let restarted = admin(&serve(pool).await);
+    let again = restarted
+        .get_daily_config("+09:00", Default::default(), Difficulty::Medium)
+        .await
+        .unwrap();
+    assert_eq!(tokyo, again);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn adds_lists_searches_and_removes_words(pool: sqlx::PgPool) {
+    let client = admin(&serve(pool).await);
+    client
+        .add_words(&owned(&["Crane", "apple", "bread"]))
+        .await
+        .unwrap();
+    assert_eq!(vec!["apple", "bread", "crane"], listed_words(&client).await);
+
+    let found = client
+        .search_words("aple", SearchMode::Fuzzy)
+        .await
+        .unwrap();
+    assert_eq!("apple", found.words[0]);
+    let found = client.search_words("br", SearchMode::Prefix).await.unwrap();
+    assert_eq!(vec!["bread"], found.words);
+    let found = client
+        .search_words("abder", SearchMode::Letters)
+        .await
+        .unwrap();
+    assert_eq!(vec!["bread"], found.words);
+
+    client.remove_words(&owned(&["apple"])).await.unwrap();
+    assert_eq!(vec!["bread", "crane"], listed_words(&client).await);
+    let found = client.search_words("ap", SearchMode::Prefix).await.unwrap();
+    assert!(found.words.is_empty());
+
+    client.restore_words(&owned(&["apple"])).await.unwrap();
+    assert_eq!(vec!["apple", "bread", "crane"], listed_words(&client).await);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn answers_bad_requests_with_a_status_and_message(pool: sqlx::PgPool) {
+    let base_url = serve(pool).await;
+    let client = admin(&base_url);
+
+    let error = client.add_words(&owned(&["cr4ne"])).await.unwrap_err();
+    assert_eq!(
+        ApiError::Status {
+            status: 422,
+            message: Some("Invalid word \"cr4ne\": '4' is not a letter from a to z".to_owned()),
+        },
+        error
+    );
+
+    let searched = client.search_words("(", SearchMode::Regex).await;
+    assert_eq!(Some(422), status(searched));
+
+    let daily = client
+        .get_daily_config("Mars/Olympus", Default::default(), Difficulty::Medium)
+        .await;
+    assert_eq!(Some(422), status(daily));
+
+    let listed = client
+        .clone()
+        .with_word_list(Some("missing"))
+        .list_words(None)
+        .await;
+    assert_eq!(Some(404), status(listed));
+
+    // Without the admin token the word list can't be changed.
+    let error = Client::new(base_url)
+        .add_words(&owned(&["apple"]))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ApiError::Status {
+            status: 401,
+            message: Some(_)
+        }
+    ));
+    assert!(listed_words(&client).await.is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn schedules_and_regenerates_puzzles(pool: sqlx::PgPool) {
+    let base_url = serve(pool).await;
+    let client = admin(&base_url);
+    let date = (chrono::Utc::now().date_naive() + chrono::Days::new(10)).to_string();
+    let puzzle = ScheduledPuzzle {
+        date: date.clone(),
+        required_letter: Some(Letter('a')),
+        other_letters: "bcdein".chars().map(Letter).collect(),
+        event: None,
+    };
+
+    client.schedule_puzzle(&puzzle).await.unwrap();
+    let schedule = client.schedule(&date, &date).await.unwrap();
+    assert_eq!(vec![puzzle], schedule.puzzles);
+    client.regenerate_puzzle(&date).await.unwrap();
+
+    let player = Client::new(base_url);
+    assert_eq!(Some(401), status(player.schedule(&date, &date).await));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn registers_and_logs_in(pool: sqlx::PgPool) {
+    let client = Client::new(serve(pool).await);
+    let credentials = Credentials {
+        username: "bee".to_owned(),
+        password: "correct horse battery".to_owned(),
+    };
+
+    let user = client.register(&credentials).await.unwrap();
+    assert_eq!("bee", user.username);
+    assert_eq!(user, client.log_in(&credentials).await.unwrap());
+    // Native clients don't keep the session cookie.
+    assert_eq!(None, client.current_user().await.unwrap());
+
+    let wrong = Credentials {
+        password: "wrong".to_owned(),
+        ..credentials
+    };
+    assert_eq!(Some(401), status(client.log_in(&wrong).await));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn adds_more_words_than_one_insert_can_bind(pool: sqlx::PgPool) {
+    let client = admin(&serve(pool).await);
+    // Every five letter word from "aaaaa" on, far more than the 65535
+    // parameters Postgres allows in one statement need.
+    let many: Vec<String> = (0..25_000u32)
+        .map(|n| {
+            (0..5)
+                .rev()
+                .map(|place| char::from(b'a' + (n / 26u32.pow(place) % 26) as u8))
+                .collect()
+        })
+        .collect();
+    client.add_words(&many).await.unwrap();
+
+    let dictionaries = client.list_dictionaries().await.unwrap();
+    assert_eq!(25_000, dictionaries.dictionaries[0].words);
+
+    let too_many = client.add_words(&owned(&["apple"; 50_001])).await;
+    assert_eq!(Some(413), status(too_many));
+}
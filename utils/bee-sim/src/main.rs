@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use clap::Parser;
+use http_body_util::{BodyExt, Empty};
+use hyper::body::Bytes;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use puzzle_config::{PuzzleConfig, Word};
+use rand::{Rng, SeedableRng};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Opts::parse();
+
+    let config = match (&opts.config_url, &opts.config_file) {
+        (Some(url), _) => fetch_config(url).await?,
+        (None, Some(path)) => {
+            let data = tokio::fs::read_to_string(path)
+                .await
+                .with_context(|| anyhow::anyhow!("Failed to read {}", path.display()))?;
+            serde_json::from_str(&data)
+                .with_context(|| anyhow::anyhow!("Invalid puzzle config in {}", path.display()))?
+        }
+        (None, None) => unreachable!("clap requires a config source"),
+    };
+    let frequencies = tokio::fs::read_to_string(&opts.frequencies)
+        .await
+        .with_context(|| anyhow::anyhow!("Failed to read {}", opts.frequencies.display()))?;
+    let ranks: HashMap<&str, usize> = frequencies
+        .lines()
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .enumerate()
+        .map(|(rank, word)| (word, rank))
+        .collect();
+
+    let max_score = config.scoring.max_score(&config.valid_words);
+    println!(
+        "Puzzle: {} / {}, {} words, max score {}",
+        config.required_letter.0,
+        config.other_letters.iter().map(|l| l.0).collect::<String>(),
+        config.valid_words.len(),
+        max_score,
+    );
+    let unranked = config
+        .valid_words
+        .iter()
+        .filter(|w| !ranks.contains_key(w.word.as_str()))
+        .count();
+    if unranked > 0 {
+        println!(
+            "{} words are missing from the frequency list and are never found",
+            unranked
+        );
+    }
+
+    let mut rng = match opts.seed {
+        Some(seed) => rand::rngs::StdRng::seed_from_u64(seed),
+        None => rand::rngs::StdRng::from_os_rng(),
+    };
+    for vocabulary in &opts.vocabularies {
+        let known: Vec<&Word> = config
+            .valid_words
+            .iter()
+            .filter(|w| ranks.get(w.word.as_str()).is_some_and(|r| r < vocabulary))
+            .collect();
+        let mut scores: Vec<u32> = (0..opts.trials)
+            .map(|_| {
+                known
+                    .iter()
+                    .filter(|_| rng.random_bool(opts.recall))
+                    .map(|w| w.score(&config.scoring))
+                    .sum()
+            })
+            .collect();
+        scores.sort();
+        report(&config, *vocabulary, known.len(), &scores, max_score);
+    }
+
+    Ok(())
+}
+
+/// Simulate players of a puzzle to see which ranks they can be expected to
+/// reach.
+///
+/// Each simulated player knows the `vocabulary` most common words from the
+/// frequency list and thinks of each known word in the puzzle with probability
+/// `recall`. Scores are reported per vocabulary size, along with the share of
+/// players reaching each rank, to calibrate the rank thresholds and the
+/// generation constraints.
+#[derive(Debug, clap::Parser)]
+#[command(group = clap::ArgGroup::new("config").required(true))]
+struct Opts {
+    /// URL of a puzzle config endpoint, e.g.
+    /// http://localhost:3000/api/puzzle/daily/config?tz=%2B00:00
+    #[arg(long, group = "config")]
+    config_url: Option<String>,
+
+    /// Filepath of a puzzle config saved as JSON.
+    #[arg(long, group = "config")]
+    config_file: Option<std::path::PathBuf>,
+
+    /// Filepath of a newline-delimited word list ordered from the most to the
+    /// least common word.
+    #[arg(short, long)]
+    frequencies: std::path::PathBuf,
+
+    /// Comma-separated vocabulary sizes to simulate.
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        default_value = "5000,10000,20000,40000,80000"
+    )]
+    vocabularies: Vec<usize>,
+
+    /// Probability that a player finds a word they know.
+    #[arg(short, long, default_value_t = 0.7)]
+    recall: f64,
+
+    /// Number of players simulated per vocabulary size.
+    #[arg(short, long, default_value_t = 1000)]
+    trials: usize,
+
+    /// Seed for reproducible runs.
+    #[arg(short, long)]
+    seed: Option<u64>,
+}
+
+async fn fetch_config(url: &str) -> anyhow::Result<PuzzleConfig> {
+    let uri: hyper::Uri = url
+        .parse()
+        .with_context(|| anyhow::anyhow!("Invalid url {}", url))?;
+    let client: Client<_, Empty<Bytes>> = Client::builder(TokioExecutor::new()).build_http();
+    let response = client
+        .get(uri)
+        .await
+        .with_context(|| anyhow::anyhow!("Request to {} failed", url))?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .with_context(|| anyhow::anyhow!("Failed to read response from {}", url))?
+        .to_bytes();
+    if !status.is_success() {
+        anyhow::bail!("Request to {} failed with status {}", url, status);
+    }
+
+    serde_json::from_slice(&body)
+        .with_context(|| anyhow::anyhow!("Invalid puzzle config from {}", url))
+}
+
+fn report(config: &PuzzleConfig, vocabulary: usize, known: usize, scores: &[u32], max_score: u32) {
+    println!();
+    println!(
+        "Vocabulary {}: knows {} of {} words",
+        vocabulary,
+        known,
+        config.valid_words.len()
+    );
+    println!(
+        "  score p10 {}, p50 {}, p90 {} of {}",
+        percentile(scores, 0.1),
+        percentile(scores, 0.5),
+        percentile(scores, 0.9),
+        max_score,
+    );
+    for (label, threshold) in &config.score_buckets {
+        let reached = scores.iter().filter(|s| *s >= threshold).count();
+        println!(
+            "  {:<12} {:>5} ({:>5.1}% of max) reached by {:>5.1}%",
+            label,
+            threshold,
+            percent(*threshold as usize, max_score as usize),
+            percent(reached, scores.len()),
+        );
+    }
+}
+
+fn percentile(sorted: &[u32], q: f64) -> u32 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((q * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+    sorted[rank - 1]
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
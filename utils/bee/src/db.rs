@@ -0,0 +1,780 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{BufWriter, Write as _};
+
+use anyhow::Context;
+use sqlx::Connection;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+use crate::Format;
+
+#[derive(Debug, clap::Subcommand)]
+pub(crate) enum Command {
+    /// Add words from a file containing a newline-delimited list of words.
+    ///
+    /// Words that trivially fail the checks of the game are skipped: words
+    /// shorter than 4 letters and words with letters outside the alphabet.
+    /// Words are downcased and folded into the alphabet, such as German `ß`
+    /// into `ss`, and words already in the database are left as they are,
+    /// but for the frequency of words in a frequency list. Once done, a report
+    /// accounts for every line of the file.
+    Import {
+        #[command(flatten)]
+        database: Database,
+
+        /// Filepath of the word list, with one word per line.
+        words_file: std::path::PathBuf,
+
+        /// Dictionary to add the words to, created if there's none called
+        /// that.
+        #[arg(long, default_value = STANDARD_WORD_LIST)]
+        word_list: String,
+
+        /// Alphabet of the word list, which should be the server's.
+        #[arg(long, default_value_t = words::Alphabet::LATIN)]
+        alphabet: words::Alphabet,
+
+        /// Read the file as a frequency list, with a word and how often it
+        /// occurs separated by a tab on each line, and store the frequencies.
+        #[arg(long)]
+        frequencies: bool,
+
+        /// Skip words of a frequency list occurring fewer times than this,
+        /// which are mostly typos and junk.
+        #[arg(long, requires = "frequencies")]
+        min_frequency: Option<u64>,
+
+        /// Number of words inserted at once.
+        #[arg(short, long, default_value_t = 1000)]
+        batch_size: usize,
+
+        /// Number of batches inserted at the same time, each on its own
+        /// connection.
+        #[arg(short, long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+
+        /// Report what would be imported without changing the database.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Filepath to write the skipped words to, each with why it was
+        /// skipped, for review.
+        #[arg(long)]
+        rejects_file: Option<std::path::PathBuf>,
+    },
+
+    /// Compare a word list with the words in the database: words of the list
+    /// the database doesn't have, words the database has but the list
+    /// doesn't, and words of the list that can't be in the game.
+    Diff {
+        #[command(flatten)]
+        database: Database,
+
+        /// Filepath of the word list, with one word per line.
+        #[arg(short, long)]
+        file: std::path::PathBuf,
+
+        /// Dictionary to compare the list with.
+        #[arg(long, default_value = STANDARD_WORD_LIST)]
+        word_list: String,
+
+        /// Alphabet of the word list, which should be the server's.
+        #[arg(long, default_value_t = words::Alphabet::LATIN)]
+        alphabet: words::Alphabet,
+
+        /// Make the database match the list, adding the words it lacks and
+        /// removing the ones the list doesn't have. Removed words can be
+        /// restored from the management page.
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// Print the words in the database in alphabetical order, as a word list
+    /// that can be imported again or as JSON with their definitions,
+    /// obscurity and frequency. Removed words are left out.
+    Export {
+        #[command(flatten)]
+        database: Database,
+
+        /// Dictionary to print the words of.
+        #[arg(long, default_value = STANDARD_WORD_LIST)]
+        word_list: String,
+    },
+}
+
+/// Dictionary the server uses unless another is chosen.
+const STANDARD_WORD_LIST: &str = "standard";
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Database {
+    /// URL of the database to connect to.
+    #[arg(short, long, env = "DATABASE_URL")]
+    database_url: String,
+}
+
+impl Database {
+    async fn connect(&self) -> anyhow::Result<sqlx::PgConnection> {
+        sqlx::PgConnection::connect(&self.database_url)
+            .await
+            .with_context(|| anyhow::anyhow!("Failed to connect to database {}", self.database_url))
+    }
+
+    async fn pool(&self, connections: u32) -> anyhow::Result<sqlx::PgPool> {
+        sqlx::postgres::PgPoolOptions::new()
+            .max_connections(connections)
+            .connect(&self.database_url)
+            .await
+            .with_context(|| anyhow::anyhow!("Failed to connect to database {}", self.database_url))
+    }
+}
+
+pub(crate) async fn run(command: Command, format: Format) -> anyhow::Result<()> {
+    match command {
+        Command::Import {
+            database,
+            words_file,
+            word_list,
+            alphabet,
+            frequencies,
+            min_frequency,
+            batch_size,
+            concurrency,
+            dry_run,
+            rejects_file,
+        } => {
+            let options = ImportOptions {
+                word_list,
+                alphabet,
+                frequencies,
+                min_frequency: min_frequency.unwrap_or(0),
+                batch_size,
+                concurrency,
+                dry_run,
+                rejects_file,
+                progress: format == Format::Text,
+            };
+            let pool = database.pool(concurrency).await?;
+            let report = import(&pool, &words_file, &options).await?;
+            report.print(&options, format);
+            Ok(())
+        }
+        Command::Diff {
+            database,
+            file,
+            word_list,
+            alphabet,
+            apply,
+        } => {
+            diff(
+                &mut database.connect().await?,
+                &file,
+                &word_list,
+                alphabet,
+                apply,
+                format,
+            )
+            .await
+        }
+        Command::Export {
+            database,
+            word_list,
+        } => export(&mut database.connect().await?, &word_list, format).await,
+    }
+}
+
+struct ImportOptions {
+    /// Name of the dictionary imported into.
+    word_list: String,
+    alphabet: words::Alphabet,
+    frequencies: bool,
+    min_frequency: u64,
+    batch_size: usize,
+    concurrency: u32,
+    dry_run: bool,
+    rejects_file: Option<std::path::PathBuf>,
+    /// Whether to print how far the import got to stderr.
+    progress: bool,
+}
+
+/// What became of each line of an imported word list.
+#[derive(Debug, Default)]
+struct ImportReport {
+    lines: u64,
+    /// Words shorter than 4 letters, blank lines included.
+    too_short: u64,
+    /// Words with letters other than a to z.
+    not_letters: u64,
+    /// Lines of a frequency list without a word and a frequency.
+    malformed: u64,
+    /// Words of a frequency list below the minimum frequency.
+    too_rare: u64,
+    /// Words seen earlier in the file or already in the database.
+    duplicates: u64,
+    inserted: u64,
+}
+
+impl ImportReport {
+    fn print(&self, options: &ImportOptions, format: Format) {
+        match format {
+            Format::Text => {
+                let inserted = if options.dry_run {
+                    "would be added"
+                } else {
+                    "added"
+                };
+                println!("{} lines read", self.lines);
+                println!("  {:>8} {}", self.inserted, inserted);
+                println!("  {:>8} already known", self.duplicates);
+                println!("  {:>8} too short", self.too_short);
+                println!("  {:>8} not spelled with a to z", self.not_letters);
+                if options.frequencies {
+                    println!("  {:>8} malformed", self.malformed);
+                    println!("  {:>8} too rare", self.too_rare);
+                }
+            }
+            Format::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "dry_run": options.dry_run,
+                    "lines": self.lines,
+                    "inserted": self.inserted,
+                    "duplicates": self.duplicates,
+                    "too_short": self.too_short,
+                    "not_letters": self.not_letters,
+                    "malformed": self.malformed,
+                    "too_rare": self.too_rare,
+                })
+            ),
+        }
+    }
+}
+
+/// A batch of words to insert, numbered in the order of the file.
+struct Batch {
+    index: usize,
+    /// Line of the file the batch ends on.
+    last_line: u64,
+    words: Vec<NewWord>,
+}
+
+struct NewWord {
+    word: String,
+    mask: words::Bitmask,
+    frequency: Option<i64>,
+}
+
+/// Batches being inserted, up to `concurrency` at a time. Batches can finish
+/// in any order, so their results are held back until the batches before them
+/// are done, which keeps the progress reported a line of the file up to which
+/// everything is imported.
+struct Importer<'a> {
+    pool: &'a sqlx::PgPool,
+    options: &'a ImportOptions,
+    /// Id of the dictionary imported into, `None` on a dry run into one that
+    /// doesn't exist yet.
+    dictionary: Option<i32>,
+    running: tokio::task::JoinSet<(usize, anyhow::Result<u64>)>,
+    /// Line each batch not yet counted ends on and its number of words, by
+    /// batch.
+    started: BTreeMap<usize, (u64, u64)>,
+    /// Finished batches waiting on earlier ones, with the number of their
+    /// words inserted.
+    finished: BTreeMap<usize, u64>,
+    next_batch: usize,
+    /// Line up to which every word is imported.
+    imported_through: u64,
+    errors: Vec<String>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(pool: &'a sqlx::PgPool, options: &'a ImportOptions, dictionary: Option<i32>) -> Self {
+        Self {
+            pool,
+            options,
+            dictionary,
+            running: tokio::task::JoinSet::new(),
+            started: BTreeMap::new(),
+            finished: BTreeMap::new(),
+            next_batch: 0,
+            imported_through: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Start inserting `batch`, first waiting for a running batch to finish if
+    /// as many as allowed are running.
+    async fn start(&mut self, batch: Batch, report: &mut ImportReport) {
+        while self.running.len() >= self.options.concurrency as usize {
+            self.finish_one(report).await;
+        }
+        self.started
+            .insert(batch.index, (batch.last_line, batch.words.len() as u64));
+        let pool = self.pool.clone();
+        let dictionary = self.dictionary;
+        let dry_run = self.options.dry_run;
+        self.running.spawn(async move {
+            let inserted = import_batch(&pool, dictionary, &batch.words, dry_run).await;
+            (batch.index, inserted)
+        });
+    }
+
+    async fn finish_one(&mut self, report: &mut ImportReport) {
+        match self.running.join_next().await {
+            Some(Ok((index, Ok(inserted)))) => {
+                self.finished.insert(index, inserted);
+            }
+            Some(Ok((index, Err(e)))) => {
+                let (last_line, _) = self.started[&index];
+                self.errors
+                    .push(format!("Batch ending on line {}: {:#}", last_line, e));
+            }
+            Some(Err(e)) => self.errors.push(format!("Batch panicked: {}", e)),
+            None => return,
+        }
+
+        let imported_through = self.imported_through;
+        while let Some(inserted) = self.finished.remove(&self.next_batch) {
+            let (last_line, len) = self.started.remove(&self.next_batch).unwrap_or_default();
+            report.inserted += inserted;
+            report.duplicates += len - inserted;
+            self.imported_through = last_line;
+            self.next_batch += 1;
+        }
+        if self.options.progress && self.imported_through != imported_through {
+            let verb = if self.options.dry_run {
+                "Checked"
+            } else {
+                "Imported"
+            };
+            eprint!("\r{} up to line {}", verb, self.imported_through);
+        }
+    }
+
+    fn has_failed(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Wait for the running batches, failing with the errors of every batch
+    /// that failed.
+    async fn finish(mut self, report: &mut ImportReport) -> anyhow::Result<()> {
+        while !self.running.is_empty() {
+            self.finish_one(report).await;
+        }
+        if self.options.progress && self.imported_through > 0 {
+            eprintln!();
+        }
+        if !self.errors.is_empty() {
+            anyhow::bail!(
+                "{} batches failed, words are imported up to line {}:\n{}",
+                self.errors.len(),
+                self.imported_through,
+                self.errors.join("\n")
+            );
+        }
+        Ok(())
+    }
+}
+
+async fn import(
+    pool: &sqlx::PgPool,
+    words_file: &std::path::Path,
+    options: &ImportOptions,
+) -> anyhow::Result<ImportReport> {
+    let file = tokio::fs::File::open(words_file)
+        .await
+        .with_context(|| anyhow::anyhow!("Failed to open file {}", words_file.display()))?;
+    let mut rejects = match &options.rejects_file {
+        Some(path) => Some(tokio::io::BufWriter::new(
+            tokio::fs::File::create(path)
+                .await
+                .with_context(|| anyhow::anyhow!("Failed to create file {}", path.display()))?,
+        )),
+        None => None,
+    };
+
+    // Dry runs leave the database alone, so a new dictionary isn't created
+    // and every word counts as new to it.
+    let dictionary = if options.dry_run {
+        find_dictionary(pool, &options.word_list).await?
+    } else {
+        Some(create_dictionary(pool, &options.word_list).await?)
+    };
+    let mut report = ImportReport::default();
+    let mut importer = Importer::new(pool, options, dictionary);
+    let mut batch_index = 0;
+    let mut seen = HashSet::new();
+    let mut reader = tokio::io::BufReader::new(file);
+    let mut batch = Vec::with_capacity(options.batch_size);
+    let mut line = String::new();
+    while !importer.has_failed() && reader.read_line(&mut line).await? != 0 {
+        report.lines += 1;
+        let fold = |word: &str| options.alphabet.fold(word.trim());
+        let (word, frequency) = match (options.frequencies, line.trim().split_once('\t')) {
+            (false, _) => (fold(&line), None),
+            (true, Some((word, frequency))) => (fold(word), frequency.trim().parse::<u64>().ok()),
+            (true, None) => (fold(&line), None),
+        };
+        line.clear();
+
+        let rejection = if options.frequencies && frequency.is_none() {
+            report.malformed += 1;
+            Some("not a word and a frequency".to_owned())
+        } else if frequency.is_some_and(|frequency| frequency < options.min_frequency) {
+            report.too_rare += 1;
+            Some("too rare".to_owned())
+        } else if word.chars().count() < 4 {
+            report.too_short += 1;
+            Some("too short".to_owned())
+        } else if !seen.insert(word.clone()) {
+            report.duplicates += 1;
+            Some("repeated".to_owned())
+        } else {
+            match options.alphabet.try_bitmask(&word) {
+                Ok(mask) => {
+                    batch.push(NewWord {
+                        word: word.clone(),
+                        mask,
+                        frequency: frequency.map(|frequency| frequency.min(i64::MAX as u64) as i64),
+                    });
+                    None
+                }
+                Err(e) => {
+                    report.not_letters += 1;
+                    Some(e.to_string())
+                }
+            }
+        };
+        if let (Some(rejects), Some(reason)) = (&mut rejects, rejection)
+            && !word.is_empty()
+        {
+            rejects
+                .write_all(format!("{}\t{}\n", word, reason).as_bytes())
+                .await?;
+        }
+
+        if batch.len() == options.batch_size {
+            let words = std::mem::replace(&mut batch, Vec::with_capacity(options.batch_size));
+            let batch = Batch {
+                index: batch_index,
+                last_line: report.lines,
+                words,
+            };
+            importer.start(batch, &mut report).await;
+            batch_index += 1;
+        }
+    }
+    if !batch.is_empty() && !importer.has_failed() {
+        let batch = Batch {
+            index: batch_index,
+            last_line: report.lines,
+            words: batch,
+        };
+        importer.start(batch, &mut report).await;
+    }
+    if let Some(mut rejects) = rejects {
+        rejects.flush().await?;
+    }
+
+    importer.finish(&mut report).await?;
+    Ok(report)
+}
+
+/// Insert the words of `batch` that `dictionary` doesn't have yet, or only
+/// count them on a dry run, returning how many were or would be.
+async fn import_batch(
+    pool: &sqlx::PgPool,
+    dictionary: Option<i32>,
+    batch: &[NewWord],
+    dry_run: bool,
+) -> anyhow::Result<u64> {
+    if dry_run {
+        let words: Vec<&str> = batch.iter().map(|word| word.word.as_str()).collect();
+        let (known,): (i64,) = sqlx::query_as(
+            "select count(*) from words where dictionary_id = $1 and word = any($2)",
+        )
+        .bind(dictionary)
+        .bind(&words)
+        .fetch_one(pool)
+        .await
+        .context("Failed to look up word batch")?;
+        Ok(batch.len() as u64 - known as u64)
+    } else {
+        let dictionary = dictionary.context("No dictionary to import into")?;
+        upsert_words(pool, dictionary, batch).await
+    }
+}
+
+/// Insert `words` that aren't in `dictionary` yet, returning how many were.
+/// Words already in it only get their frequency updated, if they come with
+/// one.
+async fn upsert_words(
+    pool: &sqlx::PgPool,
+    dictionary: i32,
+    words: &[NewWord],
+) -> anyhow::Result<u64> {
+    let mut builder = sqlx::QueryBuilder::new(
+        "insert into words (dictionary_id, word, letter_mask, length, frequency) ",
+    );
+    builder.push_values(words, |mut b, word| {
+        let mask = i64::from(word.mask);
+        let length = word.word.chars().count();
+        b.push_bind(dictionary)
+            .push_bind(&word.word)
+            .push_bind(mask)
+            .push_bind(length as i32)
+            .push_bind(word.frequency);
+    });
+
+    if words.iter().any(|word| word.frequency.is_some()) {
+        // Updated rows are returned too; only inserted ones have no xmax.
+        builder.push(
+            "on conflict (dictionary_id, word) do update set frequency = excluded.frequency
+            returning xmax = 0",
+        );
+        let inserted: Vec<(bool,)> = builder
+            .build_query_as()
+            .fetch_all(pool)
+            .await
+            .with_context(|| anyhow::anyhow!("Failed to upsert word batch"))?;
+        Ok(inserted.iter().filter(|(inserted,)| *inserted).count() as u64)
+    } else {
+        builder.push("on conflict do nothing");
+        builder
+            .build()
+            .execute(pool)
+            .await
+            .with_context(|| anyhow::anyhow!("Failed to upsert word batch"))
+            .map(|result| result.rows_affected())
+    }
+}
+
+/// Id of the dictionary called `name`, if there is one.
+async fn find_dictionary<'c>(
+    executor: impl sqlx::PgExecutor<'c>,
+    name: &str,
+) -> anyhow::Result<Option<i32>> {
+    sqlx::query_scalar("select id from dictionaries where name = $1")
+        .bind(name)
+        .fetch_optional(executor)
+        .await
+        .with_context(|| anyhow::anyhow!("Failed to look up dictionary {}", name))
+}
+
+/// Id of the dictionary called `name`, which is created if there's none.
+async fn create_dictionary(pool: &sqlx::PgPool, name: &str) -> anyhow::Result<i32> {
+    sqlx::query_scalar(
+        "insert into dictionaries (name) values ($1)
+        on conflict (name) do update set name = excluded.name
+        returning id",
+    )
+    .bind(name)
+    .fetch_one(pool)
+    .await
+    .with_context(|| anyhow::anyhow!("Failed to create dictionary {}", name))
+}
+
+/// Id of the dictionary called `name`, failing if there's none.
+async fn existing_dictionary(
+    connection: &mut sqlx::PgConnection,
+    name: &str,
+) -> anyhow::Result<i32> {
+    find_dictionary(&mut *connection, name)
+        .await?
+        .with_context(|| anyhow::anyhow!("There is no dictionary called {}", name))
+}
+
+async fn diff(
+    connection: &mut sqlx::PgConnection,
+    words_file: &std::path::Path,
+    word_list: &str,
+    alphabet: words::Alphabet,
+    apply: bool,
+    format: Format,
+) -> anyhow::Result<()> {
+    let contents = tokio::fs::read_to_string(words_file)
+        .await
+        .with_context(|| anyhow::anyhow!("Failed to read file {}", words_file.display()))?;
+    let mut listed = BTreeMap::new();
+    let mut invalid = BTreeMap::new();
+    let lines = contents
+        .lines()
+        .map(|line| alphabet.fold(line.trim()))
+        .filter(|word| !word.is_empty());
+    for word in lines {
+        if word.chars().count() < 4 {
+            invalid.insert(word, "too short".to_owned());
+            continue;
+        }
+        match alphabet.try_bitmask(&word) {
+            Ok(mask) => {
+                listed.insert(word, mask);
+            }
+            Err(e) => {
+                invalid.insert(word, e.to_string());
+            }
+        }
+    }
+
+    let dictionary = existing_dictionary(connection, word_list).await?;
+    let known: BTreeSet<String> = sqlx::query_scalar(
+        "select word from words where dictionary_id = $1 and deleted_at is null",
+    )
+    .bind(dictionary)
+    .fetch_all(&mut *connection)
+    .await
+    .context("Failed to read words")?
+    .into_iter()
+    .collect();
+    let added: Vec<(&String, words::Bitmask)> = listed
+        .iter()
+        .filter(|(word, _)| !known.contains(*word))
+        .map(|(word, mask)| (word, *mask))
+        .collect();
+    let removed: Vec<&String> = known
+        .iter()
+        .filter(|word| !listed.contains_key(*word))
+        .collect();
+
+    if apply {
+        apply_diff(connection, dictionary, &added, &removed).await?;
+    }
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    match format {
+        Format::Text => {
+            for (word, _) in &added {
+                writeln!(&mut stdout, "+ {}", word)?;
+            }
+            for word in &removed {
+                writeln!(&mut stdout, "- {}", word)?;
+            }
+            for (word, reason) in &invalid {
+                writeln!(&mut stdout, "! {}: {}", word, reason)?;
+            }
+            let (add, remove) = if apply {
+                ("added", "removed")
+            } else {
+                ("to add", "to remove")
+            };
+            writeln!(
+                &mut stdout,
+                "{} {}, {} {}, {} invalid",
+                added.len(),
+                add,
+                removed.len(),
+                remove,
+                invalid.len()
+            )?;
+        }
+        Format::Json => {
+            let invalid: Vec<serde_json::Value> = invalid
+                .iter()
+                .map(|(word, reason)| serde_json::json!({ "word": word, "reason": reason }))
+                .collect();
+            let diff = serde_json::json!({
+                "applied": apply,
+                "added": added.iter().map(|(word, _)| word).collect::<Vec<_>>(),
+                "removed": removed,
+                "invalid": invalid,
+            });
+            writeln!(&mut stdout, "{}", diff)?;
+        }
+    }
+    Ok(stdout.flush()?)
+}
+
+/// Add and remove words of `dictionary` in one transaction, recording them in
+/// the audit log like edits made on the management page, without an editor.
+async fn apply_diff(
+    connection: &mut sqlx::PgConnection,
+    dictionary: i32,
+    added: &[(&String, words::Bitmask)],
+    removed: &[&String],
+) -> anyhow::Result<()> {
+    let mut tx = connection.begin().await?;
+
+    let words: Vec<&str> = added.iter().map(|(word, _)| word.as_str()).collect();
+    let masks: Vec<i64> = added.iter().map(|(_, mask)| i64::from(*mask)).collect();
+    let lengths: Vec<i32> = added
+        .iter()
+        .map(|(word, _)| word.chars().count() as i32)
+        .collect();
+    sqlx::query(
+        "with added as (
+            insert into words (dictionary_id, word, letter_mask, length)
+            select $1, * from unnest($2::text[], $3::bigint[], $4::integer[])
+            on conflict (dictionary_id, word) do update set deleted_at = null
+            where words.deleted_at is not null
+            returning word
+        )
+        insert into word_audit (dictionary_id, word, action)
+        select $1, word, 'added' from added",
+    )
+    .bind(dictionary)
+    .bind(&words)
+    .bind(&masks)
+    .bind(&lengths)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to add words")?;
+
+    let removed: Vec<&str> = removed.iter().map(|word| word.as_str()).collect();
+    sqlx::query(
+        "with removed as (
+            update words set deleted_at = now()
+            where dictionary_id = $1 and word = any($2) and deleted_at is null
+            returning word
+        )
+        insert into word_audit (dictionary_id, word, action)
+        select $1, word, 'removed' from removed",
+    )
+    .bind(dictionary)
+    .bind(&removed)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to remove words")?;
+
+    tx.commit().await.context("Failed to apply the diff")
+}
+
+/// A word with its definition, obscurity and frequency.
+type ExportedWord = (String, Option<String>, Option<i16>, Option<i64>);
+
+async fn export(
+    connection: &mut sqlx::PgConnection,
+    word_list: &str,
+    format: Format,
+) -> anyhow::Result<()> {
+    let dictionary = existing_dictionary(connection, word_list).await?;
+    let words: Vec<ExportedWord> = sqlx::query_as(
+        "select word, definition, obscurity, frequency from words
+        where dictionary_id = $1 and deleted_at is null order by word",
+    )
+    .bind(dictionary)
+    .fetch_all(connection)
+    .await
+    .context("Failed to read words")?;
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    match format {
+        Format::Text => {
+            for (word, _, _, _) in &words {
+                writeln!(&mut stdout, "{}", word)?;
+            }
+        }
+        Format::Json => {
+            let words: Vec<serde_json::Value> = words
+                .into_iter()
+                .map(|(word, definition, obscurity, frequency)| {
+                    serde_json::json!({
+                        "word": word,
+                        "definition": definition,
+                        "obscurity": obscurity,
+                        "frequency": frequency,
+                    })
+                })
+                .collect();
+            writeln!(&mut stdout, "{}", serde_json::Value::from(words))?;
+        }
+    }
+    Ok(stdout.flush()?)
+}
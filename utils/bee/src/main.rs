@@ -0,0 +1,49 @@
+use clap::Parser;
+
+mod db;
+mod mask;
+mod puzzle;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Opts::parse();
+
+    match opts.command {
+        Command::Mask(args) => mask::run(args, opts.format),
+        Command::Db(command) => db::run(command, opts.format).await,
+        Command::Puzzle(command) => puzzle::run(command, opts.format).await,
+    }
+}
+
+/// Tools for running the spelling bee: managing the word database and
+/// inspecting puzzles.
+#[derive(Debug, clap::Parser)]
+struct Opts {
+    /// How to print results: text to read, or JSON for other tools.
+    #[arg(long, value_enum, global = true, default_value_t = Format::Text)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Compute the letter bitmasks of words or sets of letters, the letters
+    /// of masks, or which words only use the letters of a mask.
+    Mask(mask::Args),
+
+    /// Import words into the database or export them.
+    #[command(subcommand)]
+    Db(db::Command),
+
+    /// Preview and generate puzzles with a running server.
+    #[command(subcommand)]
+    Puzzle(puzzle::Command),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
@@ -0,0 +1,157 @@
+use std::io::{BufRead as _, BufWriter, Write as _};
+
+use words::{Alphabet, Bitmask};
+
+use crate::Format;
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+    /// Words or sets of letters, or masks with `--invert`.
+    #[arg(conflicts_with = "subset_of")]
+    charsets: Vec<String>,
+
+    /// Print the letters of masks instead, given in binary like the masks
+    /// printed, with or without a 0b prefix, or as integers like the ones
+    /// stored in the database.
+    #[arg(long)]
+    invert: bool,
+
+    /// Print the words read from stdin that only use the letters of this
+    /// mask, given as a mask or as letters.
+    #[arg(long, value_name = "MASK", value_parser = parse_mask)]
+    subset_of: Option<Bitmask>,
+
+    /// Alphabet of the words and letters.
+    #[arg(long, default_value_t = Alphabet::LATIN)]
+    alphabet: Alphabet,
+}
+
+pub(crate) fn run(args: Args, format: Format) -> anyhow::Result<()> {
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    match args.subset_of {
+        Some(letters) => subset_of(args.alphabet, letters, &mut stdout, format)?,
+        None if args.invert => invert(args.alphabet, &args.charsets, &mut stdout, format)?,
+        None => mask(args.alphabet, &args.charsets, &mut stdout, format)?,
+    }
+    Ok(stdout.flush()?)
+}
+
+fn mask(
+    alphabet: Alphabet,
+    charsets: &[String],
+    stdout: &mut impl std::io::Write,
+    format: Format,
+) -> anyhow::Result<()> {
+    let masks = charsets
+        .iter()
+        .map(|set| (set, alphabet.try_bitmask(&alphabet.fold(set))));
+    let width = alphabet.width() as usize;
+    match format {
+        Format::Text => {
+            for (set, mask) in masks {
+                match mask {
+                    Ok(mask) => writeln!(stdout, "{}: {:0>width$b}", set, mask.bits())?,
+                    Err(e) => writeln!(stdout, "{}: {}", set, e)?,
+                }
+            }
+        }
+        Format::Json => {
+            let masks: Vec<serde_json::Value> = masks
+                .map(|(set, mask)| match mask {
+                    Ok(mask) => serde_json::json!({ "charset": set, "mask": mask.bits() }),
+                    Err(e) => serde_json::json!({ "charset": set, "error": e.to_string() }),
+                })
+                .collect();
+            writeln!(stdout, "{}", serde_json::Value::from(masks))?;
+        }
+    }
+    Ok(())
+}
+
+fn invert(
+    alphabet: Alphabet,
+    masks: &[String],
+    stdout: &mut impl std::io::Write,
+    format: Format,
+) -> anyhow::Result<()> {
+    let letters = masks.iter().map(|mask| {
+        let letters = parse_bits(mask).and_then(|bits| {
+            alphabet
+                .try_mask(i64::from(bits))
+                .map(|mask| alphabet.letters_of(mask))
+                .map_err(|e| e.to_string())
+        });
+        (mask, letters.map(String::from_iter))
+    });
+    match format {
+        Format::Text => {
+            for (mask, letters) in letters {
+                match letters {
+                    Ok(letters) => writeln!(stdout, "{}: {}", mask, letters)?,
+                    Err(e) => writeln!(stdout, "{}: {}", mask, e)?,
+                }
+            }
+        }
+        Format::Json => {
+            let letters: Vec<serde_json::Value> = letters
+                .map(|(mask, letters)| match letters {
+                    Ok(letters) => serde_json::json!({ "mask": mask, "letters": letters }),
+                    Err(e) => serde_json::json!({ "mask": mask, "error": e }),
+                })
+                .collect();
+            writeln!(stdout, "{}", serde_json::Value::from(letters))?;
+        }
+    }
+    Ok(())
+}
+
+fn subset_of(
+    alphabet: Alphabet,
+    letters: Bitmask,
+    stdout: &mut impl std::io::Write,
+    format: Format,
+) -> anyhow::Result<()> {
+    let mut words = Vec::new();
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        let word = line.trim();
+        // Words with other characters can't be spelled with letters either.
+        if !alphabet
+            .try_bitmask(&alphabet.fold(word))
+            .is_ok_and(|mask| mask.is_subset_of(letters))
+        {
+            continue;
+        }
+        match format {
+            Format::Text => writeln!(stdout, "{}", word)?,
+            Format::Json => words.push(word.to_owned()),
+        }
+    }
+    if format == Format::Json {
+        writeln!(stdout, "{}", serde_json::Value::from(words))?;
+    }
+    Ok(())
+}
+
+/// Read a mask given as a mask or as letters of any alphabet.
+fn parse_mask(value: &str) -> Result<Bitmask, String> {
+    parse_bits(value).or_else(|e| {
+        value
+            .chars()
+            .try_fold(Bitmask::EMPTY, |mask, c| Ok(mask | Bitmask::try_from(c)?))
+            .map_err(|_: words::BitmaskError| e)
+    })
+}
+
+/// Read a mask given in binary, with an optional 0b prefix, or as an integer.
+/// Binary masks without a prefix are told apart from integers by having at
+/// least 26 digits, as they're printed.
+fn parse_bits(value: &str) -> Result<Bitmask, String> {
+    let bits = match value.strip_prefix("0b") {
+        Some(binary) => i64::from_str_radix(binary, 2),
+        None if value.len() >= 26 => i64::from_str_radix(value, 2),
+        None => value.parse(),
+    };
+    let bits = bits.map_err(|_| format!("{:?} is not a mask", value))?;
+    Bitmask::try_from(bits).map_err(|e| e.to_string())
+}
@@ -0,0 +1,223 @@
+use anyhow::Context;
+use http_body_util::{BodyExt, Empty};
+use hyper::body::Bytes;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use puzzle_config::{Difficulty, PuzzleConfig, PuzzlePreview, Word};
+
+use crate::Format;
+
+#[derive(Debug, clap::Subcommand)]
+pub(crate) enum Command {
+    /// Evaluate seven letters as a puzzle without storing it.
+    Preview {
+        #[command(flatten)]
+        server: Server,
+
+        /// The seven letters of the puzzle.
+        letters: String,
+
+        /// The letter every word must use.
+        #[arg(short, long)]
+        required: char,
+
+        /// Dictionary to take the words from, the standard one if not given.
+        #[arg(long)]
+        word_list: Option<String>,
+    },
+
+    /// Get the puzzle of a day, generating it if it hasn't been yet.
+    ///
+    /// Only days that have started in the timezone can be generated.
+    Generate {
+        #[command(flatten)]
+        server: Server,
+
+        /// Day of the puzzle, formatted as YYYY-MM-DD.
+        #[arg(long)]
+        date: String,
+
+        /// Timezone of the day, an offset like +09:00 or a zone like
+        /// Asia/Tokyo.
+        #[arg(long, default_value = "+00:00")]
+        tz: String,
+
+        #[arg(long, default_value = "medium")]
+        difficulty: Difficulty,
+
+        /// Dictionary to restrict the puzzle's words to.
+        #[arg(long, default_value = "permissive", value_parser = ["permissive", "strict"])]
+        dictionary: String,
+
+        /// Dictionary to take the words from, the standard one if not given.
+        #[arg(long)]
+        word_list: Option<String>,
+    },
+}
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Server {
+    /// Base URL of the server.
+    #[arg(long, env = "BEE_SERVER_URL", default_value = "http://localhost:3000")]
+    server_url: String,
+
+    /// The server's admin token, needed for admin routes.
+    #[arg(long, env = "BEE_ADMIN_TOKEN", hide_env_values = true)]
+    admin_token: Option<String>,
+}
+
+impl Server {
+    /// Get `path` with `query`, returning the body of a successful response.
+    async fn get(&self, path: &str, query: &[(&str, &str)]) -> anyhow::Result<Bytes> {
+        let query: Vec<String> = query
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, encode(value)))
+            .collect();
+        let url = format!(
+            "{}{}?{}",
+            self.server_url.trim_end_matches('/'),
+            path,
+            query.join("&")
+        );
+        let mut request = hyper::Request::get(&url);
+        if let Some(token) = &self.admin_token {
+            request = request.header(hyper::header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = request
+            .body(Empty::<Bytes>::new())
+            .with_context(|| anyhow::anyhow!("Invalid url {}", url))?;
+
+        let client: Client<_, Empty<Bytes>> = Client::builder(TokioExecutor::new()).build_http();
+        let response = client
+            .request(request)
+            .await
+            .with_context(|| anyhow::anyhow!("Request to {} failed", url))?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .with_context(|| anyhow::anyhow!("Failed to read response from {}", url))?
+            .to_bytes();
+        if !status.is_success() {
+            let message = serde_json::from_slice::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|body| body["message"].as_str().map(str::to_owned))
+                .unwrap_or_default();
+            anyhow::bail!(
+                "Request to {} failed with status {}: {}",
+                url,
+                status,
+                message
+            );
+        }
+        Ok(body)
+    }
+}
+
+pub(crate) async fn run(command: Command, format: Format) -> anyhow::Result<()> {
+    match command {
+        Command::Preview {
+            server,
+            letters,
+            required,
+            word_list,
+        } => {
+            let required = required.to_string();
+            let mut query = vec![("letters", letters.as_str()), ("required", &required)];
+            query.extend(word_list.as_deref().map(|name| ("word_list", name)));
+            let body = server.get("/api/admin/puzzle/preview", &query).await?;
+            match format {
+                Format::Text => {
+                    print_preview(&serde_json::from_slice(&body).context("Invalid puzzle preview")?)
+                }
+                Format::Json => println!("{}", String::from_utf8_lossy(&body)),
+            }
+        }
+        Command::Generate {
+            server,
+            date,
+            tz,
+            difficulty,
+            dictionary,
+            word_list,
+        } => {
+            let mut query = vec![
+                ("tz", tz.as_str()),
+                ("difficulty", difficulty.as_str()),
+                ("dictionary", &dictionary),
+            ];
+            query.extend(word_list.as_deref().map(|name| ("word_list", name)));
+            let body = server
+                .get(&format!("/api/puzzle/archive/{}", date), &query)
+                .await?;
+            match format {
+                Format::Text => print_puzzle(
+                    &date,
+                    &serde_json::from_slice(&body).context("Invalid puzzle config")?,
+                ),
+                Format::Json => println!("{}", String::from_utf8_lossy(&body)),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_preview(preview: &PuzzlePreview) {
+    println!(
+        "Puzzle: {} / {}, {} words, {} pangrams, max score {}",
+        preview.required_letter.0,
+        preview
+            .other_letters
+            .iter()
+            .map(|l| l.0)
+            .collect::<String>(),
+        preview.words.len(),
+        preview.pangrams.len(),
+        preview.max_score,
+    );
+    for warning in &preview.warnings {
+        println!("Warning: {}", warning);
+    }
+    println!();
+    for word in &preview.words {
+        let marker = if preview.pangrams.contains(word) {
+            " *"
+        } else {
+            ""
+        };
+        println!("{}{}", word, marker);
+    }
+}
+
+fn print_puzzle(date: &str, config: &PuzzleConfig) {
+    let mut words: Vec<&Word> = config.valid_words.iter().collect();
+    words.sort_by(|a, b| a.word.cmp(&b.word));
+    println!(
+        "Puzzle of {}: {} / {}, {} words, {} pangrams, max score {}",
+        date,
+        config.required_letter.0,
+        config.other_letters.iter().map(|l| l.0).collect::<String>(),
+        words.len(),
+        config.pangram_count(),
+        config.scoring.max_score(words.iter().copied()),
+    );
+    println!();
+    for word in words {
+        let marker = if word.is_pangram { " *" } else { "" };
+        println!("{}{}", word.word, marker);
+    }
+}
+
+/// Percent-encode `value` for a query string.
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use clap::Parser;
+use http_body_util::{BodyExt, Empty};
+use hyper::body::Bytes;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use sqlx::Connection;
+
+type HttpClient = Client<hyper_util::client::legacy::connect::HttpConnector, Empty<Bytes>>;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Opts::parse();
+
+    let uri: hyper::Uri = opts
+        .url
+        .parse()
+        .with_context(|| anyhow::anyhow!("Invalid url {}", opts.url))?;
+    let client: HttpClient = Client::builder(TokioExecutor::new()).build_http();
+    let mut connection = sqlx::PgConnection::connect(&opts.database_url)
+        .await
+        .with_context(|| anyhow::anyhow!("Failed to connect to database {}", opts.database_url))?;
+
+    // The first request of the day generates the puzzle; the budget is for the
+    // requests that follow it.
+    request(&client, &uri)
+        .await
+        .with_context(|| anyhow::anyhow!("Warm-up request to {} failed", uri))?;
+    settle_stats(opts.stats_settle_secs).await;
+
+    let transactions_before = transaction_count(&mut connection).await?;
+    let started = Instant::now();
+    let next = Arc::new(AtomicUsize::new(0));
+    let mut workers = tokio::task::JoinSet::new();
+    for _ in 0..opts.concurrency {
+        let client = client.clone();
+        let uri = uri.clone();
+        let next = next.clone();
+        let total = opts.requests;
+        workers.spawn(async move {
+            let mut latencies = Vec::new();
+            while next.fetch_add(1, Ordering::Relaxed) < total {
+                let sent = Instant::now();
+                request(&client, &uri).await?;
+                latencies.push(sent.elapsed());
+            }
+            anyhow::Ok(latencies)
+        });
+    }
+
+    let mut latencies = Vec::with_capacity(opts.requests);
+    while let Some(result) = workers.join_next().await {
+        latencies.extend(result.context("Benchmark worker panicked")??);
+    }
+    let elapsed = started.elapsed();
+
+    settle_stats(opts.stats_settle_secs).await;
+    // Includes the couple of transactions used to read the counts, which is
+    // negligible over a full run.
+    let transactions = transaction_count(&mut connection).await? - transactions_before;
+
+    latencies.sort();
+    let p50 = percentile(&latencies, 0.5);
+    let p99 = percentile(&latencies, 0.99);
+    let transactions_per_request = transactions as f64 / latencies.len() as f64;
+
+    println!(
+        "requests: {} in {:.2?} ({:.1} req/s) with concurrency {}",
+        latencies.len(),
+        elapsed,
+        latencies.len() as f64 / elapsed.as_secs_f64(),
+        opts.concurrency,
+    );
+    println!("p50: {:.2?} (budget {}ms)", p50, opts.p50_budget_ms);
+    println!("p99: {:.2?} (budget {}ms)", p99, opts.p99_budget_ms);
+    println!(
+        "db transactions per request: {:.2} (budget {:.2})",
+        transactions_per_request, opts.transactions_budget,
+    );
+
+    let mut exceeded = Vec::new();
+    if p50 > Duration::from_millis(opts.p50_budget_ms) {
+        exceeded.push("p50 latency");
+    }
+    if p99 > Duration::from_millis(opts.p99_budget_ms) {
+        exceeded.push("p99 latency");
+    }
+    if transactions_per_request > opts.transactions_budget {
+        exceeded.push("db transactions per request");
+    }
+    if !exceeded.is_empty() {
+        anyhow::bail!("Performance budget exceeded: {}", exceeded.join(", "));
+    }
+
+    println!("Within budget");
+    Ok(())
+}
+
+/// Load-testing harness for the daily puzzle config endpoint.
+///
+/// Sends `requests` GET requests to `url` from `concurrency` concurrent workers
+/// against a running, seeded server and reports latency percentiles and the
+/// number of database transactions per request. Exits with an error when any
+/// measurement is over its budget.
+///
+/// Database work is measured from the transaction counters in
+/// `pg_stat_database`, so the database should not be serving other traffic
+/// during a run. Each query the server runs outside an explicit transaction
+/// counts as one transaction.
+///
+/// The default budgets are for a release build of the server with its database
+/// on the same machine:
+///   - p50 latency of 25ms
+///   - p99 latency of 100ms
+///   - 2 database transactions per request, which allows for the exclusions
+///     lookup done on every request but not for regenerating the puzzle
+#[derive(Debug, clap::Parser)]
+struct Opts {
+    /// URL of the endpoint under test.
+    #[arg(
+        short,
+        long,
+        default_value = "http://localhost:3000/api/puzzle/daily/config?tz=%2B00:00"
+    )]
+    url: String,
+
+    /// URL of the database used by the server under test.
+    /// See the SQLX documentation on the DATABASE_URL environment variable for more details.
+    #[arg(short, long)]
+    database_url: String,
+
+    /// Total number of requests to send.
+    #[arg(short, long, default_value_t = 2000)]
+    requests: usize,
+
+    /// Number of requests in flight at once.
+    #[arg(short, long, default_value_t = 50)]
+    concurrency: usize,
+
+    /// Maximum acceptable median latency, in milliseconds.
+    #[arg(long, default_value_t = 25)]
+    p50_budget_ms: u64,
+
+    /// Maximum acceptable 99th percentile latency, in milliseconds.
+    #[arg(long, default_value_t = 100)]
+    p99_budget_ms: u64,
+
+    /// Maximum acceptable average number of database transactions per request.
+    #[arg(long, default_value_t = 2.0)]
+    transactions_budget: f64,
+
+    /// Seconds to wait for the server's database connections to report their
+    /// statistics before reading them. Postgres only guarantees idle
+    /// connections report within 10 seconds.
+    #[arg(long, default_value_t = 11)]
+    stats_settle_secs: u64,
+}
+
+async fn request(client: &HttpClient, uri: &hyper::Uri) -> anyhow::Result<()> {
+    let response = client
+        .get(uri.clone())
+        .await
+        .with_context(|| anyhow::anyhow!("Request to {} failed", uri))?;
+    let status = response.status();
+    response
+        .into_body()
+        .collect()
+        .await
+        .with_context(|| anyhow::anyhow!("Failed to read response from {}", uri))?;
+    if !status.is_success() {
+        anyhow::bail!("Request to {} failed with status {}", uri, status);
+    }
+    Ok(())
+}
+
+async fn settle_stats(secs: u64) {
+    println!("Waiting {}s for database statistics to settle", secs);
+    tokio::time::sleep(Duration::from_secs(secs)).await;
+}
+
+async fn transaction_count(conn: &mut sqlx::PgConnection) -> anyhow::Result<i64> {
+    // Statistics are cached for the rest of a transaction, so clear the snapshot
+    // to see counts reported since the last read.
+    sqlx::query("select pg_stat_clear_snapshot()")
+        .execute(&mut *conn)
+        .await
+        .context("Failed to clear statistics snapshot")?;
+    sqlx::query_scalar(
+        "select (xact_commit + xact_rollback)::bigint
+        from pg_stat_database
+        where datname = current_database()",
+    )
+    .fetch_one(conn)
+    .await
+    .context("Failed to read transaction count")
+}
+
+fn percentile(sorted: &[Duration], q: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((q * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+    sorted[rank - 1]
+}
@@ -0,0 +1,202 @@
+//! The rules a guess must pass to count, shared by the frontend checking
+//! guesses as they're played and the server checking the words of saved
+//! progress, so the two can't disagree.
+
+use puzzle_config::{Alphabet, Letter, PuzzleConfig, Word};
+use unicode_normalization::UnicodeNormalization as _;
+
+/// Shortest a word can be.
+pub const MIN_WORD_LENGTH: usize = 4;
+
+/// A guess that passed every rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidGuess {
+    /// The normalized guess, marked as a pangram if it is one.
+    pub word: Word,
+}
+
+/// Why a guess was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    MissingRequiredLetter,
+    TooShort,
+    BadLetters,
+    NotInList,
+    /// The guess was found already. The rules here don't know what was found,
+    /// so only callers keeping track of it reject guesses with this.
+    AlreadyGuessed,
+}
+
+impl ValidationError {
+    /// What to tell players about their guess.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::BadLetters => "Bad letters",
+            Self::TooShort => "Too short",
+            Self::MissingRequiredLetter => "Missing center letter",
+            Self::AlreadyGuessed => "Already found",
+            Self::NotInList => "Not in word list",
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Normalize typed or pasted input for comparison with the word list, which
+/// is all lowercase.
+pub fn normalize_guess(guess: &str) -> String {
+    guess.trim().nfc().collect::<String>().to_lowercase()
+}
+
+/// Check a guess against the puzzle. The guess is normalized and folded into
+/// the puzzle's alphabet first, so the word returned is what should be
+/// recorded as found.
+pub fn validate_guess(config: &PuzzleConfig, guess: &str) -> Result<ValidGuess, ValidationError> {
+    validate_guess_with(
+        &config.alphabet,
+        &config.required_letter,
+        &config.other_letters,
+        guess,
+        |word| config.valid_words.contains(&Word::new(word, false)),
+    )
+}
+
+/// Check a guess against a puzzle with these letters whose words are looked
+/// up with `is_listed`, for puzzles that don't have all their words at hand,
+/// like the copies saved for offline play.
+pub fn validate_guess_with(
+    alphabet: &Alphabet,
+    required_letter: &Letter,
+    other_letters: &[Letter],
+    guess: &str,
+    is_listed: impl FnOnce(&str) -> bool,
+) -> Result<ValidGuess, ValidationError> {
+    let word = alphabet.fold(&normalize_guess(guess));
+    if word.chars().count() < MIN_WORD_LENGTH {
+        return Err(ValidationError::TooShort);
+    }
+
+    if !word.contains(required_letter.0) {
+        return Err(ValidationError::MissingRequiredLetter);
+    }
+
+    if word
+        .chars()
+        .any(|c| !(required_letter.0 == c || other_letters.contains(&Letter::new(c))))
+    {
+        return Err(ValidationError::BadLetters);
+    }
+
+    if !is_listed(&word) {
+        return Err(ValidationError::NotInList);
+    }
+
+    let mut word = Word::new(&word, false);
+    word.is_pangram = word.is_pangram_of(required_letter, other_letters);
+    Ok(ValidGuess { word })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use puzzle_config::{ScoreBuckets, ScoringRules};
+
+    use super::*;
+
+    fn config() -> PuzzleConfig {
+        let words: HashSet<Word> = ["bake", "beak", "backed", "blacked"]
+            .into_iter()
+            .map(|w| Word::new(w, w == "blacked"))
+            .collect();
+        PuzzleConfig {
+            score_buckets: ScoreBuckets::default(),
+            scoring: ScoringRules::default(),
+            alphabet: Alphabet::default(),
+            required_letter: Letter::new('b'),
+            other_letters: "acdekl".chars().map(Letter::new).collect(),
+            valid_words: words,
+        }
+    }
+
+    #[test]
+    fn accepts_valid_guesses() {
+        let guess = validate_guess(&config(), "bake").unwrap();
+        assert_eq!("bake", guess.word.word);
+        assert!(!guess.word.is_pangram);
+        assert_eq!(1, guess.word.score(&config().scoring));
+
+        let guess = validate_guess(&config(), "blacked").unwrap();
+        assert!(guess.word.is_pangram);
+        assert_eq!(14, guess.word.score(&config().scoring));
+    }
+
+    #[test]
+    fn rejects_invalid_guesses() {
+        let config = config();
+        assert_eq!(
+            Err(ValidationError::TooShort),
+            validate_guess(&config, "bak")
+        );
+        assert_eq!(
+            Err(ValidationError::MissingRequiredLetter),
+            validate_guess(&config, "lace")
+        );
+        assert_eq!(
+            Err(ValidationError::BadLetters),
+            validate_guess(&config, "bike")
+        );
+        assert_eq!(
+            Err(ValidationError::NotInList),
+            validate_guess(&config, "blab")
+        );
+    }
+
+    #[test]
+    fn normalizes_guesses() {
+        let config = config();
+        assert_eq!(
+            "bake",
+            validate_guess(&config, "  BAKE\n").unwrap().word.word
+        );
+        assert_eq!("café", normalize_guess("CAFE\u{301}"));
+    }
+
+    #[test]
+    fn folds_guesses_into_the_alphabet() {
+        let config = PuzzleConfig {
+            alphabet: Alphabet::GERMAN,
+            valid_words: [Word::new("backe", false)].into_iter().collect(),
+            ..config()
+        };
+        assert_eq!("backe", validate_guess(&config, "BÄCKE").unwrap().word.word);
+
+        let config = PuzzleConfig {
+            alphabet: Alphabet::LATIN,
+            ..config
+        };
+        assert_eq!(
+            Err(ValidationError::BadLetters),
+            validate_guess(&config, "bäcke")
+        );
+    }
+
+    #[test]
+    fn looks_words_up_with_the_given_list() {
+        let config = config();
+        let guess = validate_guess_with(
+            &config.alphabet,
+            &config.required_letter,
+            &config.other_letters,
+            "blab",
+            |w| w == "blab",
+        );
+        assert_eq!("blab", guess.unwrap().word.word);
+    }
+}
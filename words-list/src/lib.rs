@@ -21,3 +21,178 @@ pub struct Pagination {
 #[derive(Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct Cursor(pub String);
+
+/// Outcome of importing a newline-delimited word list.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct ImportedWords {
+    /// Words added to the list.
+    pub inserted: u64,
+    /// Valid words that were already in the list.
+    pub skipped: u64,
+    /// Non-blank lines that aren't valid words.
+    pub rejected: u64,
+    /// Words that look like they belong to another language than the list's.
+    #[serde(default)]
+    pub foreign: u64,
+    /// The first few foreign words, to judge whether the check was right.
+    #[serde(default)]
+    pub foreign_examples: Vec<String>,
+    /// Inflected forms of other words disallowed by the morphology policy.
+    #[serde(default)]
+    pub inflected: u64,
+}
+
+/// Words in the list that the morphology policy keeps out of puzzles.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ExcludedWords {
+    pub words: Vec<ExcludedWord>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ExcludedWord {
+    pub word: String,
+    /// The word it is an inflected form of.
+    pub stem: String,
+    /// `plural`, `participle` or `past_tense`.
+    pub inflection: String,
+}
+
+/// Metadata of a word shown to players once they've found it.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct WordMetadata {
+    pub word: String,
+    pub definition: Option<String>,
+    /// How obscure the word is, from 1 for everyday words to 5 for the rarest.
+    pub obscurity: Option<u8>,
+}
+
+/// Everything known about a word in the list, for managing it.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WordDetail {
+    pub word: String,
+    /// Bits of the letters of the word, with `a` in the lowest bit.
+    pub letter_mask: u64,
+    /// The letters of the word, once each in alphabetical order.
+    pub letters: String,
+    pub length: usize,
+    /// Whether the word has seven different letters, so it could be the
+    /// pangram of a puzzle.
+    pub pangram_potential: bool,
+    pub definition: Option<String>,
+    pub obscurity: Option<u8>,
+    /// When the word was added, in RFC 3339. Unknown for words added before
+    /// this was recorded.
+    pub added_at: Option<String>,
+    /// When the word was flagged, in RFC 3339, if it's kept out of puzzles.
+    pub flagged_at: Option<String>,
+    /// Stored puzzles the word is an answer of, the most recent first.
+    pub puzzles: Vec<WordPuzzle>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WordPuzzle {
+    /// Day of the puzzle as `YYYY-MM-DD`.
+    pub date: String,
+    /// UTC offset in seconds the puzzle was generated for.
+    pub utc_offset: i32,
+    /// Whether the word was a pangram of the puzzle.
+    pub pangram: bool,
+}
+
+/// Words kept out of puzzles until they're reviewed, the most recently
+/// flagged first.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct FlaggedWords {
+    pub words: Vec<FlaggedWord>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FlaggedWord {
+    pub word: String,
+    /// When the word was flagged, in RFC 3339.
+    pub flagged_at: String,
+}
+
+/// What a player thinks is wrong with the word list.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WordReportKind {
+    /// A word the puzzle rejected but should accept.
+    Missing,
+    /// A word the puzzle accepted but shouldn't, such as an offensive one.
+    Invalid,
+}
+
+impl WordReportKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Missing => "missing",
+            Self::Invalid => "invalid",
+        }
+    }
+}
+
+/// A player's report about a word, for curators to review.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WordReport {
+    pub word: String,
+    pub kind: WordReportKind,
+}
+
+/// Reports about the same word and of the same kind, taken together.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ReportedWord {
+    pub word: String,
+    pub kind: WordReportKind,
+    /// How many times the word was reported.
+    pub reports: i64,
+    /// When the word was last reported, in RFC 3339.
+    pub last_reported_at: String,
+    /// Whether the word is in the list now, so the report may be dealt with.
+    pub in_list: bool,
+}
+
+/// Reported words, the most reported first.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ReportedWords {
+    pub words: Vec<ReportedWord>,
+}
+
+/// The latest changes to the word list, the most recent first.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WordAudit {
+    pub entries: Vec<WordAuditEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct WordAuditEntry {
+    pub word: String,
+    /// `added`, `removed` or `restored`.
+    pub action: String,
+    /// The admin who made the change, `None` for the server itself and
+    /// scripts holding the admin token.
+    pub username: Option<String>,
+    /// When the change was made, in RFC 3339.
+    pub recorded_at: String,
+}
+
+/// A word list the server hosts. Puzzles and the words managed are those of
+/// the standard dictionary unless another is named.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Dictionary {
+    pub name: String,
+    /// Words in the dictionary, not counting removed ones.
+    pub words: u64,
+}
+
+/// Every dictionary, in order of name.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Dictionaries {
+    pub dictionaries: Vec<Dictionary>,
+}
+
+/// Body of a request creating a dictionary.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct NewDictionary {
+    pub name: String,
+}
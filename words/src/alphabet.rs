@@ -0,0 +1,244 @@
+//! Alphabets puzzles can be made in.
+//!
+//! Every alphabet shares one layout of [`Bitmask`] bits: the latin letters
+//! from `a` in the lowest bit to `z`, followed by the letters some alphabets
+//! add to them. A mask means the same letters whichever alphabet made it, so
+//! masks of latin words stay valid in every alphabet.
+
+use crate::{Bitmask, BitmaskError};
+
+/// Letters some alphabets add to the latin ones, in the order of their bits
+/// after `z`.
+const EXTRA_LETTERS: [char; 1] = ['ñ'];
+
+/// Number of letters with a bit, across every alphabet.
+pub const LAYOUT_LETTERS: usize = 26 + EXTRA_LETTERS.len();
+
+/// Mask of every letter with a bit.
+pub(crate) const ANY: Bitmask = Bitmask((1 << LAYOUT_LETTERS) - 1);
+
+/// Bit of `letter` in the shared layout, if any alphabet has it.
+pub fn offset(letter: char) -> Option<u32> {
+    if letter.is_ascii_lowercase() {
+        Some(letter as u32 - 'a' as u32)
+    } else {
+        EXTRA_LETTERS
+            .iter()
+            .position(|l| *l == letter)
+            .map(|i| 26 + i as u32)
+    }
+}
+
+/// The letter with the bit `offset` in the shared layout.
+pub fn letter_at(offset: u32) -> Option<char> {
+    match offset {
+        0..26 => char::from_u32('a' as u32 + offset),
+        _ => EXTRA_LETTERS.get(offset as usize - 26).copied(),
+    }
+}
+
+/// The letters of a language, and how words are spelled with them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Alphabet {
+    name: &'static str,
+    /// Every letter in alphabetical order.
+    letters: &'static [char],
+    /// Characters written in the language that puzzles spell with other
+    /// letters, such as German umlauts.
+    folds: &'static [(char, &'static str)],
+}
+
+const LATIN_LETTERS: [char; 26] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+const SPANISH_LETTERS: [char; 27] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'ñ', 'o', 'p', 'q', 'r',
+    's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+impl Alphabet {
+    /// The 26 letters from `a` to `z`, which English puzzles use.
+    pub const LATIN: Self = Self {
+        name: "latin",
+        letters: &LATIN_LETTERS,
+        folds: &[],
+    };
+
+    /// The latin letters and `ñ`. Accented vowels are spelled without their
+    /// accents.
+    pub const SPANISH: Self = Self {
+        name: "spanish",
+        letters: &SPANISH_LETTERS,
+        folds: &[
+            ('á', "a"),
+            ('é', "e"),
+            ('í', "i"),
+            ('ó', "o"),
+            ('ú', "u"),
+            ('ü', "u"),
+        ],
+    };
+
+    /// The latin letters, with umlauts spelled without them and `ß` as `ss`,
+    /// as in crosswords.
+    pub const GERMAN: Self = Self {
+        name: "german",
+        letters: &LATIN_LETTERS,
+        folds: &[('ä', "a"), ('ö', "o"), ('ü', "u"), ('ß', "ss")],
+    };
+
+    /// Every alphabet there is.
+    pub const SUPPORTED: [Self; 3] = [Self::LATIN, Self::SPANISH, Self::GERMAN];
+
+    /// The alphabet called `name`, if it's supported.
+    pub fn named(name: &str) -> Option<Self> {
+        Self::SUPPORTED.into_iter().find(|a| a.name == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Every letter in alphabetical order.
+    pub fn letters(&self) -> &'static [char] {
+        self.letters
+    }
+
+    /// Number of bits a mask of these letters needs.
+    pub fn width(&self) -> u32 {
+        self.letters
+            .iter()
+            .filter_map(|l| offset(*l))
+            .max()
+            .map_or(0, |offset| offset + 1)
+    }
+
+    /// Every letter of the alphabet.
+    pub fn all(&self) -> Bitmask {
+        self.letters.iter().copied().collect()
+    }
+
+    pub fn contains(&self, letter: char) -> bool {
+        self.letters.contains(&letter)
+    }
+
+    /// Lowercase `word` and spell it with the letters of the alphabet where
+    /// the language allows. Characters with no such spelling are kept, so
+    /// they can be rejected by [`Self::try_bitmask`].
+    pub fn fold(&self, word: &str) -> String {
+        let mut folded = String::with_capacity(word.len());
+        for c in word.chars().flat_map(char::to_lowercase) {
+            match self.folds.iter().find(|(from, _)| *from == c) {
+                Some((_, to)) => folded.push_str(to),
+                None => folded.push(c),
+            }
+        }
+        folded
+    }
+
+    /// Compute the bitmask of a letter, failing if it is not in the alphabet.
+    pub fn try_letter_bitmask(&self, letter: char) -> Result<Bitmask, BitmaskError> {
+        offset(letter)
+            .filter(|_| self.contains(letter))
+            .map(|offset| Bitmask(1 << offset))
+            .ok_or(BitmaskError::InvalidLetter(letter))
+    }
+
+    /// Compute the bitmask of a word, failing on any character that is not a
+    /// letter of the alphabet. Words should be [folded](Self::fold) first.
+    pub fn try_bitmask(&self, word: &str) -> Result<Bitmask, BitmaskError> {
+        word.chars().try_fold(Bitmask::EMPTY, |mask, c| {
+            Ok(mask | self.try_letter_bitmask(c)?)
+        })
+    }
+
+    /// Check that `bits`, as stored in the database, are a set of letters of
+    /// the alphabet.
+    pub fn try_mask(&self, bits: i64) -> Result<Bitmask, BitmaskError> {
+        Bitmask::try_from(bits)
+            .ok()
+            .filter(|mask| mask.is_subset_of(self.all()))
+            .ok_or(BitmaskError::InvalidBits(bits))
+    }
+
+    /// The letters of `mask` in alphabetical order.
+    pub fn letters_of(&self, mask: Bitmask) -> Vec<char> {
+        self.letters
+            .iter()
+            .copied()
+            .filter(|l| mask.contains(*l))
+            .collect()
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::LATIN
+    }
+}
+
+impl std::fmt::Display for Alphabet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+impl std::str::FromStr for Alphabet {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::named(name).ok_or_else(|| {
+            let names: Vec<_> = Self::SUPPORTED.iter().map(|a| a.name).collect();
+            format!(
+                "Unsupported alphabet {:?}, expected one of {}",
+                name,
+                names.join(", ")
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_letters_of_the_alphabet() {
+        let spanish = Alphabet::SPANISH;
+        let mask = spanish.try_bitmask("año").unwrap();
+
+        assert_eq!(Bitmask(1 | 1 << 14 | 1 << 26), mask);
+        assert_eq!(vec!['a', 'ñ', 'o'], spanish.letters_of(mask));
+        assert_eq!(vec!['a', 'o', 'ñ'], mask.letters().collect::<Vec<_>>());
+        assert_eq!(
+            Err(BitmaskError::InvalidLetter('ñ')),
+            Alphabet::LATIN.try_bitmask("año")
+        );
+        assert_eq!(Ok(mask), spanish.try_mask(i64::from(mask)));
+        assert_eq!(
+            Err(BitmaskError::InvalidBits(i64::from(mask))),
+            Alphabet::LATIN.try_mask(i64::from(mask))
+        );
+    }
+
+    #[test]
+    fn folds_words_into_the_alphabet() {
+        assert_eq!("grosse", Alphabet::GERMAN.fold("Größe"));
+        assert_eq!("nacion", Alphabet::SPANISH.fold("nación"));
+        assert_eq!("niño", Alphabet::SPANISH.fold("NIÑO"));
+        assert_eq!("größe", Alphabet::LATIN.fold("größe"));
+    }
+
+    #[test]
+    fn measures_alphabets() {
+        assert_eq!(26, Alphabet::LATIN.width());
+        assert_eq!(27, Alphabet::SPANISH.width());
+        assert_eq!(26, Alphabet::GERMAN.width());
+        assert_eq!(Bitmask::ALL, Alphabet::LATIN.all());
+        assert_eq!(ANY, Alphabet::SPANISH.all());
+        assert_eq!(Some(Alphabet::GERMAN), "german".parse().ok());
+        assert!("klingon".parse::<Alphabet>().is_err());
+    }
+}
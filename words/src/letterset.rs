@@ -0,0 +1,224 @@
+//! Metrics for judging how playable a set of letters is before building a
+//! puzzle from it.
+
+use std::collections::HashMap;
+
+use crate::Bitmask;
+
+/// Letter sets yielding fewer words than this are flagged by [`Quality::warnings`].
+pub const MIN_YIELD: usize = 20;
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+/// Penalties for letters that appear in few words and so shrink the word list
+/// of any puzzle using them.
+const RARE_LETTERS: [(char, u32); 6] = [('q', 3), ('j', 2), ('x', 2), ('z', 2), ('k', 1), ('v', 1)];
+
+/// Index of dictionary words by their letter mask, used to count the words a
+/// letter set can spell without looking at the words themselves.
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    counts: HashMap<Bitmask, usize>,
+}
+
+impl Dictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `count` words spelled with exactly the letters in `mask`.
+    pub fn insert(&mut self, mask: Bitmask, count: usize) {
+        *self.counts.entry(mask).or_default() += count;
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Letter sets of exactly `size` letters that words are spelled with, and
+    /// how many words each spells using all of its letters.
+    pub fn letter_sets(&self, size: usize) -> impl Iterator<Item = (Bitmask, usize)> + '_ {
+        self.counts
+            .iter()
+            .filter(move |(mask, _)| mask.len() == size)
+            .map(|(mask, count)| (*mask, *count))
+    }
+
+    /// Counts of words spelled only with letters in `mask`, by their own mask.
+    fn within(&self, mask: Bitmask) -> Vec<(Bitmask, usize)> {
+        if 1usize << mask.len() <= self.counts.len() {
+            // Visiting every subset of a small letter set is cheaper than scanning
+            // the whole index.
+            let mut found = Vec::new();
+            let mut subset = mask.bits();
+            loop {
+                if let Some(count) = self.counts.get(&Bitmask(subset)) {
+                    found.push((Bitmask(subset), *count));
+                }
+                if subset == 0 {
+                    break;
+                }
+                subset = (subset - 1) & mask.bits();
+            }
+            found
+        } else {
+            self.counts
+                .iter()
+                .filter(|(m, _)| m.is_subset_of(mask))
+                .map(|(m, count)| (*m, *count))
+                .collect()
+        }
+    }
+}
+
+impl FromIterator<Bitmask> for Dictionary {
+    fn from_iter<T: IntoIterator<Item = Bitmask>>(iter: T) -> Self {
+        let mut dictionary = Self::new();
+        for mask in iter {
+            dictionary.insert(mask, 1);
+        }
+        dictionary
+    }
+}
+
+/// How playable a letter set is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quality {
+    pub vowels: u32,
+    /// Sum of the penalties of the rare letters in the set. Zero for sets
+    /// without any.
+    pub rare_letter_penalty: u32,
+    /// Dictionary words spelled only with letters from the set, whichever
+    /// letter is required.
+    pub words: usize,
+    /// Dictionary words using every letter of the set.
+    pub pangrams: usize,
+    /// Dictionary words containing each letter of the set, in alphabetical
+    /// order. This is the word yield of a puzzle requiring that letter.
+    pub words_by_letter: Vec<(char, usize)>,
+}
+
+impl Quality {
+    /// The word yield of a puzzle from this set requiring `letter`.
+    pub fn words_with(&self, letter: char) -> usize {
+        self.words_by_letter
+            .iter()
+            .find(|(l, _)| *l == letter)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// Human-readable problems with the set, empty if it looks playable.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.words < MIN_YIELD {
+            warnings.push(format!("This letter set yields only {} words", self.words));
+        }
+        if self.pangrams == 0 {
+            warnings.push("This letter set has no pangram".to_owned());
+        }
+        if self.vowels == 0 {
+            warnings.push("This letter set has no vowels".to_owned());
+        }
+        if self.rare_letter_penalty > 2 {
+            warnings.push("This letter set has several rare letters".to_owned());
+        }
+        warnings
+    }
+}
+
+/// Compute the [`Quality`] of the letter set `mask` against `dictionary`.
+pub fn quality(mask: Bitmask, dictionary: &Dictionary) -> Quality {
+    let within = dictionary.within(mask);
+
+    Quality {
+        vowels: VOWELS.iter().filter(|l| mask.contains(**l)).count() as u32,
+        rare_letter_penalty: RARE_LETTERS
+            .iter()
+            .filter(|(l, _)| mask.contains(*l))
+            .map(|(_, penalty)| penalty)
+            .sum(),
+        words: within.iter().map(|(_, count)| count).sum(),
+        pangrams: within
+            .iter()
+            .filter(|(m, _)| *m == mask)
+            .map(|(_, count)| count)
+            .sum(),
+        words_by_letter: mask
+            .letters()
+            .map(|l| {
+                let count = within
+                    .iter()
+                    .filter(|(m, _)| m.contains(l))
+                    .map(|(_, count)| count)
+                    .sum();
+                (l, count)
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitmask;
+
+    fn dictionary(words: &[&str]) -> Dictionary {
+        words.iter().map(|w| bitmask(w)).collect()
+    }
+
+    #[test]
+    fn counts_words_in_letter_set() {
+        let dictionary = dictionary(&["cabined", "abidance", "bead", "dance", "zebra", "cane"]);
+        let quality = quality(bitmask("abcdein"), &dictionary);
+
+        assert_eq!(5, quality.words);
+        assert_eq!(2, quality.pangrams);
+        assert_eq!(3, quality.vowels);
+        assert_eq!(0, quality.rare_letter_penalty);
+        assert_eq!(4, quality.words_with('d'));
+        assert_eq!(2, quality.words_with('i'));
+        assert_eq!(0, quality.words_with('z'));
+    }
+
+    #[test]
+    fn scans_index_for_large_letter_sets() {
+        let dictionary = dictionary(&["bead", "zebra"]);
+        let quality = quality(bitmask("abcdefghijklmnopqrstuvwxyz"), &dictionary);
+
+        assert_eq!(2, quality.words);
+        assert_eq!(0, quality.pangrams);
+    }
+
+    #[test]
+    fn lists_letter_sets_of_a_size() {
+        let dictionary = dictionary(&["cabined", "abidance", "bead", "zebra"]);
+        let mut sets: Vec<_> = dictionary.letter_sets(7).collect();
+        sets.sort();
+
+        assert_eq!(vec![(bitmask("abcdein"), 2)], sets);
+        assert_eq!(1, dictionary.letter_sets(4).count());
+        assert_eq!(0, dictionary.letter_sets(6).count());
+    }
+
+    #[test]
+    fn warns_about_poor_letter_sets() {
+        let dictionary = dictionary(&["jinx", "quiz"]);
+        let quality = quality(bitmask("jqxzkvw"), &dictionary);
+
+        assert_eq!(0, quality.vowels);
+        assert_eq!(11, quality.rare_letter_penalty);
+        assert_eq!(
+            vec![
+                "This letter set yields only 0 words".to_owned(),
+                "This letter set has no pangram".to_owned(),
+                "This letter set has no vowels".to_owned(),
+                "This letter set has several rare letters".to_owned(),
+            ],
+            quality.warnings()
+        );
+    }
+}
@@ -1,27 +1,200 @@
-pub type Bitmask = i32;
+pub mod alphabet;
+pub mod letterset;
+
+pub use alphabet::Alphabet;
+
+/// Set of letters, stored as a bit per letter with `a` in the lowest bit. The
+/// latin letters are followed by those other [`Alphabet`]s add.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bitmask(u64);
+
+impl Bitmask {
+    pub const EMPTY: Self = Self(0);
+
+    /// Every letter from `a` to `z`.
+    pub const ALL: Self = Self((1 << 26) - 1);
+
+    /// The raw bits of the mask.
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub fn contains(self, letter: char) -> bool {
+        alphabet::offset(letter).is_some_and(|offset| self.0 & (1 << offset) != 0)
+    }
+
+    pub fn is_subset_of(self, other: Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Number of letters in the set.
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The letters in the set in the order of their bits, which is
+    /// alphabetical for latin letters. Use [`Alphabet::letters_of`] to order
+    /// letters of other alphabets.
+    pub fn letters(self) -> Letters {
+        Letters(self.0)
+    }
+}
+
+impl std::ops::BitOr for Bitmask {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl std::ops::BitOrAssign for Bitmask {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = self.union(other);
+    }
+}
+
+/// The mask of a letter of any alphabet.
+impl TryFrom<char> for Bitmask {
+    type Error = BitmaskError;
+
+    fn try_from(letter: char) -> Result<Self, Self::Error> {
+        alphabet::offset(letter)
+            .map(|offset| Self(1 << offset))
+            .ok_or(BitmaskError::InvalidLetter(letter))
+    }
+}
+
+impl TryFrom<&str> for Bitmask {
+    type Error = BitmaskError;
+
+    fn try_from(word: &str) -> Result<Self, Self::Error> {
+        try_bitmask(word)
+    }
+}
+
+/// Masks are stored in signed bigint columns, wide enough for the letters of
+/// every alphabet.
+impl From<Bitmask> for i64 {
+    fn from(mask: Bitmask) -> Self {
+        mask.0 as i64
+    }
+}
+
+impl TryFrom<i64> for Bitmask {
+    type Error = BitmaskError;
+
+    fn try_from(bits: i64) -> Result<Self, Self::Error> {
+        u64::try_from(bits)
+            .ok()
+            .filter(|bits| bits & !alphabet::ANY.0 == 0)
+            .map(Self)
+            .ok_or(BitmaskError::InvalidBits(bits))
+    }
+}
+
+impl IntoIterator for Bitmask {
+    type Item = char;
+    type IntoIter = Letters;
+
+    fn into_iter(self) -> Letters {
+        self.letters()
+    }
+}
+
+impl FromIterator<char> for Bitmask {
+    /// Collect letters into a mask.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the characters is not a letter of any alphabet.
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::EMPTY, |mask, c| {
+            mask | Self::try_from(c).unwrap_or_else(|e| panic!("{}", e))
+        })
+    }
+}
+
+/// Iterator over the letters of a [`Bitmask`].
+#[derive(Debug, Clone)]
+pub struct Letters(u64);
+
+impl Iterator for Letters {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.0 == 0 {
+            return None;
+        }
+        let offset = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        alphabet::letter_at(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitmaskError {
+    /// The character isn't a lowercase latin letter.
+    InvalidLetter(char),
+    /// The bits don't correspond to a set of letters.
+    InvalidBits(i64),
+}
+
+impl std::fmt::Display for BitmaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLetter(c) => write!(f, "{:?} is not a letter from a to z", c),
+            Self::InvalidBits(bits) => write!(f, "{:#b} is not a set of letters", bits),
+        }
+    }
+}
+
+impl std::error::Error for BitmaskError {}
+
+/// Compute the bitmask of a word, failing on any character that is not a
+/// lowercase latin letter. Use [`Alphabet::try_bitmask`] for words of other
+/// alphabets.
+pub fn try_bitmask(word: &str) -> Result<Bitmask, BitmaskError> {
+    Alphabet::LATIN.try_bitmask(word)
+}
 
 /// Compute the bitmask of a word.
 ///
 /// # Panics
 ///
-/// Panics if the word contains any characters that are not
-/// covered by [`std::char::to_digit`](https://doc.rust-lang.org/std/primitive.char.html#method.to_digit)
+/// Panics if the word contains any characters that are not lowercase latin
+/// letters. Use [`try_bitmask`] for words that haven't been checked.
 pub fn bitmask(word: &str) -> Bitmask {
-    word.chars().fold(0, |bm, c| {
-        bm | letters::bitmask(&c)
-    })
+    try_bitmask(word).unwrap_or_else(|e| panic!("Invalid word {:?}: {}", word, e))
 }
 
 pub fn vec_from_bitmask(bm: &Bitmask) -> Vec<char> {
-    (0..26).into_iter().filter_map(|offset| {
-        let mask = bm & (1 << offset);
-        if mask > 0 {
-            Some(crate::letters::from_bitmask(&mask))
-        } else {
-            None
-        }
-    })
-    .collect()
+    bm.letters().collect()
+}
+
+/// Whether a word with `word_mask` can be spelled with the letters of
+/// `letters_mask` alone.
+pub fn is_subset(word_mask: Bitmask, letters_mask: Bitmask) -> bool {
+    word_mask.is_subset_of(letters_mask)
+}
+
+/// Number of distinct letters in `mask`.
+pub fn letter_count(mask: Bitmask) -> usize {
+    mask.len()
+}
+
+/// Whether a word with `word_mask` is a pangram of a puzzle with the letters
+/// of `letters_mask`: it uses every one of them and nothing else.
+pub fn is_pangram_for(word_mask: Bitmask, letters_mask: Bitmask) -> bool {
+    word_mask == letters_mask
 }
 
 #[test]
@@ -32,63 +205,102 @@ fn test_vec_roundtrip() {
     )
 }
 
+#[test]
+fn test_rejects_non_latin_letters() {
+    assert_eq!(Err(BitmaskError::InvalidLetter('é')), try_bitmask("café"));
+    assert_eq!(
+        Err(BitmaskError::InvalidLetter('A')),
+        Bitmask::try_from("Abc")
+    );
+    assert_eq!(Err(BitmaskError::InvalidBits(-1)), Bitmask::try_from(-1));
+    assert_eq!(
+        Err(BitmaskError::InvalidBits(1 << 27)),
+        Bitmask::try_from(1 << 27)
+    );
+}
+
+#[test]
+fn test_set_operations() {
+    let bead = bitmask("bead");
+    let abides = bitmask("abides");
+
+    assert!(bead.contains('d'));
+    assert!(!bead.contains('z'));
+    assert!(!bead.contains('é'));
+    assert!(bead.is_subset_of(abides));
+    assert!(!abides.is_subset_of(bead));
+    assert_eq!(bitmask("abdeis"), bead.union(abides));
+    assert_eq!(6, abides.len());
+    assert_eq!(Ok(abides), Bitmask::try_from(i64::from(abides)));
+    assert_eq!(abides, abides.into_iter().collect());
+}
+
+#[test]
+fn test_puzzle_helpers() {
+    let letters = bitmask("portend");
+
+    assert!(is_subset(bitmask("tend"), letters));
+    assert!(is_subset(letters, letters));
+    assert!(is_subset(Bitmask::EMPTY, letters));
+    assert!(!is_subset(bitmask("tender"), bitmask("tend")));
+    assert!(!is_subset(bitmask("pretends"), letters));
+
+    assert_eq!(0, letter_count(Bitmask::EMPTY));
+    assert_eq!(3, letter_count(bitmask("tenet")));
+    assert_eq!(7, letter_count(letters));
+    assert_eq!(26, letter_count(Bitmask::ALL));
+
+    assert!(is_pangram_for(bitmask("portend"), letters));
+    assert!(is_pangram_for(bitmask("portended"), letters));
+    assert!(!is_pangram_for(bitmask("pretend"), letters));
+    assert!(!is_pangram_for(bitmask("portends"), letters));
+}
+
 /// Utilities to bitmask individual characters
 ///
 /// ## Round tripping
 ///
-/// if you attempt to round trip a character through bitmask -> from_bitmask
-/// and that charcter is not a lowercase ascii/latin character you 
+/// Only lowercase latin letters have a bitmask, so every character accepted by
+/// [`letters::bitmask`] round trips through [`letters::from_bitmask`].
 pub mod letters {
-    const REFERENCE_ORD: i32 = 'a'.to_ascii_lowercase() as u8 as i32; // 0b1100001; // 'a'
+    /// Compute the bitmask of a character, failing if it is not a lowercase
+    /// latin letter.
+    ///
+    /// This bitmask will have the bit that corresponds to `letter`'s position
+    /// (0-indexed) in the lowercase latin alphabet set to 1.
+    pub fn try_bitmask(letter: char) -> Result<super::Bitmask, super::BitmaskError> {
+        super::Alphabet::LATIN.try_letter_bitmask(letter)
+    }
 
     /// Compute the bitmask of a character.
     ///
-    /// This bitmask will be an i32 with the bit that corresponds to `letter`'s
-    /// position (0-indexed) in the lowercase latin alphabet set to 1.
+    /// # Panics
+    ///
+    /// Panics if `letter` is not a lowercase latin letter. Use [`try_bitmask`]
+    /// for characters that haven't been checked.
     pub fn bitmask(letter: &char) -> super::Bitmask {
-        (1 << *letter as u8 as i32 - REFERENCE_ORD) as super::Bitmask
+        try_bitmask(*letter).unwrap_or_else(|e| panic!("{}", e))
     }
 
     /// Reverse the process of `bitmask`.
     ///
-    /// This assumes that `bm` is a bitmask with only one bit set to `1`.
-    /// It also assumes the bitmask represents a lowercase character.
+    /// This assumes that `bm` is a bitmask with only one bit set, and returns
+    /// its lowest letter otherwise.
     ///
     /// ## Panics
     ///
-    /// If more than one position is set it may panic.
+    /// Panics if `bm` is empty.
     pub fn from_bitmask(bm: &super::Bitmask) -> char {
-        LETTERS[bm.ilog2() as usize]
-    }
-
-    const LETTERS: [char; 26] = [
-        'a',
-        'b',
-        'c',
-        'd',
-        'e',
-        'f',
-        'g',
-        'h',
-        'i',
-        'j',
-        'k',
-        'l',
-        'm',
-        'n',
-        'o',
-        'p',
-        'q',
-        'r',
-        's',
-        't',
-        'u',
-        'v',
-        'w',
-        'x',
-        'y',
-        'z',
-    ];
+        bm.letters().next().expect("Empty bitmask has no letter")
+    }
+
+    #[test]
+    fn test_rejects_non_letters() {
+        assert_eq!(Ok(super::Bitmask(1 << 25)), try_bitmask('z'));
+        for c in ['A', '1', ' ', '`', '{', 'ß'] {
+            assert_eq!(Err(super::BitmaskError::InvalidLetter(c)), try_bitmask(c));
+        }
+    }
 
     #[test]
     fn test_roundtrip() {
@@ -99,3 +311,82 @@ pub mod letters {
     }
 }
 
+/// Properties of masks checked against many random words. Failures are shrunk
+/// to the smallest words that still fail.
+#[cfg(test)]
+mod properties {
+    use std::collections::{BTreeSet, HashSet};
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// A word of up to 20 letters from `first` to `last`.
+    fn word_of(first: char, last: char) -> impl Strategy<Value = String> {
+        prop::collection::vec(prop::char::range(first, last), 0..=20).prop_map(String::from_iter)
+    }
+
+    fn word() -> impl Strategy<Value = String> {
+        word_of('a', 'z')
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(512))]
+
+        #[test]
+        fn masks_round_trip_to_sorted_unique_letters(word in word()) {
+            let letters: Vec<char> = word.chars().collect::<BTreeSet<_>>().into_iter().collect();
+            prop_assert_eq!(&letters, &vec_from_bitmask(&bitmask(&word)));
+            prop_assert_eq!(letters.len(), letter_count(bitmask(&word)));
+        }
+
+        #[test]
+        fn masks_ignore_the_order_of_letters(
+            (word, shuffled) in word().prop_flat_map(|word| {
+                let letters: Vec<char> = word.chars().collect();
+                (Just(word), Just(letters).prop_shuffle())
+            })
+        ) {
+            prop_assert_eq!(bitmask(&word), bitmask(&String::from_iter(shuffled)));
+        }
+
+        // Few letters, so that one word is often spelled with the other's.
+        #[test]
+        fn subsets_match_subsets_of_letters(
+            word in word_of('a', 'f'),
+            letters in word_of('a', 'f'),
+        ) {
+            let expected = word
+                .chars()
+                .collect::<HashSet<_>>()
+                .is_subset(&letters.chars().collect());
+            prop_assert_eq!(expected, is_subset(bitmask(&word), bitmask(&letters)));
+        }
+
+        #[test]
+        fn rejects_words_with_other_characters(
+            (word, at) in word().prop_flat_map(|word| {
+                let len = word.len();
+                (Just(word), 0..=len)
+            }),
+            invalid in any::<char>().prop_filter("a latin letter", |c| !c.is_ascii_lowercase()),
+        ) {
+            let mut word: Vec<char> = word.chars().collect();
+            word.insert(at, invalid);
+            prop_assert_eq!(
+                Err(BitmaskError::InvalidLetter(invalid)),
+                try_bitmask(&String::from_iter(word))
+            );
+        }
+
+        // Around the width of every alphabet, negatives included.
+        #[test]
+        fn stored_masks_round_trip_or_fail(word in word(), bits in -(1i64 << 28)..1 << 28) {
+            let mask = bitmask(&word);
+            prop_assert_eq!(Ok(mask), Bitmask::try_from(i64::from(mask)));
+
+            let valid = (0..=alphabet::ANY.0 as i64).contains(&bits);
+            prop_assert_eq!(valid, Bitmask::try_from(bits).is_ok(), "{:#b}", bits);
+        }
+    }
+}